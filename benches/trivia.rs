@@ -0,0 +1,41 @@
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use full_moon::{tokenizer::TriviaMode, LuaVersion, ParseOptions};
+
+const SOURCES: &[(&str, &str)] = &[
+    ("date", include_str!("./date.lua")),
+    ("t", include_str!("./t.lua")),
+];
+
+const TRIVIA_MODES: &[TriviaMode] = &[
+    TriviaMode::KeepAll,
+    TriviaMode::KeepComments,
+    TriviaMode::DropAll,
+];
+
+fn parse(criterion: &mut Criterion) {
+    let mut group = criterion.benchmark_group("get ast with trivia mode");
+
+    for (name, source) in SOURCES {
+        for trivia_mode in TRIVIA_MODES {
+            let options = ParseOptions::new(LuaVersion::new())
+                .with_max_depth(usize::MAX)
+                .with_trivia_mode(*trivia_mode);
+
+            group.bench_with_input(
+                BenchmarkId::new(format!("{trivia_mode:?}"), name),
+                source,
+                |b, source| b.iter(|| full_moon::parse_with_options(source, &options)),
+            );
+        }
+    }
+
+    group.finish();
+}
+
+criterion_group! {
+    name = benches;
+    config = Criterion::default().sample_size(20);
+    targets = parse
+}
+
+criterion_main!(benches);
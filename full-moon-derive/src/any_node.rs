@@ -0,0 +1,220 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{
+    parse::{Parse, ParseStream},
+    parse_macro_input,
+    punctuated::Punctuated,
+};
+
+struct NodeType {
+    attrs: Vec<syn::Attribute>,
+    path: syn::Path,
+}
+
+impl NodeType {
+    fn name(&self) -> &syn::Ident {
+        &self.path.segments.last().expect("no last segment?").ident
+    }
+}
+
+impl Parse for NodeType {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        Ok(Self {
+            attrs: input.call(syn::Attribute::parse_outer)?,
+            path: input.parse()?,
+        })
+    }
+}
+
+struct NodeTypes(Punctuated<NodeType, syn::Token![,]>);
+
+impl Parse for NodeTypes {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        Ok(Self(Punctuated::parse_terminated(input)?))
+    }
+}
+
+pub fn any_node(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let NodeTypes(node_types) = parse_macro_input!(input as NodeTypes);
+
+    let attrs: Vec<_> = node_types
+        .iter()
+        .map(|node_type| &node_type.attrs)
+        .collect();
+    let names: Vec<_> = node_types.iter().map(NodeType::name).collect();
+    let paths: Vec<_> = node_types.iter().map(|node_type| &node_type.path).collect();
+
+    let expanded: TokenStream = quote! {
+        /// The kind of an [`AnyNode`], one for every type of node
+        #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+        #[non_exhaustive]
+        pub enum SyntaxKind {
+            #(
+                #(#attrs)*
+                #[allow(missing_docs)]
+                #names,
+            )*
+        }
+
+        /// A reference to any node, for walking a tree without knowing the types in it,
+        /// such as for printing the tree. Every node can be turned into one with `AnyNode::from`.
+        ///
+        /// ```rust
+        /// # use full_moon::node::{AnyNode, SyntaxKind};
+        /// # fn main() -> Result<(), Vec<full_moon::Error>> {
+        /// let ast = full_moon::parse("print(1)")?;
+        /// let root = AnyNode::from(ast.nodes());
+        /// assert_eq!(root.kind(), SyntaxKind::Block);
+        ///
+        /// let call = root.children().next().unwrap().children().next().unwrap();
+        /// assert_eq!(call.kind(), SyntaxKind::FunctionCall);
+        ///
+        /// let kinds = call.children().map(|child| child.kind()).collect::<Vec<_>>();
+        /// assert_eq!(kinds, [SyntaxKind::Prefix, SyntaxKind::Suffix]);
+        /// # Ok(())
+        /// # }
+        /// ```
+        #[derive(Clone, Copy, Debug)]
+        #[non_exhaustive]
+        pub enum AnyNode<'a> {
+            #(
+                #(#attrs)*
+                #[allow(missing_docs)]
+                #names(&'a #paths),
+            )*
+        }
+
+        impl<'a> AnyNode<'a> {
+            /// The kind of node this is
+            pub fn kind(&self) -> SyntaxKind {
+                match self {
+                    #(
+                        #(#attrs)*
+                        AnyNode::#names(_) => SyntaxKind::#names,
+                    )*
+                }
+            }
+
+            /// The nodes directly inside of this one, in the order of the fields they are in.
+            /// Containers such as `Option`, `Vec`, and `Punctuated` are not nodes, so the nodes
+            /// inside of them are given instead.
+            pub fn children(&self) -> impl Iterator<Item = AnyNode<'a>> {
+                let mut children = Vec::new();
+
+                match *self {
+                    #(
+                        #(#attrs)*
+                        AnyNode::#names(node) => Children::add_children(node, &mut children),
+                    )*
+                }
+
+                children.into_iter()
+            }
+        }
+
+        impl Node for AnyNode<'_> {
+            fn start_position(&self) -> Option<Position> {
+                match self {
+                    #(
+                        #(#attrs)*
+                        AnyNode::#names(node) => node.start_position(),
+                    )*
+                }
+            }
+
+            fn end_position(&self) -> Option<Position> {
+                match self {
+                    #(
+                        #(#attrs)*
+                        AnyNode::#names(node) => node.end_position(),
+                    )*
+                }
+            }
+
+            fn similar(&self, other: &Self) -> bool {
+                match (self, other) {
+                    #(
+                        #(#attrs)*
+                        (AnyNode::#names(node), AnyNode::#names(other)) => node.similar(other),
+                    )*
+                    _ => false,
+                }
+            }
+
+            fn hash_similar(&self, state: &mut dyn std::hash::Hasher) {
+                match self {
+                    #(
+                        #(#attrs)*
+                        AnyNode::#names(node) => node.hash_similar(state),
+                    )*
+                }
+            }
+
+            fn tokens(&self) -> Tokens<'_> {
+                match self {
+                    #(
+                        #(#attrs)*
+                        AnyNode::#names(node) => node.tokens(),
+                    )*
+                }
+            }
+        }
+
+        impl crate::private::Sealed for AnyNode<'_> {}
+
+        /// A type of node that an [`AnyNode`] can be, such as [`Stmt`](crate::ast::Stmt), used by
+        /// [`AnyNode::cast`].
+        ///
+        /// This trait is sealed and cannot be implemented for types outside of `full-moon`
+        pub trait AnyNodeType: Node {
+            /// The node as an [`AnyNode`]
+            fn as_any_node(&self) -> AnyNode<'_>;
+
+            /// The node, if the AnyNode is one of this type
+            fn from_any_node<'a>(node: AnyNode<'a>) -> Option<&'a Self>;
+        }
+
+        impl<'a> AnyNode<'a> {
+            /// The typed node, if it's a `T`.
+            /// For example, `node.cast::<ast::Stmt>()` gives the statement if the node is one.
+            pub fn cast<T: AnyNodeType>(&self) -> Option<&'a T> {
+                T::from_any_node(*self)
+            }
+
+            // The address of the typed node, which along with the kind tells apart every node
+            pub(crate) fn address(&self) -> usize {
+                match self {
+                    #(
+                        #(#attrs)*
+                        AnyNode::#names(node) => *node as *const #paths as usize,
+                    )*
+                }
+            }
+        }
+
+        #(
+            #(#attrs)*
+            impl<'a> From<&'a #paths> for AnyNode<'a> {
+                fn from(node: &'a #paths) -> Self {
+                    AnyNode::#names(node)
+                }
+            }
+
+            #(#attrs)*
+            impl AnyNodeType for #paths {
+                fn as_any_node(&self) -> AnyNode<'_> {
+                    AnyNode::#names(self)
+                }
+
+                fn from_any_node<'a>(node: AnyNode<'a>) -> Option<&'a Self> {
+                    match node {
+                        AnyNode::#names(node) => Some(node),
+                        _ => None,
+                    }
+                }
+            }
+        )*
+    };
+
+    expanded.into()
+}
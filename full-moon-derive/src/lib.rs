@@ -2,7 +2,9 @@
 
 extern crate proc_macro;
 
+mod any_node;
 mod derive;
+mod mut_accessors;
 mod node;
 mod visit;
 
@@ -15,7 +17,17 @@ pub fn derive_visit(input: TokenStream) -> TokenStream {
     visit::VisitGenerator::derive(input)
 }
 
+#[proc_macro_derive(MutAccessors, attributes(mut_accessor))]
+pub fn derive_mut_accessors(input: TokenStream) -> TokenStream {
+    mut_accessors::derive(input)
+}
+
 #[proc_macro_derive(Node, attributes(node))]
 pub fn derive_node(input: TokenStream) -> TokenStream {
     node::NodeGenerator::derive(input)
 }
+
+#[proc_macro]
+pub fn any_node(input: TokenStream) -> TokenStream {
+    any_node::any_node(input)
+}
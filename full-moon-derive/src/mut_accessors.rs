@@ -0,0 +1,88 @@
+use crate::derive::*;
+
+use quote::{format_ident, quote};
+use syn::{parse_macro_input, DeriveInput};
+
+#[derive(Debug, PartialEq)]
+enum MutAccessorHint {
+    Name(String),
+}
+
+impl Hint for MutAccessorHint {
+    fn key_value(key: String, value: String) -> Option<Self> {
+        if key == "name" {
+            Some(MutAccessorHint::Name(value))
+        } else {
+            None
+        }
+    }
+}
+
+// `Box<T>` is returned as `&mut T`, so that callers don't need to know what is boxed
+fn unboxed(ty: &syn::Type) -> &syn::Type {
+    if let syn::Type::Path(path) = ty {
+        let segment = path.path.segments.last().expect("no last segment?");
+
+        if segment.ident == "Box" {
+            if let syn::PathArguments::AngleBracketed(arguments) = &segment.arguments {
+                if let Some(syn::GenericArgument::Type(inner)) = arguments.args.first() {
+                    return inner;
+                }
+            }
+        }
+    }
+
+    ty
+}
+
+pub fn derive(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    let input_ident = &input.ident;
+
+    let fields = match &input.data {
+        syn::Data::Struct(syn::DataStruct {
+            fields: syn::Fields::Named(fields),
+            ..
+        }) => &fields.named,
+        _ => unimplemented!("MutAccessors can only be derived for structs with named fields"),
+    };
+
+    let mut accessors = Vec::with_capacity(fields.len());
+
+    // Public fields can already be changed in place
+    for field in fields
+        .iter()
+        .filter(|field| !matches!(field.vis, syn::Visibility::Public(_)))
+    {
+        let ident = field.ident.as_ref().unwrap();
+
+        let name = match search_hint("mut_accessor", &field.attrs) {
+            Some(MutAccessorHint::Name(name)) => name,
+            None => ident.to_string().trim_start_matches("r#").to_owned(),
+        };
+
+        let accessor = format_ident!("{}_mut", name);
+        let ty = unboxed(&field.ty);
+        let doc = format!(
+            " Returns a mutable reference to the {} of this {}, for changing it in place",
+            name.replace('_', " "),
+            input_ident
+        );
+
+        accessors.push(quote! {
+            #[doc = #doc]
+            pub fn #accessor(&mut self) -> &mut #ty {
+                &mut self.#ident
+            }
+        });
+    }
+
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    quote! {
+        impl #impl_generics #input_ident #ty_generics #where_clause {
+            #(#accessors)*
+        }
+    }
+    .into()
+}
@@ -57,7 +57,9 @@ impl DeriveGenerator for NodeGenerator {
         let pattern = quote! {{
             range => $range:expr,
             similar => $similar:expr,
+            hash_similar => $hash_similar:expr,
             tokens => $tokens:expr,
+            children => $children:expr,
         }};
 
         quote! {
@@ -70,8 +72,16 @@ impl DeriveGenerator for NodeGenerator {
                     $similar
                 };
 
+                ("hash_similar", #pattern) => {
+                    $hash_similar
+                };
+
                 ("tokens", #pattern) => {
                     $tokens
+                };
+
+                ("children", #pattern) => {
+                    $children
                 }
             }
 
@@ -88,12 +98,26 @@ impl DeriveGenerator for NodeGenerator {
                     #macro_name!("similar", { #tokens })
                 }
 
+                fn hash_similar(&self, state: &mut dyn std::hash::Hasher) {
+                    #macro_name!("hash_similar", { #tokens })
+                }
+
                 fn tokens<'a>(&'a self) -> crate::node::Tokens<'a> {
                     #macro_name!("tokens", { #tokens })
                 }
             }
 
             impl #impl_generics crate::private::Sealed for #input_ident #ty_generics #where_clause {}
+
+            impl<'a> crate::node::Children<'a> for #input_ident {
+                fn add_as_child(&'a self, children: &mut Vec<crate::node::AnyNode<'a>>) {
+                    children.push(crate::node::AnyNode::from(self));
+                }
+
+                fn add_children(&'a self, children: &mut Vec<crate::node::AnyNode<'a>>) {
+                    #macro_name!("children", { #tokens })
+                }
+            }
         }
     }
 }
@@ -102,12 +126,16 @@ impl StructGenerator for NodeGenerator {
     fn generate(ident: &syn::Ident, strukt: &syn::DataStruct) -> TokenStream {
         let range = StructRangeGenerator::generate(ident, strukt);
         let similar = StructSimilarGenerator::generate(ident, strukt);
+        let hash_similar = StructHashSimilarGenerator::generate(ident, strukt);
         let tokens = StructTokensGenerator::generate(ident, strukt);
+        let children = StructChildrenGenerator::generate(ident, strukt);
 
         quote! {
             range => { #range },
             similar => { #similar },
+            hash_similar => { #hash_similar },
             tokens => { #tokens },
+            children => { #children },
         }
     }
 }
@@ -179,6 +207,24 @@ impl StructGenerator for StructSimilarGenerator {
     }
 }
 
+pub struct StructHashSimilarGenerator;
+
+impl StructGenerator for StructHashSimilarGenerator {
+    fn generate(_: &syn::Ident, strukt: &syn::DataStruct) -> TokenStream {
+        let fields = strukt
+            .fields
+            .iter()
+            .map(|field| field.ident.as_ref().unwrap())
+            .collect::<Vec<_>>();
+
+        quote! {
+            #(
+                self.#fields.hash_similar(state);
+            )*
+        }
+    }
+}
+
 pub struct StructTokensGenerator;
 
 impl StructGenerator for StructTokensGenerator {
@@ -206,11 +252,31 @@ impl StructGenerator for StructTokensGenerator {
     }
 }
 
+pub struct StructChildrenGenerator;
+
+impl StructGenerator for StructChildrenGenerator {
+    fn generate(_: &syn::Ident, strukt: &syn::DataStruct) -> TokenStream {
+        let fields = strukt
+            .fields
+            .iter()
+            .map(|field| field.ident.as_ref().unwrap())
+            .collect::<Vec<_>>();
+
+        quote! {
+            #(
+                crate::node::Children::add_as_child(&self.#fields, children);
+            )*
+        }
+    }
+}
+
 impl EnumGenerator for NodeGenerator {
     fn generate(ident: &syn::Ident, enumm: &syn::DataEnum) -> TokenStream {
         let range = EnumRangeGenerator::generate(ident, enumm);
         let similar = EnumSimilarGenerator::generate(ident, enumm);
+        let hash_similar = EnumHashSimilarGenerator::generate(ident, enumm);
         let tokens = EnumTokensGenerator::generate(ident, enumm);
+        let children = EnumChildrenGenerator::generate(ident, enumm);
 
         quote! {
             range => {
@@ -219,7 +285,9 @@ impl EnumGenerator for NodeGenerator {
             },
 
             similar => { #similar },
+            hash_similar => { #hash_similar },
             tokens => { #tokens },
+            children => { #children },
         }
     }
 }
@@ -402,6 +470,65 @@ impl MatchEnumGenerator for EnumSimilarGenerator {
     }
 }
 
+// Variants are hashed by name rather than by discriminant, which changes with the variants that
+// are enabled by features
+pub struct EnumHashSimilarGenerator;
+
+impl MatchEnumGenerator for EnumHashSimilarGenerator {
+    fn case_named(
+        input: &syn::Ident,
+        variant: &syn::Ident,
+        named: &syn::FieldsNamed,
+    ) -> TokenStream {
+        let fields = named
+            .named
+            .iter()
+            .map(|field| field.ident.as_ref().unwrap())
+            .collect::<Vec<_>>();
+
+        quote! {
+            #input::#variant {
+                #(#fields,)*
+            } => {
+                state.write(stringify!(#variant).as_bytes());
+                #(
+                    #fields.hash_similar(state);
+                )*
+            }
+        }
+    }
+
+    fn case_unnamed(
+        input: &syn::Ident,
+        variant: &syn::Ident,
+        fields: &syn::FieldsUnnamed,
+    ) -> TokenStream {
+        let fields: Vec<_> = fields
+            .unnamed
+            .iter()
+            .enumerate()
+            .map(|(index, _)| format_ident!("__self_{}", index))
+            .collect();
+
+        quote! {
+            #input::#variant(
+                #(#fields,)*
+            ) => {
+                state.write(stringify!(#variant).as_bytes());
+                #(
+                    #fields.hash_similar(state);
+                )*
+            }
+        }
+    }
+
+    fn case_unit(input: &syn::Ident, variant: &syn::Ident) -> TokenStream {
+        quote! {
+            #input::#variant => state.write(stringify!(#variant).as_bytes()),
+        }
+    }
+}
+
 pub struct EnumTokensGenerator;
 
 impl MatchEnumGenerator for EnumTokensGenerator {
@@ -471,3 +598,58 @@ impl MatchEnumGenerator for EnumTokensGenerator {
         }
     }
 }
+
+pub struct EnumChildrenGenerator;
+
+impl MatchEnumGenerator for EnumChildrenGenerator {
+    fn case_named(
+        input: &syn::Ident,
+        variant: &syn::Ident,
+        named: &syn::FieldsNamed,
+    ) -> TokenStream {
+        let fields = named
+            .named
+            .iter()
+            .map(|field| field.ident.as_ref().unwrap())
+            .collect::<Vec<_>>();
+
+        quote! {
+            #input::#variant {
+                #(#fields,)*
+            } => {
+                #(
+                    crate::node::Children::add_as_child(#fields, children);
+                )*
+            }
+        }
+    }
+
+    fn case_unnamed(
+        input: &syn::Ident,
+        variant: &syn::Ident,
+        fields: &syn::FieldsUnnamed,
+    ) -> TokenStream {
+        let fields: Vec<_> = fields
+            .unnamed
+            .iter()
+            .enumerate()
+            .map(|(index, _)| format_ident!("__self_{}", index))
+            .collect();
+
+        quote! {
+            #input::#variant(
+                #(#fields,)*
+            ) => {
+                #(
+                    crate::node::Children::add_as_child(#fields, children);
+                )*
+            }
+        }
+    }
+
+    fn case_unit(input: &syn::Ident, variant: &syn::Ident) -> TokenStream {
+        quote! {
+            #input::#variant => {}
+        }
+    }
+}
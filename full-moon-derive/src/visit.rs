@@ -137,7 +137,7 @@ impl DeriveGenerator for VisitGenerator {
         quote! {
             #[allow(unused_macros)]
             impl #impl_generics crate::visitors::Visit for #input_ident #ty_generics #where_clause {
-                fn visit<V: crate::visitors::Visitor>(&self, visitor: &mut V) {
+                fn visit<'ast, V: crate::visitors::VisitorRef<'ast>>(&'ast self, visitor: &mut V) {
                     macro_rules! visit {
                         ($visit_what: expr, $visitor: expr) => {
                             $visit_what.visit($visitor);
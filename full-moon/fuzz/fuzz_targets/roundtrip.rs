@@ -0,0 +1,43 @@
+#![no_main]
+
+use full_moon::{ast::Ast, generate::Generator, node::Node, LuaVersion};
+use libfuzzer_sys::{
+    arbitrary::{self, Arbitrary, Unstructured},
+    fuzz_target,
+};
+
+#[derive(Debug)]
+struct GeneratedAst {
+    ast: Ast,
+    lua_version: LuaVersion,
+}
+
+impl<'a> Arbitrary<'a> for GeneratedAst {
+    fn arbitrary(u: &mut Unstructured<'a>) -> arbitrary::Result<Self> {
+        let lua_version = *u.choose(&[
+            LuaVersion::lua51(),
+            LuaVersion::luau(),
+            LuaVersion::lua52(),
+            LuaVersion::lua53(),
+            LuaVersion::lua54(),
+            LuaVersion::luajit(),
+        ])?;
+
+        let ast = Generator::from_unstructured(u, lua_version)?.ast();
+        Ok(GeneratedAst { ast, lua_version })
+    }
+}
+
+fuzz_target!(|generated: GeneratedAst| {
+    let code = generated.ast.to_string();
+
+    let parsed = match full_moon::parse_fallible(&code, generated.lua_version).into_result() {
+        Ok(parsed) => parsed,
+        Err(errors) => panic!("generated code doesn't parse: {errors:?}\n{code}"),
+    };
+
+    assert!(
+        generated.ast.similar(&parsed),
+        "generated code parsed differently:\n{code}"
+    );
+});
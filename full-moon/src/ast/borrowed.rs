@@ -0,0 +1,761 @@
+//! A compact, read-only, untyped syntax tree that borrows the source code it was parsed from.
+//!
+//! The owned [`crate::ast::Ast`] copies the text of every token and its trivia, and boxes nodes
+//! individually. This tree instead keeps tokens as byte ranges of the source, with trivia being
+//! the source between them, and keeps every node in a single arena, for when many files need to be
+//! kept in memory at once, such as in an indexer. The owned nodes are only made for one top level
+//! statement at a time while parsing, and are dropped once it has been added to the arena.
+//!
+//! Nodes are untyped [`NodeRef`]s with the [`SyntaxKind`] of the node they were made from, and are
+//! navigated through their parent, children, and tokens. They don't have the accessors of the
+//! owned nodes, such as `LocalAssignment::names`, and are visited with this module's [`Visitor`]
+//! rather than [`crate::visitors::Visitor`] or [`crate::visitors::VisitorRef`]. When the typed
+//! nodes are needed, such as to edit the code, use [`Ast::to_owned_ast`], which parses all of the
+//! code again.
+//!
+//! ```rust
+//! # use full_moon::{ast::borrowed::Ast, node::SyntaxKind, LuaVersion};
+//! let source = String::from("local x = 1\nprint(x)");
+//! let ast = Ast::parse(&source, LuaVersion::new()).unwrap();
+//!
+//! let call = ast
+//!     .nodes()
+//!     .find(|node| node.kind() == SyntaxKind::FunctionCall)
+//!     .unwrap();
+//!
+//! assert_eq!(call.text(), "print(x)");
+//! assert_eq!(call.parent().unwrap().kind(), SyntaxKind::Stmt);
+//! assert_eq!(ast.to_string(), source);
+//! ```
+use std::{fmt, ops::Range};
+
+use super::{
+    parser_structs::{ParserResult, ParserState},
+    parsers::{parse_last_stmt, parse_stmt, StmtVariant},
+};
+use crate::{
+    ast::{self, *},
+    node::SyntaxKind,
+    tokenizer::{Lexer, Position, Symbol, Token, TokenKind, TokenType},
+    visitors::{Visit, VisitorRef},
+    Error,
+};
+
+macro_rules! builder_visitor {
+    (
+        $($kind:ident => $visit_name:ident,)+
+
+        $(#[$meta:meta] {
+            $($meta_kind:ident => $meta_visit_name:ident,)+
+        })+
+    ) => {
+        impl<'a> VisitorRef<'a> for Builder {
+            fn visit_token(&mut self, token: &'a Token) {
+                self.token(token);
+            }
+
+            paste::item! {
+                $(
+                    fn $visit_name(&mut self, _node: &'a $kind) {
+                        self.enter(SyntaxKind::$kind);
+                    }
+
+                    fn [<$visit_name _end>](&mut self, _node: &'a $kind) {
+                        self.exit();
+                    }
+                )+
+
+                $($(
+                    #[$meta]
+                    fn $meta_visit_name(&mut self, _node: &'a $meta_kind) {
+                        self.enter(SyntaxKind::$meta_kind);
+                    }
+
+                    #[$meta]
+                    fn [<$meta_visit_name _end>](&mut self, _node: &'a $meta_kind) {
+                        self.exit();
+                    }
+                )+)+
+            }
+        }
+    };
+}
+
+// Calls the macro with every kind of node that visitors enter, along with the `Visitor` method
+// that visits it. These are every `SyntaxKind` other than tokens, contained spans, and the few
+// nodes that visitors only visit the tokens of, such as `BinOp`.
+macro_rules! with_node_kinds {
+    ($macro:ident) => {
+        $macro! {
+            Assignment => visit_assignment,
+            Block => visit_block,
+            Call => visit_call,
+            Do => visit_do,
+            ElseIf => visit_else_if,
+            Expression => visit_expression,
+            Field => visit_field,
+            FunctionArgs => visit_function_args,
+            FunctionBody => visit_function_body,
+            FunctionCall => visit_function_call,
+            FunctionDeclaration => visit_function_declaration,
+            FunctionName => visit_function_name,
+            GenericFor => visit_generic_for,
+            If => visit_if,
+            Index => visit_index,
+            LocalAssignment => visit_local_assignment,
+            LocalFunction => visit_local_function,
+            LastStmt => visit_last_stmt,
+            MethodCall => visit_method_call,
+            NumericFor => visit_numeric_for,
+            Parameter => visit_parameter,
+            Prefix => visit_prefix,
+            Return => visit_return,
+            Repeat => visit_repeat,
+            Stmt => visit_stmt,
+            Suffix => visit_suffix,
+            TableConstructor => visit_table_constructor,
+            UnOp => visit_un_op,
+            Var => visit_var,
+            VarExpression => visit_var_expression,
+            While => visit_while,
+
+            #[cfg(feature = "luau")] {
+                ElseIfExpression => visit_else_if_expression,
+                ExportedTypeDeclaration => visit_exported_type_declaration,
+                ExportedTypeFunction => visit_exported_type_function,
+                GenericDeclaration => visit_generic_declaration,
+                GenericDeclarationParameter => visit_generic_declaration_parameter,
+                GenericParameterInfo => visit_generic_parameter_info,
+                IfExpression => visit_if_expression,
+                IndexedTypeInfo => visit_indexed_type_info,
+                InterpolatedString => visit_interpolated_string,
+                TypeArgument => visit_type_argument,
+                TypeAssertion => visit_type_assertion,
+                TypeDeclaration => visit_type_declaration,
+                TypeField => visit_type_field,
+                TypeFieldKey => visit_type_field_key,
+                TypeFunction => visit_type_function,
+                TypeInfo => visit_type_info,
+                TypeIntersection => visit_type_intersection,
+                TypeSpecifier => visit_type_specifier,
+                TypeUnion => visit_type_union,
+            }
+
+            #[cfg(any(feature = "lua52", feature = "luajit"))] {
+                Goto => visit_goto,
+                Label => visit_label,
+            }
+
+            #[cfg(feature = "lua54")] {
+                Attribute => visit_attribute,
+            }
+
+            #[cfg(any(feature = "cfxlua", feature = "luau"))] {
+                CompoundAssignment => visit_compound_assignment,
+                CompoundOp => visit_compound_op,
+            }
+        }
+    };
+}
+
+pub(super) use with_node_kinds;
+
+with_node_kinds!(builder_visitor);
+
+// Byte offsets are kept as u32 to keep tokens and nodes small, so sources must be under 4 GiB
+#[derive(Clone, Copy, Debug)]
+struct TokenData {
+    // Where the leading trivia starts
+    full_start: u32,
+    start: u32,
+    end: u32,
+    kind: TokenKind,
+    symbol: Option<Symbol>,
+}
+
+#[derive(Clone, Copy, Debug)]
+struct NodeData {
+    kind: SyntaxKind,
+    parent: Option<u32>,
+    // The range of tokens in the node
+    first_token: u32,
+    token_end: u32,
+    // Nodes are stored in preorder, so every node after this one up until `subtree_end` is inside it
+    subtree_end: u32,
+}
+
+/// A read-only, untyped syntax tree that borrows the source code it was parsed from.
+/// Read the [module documentation](self) for more information.
+#[derive(Clone, Debug)]
+pub struct Ast<'src> {
+    source: &'src str,
+    lua_version: LuaVersion,
+    // Every token, ending with the end of file
+    tokens: Vec<TokenData>,
+    nodes: Vec<NodeData>,
+    line_starts: Vec<u32>,
+}
+
+impl<'src> Ast<'src> {
+    /// Parses the source code, returning any errors found. Like [`parse`](crate::parse), no Ast
+    /// is returned if there are any errors.
+    ///
+    /// The code is parsed one top level statement at a time, with each being added to the Ast
+    /// before the next is parsed, so the owned nodes of all of the code never exist at once.
+    #[allow(clippy::result_large_err)]
+    pub fn parse(source: &'src str, lua_version: LuaVersion) -> Result<Self, Vec<Error>> {
+        assert!(
+            u32::try_from(source.len()).is_ok(),
+            "source is too large for a borrowed Ast"
+        );
+
+        let mut builder = Builder::default();
+
+        if builder.parse(source, lua_version) {
+            return Ok(Self::new(source, lua_version, builder));
+        }
+
+        // The code has errors, so it's parsed again the usual way, which recovers from errors
+        // to find all of them
+        let ast = crate::parse_fallible(source, lua_version).into_result()?;
+        Ok(Self::from_ast(source, lua_version, &ast))
+    }
+
+    // The Ast must have been parsed from the source without any errors
+    fn from_ast(source: &'src str, lua_version: LuaVersion, ast: &ast::Ast) -> Self {
+        let mut builder = Builder::default();
+        ast.nodes().visit(&mut builder);
+        ast.eof().visit(&mut builder);
+
+        Self::new(source, lua_version, builder)
+    }
+
+    fn new(source: &'src str, lua_version: LuaVersion, builder: Builder) -> Self {
+        let line_starts = std::iter::once(0)
+            .chain(
+                source
+                    .match_indices('\n')
+                    .map(|(index, _)| index as u32 + 1),
+            )
+            .collect();
+
+        Self {
+            source,
+            lua_version,
+            tokens: builder.tokens,
+            nodes: builder.nodes,
+            line_starts,
+        }
+    }
+
+    /// The source code the Ast was parsed from
+    pub fn source(&self) -> &'src str {
+        self.source
+    }
+
+    /// The Lua version the Ast was parsed with
+    pub fn lua_version(&self) -> LuaVersion {
+        self.lua_version
+    }
+
+    /// The block of the entire code
+    pub fn root(&self) -> NodeRef<'_, 'src> {
+        self.node(0)
+    }
+
+    /// Every node, in the order they appear in the code, with parents before their children
+    pub fn nodes(&self) -> impl Iterator<Item = NodeRef<'_, 'src>> {
+        (0..self.nodes.len() as u32).map(|index| self.node(index))
+    }
+
+    /// Every token, not including the end of file
+    pub fn tokens(&self) -> impl Iterator<Item = TokenRef<'_, 'src>> {
+        (0..self.tokens.len() as u32 - 1).map(|index| self.token(index))
+    }
+
+    /// The end of file token, whose leading trivia is everything after the last token
+    pub fn eof(&self) -> TokenRef<'_, 'src> {
+        self.token(self.tokens.len() as u32 - 1)
+    }
+
+    /// Visits every node and token in the order they appear in the code
+    pub fn visit<V: Visitor<'src>>(&self, visitor: &mut V) {
+        // Nodes are stored in preorder, so visiting is a walk over them, rather than recursive
+        let mut open_nodes: Vec<NodeRef<'_, 'src>> = Vec::new();
+        let mut next_token = 0;
+
+        let mut visit_tokens_until = |visitor: &mut V, token_end: u32| {
+            while next_token < token_end {
+                visitor.visit_token(self.token(next_token));
+                next_token += 1;
+            }
+        };
+
+        for node in self.nodes() {
+            while let Some(open_node) = open_nodes.last() {
+                if open_node.data().subtree_end > node.index {
+                    break;
+                }
+
+                visit_tokens_until(visitor, open_node.data().token_end);
+                visitor.visit_node_end(*open_node);
+                open_nodes.pop();
+            }
+
+            visit_tokens_until(visitor, node.data().first_token);
+            visitor.visit_node(node);
+            open_nodes.push(node);
+        }
+
+        while let Some(open_node) = open_nodes.pop() {
+            visit_tokens_until(visitor, open_node.data().token_end);
+            visitor.visit_node_end(open_node);
+        }
+
+        visit_tokens_until(visitor, self.tokens.len() as u32);
+    }
+
+    /// Creates the owned Ast, with typed nodes that can be edited.
+    /// This parses all of the source code again, which will always succeed.
+    pub fn to_owned_ast(&self) -> ast::Ast {
+        crate::parse_fallible(self.source, self.lua_version).into_ast()
+    }
+
+    fn node(&self, index: u32) -> NodeRef<'_, 'src> {
+        NodeRef { ast: self, index }
+    }
+
+    fn token(&self, index: u32) -> TokenRef<'_, 'src> {
+        TokenRef { ast: self, index }
+    }
+
+    fn text(&self, start: u32, end: u32) -> &'src str {
+        &self.source[start as usize..end as usize]
+    }
+
+    fn position(&self, bytes: u32) -> Position {
+        let line = self.line_starts.partition_point(|&start| start <= bytes);
+        let line_start = self.line_starts[line - 1];
+
+        Position {
+            bytes: bytes as usize,
+            line,
+            character: self.text(line_start, bytes).chars().count() + 1,
+        }
+    }
+}
+
+impl fmt::Display for Ast<'_> {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str(self.source)
+    }
+}
+
+/// An untyped node in a borrowed [`Ast`], which has the [`SyntaxKind`] of the node it was made
+/// from
+#[derive(Clone, Copy)]
+pub struct NodeRef<'a, 'src> {
+    ast: &'a Ast<'src>,
+    index: u32,
+}
+
+impl<'a, 'src> NodeRef<'a, 'src> {
+    fn data(&self) -> &'a NodeData {
+        &self.ast.nodes[self.index as usize]
+    }
+
+    /// The kind of node this was made from
+    pub fn kind(&self) -> SyntaxKind {
+        self.data().kind
+    }
+
+    /// The node this is directly inside of, which is `None` for the [root](Ast::root)
+    pub fn parent(&self) -> Option<NodeRef<'a, 'src>> {
+        Some(self.ast.node(self.data().parent?))
+    }
+
+    /// The nodes directly inside of this one, in the order they appear in the code
+    pub fn children(&self) -> impl Iterator<Item = NodeRef<'a, 'src>> {
+        let ast = self.ast;
+        let subtree_end = self.data().subtree_end;
+        let mut next = self.index + 1;
+
+        std::iter::from_fn(move || {
+            if next >= subtree_end {
+                return None;
+            }
+
+            let child = ast.node(next);
+            next = child.data().subtree_end;
+            Some(child)
+        })
+    }
+
+    /// The nodes inside of this one at any depth, with parents before their children
+    pub fn descendants(&self) -> impl Iterator<Item = NodeRef<'a, 'src>> {
+        let ast = self.ast;
+        (self.index + 1..self.data().subtree_end).map(move |index| ast.node(index))
+    }
+
+    /// The tokens in the node, in the order they appear in the code
+    pub fn tokens(&self) -> impl Iterator<Item = TokenRef<'a, 'src>> {
+        let ast = self.ast;
+        (self.data().first_token..self.data().token_end).map(move |index| ast.token(index))
+    }
+
+    /// The byte range of the node, from the start of its first token to the end of its last,
+    /// or `None` if it has no tokens, such as an empty block
+    pub fn byte_range(&self) -> Option<Range<usize>> {
+        let data = self.data();
+
+        if data.first_token == data.token_end {
+            return None;
+        }
+
+        let start = self.ast.tokens[data.first_token as usize].start;
+        let end = self.ast.tokens[data.token_end as usize - 1].end;
+        Some(start as usize..end as usize)
+    }
+
+    /// The start and end position of the node, not including the trivia around it,
+    /// or `None` if it has no tokens
+    pub fn range(&self) -> Option<(Position, Position)> {
+        let range = self.byte_range()?;
+
+        Some((
+            self.ast.position(range.start as u32),
+            self.ast.position(range.end as u32),
+        ))
+    }
+
+    /// The code of the node, not including the trivia around it
+    pub fn text(&self) -> &'src str {
+        match self.byte_range() {
+            Some(range) => &self.ast.source[range],
+            None => "",
+        }
+    }
+}
+
+impl fmt::Debug for NodeRef<'_, '_> {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "{:?}({:?})", self.kind(), self.text())
+    }
+}
+
+impl PartialEq for NodeRef<'_, '_> {
+    fn eq(&self, other: &Self) -> bool {
+        std::ptr::eq(self.ast, other.ast) && self.index == other.index
+    }
+}
+
+impl Eq for NodeRef<'_, '_> {}
+
+/// A token in a borrowed [`Ast`], along with its trivia
+#[derive(Clone, Copy)]
+pub struct TokenRef<'a, 'src> {
+    ast: &'a Ast<'src>,
+    index: u32,
+}
+
+impl<'a, 'src> TokenRef<'a, 'src> {
+    fn data(&self) -> &'a TokenData {
+        &self.ast.tokens[self.index as usize]
+    }
+
+    /// The kind of the token
+    pub fn kind(&self) -> TokenKind {
+        self.data().kind
+    }
+
+    /// The symbol, if the token is one
+    pub fn symbol(&self) -> Option<Symbol> {
+        self.data().symbol
+    }
+
+    /// The code of the token
+    pub fn text(&self) -> &'src str {
+        self.ast.text(self.data().start, self.data().end)
+    }
+
+    /// The trivia before the token, such as whitespace and comments
+    pub fn leading_trivia(&self) -> &'src str {
+        self.ast.text(self.data().full_start, self.data().start)
+    }
+
+    /// The trivia after the token, up to and including the end of its line
+    pub fn trailing_trivia(&self) -> &'src str {
+        let end = match self.ast.tokens.get(self.index as usize + 1) {
+            Some(next) => next.full_start,
+            None => self.data().end,
+        };
+
+        self.ast.text(self.data().end, end)
+    }
+
+    /// The byte range of the token, not including its trivia
+    pub fn byte_range(&self) -> Range<usize> {
+        self.data().start as usize..self.data().end as usize
+    }
+
+    /// The position the token starts at
+    pub fn start_position(&self) -> Position {
+        self.ast.position(self.data().start)
+    }
+
+    /// The position the token ends at
+    pub fn end_position(&self) -> Position {
+        self.ast.position(self.data().end)
+    }
+}
+
+impl fmt::Debug for TokenRef<'_, '_> {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "{:?}({:?})", self.kind(), self.text())
+    }
+}
+
+impl fmt::Display for TokenRef<'_, '_> {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            formatter,
+            "{}{}{}",
+            self.leading_trivia(),
+            self.text(),
+            self.trailing_trivia()
+        )
+    }
+}
+
+/// A visitor over a borrowed [`Ast`], used with [`Ast::visit`].
+/// Unlike [`crate::visitors::Visitor`], which has a method for every type of node, nodes are untyped
+/// and passed to the same method, so tell them apart with [`NodeRef::kind`].
+pub trait Visitor<'src> {
+    /// Called when entering a node, before any of its tokens
+    fn visit_node(&mut self, _node: NodeRef<'_, 'src>) {}
+
+    /// Called when leaving a node, after all of its tokens
+    fn visit_node_end(&mut self, _node: NodeRef<'_, 'src>) {}
+
+    /// Called for every token, including the end of file
+    fn visit_token(&mut self, _token: TokenRef<'_, 'src>) {}
+}
+
+#[derive(Default)]
+struct Builder {
+    tokens: Vec<TokenData>,
+    nodes: Vec<NodeData>,
+    // The nodes being built
+    stack: Vec<u32>,
+    // Whether the last token was visited for the first time, but not yet for the second
+    in_leading_trivia: bool,
+}
+
+impl Builder {
+    // Parses the code one top level statement at a time, building each before parsing the next.
+    // Returns false if the code has any errors, which are left to be found by the usual parser.
+    fn parse(&mut self, source: &str, lua_version: LuaVersion) -> bool {
+        let mut state = ParserState::new(Lexer::new(source, lua_version));
+        self.enter(SyntaxKind::Block);
+
+        loop {
+            match parse_stmt(&mut state) {
+                ParserResult::Value(StmtVariant::Stmt(stmt)) => {
+                    let semicolon = state.consume_if(Symbol::Semicolon);
+                    stmt.visit(self);
+                    semicolon.visit(self);
+                }
+
+                ParserResult::Value(StmtVariant::LastStmt(last_stmt)) => {
+                    let semicolon = state.consume_if(Symbol::Semicolon);
+                    last_stmt.visit(self);
+                    semicolon.visit(self);
+                    break;
+                }
+
+                ParserResult::NotFound => {
+                    if let ParserResult::Value(last_stmt) = parse_last_stmt(&mut state) {
+                        last_stmt.visit(self);
+                    }
+
+                    break;
+                }
+
+                ParserResult::LexerMoved => return false,
+            }
+
+            if !state.errors.is_empty() {
+                return false;
+            }
+        }
+
+        self.exit();
+
+        match state.consume() {
+            ParserResult::Value(eof)
+                if eof.token_kind() == TokenKind::Eof && state.errors.is_empty() =>
+            {
+                eof.visit(self);
+                true
+            }
+
+            _ => false,
+        }
+    }
+
+    fn enter(&mut self, kind: SyntaxKind) {
+        self.stack.push(self.nodes.len() as u32);
+        self.nodes.push(NodeData {
+            kind,
+            parent: self.stack.iter().rev().nth(1).copied(),
+            first_token: self.tokens.len() as u32,
+            token_end: 0,
+            subtree_end: 0,
+        });
+    }
+
+    fn exit(&mut self) {
+        let index = self.stack.pop().expect("exited more nodes than entered");
+        let (token_end, subtree_end) = (self.tokens.len() as u32, self.nodes.len() as u32);
+        let node = &mut self.nodes[index as usize];
+        node.token_end = token_end;
+        node.subtree_end = subtree_end;
+    }
+
+    // A TokenReference visits its token, then its leading trivia, then its token again, and then
+    // its trailing trivia. Only the leading trivia needs to be kept track of, as the trailing trivia
+    // is everything up until the next leading trivia.
+    fn token(&mut self, token: &Token) {
+        let start = token.start_position().bytes() as u32;
+
+        if token.token_type().is_trivia() {
+            if self.in_leading_trivia {
+                let last_token = self.tokens.last_mut().unwrap();
+                last_token.full_start = last_token.full_start.min(start);
+            }
+
+            return;
+        }
+
+        if self.in_leading_trivia {
+            self.in_leading_trivia = false;
+            return;
+        }
+
+        self.tokens.push(TokenData {
+            full_start: start,
+            start,
+            end: token.end_position().bytes() as u32,
+            kind: token.token_kind(),
+            symbol: match token.token_type() {
+                TokenType::Symbol { symbol } => Some(*symbol),
+                _ => None,
+            },
+        });
+
+        self.in_leading_trivia = true;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    #[test]
+    fn test_nodes() {
+        let source = "local function f(a, b)\n\treturn a + b\nend\n\nf(1, 2) -- call\n";
+        let ast = Ast::parse(source, LuaVersion::new()).unwrap();
+
+        let root = ast.root();
+        assert_eq!(root.kind(), SyntaxKind::Block);
+        assert_eq!(root.parent(), None);
+        assert_eq!(root.children().count(), 2);
+        assert_eq!(root.text(), source.trim_end().trim_end_matches(" -- call"));
+
+        let function_body = ast
+            .nodes()
+            .find(|node| node.kind() == SyntaxKind::FunctionBody)
+            .unwrap();
+
+        assert_eq!(function_body.text(), "(a, b)\n\treturn a + b\nend");
+        assert_eq!(
+            function_body.parent().unwrap().kind(),
+            SyntaxKind::LocalFunction
+        );
+
+        let range = function_body.range().unwrap();
+        assert_eq!((range.0.line(), range.0.character()), (1, 17));
+        assert_eq!((range.1.line(), range.1.character()), (3, 4));
+
+        let last_token = ast.tokens().last().unwrap();
+        assert_eq!(last_token.text(), ")");
+        assert_eq!(last_token.trailing_trivia(), " -- call\n");
+        assert_eq!(ast.eof().leading_trivia(), "");
+    }
+
+    #[test]
+    fn test_visit() {
+        #[derive(Default)]
+        struct Printer {
+            output: String,
+            depth: usize,
+            max_depth: usize,
+        }
+
+        impl Visitor<'_> for Printer {
+            fn visit_node(&mut self, _node: NodeRef) {
+                self.depth += 1;
+                self.max_depth = self.max_depth.max(self.depth);
+            }
+
+            fn visit_node_end(&mut self, _node: NodeRef) {
+                self.depth -= 1;
+            }
+
+            fn visit_token(&mut self, token: TokenRef) {
+                self.output.push_str(&token.to_string());
+            }
+        }
+
+        let source = "--!strict\nlocal x = { a = (1 + 2) * 3 }\nreturn x\n-- end\n";
+        let ast = Ast::parse(source, LuaVersion::new()).unwrap();
+
+        let mut printer = Printer::default();
+        ast.visit(&mut printer);
+
+        assert_eq!(printer.output, source);
+        assert_eq!(printer.depth, 0);
+        assert!(printer.max_depth > 5);
+        assert_eq!(ast.to_owned_ast().to_string(), source);
+    }
+
+    #[test]
+    fn test_parse_one_statement_at_a_time() {
+        let cases = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/cases/pass");
+
+        for entry in std::fs::read_dir(cases).unwrap() {
+            let source = std::fs::read_to_string(entry.unwrap().path().join("source.lua")).unwrap();
+
+            let Ok(owned_ast) = crate::parse_fallible(&source, LuaVersion::new()).into_result()
+            else {
+                continue;
+            };
+
+            let ast = Ast::parse(&source, LuaVersion::new()).unwrap();
+            let from_owned_ast = Ast::from_ast(&source, LuaVersion::new(), &owned_ast);
+
+            assert_eq!(
+                format!("{:?}", ast.nodes),
+                format!("{:?}", from_owned_ast.nodes)
+            );
+            assert_eq!(
+                format!("{:?}", ast.tokens),
+                format!("{:?}", from_owned_ast.tokens)
+            );
+        }
+
+        let errors = Ast::parse("local x = 1\nlocal = 2\nend", LuaVersion::new()).unwrap_err();
+        assert_eq!(errors.len(), 2);
+    }
+}
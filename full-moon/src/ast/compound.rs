@@ -1,7 +1,7 @@
 use crate::ast::{Expression, Var};
 use crate::tokenizer::{Symbol, TokenReference};
 use derive_more::Display;
-use full_moon_derive::{Node, Visit};
+use full_moon_derive::{MutAccessors, Node, Visit};
 use serde::{Deserialize, Serialize};
 
 #[derive(Clone, Debug, Display, PartialEq, Eq, Node, Visit)]
@@ -105,7 +105,7 @@ impl CompoundOp {
 }
 
 /// A Compound Assignment statement, such as `x += 1` or `x -= 1`
-#[derive(Clone, Debug, Display, PartialEq, Node, Visit)]
+#[derive(Clone, Debug, Display, PartialEq, MutAccessors, Node, Visit)]
 #[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 #[display("{lhs}{compound_operator}{rhs}")]
 pub struct CompoundAssignment {
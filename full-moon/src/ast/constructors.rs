@@ -0,0 +1,689 @@
+//! Constructors for building nodes from scratch that read like hand-written Lua, such as
+//! `Stmt::local(["x"], [Expression::number(1.0)])` printing as `local x = 1`.
+//!
+//! Every node built here has no whitespace before its first token or after its last, with the
+//! spacing in between given to the tokens it creates. Nodes given to these constructors are kept
+//! as they are, apart from blocks, which are indented when nested inside of another statement.
+use super::{
+    punctuated::{Pair, Punctuated},
+    span::ContainedSpan,
+    template::{parenthesize, with_trivia},
+    Assignment, BinOp, Block, Call, Do, Expression, FunctionArgs, FunctionBody, FunctionCall,
+    FunctionDeclaration, FunctionName, GenericFor, If, Index, LastStmt, LocalAssignment,
+    LocalFunction, NumericFor, Parameter, Prefix, Return, Stmt, Suffix, UnOp, Var, VarExpression,
+    While,
+};
+use crate::{
+    node::Node,
+    tokenizer::{StringLiteralQuoteType, Symbol, Token, TokenReference, TokenType},
+    visitors::{VisitMut, VisitorMut},
+};
+use std::fmt::{self, Write};
+
+impl Block {
+    /// Creates a Block from the given statements, putting each one on its own line.
+    /// A semicolon is added wherever the next statement would otherwise continue the one before
+    /// it, such as `f()` followed by `(g or h)()`.
+    ///
+    /// ```rust
+    /// # use full_moon::ast::{Block, Expression, Stmt};
+    /// let block = Block::from_stmts([
+    ///     Stmt::local(["x"], [Expression::number(1.0)]),
+    ///     Stmt::if_then(
+    ///         Expression::name("x"),
+    ///         Block::from_stmts([Stmt::call(Expression::name("print"), [Expression::name("x")])]),
+    ///     ),
+    /// ]);
+    ///
+    /// assert_eq!(block.to_string(), "local x = 1\nif x then\n\tprint(x)\nend");
+    /// ```
+    pub fn from_stmts(stmts: impl IntoIterator<Item = Stmt>) -> Self {
+        block(stmts, None)
+    }
+
+    /// Creates a Block from the given statements followed by the given last statement, such as a
+    /// `return`, each on its own line. See [`Block::from_stmts`].
+    pub fn from_stmts_and_last_stmt(
+        stmts: impl IntoIterator<Item = Stmt>,
+        last_stmt: LastStmt,
+    ) -> Self {
+        block(stmts, Some(last_stmt))
+    }
+}
+
+impl LastStmt {
+    /// Creates a `return` of the given values, such as `return x, y`
+    pub fn returns(returns: impl IntoIterator<Item = Expression>) -> Self {
+        let returns = comma_separated(returns);
+        let token = if returns.is_empty() {
+            "return"
+        } else {
+            "return "
+        };
+
+        LastStmt::Return(
+            Return::new()
+                .with_token(symbol(token))
+                .with_returns(returns),
+        )
+    }
+}
+
+impl Stmt {
+    /// Creates a local assignment, such as `local x, y = 1, 2`.
+    /// When no expressions are given, the names are declared without being assigned, such as `local x`.
+    pub fn local(
+        names: impl IntoIterator<Item = impl AsRef<str>>,
+        expressions: impl IntoIterator<Item = Expression>,
+    ) -> Self {
+        let names = names_list(names);
+        let expressions = comma_separated(expressions);
+        let equal_token = (!expressions.is_empty()).then(|| symbol(" = "));
+
+        // Like the parser, every name is given no type or attribute, rather than leaving them out
+        #[cfg(feature = "luau")]
+        let type_specifiers = vec![None; names.len()];
+        #[cfg(any(feature = "lua54", feature = "cfxlua"))]
+        let attributes = vec![None; names.len()];
+
+        let local_assignment = LocalAssignment::new(names)
+            .with_local_token(symbol("local "))
+            .with_equal_token(equal_token)
+            .with_expressions(expressions);
+
+        #[cfg(feature = "luau")]
+        let local_assignment = local_assignment.with_type_specifiers(type_specifiers);
+        #[cfg(any(feature = "lua54", feature = "cfxlua"))]
+        let local_assignment = local_assignment.with_attributes(attributes);
+
+        Stmt::LocalAssignment(local_assignment)
+    }
+
+    /// Creates an assignment, such as `x, y = 1, 2`
+    pub fn assign(
+        variables: impl IntoIterator<Item = Var>,
+        expressions: impl IntoIterator<Item = Expression>,
+    ) -> Self {
+        Stmt::Assignment(
+            Assignment::new(comma_separated(variables), comma_separated(expressions))
+                .with_equal_token(symbol(" = ")),
+        )
+    }
+
+    /// Creates a function call statement, such as `print(x)`. See [`Expression::call`].
+    pub fn call(function: Expression, arguments: impl IntoIterator<Item = Expression>) -> Self {
+        Stmt::FunctionCall(function_call(function, arguments))
+    }
+
+    /// Creates a `do` block
+    pub fn do_block(block: Block) -> Self {
+        Stmt::Do(
+            Do::new()
+                .with_do_token(opening("do", &block))
+                .with_block(indent(block))
+                .with_end_token(symbol("end")),
+        )
+    }
+
+    /// Creates an if statement with no else, such as `if x then ... end`
+    pub fn if_then(condition: Expression, block: Block) -> Self {
+        Stmt::If(if_then(condition, block).with_end_token(symbol("end")))
+    }
+
+    /// Creates an if statement with an else, such as `if x then ... else ... end`
+    pub fn if_then_else(condition: Expression, block: Block, else_block: Block) -> Self {
+        Stmt::If(
+            if_then(condition, block)
+                .with_else_token(Some(opening("else", &else_block)))
+                .with_else(Some(indent(else_block)))
+                .with_end_token(symbol("end")),
+        )
+    }
+
+    /// Creates a while loop, such as `while x do ... end`
+    pub fn while_loop(condition: Expression, block: Block) -> Self {
+        Stmt::While(
+            While::new(condition)
+                .with_while_token(symbol("while "))
+                .with_do_token(opening(" do", &block))
+                .with_block(indent(block))
+                .with_end_token(symbol("end")),
+        )
+    }
+
+    /// Creates a numeric for loop, such as `for i = 1, 10 do ... end`
+    pub fn numeric_for(index: &str, start: Expression, end: Expression, block: Block) -> Self {
+        Stmt::NumericFor(
+            NumericFor::new(identifier(index), start, end)
+                .with_for_token(symbol("for "))
+                .with_equal_token(symbol(" = "))
+                .with_start_end_comma(symbol(", "))
+                .with_do_token(opening(" do", &block))
+                .with_block(indent(block))
+                .with_end_token(symbol("end")),
+        )
+    }
+
+    /// Creates a generic for loop, such as `for key, value in pairs(t) do ... end`
+    pub fn generic_for(
+        names: impl IntoIterator<Item = impl AsRef<str>>,
+        expressions: impl IntoIterator<Item = Expression>,
+        block: Block,
+    ) -> Self {
+        let names = names_list(names);
+
+        #[cfg(feature = "luau")]
+        let type_specifiers = vec![None; names.len()];
+
+        let generic_for = GenericFor::new(names, comma_separated(expressions))
+            .with_for_token(symbol("for "))
+            .with_in_token(symbol(" in "))
+            .with_do_token(opening(" do", &block))
+            .with_block(indent(block))
+            .with_end_token(symbol("end"));
+
+        #[cfg(feature = "luau")]
+        let generic_for = generic_for.with_type_specifiers(type_specifiers);
+
+        Stmt::GenericFor(generic_for)
+    }
+
+    /// Creates a function declaration, such as `function M.new(x) ... end`.
+    /// The name can be made of several names split by `.`, and can end with a method such as `M:update`.
+    pub fn function(
+        name: &str,
+        parameters: impl IntoIterator<Item = impl AsRef<str>>,
+        block: Block,
+    ) -> Self {
+        let (names, method) = match name.rsplit_once(':') {
+            Some((names, method)) => (names, Some((symbol(":"), identifier(method)))),
+            None => (name, None),
+        };
+
+        let names = punctuated(names.split('.').map(identifier), ".");
+
+        Stmt::FunctionDeclaration(
+            FunctionDeclaration::new(FunctionName::new(names).with_method(method))
+                .with_function_token(symbol("function "))
+                .with_body(function_body(parameters, block)),
+        )
+    }
+
+    /// Creates a local function, such as `local function f(x) ... end`
+    pub fn local_function(
+        name: &str,
+        parameters: impl IntoIterator<Item = impl AsRef<str>>,
+        block: Block,
+    ) -> Self {
+        Stmt::LocalFunction(
+            LocalFunction::new(identifier(name))
+                .with_local_token(symbol("local "))
+                .with_function_token(symbol("function "))
+                .with_body(function_body(parameters, block)),
+        )
+    }
+}
+
+impl Expression {
+    /// Creates `nil`
+    pub fn nil() -> Self {
+        Expression::Symbol(symbol("nil"))
+    }
+
+    /// Creates `true` or `false`
+    pub fn boolean(value: bool) -> Self {
+        Expression::Symbol(symbol(if value { "true" } else { "false" }))
+    }
+
+    /// Creates a number, such as `1` or `0.5`. Negative numbers are negated with `-`, and numbers
+    /// that have no literal are divided out, such as `1 / 0` for infinity.
+    pub fn number(value: f64) -> Self {
+        if value.is_nan() {
+            return divide(Expression::number(0.0), Expression::number(0.0));
+        }
+
+        if value.is_sign_negative() {
+            if value.is_infinite() {
+                return divide(Expression::number(-1.0), Expression::number(0.0));
+            }
+
+            return Expression::UnaryOperator {
+                unop: UnOp::Minus(symbol("-")),
+                expression: Box::new(Expression::number(-value)),
+            };
+        }
+
+        if value.is_infinite() {
+            return divide(Expression::number(1.0), Expression::number(0.0));
+        }
+
+        // Integers are written without a fraction, other numbers as short as they can be written
+        let text = if value.fract() == 0.0 && value < 1e15 {
+            format!("{value}")
+        } else {
+            format!("{value:?}")
+        };
+
+        Expression::Number(token(TokenType::Number { text: text.into() }))
+    }
+
+    /// Creates a string, such as `"hello"`, escaping any characters that need it
+    pub fn string(value: &str) -> Self {
+        let mut literal = String::with_capacity(value.len());
+
+        for character in value.chars() {
+            match character {
+                '\\' => literal.push_str("\\\\"),
+                '"' => literal.push_str("\\\""),
+                '\n' => literal.push_str("\\n"),
+                '\r' => literal.push_str("\\r"),
+                '\t' => literal.push_str("\\t"),
+
+                // Always three digits, so that a digit after it isn't read as part of it
+                character if character.is_ascii_control() => {
+                    write!(literal, "\\{:03}", character as u32).unwrap()
+                }
+
+                character => literal.push(character),
+            }
+        }
+
+        Expression::String(token(TokenType::StringLiteral {
+            literal: literal.into(),
+            multi_line_depth: 0,
+            quote_type: StringLiteralQuoteType::Double,
+        }))
+    }
+
+    /// Creates a name, such as `x`
+    pub fn name(name: &str) -> Self {
+        Expression::Var(Var::name(name))
+    }
+
+    /// Creates an index by name, such as `string.format` from `string` and `format`
+    pub fn field(expression: Expression, name: &str) -> Self {
+        let (prefix, mut suffixes) = prefix_and_suffixes(expression);
+
+        suffixes.push(Suffix::Index(Index::Dot {
+            dot: symbol("."),
+            name: identifier(name),
+        }));
+
+        Expression::Var(Var::Expression(Box::new(
+            VarExpression::new(prefix).with_suffixes(suffixes),
+        )))
+    }
+
+    /// Creates a function call, such as `print(x)`.
+    /// Functions that can't be called as they are, such as `f or g`, are put in parentheses.
+    pub fn call(function: Expression, arguments: impl IntoIterator<Item = Expression>) -> Self {
+        Expression::FunctionCall(function_call(function, arguments))
+    }
+
+    /// Creates an anonymous function, such as `function(x) ... end`.
+    /// A parameter of `...` takes any number of arguments.
+    pub fn function(parameters: impl IntoIterator<Item = impl AsRef<str>>, block: Block) -> Self {
+        Expression::Function(Box::new((
+            symbol("function"),
+            function_body(parameters, block),
+        )))
+    }
+}
+
+impl Var {
+    /// Creates a name, such as `x`
+    pub fn name(name: &str) -> Self {
+        Var::Name(identifier(name))
+    }
+}
+
+// Whether the statement would continue the one before it without a semicolon between them, such as
+// `x = y (z)()` calling `y`, or `x = y ::label::` being a type assertion
+pub(crate) fn needs_semicolon_before(stmt: &Stmt) -> bool {
+    match stmt.tokens().next().map(|token| token.token_type()) {
+        Some(TokenType::Symbol {
+            symbol: Symbol::LeftParen,
+        }) => true,
+
+        #[cfg(any(feature = "lua52", feature = "luajit"))]
+        Some(TokenType::Symbol {
+            symbol: Symbol::TwoColons,
+        }) => true,
+
+        _ => false,
+    }
+}
+
+fn token(token_type: TokenType) -> TokenReference {
+    TokenReference::new(Vec::new(), Token::new(token_type), Vec::new())
+}
+
+// A symbol, with any whitespace around it in the text kept as trivia
+fn symbol(text: &str) -> TokenReference {
+    TokenReference::basic_symbol(text)
+}
+
+fn identifier(name: &str) -> TokenReference {
+    token(TokenType::Identifier {
+        identifier: name.into(),
+    })
+}
+
+fn whitespace(characters: &str) -> Token {
+    Token::new(TokenType::Whitespace {
+        characters: characters.into(),
+    })
+}
+
+fn punctuated<T>(values: impl IntoIterator<Item = T>, punctuation: &str) -> Punctuated<T> {
+    let mut values = values.into_iter().peekable();
+    let mut punctuated = Punctuated::new();
+
+    while let Some(value) = values.next() {
+        match values.peek() {
+            Some(_) => punctuated.push(Pair::Punctuated(value, symbol(punctuation))),
+            None => punctuated.push(Pair::End(value)),
+        }
+    }
+
+    punctuated
+}
+
+fn comma_separated<T>(values: impl IntoIterator<Item = T>) -> Punctuated<T> {
+    punctuated(values, ", ")
+}
+
+fn names_list(names: impl IntoIterator<Item = impl AsRef<str>>) -> Punctuated<TokenReference> {
+    comma_separated(names.into_iter().map(|name| identifier(name.as_ref())))
+}
+
+fn divide(lhs: Expression, rhs: Expression) -> Expression {
+    Expression::BinaryOperator {
+        lhs: Box::new(lhs),
+        binop: BinOp::Slash(symbol(" / ")),
+        rhs: Box::new(rhs),
+    }
+}
+
+// Splits an expression into what can be called or indexed, putting it in parentheses if it can't be
+fn prefix_and_suffixes(expression: Expression) -> (Prefix, Vec<Suffix>) {
+    match &expression {
+        Expression::Var(Var::Name(name)) => (Prefix::Name(name.clone()), Vec::new()),
+
+        Expression::Var(Var::Expression(var_expression)) => (
+            var_expression.prefix().clone(),
+            var_expression.suffixes().cloned().collect(),
+        ),
+
+        Expression::FunctionCall(function_call) => (
+            function_call.prefix().clone(),
+            function_call.suffixes().cloned().collect(),
+        ),
+
+        Expression::Parentheses { .. } => (Prefix::Expression(Box::new(expression)), Vec::new()),
+
+        _ => (
+            Prefix::Expression(Box::new(parenthesize(expression))),
+            Vec::new(),
+        ),
+    }
+}
+
+fn function_call(
+    function: Expression,
+    arguments: impl IntoIterator<Item = Expression>,
+) -> FunctionCall {
+    let (prefix, mut suffixes) = prefix_and_suffixes(function);
+
+    suffixes.push(Suffix::Call(Call::AnonymousCall(
+        FunctionArgs::Parentheses {
+            parentheses: ContainedSpan::new(symbol("("), symbol(")")),
+            arguments: comma_separated(arguments),
+        },
+    )));
+
+    FunctionCall::new(prefix).with_suffixes(suffixes)
+}
+
+fn function_body(
+    parameters: impl IntoIterator<Item = impl AsRef<str>>,
+    block: Block,
+) -> FunctionBody {
+    let parameters = comma_separated(parameters.into_iter().map(
+        |parameter| match parameter.as_ref() {
+            "..." => Parameter::Ellipsis(symbol("...")),
+            name => Parameter::Name(identifier(name)),
+        },
+    ));
+
+    #[cfg(feature = "luau")]
+    let type_specifiers = vec![None; parameters.len()];
+
+    let function_body = FunctionBody::new()
+        .with_parameters_parentheses(ContainedSpan::new(symbol("("), opening(")", &block)))
+        .with_parameters(parameters)
+        .with_block(indent(block))
+        .with_end_token(symbol("end"));
+
+    #[cfg(feature = "luau")]
+    let function_body = function_body.with_type_specifiers(type_specifiers);
+
+    function_body
+}
+
+fn if_then(condition: Expression, block: Block) -> If {
+    If::new(condition)
+        .with_if_token(symbol("if "))
+        .with_then_token(opening(" then", &block))
+        .with_block(indent(block))
+}
+
+fn is_empty(block: &Block) -> bool {
+    block.stmts().next().is_none() && block.last_stmt().is_none()
+}
+
+// The token before a block, such as `do`. An empty block is kept on the same line, as in `do end`.
+fn opening(text: &str, block: &Block) -> TokenReference {
+    if is_empty(block) {
+        symbol(&format!("{text} "))
+    } else {
+        symbol(text)
+    }
+}
+
+fn on_new_line<T: Node + VisitMut + fmt::Display>(node: T) -> T {
+    with_trivia(node, (vec![whitespace("\n")], Vec::new()))
+}
+
+fn block(stmts: impl IntoIterator<Item = Stmt>, last_stmt: Option<LastStmt>) -> Block {
+    let mut formatted_stmts: Vec<(Stmt, Option<TokenReference>)> = Vec::new();
+
+    for stmt in stmts {
+        let Some((_, semicolon)) = formatted_stmts.last_mut() else {
+            formatted_stmts.push((stmt, None));
+            continue;
+        };
+
+        if needs_semicolon_before(&stmt) {
+            *semicolon = Some(symbol(";"));
+        }
+
+        formatted_stmts.push((on_new_line(stmt), None));
+    }
+
+    let last_stmt = last_stmt.map(|last_stmt| {
+        if formatted_stmts.is_empty() {
+            (last_stmt, None)
+        } else {
+            (on_new_line(last_stmt), None)
+        }
+    });
+
+    Block::new()
+        .with_stmts(formatted_stmts)
+        .with_last_stmt(last_stmt)
+}
+
+struct Indenter;
+
+impl VisitorMut for Indenter {
+    fn visit_token_reference(&mut self, mut token: TokenReference) -> TokenReference {
+        for trivia in token
+            .leading_trivia
+            .iter_mut()
+            .chain(token.trailing_trivia.iter_mut())
+        {
+            if let TokenType::Whitespace { characters } = trivia.token_type() {
+                if characters.contains('\n') {
+                    *trivia = whitespace(&characters.replace('\n', "\n\t"));
+                }
+            }
+        }
+
+        token
+    }
+}
+
+// Indents every line of a block, and puts it on its own lines between the tokens around it
+fn indent(block: Block) -> Block {
+    if is_empty(&block) {
+        return block;
+    }
+
+    let block = block.visit_mut(&mut Indenter);
+    with_trivia(block, (vec![whitespace("\n\t")], vec![whitespace("\n")]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn assert_reparses(block: &Block) {
+        let code = block.to_string();
+        let ast = crate::parse(&code).unwrap();
+
+        assert!(ast.nodes().similar(block), "{code} parsed differently");
+    }
+
+    #[test]
+    fn test_constructors_format() {
+        let block = Block::from_stmts_and_last_stmt(
+            [
+                Stmt::local(
+                    ["M"],
+                    [Expression::call(Expression::name("setmetatable"), [])],
+                ),
+                Stmt::function(
+                    "M:update",
+                    ["dt", "..."],
+                    Block::from_stmts([
+                        Stmt::assign(
+                            [Var::name("x")],
+                            [Expression::number(-1.5), Expression::string("a\"b\n")],
+                        ),
+                        Stmt::numeric_for(
+                            "i",
+                            Expression::number(1.0),
+                            Expression::number(10.0),
+                            Block::from_stmts([Stmt::call(
+                                Expression::field(Expression::name("string"), "format"),
+                                [Expression::string("%d"), Expression::name("i")],
+                            )]),
+                        ),
+                    ]),
+                ),
+                Stmt::while_loop(Expression::boolean(true), Block::new()),
+                Stmt::local_function(
+                    "each",
+                    ["t"],
+                    Block::from_stmts([Stmt::generic_for(
+                        ["key", "value"],
+                        [Expression::call(
+                            Expression::name("pairs"),
+                            [Expression::name("t")],
+                        )],
+                        Block::from_stmts([Stmt::call(
+                            Expression::name("print"),
+                            [Expression::name("key"), Expression::name("value")],
+                        )]),
+                    )]),
+                ),
+                Stmt::if_then_else(
+                    Expression::name("x"),
+                    Block::from_stmts([Stmt::do_block(Block::from_stmts([Stmt::local(
+                        ["y"],
+                        [],
+                    )]))]),
+                    Block::from_stmts_and_last_stmt([], LastStmt::returns([])),
+                ),
+            ],
+            LastStmt::returns([Expression::name("M")]),
+        );
+
+        assert_eq!(
+            block.to_string(),
+            r#"local M = setmetatable()
+function M:update(dt, ...)
+	x = -1.5, "a\"b\n"
+	for i = 1, 10 do
+		string.format("%d", i)
+	end
+end
+while true do end
+local function each(t)
+	for key, value in pairs(t) do
+		print(key, value)
+	end
+end
+if x then
+	do
+		local y
+	end
+else
+	return
+end
+return M"#
+        );
+
+        assert_reparses(&block);
+    }
+
+    #[test]
+    fn test_constructors_semicolons() {
+        let block = Block::from_stmts([
+            Stmt::call(Expression::name("f"), []),
+            Stmt::call(
+                Expression::call(
+                    Expression::function(Vec::<&str>::new(), Block::new()),
+                    [Expression::nil()],
+                ),
+                [],
+            ),
+        ]);
+
+        assert_eq!(block.to_string(), "f();\n(function() end)(nil)()");
+        assert_reparses(&block);
+    }
+
+    #[test]
+    fn test_constructors_numbers() {
+        for (value, code) in [
+            (0.0, "0"),
+            (42.0, "42"),
+            (0.5, "0.5"),
+            (1e300, "1e300"),
+            (-3.0, "-3"),
+            (f64::INFINITY, "1 / 0"),
+            (f64::NEG_INFINITY, "-1 / 0"),
+            (f64::NAN, "0 / 0"),
+        ] {
+            assert_eq!(Expression::number(value).to_string(), code);
+        }
+
+        assert_eq!(
+            Expression::string("\0tab\t\\").to_string(),
+            r#""\000tab\t\\""#
+        );
+    }
+}
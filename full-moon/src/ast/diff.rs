@@ -0,0 +1,825 @@
+//! Comparing two [`Ast`]s by their structure, such as for code review, rather than by their text.
+//!
+//! [`diff`] gives the [`Edit`]s that turn the old Ast into the new one: nodes that were inserted,
+//! deleted, or moved, and tokens that were updated. Nodes are matched with [`Node::similar`], so a
+//! function that was moved is given as a move of the whole function, rather than every line of it
+//! being deleted and inserted. Changes to comments and whitespace alone are given as updates to
+//! the tokens they're around, unless they're ignored with [`DiffOptions::with_ignore_trivia`].
+//!
+//! ```rust
+//! # use full_moon::{ast::diff::{self, DiffOptions, Edit}, node::{Node, SyntaxKind}};
+//! # fn main() -> Result<(), Vec<full_moon::Error>> {
+//! let old = full_moon::parse("local function foo() end\nlocal function bar() end\nprint(1)")?;
+//! let new = full_moon::parse("local function bar() end\nlocal function foo() end\nprint(1, 2)")?;
+//!
+//! let edits = diff::diff(&old, &new, &DiffOptions::new());
+//!
+//! // `bar` was moved from the second line to the first
+//! let Edit::Move { old: moved_from, new: moved_to } = edits[0] else { panic!() };
+//! assert_eq!(moved_from.kind(), SyntaxKind::Stmt);
+//! assert_eq!(moved_from.start_position().unwrap().line(), 2);
+//! assert_eq!(moved_to.start_position().unwrap().line(), 1);
+//!
+//! // `2` was added to the call on the third line, along with the comma before it
+//! let inserted = edits[1..]
+//!     .iter()
+//!     .map(|edit| match edit {
+//!         Edit::Insert { node, parent, .. } => (node.kind(), parent.kind()),
+//!         _ => panic!(),
+//!     })
+//!     .collect::<Vec<_>>();
+//!
+//! assert_eq!(inserted, [
+//!     (SyntaxKind::TokenReference, SyntaxKind::FunctionArgs),
+//!     (SyntaxKind::Expression, SyntaxKind::FunctionArgs),
+//! ]);
+//! # Ok(())
+//! # }
+//! ```
+use std::{
+    cmp::Reverse,
+    collections::{HashMap, HashSet, VecDeque},
+    hash::{Hash, Hasher},
+};
+
+use super::Ast;
+use crate::{
+    node::{AnyNode, Node, StructuralHasher, SyntaxKind},
+    tokenizer::{Token, TokenKind, TokenReference},
+};
+
+/// Options for [`diff`], made with [`DiffOptions::new`] and changed with its `with_*` methods.
+#[derive(Clone, Debug, Default)]
+pub struct DiffOptions {
+    ignore_trivia: bool,
+}
+
+impl DiffOptions {
+    /// Creates options for a diff that includes changes to comments and whitespace
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Whether changes to comments and whitespace alone are left out. Off by default.
+    pub fn ignore_trivia(&self) -> bool {
+        self.ignore_trivia
+    }
+
+    /// Returns a new DiffOptions with changes to trivia ignored or not
+    pub fn with_ignore_trivia(self, ignore_trivia: bool) -> Self {
+        Self { ignore_trivia }
+    }
+}
+
+/// A change that turns the old Ast given to [`diff`] into the new one.
+/// Nodes from the old Ast are positioned in the old code, and nodes from the new Ast in the new code.
+#[derive(Clone, Copy, Debug)]
+#[non_exhaustive]
+pub enum Edit<'a> {
+    /// A node was added. Everything inside of it was added with it, other than nodes that were
+    /// moved into it, which are given as their own [`Edit::Move`]s.
+    Insert {
+        /// The added node, from the new Ast
+        node: AnyNode<'a>,
+        /// The node it was added to, from the new Ast
+        parent: AnyNode<'a>,
+        /// Where it was added in the [`children`](AnyNode::children) of its parent
+        index: usize,
+    },
+    /// A node was removed. Everything inside of it was removed with it, other than nodes that were
+    /// moved out of it, which are given as their own [`Edit::Move`]s.
+    Delete {
+        /// The removed node, from the old Ast
+        node: AnyNode<'a>,
+        /// The node it was removed from, from the old Ast
+        parent: AnyNode<'a>,
+    },
+    /// A token was changed, such as a name that was renamed, or a comment that was added before it
+    Update {
+        /// The token from the old Ast
+        old: &'a TokenReference,
+        /// The token from the new Ast
+        new: &'a TokenReference,
+    },
+    /// A node was moved into a different node, or to a different place in the same one, such as a
+    /// function that was moved below another. Changes inside of it are given as their own edits.
+    Move {
+        /// The node from the old Ast
+        old: AnyNode<'a>,
+        /// The node from the new Ast
+        new: AnyNode<'a>,
+    },
+}
+
+impl Edit<'_> {
+    /// Whether this is an update to a token's comments or whitespace, rather than the token itself
+    pub fn is_trivia_only(&self) -> bool {
+        match self {
+            Edit::Update { old, new } => old.similar(new),
+            _ => false,
+        }
+    }
+}
+
+/// Returns the edits that turn the old Ast into the new one. Deletions come first, in the order
+/// they are in the old code, followed by every other edit in the order they are in the new code.
+/// Nodes are matched without comparing every node with every other, so large files can be diffed.
+/// Read the [module documentation](self) for more information.
+pub fn diff<'a>(old: &'a Ast, new: &'a Ast, options: &DiffOptions) -> Vec<Edit<'a>> {
+    let old_tree = Tree::new(old);
+    let new_tree = Tree::new(new);
+
+    let mut matcher = Matcher {
+        old_to_new: vec![None; old_tree.nodes.len()],
+        new_to_old: vec![None; new_tree.nodes.len()],
+        old: old_tree,
+        new: new_tree,
+    };
+
+    matcher.match_roots();
+    matcher.match_identical();
+    matcher.match_containing();
+
+    let mut edits = matcher.edits(options);
+
+    if !options.ignore_trivia && !same_trivia(old.eof(), new.eof()) {
+        edits.push(Edit::Update {
+            old: old.eof(),
+            new: new.eof(),
+        });
+    }
+
+    edits
+}
+
+fn same_trivia(old: &TokenReference, new: &TokenReference) -> bool {
+    let same = |old: &mut dyn Iterator<Item = &Token>, new: &mut dyn Iterator<Item = &Token>| {
+        old.map(Token::token_type).eq(new.map(Token::token_type))
+    };
+
+    same(&mut old.leading_trivia(), &mut new.leading_trivia())
+        && same(&mut old.trailing_trivia(), &mut new.trailing_trivia())
+}
+
+struct TreeNode<'a> {
+    node: AnyNode<'a>,
+    parent: Option<usize>,
+    children: Vec<usize>,
+    // Nodes are stored in preorder, so every node after this one up until `end` is inside it
+    end: usize,
+    // The same for nodes that are similar, and most likely different for ones that aren't
+    hash: u64,
+}
+
+struct Tree<'a> {
+    nodes: Vec<TreeNode<'a>>,
+}
+
+impl<'a> Tree<'a> {
+    fn new(ast: &'a Ast) -> Self {
+        let mut tree = Self { nodes: Vec::new() };
+        tree.add(AnyNode::from(ast.nodes()), None);
+        tree
+    }
+
+    fn add(&mut self, node: AnyNode<'a>, parent: Option<usize>) -> usize {
+        let index = self.nodes.len();
+
+        self.nodes.push(TreeNode {
+            node,
+            parent,
+            children: Vec::new(),
+            end: index + 1,
+            hash: 0,
+        });
+
+        let mut hasher = StructuralHasher::default();
+        node.kind().hash(&mut hasher);
+
+        if let AnyNode::TokenReference(token) = node {
+            token.token_type().hash(&mut hasher);
+        }
+
+        for child in node.children() {
+            let child = self.add(child, Some(index));
+            self.nodes[child].hash.hash(&mut hasher);
+            self.nodes[index].children.push(child);
+        }
+
+        self.nodes[index].end = self.nodes.len();
+        self.nodes[index].hash = hasher.finish();
+        index
+    }
+
+    fn size(&self, index: usize) -> usize {
+        self.nodes[index].end - index
+    }
+
+    fn ancestors(&self, index: usize) -> impl Iterator<Item = usize> + '_ {
+        std::iter::successors(self.nodes[index].parent, |&index| self.nodes[index].parent)
+    }
+
+    // What children are compared by when there's nothing else to match them by
+    fn kind_key(&self, index: usize) -> (SyntaxKind, Option<TokenKind>) {
+        let node = self.nodes[index].node;
+
+        match node {
+            AnyNode::TokenReference(token) => (node.kind(), Some(token.token_kind())),
+            _ => (node.kind(), None),
+        }
+    }
+}
+
+// How many more nodes a node can be inside of in the new Ast than in the old one and still be
+// counted toward the node around it being matched, such as the `Stmt`, `Do`, and `Block` that
+// statements moved into a `do` block are inside of
+const MAX_ADDED_DEPTH: usize = 4;
+
+struct Matcher<'a> {
+    old: Tree<'a>,
+    new: Tree<'a>,
+    old_to_new: Vec<Option<usize>>,
+    new_to_old: Vec<Option<usize>>,
+}
+
+impl<'a> Matcher<'a> {
+    fn add_match(&mut self, old: usize, new: usize) {
+        self.old_to_new[old] = Some(new);
+        self.new_to_old[new] = Some(old);
+    }
+
+    // Matches every node inside of two similar nodes, which have the same shape
+    fn match_subtrees(&mut self, old: usize, new: usize) {
+        for offset in 0..self.old.size(old) {
+            self.add_match(old + offset, new + offset);
+        }
+    }
+
+    // The blocks of both files are always matched, even if one is the same as a block inside of
+    // the other, such as when the whole file is wrapped in a `do` block
+    fn match_roots(&mut self) {
+        if self.old.nodes[0].node.similar(&self.new.nodes[0].node) {
+            self.match_subtrees(0, 0);
+        } else {
+            self.add_match(0, 0);
+        }
+    }
+
+    // Matches nodes that are the same as each other, from the largest to the smallest, so that
+    // nodes are matched along with the ones around them where they can be
+    fn match_identical(&mut self) {
+        let mut old_counts: HashMap<u64, usize> = HashMap::new();
+        for node in &self.old.nodes {
+            *old_counts.entry(node.hash).or_default() += 1;
+        }
+
+        let mut new_counts: HashMap<u64, usize> = HashMap::new();
+        let mut new_by_hash: HashMap<u64, usize> = HashMap::new();
+        // The children of every node in the new Ast, grouped by their hash, in order
+        let mut new_by_parent: HashMap<(usize, u64), VecDeque<usize>> = HashMap::new();
+
+        for (index, node) in self.new.nodes.iter().enumerate() {
+            *new_counts.entry(node.hash).or_default() += 1;
+            new_by_hash.insert(node.hash, index);
+
+            if let Some(parent) = node.parent {
+                new_by_parent
+                    .entry((parent, node.hash))
+                    .or_default()
+                    .push_back(index);
+            }
+        }
+
+        let mut order: Vec<usize> = (0..self.old.nodes.len()).collect();
+        order.sort_by_key(|&index| Reverse(self.old.size(index)));
+
+        for old in order {
+            if self.old_to_new[old].is_some() {
+                continue;
+            }
+
+            let old_node = &self.old.nodes[old];
+            let is_match = |new: usize| {
+                self.new_to_old[new].is_none() && old_node.node.similar(&self.new.nodes[new].node)
+            };
+
+            // A node is matched with one in the node its parent was matched with, or failing
+            // that, with the only other node like it, such as a function that was moved. Nodes
+            // that are common, such as single tokens, are left to be matched by what they're in.
+            let in_parent = old_node
+                .parent
+                .and_then(|parent| self.old_to_new[parent])
+                .and_then(|parent| new_by_parent.get_mut(&(parent, old_node.hash)))
+                .and_then(|siblings| {
+                    // Siblings are mostly matched in order, so the matched ones are dropped from
+                    // the front rather than skipped every time
+                    while siblings
+                        .front()
+                        .is_some_and(|&new| self.new_to_old[new].is_some())
+                    {
+                        siblings.pop_front();
+                    }
+
+                    siblings.iter().copied().find(|&new| is_match(new))
+                });
+
+            let new = in_parent.or_else(|| {
+                let unique = old_counts[&old_node.hash] == 1
+                    && new_counts.get(&old_node.hash) == Some(&1)
+                    && self.old.size(old) > 1;
+
+                unique
+                    .then(|| new_by_hash[&old_node.hash])
+                    .filter(|&new| is_match(new))
+            });
+
+            if let Some(new) = new {
+                self.match_subtrees(old, new);
+            }
+        }
+    }
+
+    // Matches nodes that aren't the same, but contain many of the same nodes, such as a function
+    // with a line added to it, going from the innermost nodes outwards
+    fn match_containing(&mut self) {
+        // How many of the nodes inside of every old node are matched, filled in as they're passed
+        let mut matched_inside = vec![0; self.old.nodes.len()];
+
+        for old in (1..self.old.nodes.len()).rev() {
+            if self.old_to_new[old].is_none() && !self.old.nodes[old].children.is_empty() {
+                if let Some(new) = self.most_in_common(old, &matched_inside) {
+                    self.add_match(old, new);
+                    self.match_children(old, new);
+                }
+            }
+
+            matched_inside[old] = self.old.nodes[old]
+                .children
+                .iter()
+                .map(|&child| matched_inside[child] + usize::from(self.old_to_new[child].is_some()))
+                .sum();
+        }
+
+        self.match_children(0, 0);
+    }
+
+    fn most_in_common(&self, old: usize, matched_inside: &[usize]) -> Option<usize> {
+        let kind = self.old.nodes[old].node.kind();
+        let mut in_common: HashMap<usize, usize> = HashMap::new();
+
+        // Only the outermost matched nodes inside of the old node are looked at, along with how
+        // far below it they are, as the nodes inside of them were matched along with them
+        let mut descendants: Vec<(usize, usize)> = self.old.nodes[old]
+            .children
+            .iter()
+            .map(|&child| (child, 1))
+            .collect();
+
+        while let Some((descendant, depth)) = descendants.pop() {
+            let Some(matched) = self.old_to_new[descendant] else {
+                if matched_inside[descendant] > 0 {
+                    descendants.extend(
+                        self.old.nodes[descendant]
+                            .children
+                            .iter()
+                            .map(|&child| (child, depth + 1)),
+                    );
+                }
+
+                continue;
+            };
+
+            // The node the old one was matched with is about as far above the match as the
+            // old node is above the descendant, so the walk up stops not far past that
+            for ancestor in self.new.ancestors(matched).take(depth + MAX_ADDED_DEPTH) {
+                if self.new_to_old[ancestor].is_none()
+                    && self.new.nodes[ancestor].node.kind() == kind
+                {
+                    *in_common.entry(ancestor).or_default() += matched_inside[descendant] + 1;
+                }
+            }
+        }
+
+        let (new, count) = in_common
+            .into_iter()
+            .max_by_key(|&(new, count)| (count, Reverse(new)))?;
+
+        let descendants = self.old.size(old) - 1 + self.new.size(new) - 1;
+        (count * 4 >= descendants).then_some(new)
+    }
+
+    // Matches the children of matched nodes that weren't matched by anything else, in order, by
+    // their kind, such as a name that was renamed
+    fn match_children(&mut self, old: usize, new: usize) {
+        let unmatched = |tree: &Tree, matches: &[Option<usize>], parent: usize| -> Vec<usize> {
+            tree.nodes[parent]
+                .children
+                .iter()
+                .copied()
+                .filter(|&child| matches[child].is_none())
+                .collect()
+        };
+
+        let old_children = unmatched(&self.old, &self.old_to_new, old);
+        let new_children = unmatched(&self.new, &self.new_to_old, new);
+
+        let pairs = self.pair_in_order(&old_children, &new_children);
+
+        for (old, new) in pairs {
+            if self.old.nodes[old].hash == self.new.nodes[new].hash
+                && self.old.nodes[old].node.similar(&self.new.nodes[new].node)
+            {
+                self.match_subtrees(old, new);
+            } else {
+                self.add_match(old, new);
+                self.match_children(old, new);
+            }
+        }
+    }
+
+    // Pairs up children while keeping both in order, without comparing every child with every
+    // other, as a longest common subsequence would. Children whose hash is unique on both sides
+    // are paired first, as many of them as can be kept in order, and the children between them are
+    // paired by their kind from the start and the end.
+    fn pair_in_order(&self, old: &[usize], new: &[usize]) -> Vec<(usize, usize)> {
+        let mut counts: HashMap<u64, (usize, usize)> = HashMap::new();
+
+        for &child in old {
+            counts.entry(self.old.nodes[child].hash).or_default().0 += 1;
+        }
+
+        for &child in new {
+            counts.entry(self.new.nodes[child].hash).or_default().1 += 1;
+        }
+
+        let unique_new: HashMap<u64, usize> = new
+            .iter()
+            .enumerate()
+            .filter(|(_, &child)| counts[&self.new.nodes[child].hash] == (1, 1))
+            .map(|(index, &child)| (self.new.nodes[child].hash, index))
+            .collect();
+
+        let anchors: Vec<(usize, usize)> = old
+            .iter()
+            .enumerate()
+            .filter_map(|(old_index, &child)| {
+                let new_index = *unique_new.get(&self.old.nodes[child].hash)?;
+                (self.old.kind_key(child) == self.new.kind_key(new[new_index]))
+                    .then_some((old_index, new_index))
+            })
+            .collect();
+
+        let in_order = longest_increasing(
+            &anchors
+                .iter()
+                .map(|&(_, new_index)| new_index)
+                .collect::<Vec<_>>(),
+        );
+
+        let mut pairs = Vec::new();
+        let (mut old_start, mut new_start) = (0, 0);
+
+        for (index, &(old_index, new_index)) in anchors.iter().enumerate() {
+            if !in_order.contains(&index) {
+                continue;
+            }
+
+            self.pair_by_kind(
+                &old[old_start..old_index],
+                &new[new_start..new_index],
+                &mut pairs,
+            );
+
+            pairs.push((old[old_index], new[new_index]));
+            (old_start, new_start) = (old_index + 1, new_index + 1);
+        }
+
+        self.pair_by_kind(&old[old_start..], &new[new_start..], &mut pairs);
+        pairs
+    }
+
+    // Pairs the children at the start of both that have the same kinds, then those at the end
+    fn pair_by_kind(&self, old: &[usize], new: &[usize], pairs: &mut Vec<(usize, usize)>) {
+        let same_kind =
+            |(&old, &new): (&usize, &usize)| self.old.kind_key(old) == self.new.kind_key(new);
+
+        let start = old
+            .iter()
+            .zip(new)
+            .take_while(|&pair| same_kind(pair))
+            .count();
+        let end = old[start..]
+            .iter()
+            .rev()
+            .zip(new[start..].iter().rev())
+            .take_while(|&pair| same_kind(pair))
+            .count();
+
+        pairs.extend(
+            old[..start]
+                .iter()
+                .copied()
+                .zip(new[..start].iter().copied()),
+        );
+        pairs.extend(
+            old[old.len() - end..]
+                .iter()
+                .copied()
+                .zip(new[new.len() - end..].iter().copied()),
+        );
+    }
+
+    fn edits(&self, options: &DiffOptions) -> Vec<Edit<'a>> {
+        let mut edits = Vec::new();
+
+        for (old, node) in self.old.nodes.iter().enumerate() {
+            let Some(parent) = node.parent else {
+                continue;
+            };
+
+            // Nodes inside of deleted nodes are deleted with them
+            if self.old_to_new[old].is_none() && self.old_to_new[parent].is_some() {
+                edits.push(Edit::Delete {
+                    node: node.node,
+                    parent: self.old.nodes[parent].node,
+                });
+            }
+        }
+
+        let moved_in_place = self.moved_in_place();
+
+        for (new, node) in self.new.nodes.iter().enumerate() {
+            let Some(old) = self.new_to_old[new] else {
+                // Nodes inside of inserted nodes are inserted with them
+                if let Some(parent) = node
+                    .parent
+                    .filter(|&parent| self.new_to_old[parent].is_some())
+                {
+                    edits.push(Edit::Insert {
+                        node: node.node,
+                        parent: self.new.nodes[parent].node,
+                        index: self.new.nodes[parent]
+                            .children
+                            .iter()
+                            .position(|&child| child == new)
+                            .unwrap(),
+                    });
+                }
+
+                continue;
+            };
+
+            let old_node = &self.old.nodes[old];
+
+            let moved = match (old_node.parent, node.parent) {
+                (Some(old_parent), Some(new_parent)) => {
+                    self.new_to_old[new_parent] != Some(old_parent) || moved_in_place[new]
+                }
+                _ => false,
+            };
+
+            if moved {
+                edits.push(Edit::Move {
+                    old: old_node.node,
+                    new: node.node,
+                });
+            }
+
+            if let (AnyNode::TokenReference(old_token), AnyNode::TokenReference(new_token)) =
+                (old_node.node, node.node)
+            {
+                if !old_token.similar(new_token)
+                    || (!options.ignore_trivia && !same_trivia(old_token, new_token))
+                {
+                    edits.push(Edit::Update {
+                        old: old_token,
+                        new: new_token,
+                    });
+                }
+            }
+        }
+
+        edits
+    }
+
+    // The nodes that are still in the same node, but were moved to a different place in it, as
+    // few as can explain the new order
+    fn moved_in_place(&self) -> Vec<bool> {
+        let mut moved = vec![false; self.new.nodes.len()];
+
+        for (new, node) in self.new.nodes.iter().enumerate() {
+            let Some(old) = self.new_to_old[new] else {
+                continue;
+            };
+
+            let stayed: Vec<usize> = node
+                .children
+                .iter()
+                .copied()
+                .filter(|&child| {
+                    self.new_to_old[child]
+                        .is_some_and(|matched| self.old.nodes[matched].parent == Some(old))
+                })
+                .collect();
+
+            let old_order: Vec<usize> = stayed
+                .iter()
+                .map(|&child| self.new_to_old[child].unwrap())
+                .collect();
+
+            let in_order = longest_increasing(&old_order);
+
+            for (index, &child) in stayed.iter().enumerate() {
+                moved[child] = !in_order.contains(&index);
+            }
+        }
+
+        moved
+    }
+}
+
+// The indices of the items that are in increasing order, as many of them as there can be
+pub(crate) fn longest_increasing(items: &[usize]) -> HashSet<usize> {
+    // The index of the last item of the longest increasing run of every length found so far,
+    // keeping the one that ends with the smallest item
+    let mut ends: Vec<usize> = Vec::new();
+    let mut previous: Vec<Option<usize>> = vec![None; items.len()];
+
+    for (index, item) in items.iter().enumerate() {
+        let length = ends.partition_point(|&end| items[end] < *item);
+        previous[index] = length.checked_sub(1).map(|length| ends[length]);
+
+        if length == ends.len() {
+            ends.push(index);
+        } else {
+            ends[length] = index;
+        }
+    }
+
+    std::iter::successors(ends.last().copied(), |&index| previous[index]).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse;
+    use pretty_assertions::assert_eq;
+
+    // Every edit, with the code of the nodes in it
+    fn diff_code(old: &str, new: &str, options: &DiffOptions) -> Vec<String> {
+        let (old, new) = (parse(old).unwrap(), parse(new).unwrap());
+        // Node::tokens gives the tokens of a ContainedSpan before those inside it
+        let code = |node: AnyNode| {
+            let mut tokens = node.tokens().collect::<Vec<_>>();
+            tokens.sort_by_key(|token| token.token().start_position().bytes());
+
+            tokens
+                .iter()
+                .map(|token| token.token().to_string())
+                .collect::<Vec<_>>()
+                .join(" ")
+        };
+
+        diff(&old, &new, options)
+            .into_iter()
+            .map(|edit| match edit {
+                Edit::Insert {
+                    node,
+                    parent,
+                    index,
+                } => format!("insert {} into {:?} at {index}", code(node), parent.kind()),
+                Edit::Delete { node, parent } => {
+                    format!("delete {} from {:?}", code(node), parent.kind())
+                }
+                Edit::Update { old, new } => format!(
+                    "update {old:?} to {new:?}",
+                    old = old.to_string(),
+                    new = new.to_string()
+                ),
+                Edit::Move { old, new } => format!(
+                    "move {} from line {} to line {}",
+                    code(old),
+                    old.start_position().unwrap().line(),
+                    new.start_position().unwrap().line()
+                ),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_same() {
+        let code = "local x = 1\nprint(x)";
+        assert!(diff_code(code, code, &DiffOptions::new()).is_empty());
+    }
+
+    #[test]
+    fn test_trivia() {
+        let old = "local x = 1 -- one\nprint(x)";
+        let new = "local x = 1 -- uno\nprint( x )\n";
+
+        assert_eq!(
+            diff_code(old, new, &DiffOptions::new()),
+            [
+                r#"update "1 -- one\n" to "1 -- uno\n""#,
+                r#"update "(" to "( ""#,
+                r#"update ")" to ")\n""#,
+                r#"update "x" to "x ""#,
+            ]
+        );
+
+        let (old_ast, new_ast) = (parse(old).unwrap(), parse(new).unwrap());
+        assert!(diff(&old_ast, &new_ast, &DiffOptions::new())
+            .iter()
+            .all(Edit::is_trivia_only));
+
+        assert!(diff_code(old, new, &DiffOptions::new().with_ignore_trivia(true)).is_empty());
+    }
+
+    #[test]
+    fn test_update() {
+        assert_eq!(
+            diff_code(
+                "local x = 1\nprint(x)",
+                "local y = 1\nprint(y)",
+                &DiffOptions::new()
+            ),
+            [r#"update "x " to "y ""#, r#"update "x" to "y""#]
+        );
+    }
+
+    #[test]
+    fn test_insert_and_delete() {
+        let options = DiffOptions::new().with_ignore_trivia(true);
+
+        assert_eq!(
+            diff_code("local x = 1\nf(x)\ng(x)", "local x = 1\nf(x, 2)", &options),
+            [
+                "delete g ( x ) from Block",
+                "insert , into FunctionArgs at 2",
+                "insert 2 into FunctionArgs at 3",
+            ]
+        );
+
+        assert_eq!(
+            diff_code("f()", "f()\nif x then g() end", &options),
+            ["insert if x then g ( ) end into Block at 1"]
+        );
+    }
+
+    #[test]
+    fn test_move() {
+        let options = DiffOptions::new().with_ignore_trivia(true);
+
+        assert_eq!(
+            diff_code(
+                "function first() end\nfunction second() end\nfunction third() end",
+                "function third() end\nfunction first() end\nfunction second() end",
+                &options
+            ),
+            ["move function third ( ) end from line 3 to line 1"]
+        );
+
+        // Statements moved into a new block are moved, rather than deleted and inserted
+        assert_eq!(
+            diff_code(
+                "local value = compute()\nprint(value)",
+                "do\n\tlocal value = compute()\n\tprint(value)\nend",
+                &options
+            ),
+            [
+                "insert do local value = compute ( ) print ( value ) end into Block at 0",
+                "move local value = compute ( ) from line 1 to line 2",
+                "move print ( value ) from line 2 to line 3",
+            ]
+        );
+    }
+
+    // Diffing neither compares every statement with every other, nor keeps a table of them
+    #[test]
+    fn test_large() {
+        let options = DiffOptions::new().with_ignore_trivia(true);
+
+        let old = (0..10_000)
+            .map(|index| format!("local x{index} = {index}\n"))
+            .collect::<String>();
+        let new = old.replace("local x5000 = 5000\n", "");
+
+        assert_eq!(
+            diff_code(&old, &new, &options),
+            ["delete local x5000 = 5000 from Block"]
+        );
+
+        // Statements that are all the same are paired in order
+        let old = "print(1)\n".repeat(10_000);
+        let new = format!(
+            "{}print(2)\n{}",
+            "print(1)\n".repeat(5_000),
+            "print(1)\n".repeat(5_000)
+        );
+
+        assert_eq!(
+            diff_code(&old, &new, &options),
+            ["insert print ( 2 ) into Block at 5000"]
+        );
+    }
+}
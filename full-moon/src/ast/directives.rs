@@ -0,0 +1,377 @@
+//! Comment directives, such as Luau's `--!strict` hot comments, and inline tool directives like
+//! `-- selene: allow(unused_variable)`.
+//!
+//! ```rust
+//! # use full_moon::ast::directives::HotCommentKind;
+//! # fn main() -> Result<(), Vec<full_moon::Error>> {
+//! let ast = full_moon::parse("--!strict\n-- selene: allow(unused_variable)\nlocal x = 1")?;
+//!
+//! let hot_comments = ast.hot_comments();
+//! assert_eq!(hot_comments[0].kind(), HotCommentKind::Strict);
+//!
+//! let directives = ast.directives();
+//! assert_eq!(directives[0].tool(), "selene");
+//! assert_eq!(directives[0].command(), "allow");
+//! assert_eq!(directives[0].arguments(), ["unused_variable"]);
+//! # Ok(())
+//! # }
+//! ```
+use crate::{
+    ast::{Ast, LastStmt, Stmt},
+    node::Node,
+    tokenizer::{Position, Token, TokenType},
+    visitors::Visitor,
+};
+
+/// A file-level hot comment, such as `--!strict` or `--!optimize 2`.
+/// Only comments before the first token of the file are hot comments.
+#[derive(Clone, Debug, PartialEq)]
+pub struct HotComment {
+    name: String,
+    value: Option<String>,
+    token: Token,
+    applies_to: Option<(Position, Position)>,
+}
+
+impl HotComment {
+    /// The name of the hot comment, such as `strict` for `--!strict`
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The value given after the name, such as `2` for `--!optimize 2`
+    pub fn value(&self) -> Option<&str> {
+        self.value.as_deref()
+    }
+
+    /// What the hot comment means to Luau, parsed from its name and value
+    pub fn kind(&self) -> HotCommentKind {
+        match (self.name.as_str(), self.value()) {
+            ("strict", None) => HotCommentKind::Strict,
+            ("nonstrict", None) => HotCommentKind::NonStrict,
+            ("nocheck", None) => HotCommentKind::NoCheck,
+            ("native", None) => HotCommentKind::Native,
+            ("optimize", Some(level)) => match level.parse() {
+                Ok(level) => HotCommentKind::Optimize(level),
+                Err(_) => HotCommentKind::Unknown,
+            },
+            _ => HotCommentKind::Unknown,
+        }
+    }
+
+    /// The comment token the hot comment was read from
+    pub fn token(&self) -> &Token {
+        &self.token
+    }
+
+    /// The range the hot comment applies to, which is the entire file.
+    /// None if the file has no nodes.
+    pub fn applies_to(&self) -> Option<(Position, Position)> {
+        self.applies_to
+    }
+}
+
+/// The meaning of a [`HotComment`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum HotCommentKind {
+    /// `--!strict`
+    Strict,
+    /// `--!nonstrict`
+    NonStrict,
+    /// `--!nocheck`
+    NoCheck,
+    /// `--!native`
+    Native,
+    /// `--!optimize <level>`
+    Optimize(u8),
+    /// Any hot comment not listed above, or one with an unexpected value
+    Unknown,
+}
+
+/// An inline directive comment of the form `tool: command` or `tool: command(arguments)`,
+/// such as `-- selene: allow(unused_variable)`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Directive {
+    tool: String,
+    command: String,
+    arguments: Vec<String>,
+    token: Token,
+    applies_to: Option<(Position, Position)>,
+}
+
+impl Directive {
+    /// The tool the directive is for, such as `selene`
+    pub fn tool(&self) -> &str {
+        &self.tool
+    }
+
+    /// The command given to the tool, such as `allow`
+    pub fn command(&self) -> &str {
+        &self.command
+    }
+
+    /// The comma separated arguments given to the command, such as `unused_variable`
+    pub fn arguments(&self) -> &[String] {
+        &self.arguments
+    }
+
+    /// The comment token the directive was read from
+    pub fn token(&self) -> &Token {
+        &self.token
+    }
+
+    /// The range of the node the directive applies to.
+    /// This is the statement the comment is directly before or after, otherwise the innermost
+    /// statement containing the comment, otherwise the entire file.
+    /// None if the file has no nodes.
+    pub fn applies_to(&self) -> Option<(Position, Position)> {
+        self.applies_to
+    }
+}
+
+fn comment_text(token: &Token) -> Option<&str> {
+    match token.token_type() {
+        TokenType::SingleLineComment { comment } => Some(comment),
+        TokenType::MultiLineComment { comment, .. } => Some(comment),
+        _ => None,
+    }
+}
+
+fn is_directive_word(word: &str) -> bool {
+    !word.is_empty()
+        && word
+            .chars()
+            .all(|character| character.is_alphanumeric() || matches!(character, '_' | '-'))
+}
+
+fn parse_directive(text: &str) -> Option<(String, String, Vec<String>)> {
+    let (tool, rest) = text.split_once(':')?;
+    let tool = tool.trim();
+    let rest = rest.trim();
+
+    let (command, arguments) = match rest.split_once('(') {
+        Some((command, arguments)) => (
+            command.trim_end(),
+            arguments
+                .strip_suffix(')')?
+                .split(',')
+                .map(str::trim)
+                .filter(|argument| !argument.is_empty())
+                .map(ToOwned::to_owned)
+                .collect(),
+        ),
+
+        None => (rest, Vec::new()),
+    };
+
+    if !is_directive_word(tool) || !is_directive_word(command) {
+        return None;
+    }
+
+    Some((tool.to_owned(), command.to_owned(), arguments))
+}
+
+#[derive(Default)]
+struct DirectiveCollector {
+    statement_ranges: Vec<(Position, Position)>,
+    attached_comments: Vec<(Position, (Position, Position))>,
+    comments: Vec<Token>,
+}
+
+impl DirectiveCollector {
+    fn add_statement(&mut self, node: &impl Node) {
+        let Some(range) = node.range() else {
+            return;
+        };
+
+        let (leading_trivia, trailing_trivia) = node.surrounding_trivia();
+
+        for token in leading_trivia.into_iter().chain(trailing_trivia) {
+            if comment_text(token).is_some() {
+                self.attached_comments.push((token.start_position(), range));
+            }
+        }
+
+        self.statement_ranges.push(range);
+    }
+
+    fn applies_to(&self, comment: &Token) -> Option<(Position, Position)> {
+        let position = comment.start_position();
+
+        // Statements are visited outside in, so the last match is the innermost
+        self.attached_comments
+            .iter()
+            .rev()
+            .find(|(start, _)| *start == position)
+            .map(|(_, range)| *range)
+            .or_else(|| {
+                self.statement_ranges
+                    .iter()
+                    .rev()
+                    .find(|(start, end)| *start <= position && position < *end)
+                    .copied()
+            })
+    }
+}
+
+impl Visitor for DirectiveCollector {
+    fn visit_stmt(&mut self, stmt: &Stmt) {
+        self.add_statement(stmt);
+    }
+
+    fn visit_last_stmt(&mut self, last_stmt: &LastStmt) {
+        self.add_statement(last_stmt);
+    }
+
+    fn visit_single_line_comment(&mut self, token: &Token) {
+        self.comments.push(token.clone());
+    }
+
+    fn visit_multi_line_comment(&mut self, token: &Token) {
+        self.comments.push(token.clone());
+    }
+}
+
+impl Ast {
+    /// Returns the hot comments at the top of the file, such as `--!strict` or `--!optimize 2`.
+    /// These are the `--!` comments that come before any code.
+    ///
+    /// ```rust
+    /// # use full_moon::ast::directives::HotCommentKind;
+    /// # fn main() -> Result<(), Vec<full_moon::Error>> {
+    /// let ast = full_moon::parse("--!nonstrict\n--!optimize 2\nlocal x = 1")?;
+    /// let kinds = ast.hot_comments().iter().map(|comment| comment.kind()).collect::<Vec<_>>();
+    /// assert_eq!(kinds, vec![HotCommentKind::NonStrict, HotCommentKind::Optimize(2)]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn hot_comments(&self) -> Vec<HotComment> {
+        let first_token = self.nodes().tokens().next().unwrap_or(self.eof());
+        let applies_to = self.range();
+
+        first_token
+            .leading_trivia()
+            .filter_map(|token| {
+                let text = match token.token_type() {
+                    TokenType::SingleLineComment { comment } => comment.strip_prefix('!')?,
+                    _ => return None,
+                };
+
+                let (name, value) = match text.trim().split_once(char::is_whitespace) {
+                    Some((name, value)) => (name, Some(value.trim().to_owned())),
+                    None => (text.trim(), None),
+                };
+
+                if name.is_empty() {
+                    return None;
+                }
+
+                Some(HotComment {
+                    name: name.to_owned(),
+                    value,
+                    token: token.clone(),
+                    applies_to,
+                })
+            })
+            .collect()
+    }
+
+    /// Returns every comment in the file of the form `tool: command` or `tool: command(arguments)`,
+    /// such as `-- selene: allow(unused_variable)`, in the order they appear.
+    /// See [`Directive::applies_to`] for which node each directive applies to.
+    pub fn directives(&self) -> Vec<Directive> {
+        let mut collector = DirectiveCollector::default();
+        collector.visit_ast(self);
+
+        collector
+            .comments
+            .iter()
+            .filter_map(|token| {
+                let (tool, command, arguments) = parse_directive(comment_text(token)?)?;
+
+                Some(Directive {
+                    tool,
+                    command,
+                    arguments,
+                    applies_to: collector.applies_to(token).or_else(|| self.range()),
+                    token: token.clone(),
+                })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_hot_comments() {
+        let ast = parse("--!strict\n--!native\n--!optimize 2\n--!custom value\n-- not hot\nlocal x = 1\n--!nocheck\n")
+            .unwrap();
+
+        let hot_comments = ast.hot_comments();
+        assert_eq!(
+            hot_comments
+                .iter()
+                .map(|comment| (comment.name(), comment.value(), comment.kind()))
+                .collect::<Vec<_>>(),
+            vec![
+                ("strict", None, HotCommentKind::Strict),
+                ("native", None, HotCommentKind::Native),
+                ("optimize", Some("2"), HotCommentKind::Optimize(2)),
+                ("custom", Some("value"), HotCommentKind::Unknown),
+            ]
+        );
+
+        assert_eq!(hot_comments[0].applies_to(), ast.range());
+    }
+
+    #[test]
+    fn test_directives() {
+        let ast = parse(
+            "-- selene: allow(unused_variable, shadowing)\nlocal x = 1\n\nlocal function f()\n\treturn y -- stylua: ignore\nend\n-- TODO: fix this\n",
+        )
+        .unwrap();
+
+        let directives = ast.directives();
+        assert_eq!(directives.len(), 2);
+
+        assert_eq!(directives[0].tool(), "selene");
+        assert_eq!(directives[0].command(), "allow");
+        assert_eq!(directives[0].arguments(), ["unused_variable", "shadowing"]);
+        assert_eq!(
+            directives[0].applies_to(),
+            ast.nodes().stmts().next().unwrap().range()
+        );
+
+        assert_eq!(directives[1].tool(), "stylua");
+        assert_eq!(directives[1].command(), "ignore");
+        assert!(directives[1].arguments().is_empty());
+
+        let function_body_return = match ast.nodes().stmts().nth(1).unwrap() {
+            Stmt::LocalFunction(local_function) => local_function.body().block().last_stmt(),
+            _ => unreachable!(),
+        };
+
+        assert_eq!(
+            directives[1].applies_to(),
+            function_body_return.unwrap().range()
+        );
+    }
+
+    #[test]
+    fn test_directive_inside_statement() {
+        let ast = parse("call(\n\t-- selene: allow(x)\n\tvalue\n)").unwrap();
+        let directives = ast.directives();
+
+        assert_eq!(directives.len(), 1);
+        assert_eq!(
+            directives[0].applies_to(),
+            ast.nodes().stmts().next().unwrap().range()
+        );
+    }
+}
@@ -0,0 +1,178 @@
+use std::{
+    cmp::Reverse,
+    collections::{HashMap, HashSet},
+};
+
+use super::Ast;
+use crate::{
+    node::{AnyNode, Node, Similar},
+    tokenizer::TokenReference,
+};
+
+// Every node in preorder, along with how many tokens it has
+fn add_nodes<'a>(node: AnyNode<'a>, nodes: &mut Vec<(AnyNode<'a>, usize)>) {
+    nodes.push((node, node.tokens().count()));
+
+    for child in node.children() {
+        add_nodes(child, nodes);
+    }
+}
+
+impl Ast {
+    /// Returns groups of nodes that are [`similar`](Node::similar) to each other, such as code
+    /// that was copied and pasted, ignoring trivia. Only nodes with at least `min_tokens` tokens
+    /// are included, and nodes inside of other duplicates are left out, so a duplicated function is
+    /// given once rather than along with every statement in it.
+    ///
+    /// Groups are in the order of their first node in the code, as are the nodes in each group.
+    ///
+    /// ```rust
+    /// # use full_moon::node::{Node, SyntaxKind};
+    /// # fn main() -> Result<(), Vec<full_moon::Error>> {
+    /// let ast = full_moon::parse(
+    ///     "local a = clamp(x * 2, 0, 1)\nlocal b = clamp(x * 2, 0, 1) -- again\nlocal c = 1",
+    /// )?;
+    ///
+    /// let duplicates = ast.duplicates(5);
+    /// assert_eq!(duplicates.len(), 1);
+    ///
+    /// let lines = duplicates[0]
+    ///     .iter()
+    ///     .map(|node| (node.kind(), node.start_position().unwrap().line()))
+    ///     .collect::<Vec<_>>();
+    ///
+    /// assert_eq!(lines, [(SyntaxKind::Expression, 1), (SyntaxKind::Expression, 2)]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn duplicates(&self, min_tokens: usize) -> Vec<Vec<AnyNode<'_>>> {
+        let mut nodes = Vec::new();
+        add_nodes(AnyNode::from(self.nodes()), &mut nodes);
+
+        let mut groups: HashMap<Similar<AnyNode>, Vec<usize>> = HashMap::new();
+
+        for (index, &(node, tokens)) in nodes.iter().enumerate() {
+            if tokens > 0 && tokens >= min_tokens {
+                groups.entry(Similar(node)).or_default().push(index);
+            }
+        }
+
+        let mut groups: Vec<Vec<usize>> = groups
+            .into_values()
+            .filter(|group| group.len() > 1)
+            .collect();
+
+        // Larger nodes come first, so that the duplicates inside of them are covered by them.
+        // Nodes with the same tokens as the node they're in, such as the call of a call statement,
+        // come after it.
+        groups.sort_by_key(|group| (Reverse(nodes[group[0]].1), group[0]));
+
+        let mut covered: HashSet<*const TokenReference> = HashSet::new();
+        let mut duplicates = Vec::new();
+
+        for group in groups {
+            let uncovered: Vec<usize> = group
+                .into_iter()
+                .filter(|&index| {
+                    nodes[index]
+                        .0
+                        .tokens()
+                        .next()
+                        .is_some_and(|token| !covered.contains(&(token as *const _)))
+                })
+                .collect();
+
+            if uncovered.len() < 2 {
+                continue;
+            }
+
+            for &index in &uncovered {
+                covered.extend(nodes[index].0.tokens().map(|token| token as *const _));
+            }
+
+            duplicates.push(uncovered);
+        }
+
+        duplicates.sort_by_key(|group| group[0]);
+
+        duplicates
+            .into_iter()
+            .map(|group| group.into_iter().map(|index| nodes[index].0).collect())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        node::{Node, SyntaxKind},
+        parse,
+    };
+    use pretty_assertions::assert_eq;
+
+    // The kind and code of every node in every group of duplicates
+    fn duplicates(code: &str, min_tokens: usize) -> Vec<Vec<(SyntaxKind, String)>> {
+        let ast = parse(code).unwrap();
+
+        ast.duplicates(min_tokens)
+            .into_iter()
+            .map(|group| {
+                group
+                    .into_iter()
+                    .map(|node| {
+                        let mut tokens = node.tokens().collect::<Vec<_>>();
+                        tokens.sort_by_key(|token| token.token().start_position().bytes());
+
+                        let code = tokens
+                            .iter()
+                            .map(|token| token.token().to_string())
+                            .collect::<Vec<_>>()
+                            .join(" ");
+
+                        (node.kind(), code)
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_duplicates() {
+        let code = "function f()\n\tprint(1)\n\tprint(2)\nend\nprint(1)\nfunction g()\n\tprint(1)\n\tprint(2)\nend";
+
+        // The statements inside of the functions are left out, so `print(1)` outside of them
+        // isn't a duplicate of anything
+        assert_eq!(
+            duplicates(code, 4),
+            [vec![
+                (
+                    SyntaxKind::FunctionBody,
+                    "( ) print ( 1 ) print ( 2 ) end".to_owned()
+                ),
+                (
+                    SyntaxKind::FunctionBody,
+                    "( ) print ( 1 ) print ( 2 ) end".to_owned()
+                ),
+            ]]
+        );
+
+        assert_eq!(
+            duplicates("print(1)\nprint(1)", 4),
+            [vec![
+                (SyntaxKind::Stmt, "print ( 1 )".to_owned()),
+                (SyntaxKind::Stmt, "print ( 1 )".to_owned()),
+            ]]
+        );
+
+        assert_eq!(duplicates(code, 12), Vec::<Vec<_>>::new());
+
+        // Tables with different separators are still duplicates
+        assert_eq!(
+            duplicates("local a = {1, 2}\nlocal b = {1; 2;}", 4),
+            [vec![
+                (SyntaxKind::Expression, "{ 1 , 2 }".to_owned()),
+                (SyntaxKind::Expression, "{ 1 ; 2 ; }".to_owned()),
+            ]]
+        );
+    }
+}
@@ -0,0 +1,175 @@
+use std::str::FromStr;
+
+use super::{
+    parser_structs::{FragmentResult, ParserResult},
+    parsers::{self, StmtVariant},
+    AstErrorKind, Block, Expression, FunctionBody, LuaVersion, Stmt,
+};
+use crate::node::Node;
+
+#[cfg(feature = "luau")]
+use super::luau::TypeInfo;
+
+pub(crate) fn parse_expression(code: &str, lua_version: LuaVersion) -> FragmentResult<Expression> {
+    FragmentResult::parse(
+        code,
+        lua_version,
+        parsers::parse_expression,
+        (AstErrorKind::ExpectedExpression, "expected an expression"),
+    )
+}
+
+pub(crate) fn parse_statement(code: &str, lua_version: LuaVersion) -> FragmentResult<Stmt> {
+    FragmentResult::parse(
+        code,
+        lua_version,
+        |state| match parsers::parse_stmt(state) {
+            ParserResult::Value(StmtVariant::Stmt(stmt)) => ParserResult::Value(stmt),
+
+            // Last statements such as `continue` can't be represented as a Stmt
+            ParserResult::Value(StmtVariant::LastStmt(last_stmt)) => {
+                if let Some(token) = last_stmt.tokens().next() {
+                    state.token_error(
+                        token.clone(),
+                        AstErrorKind::UnexpectedLastStatement,
+                        "expected a statement, got a last statement",
+                    );
+                }
+
+                ParserResult::LexerMoved
+            }
+
+            ParserResult::NotFound => ParserResult::NotFound,
+            ParserResult::LexerMoved => ParserResult::LexerMoved,
+        },
+        (AstErrorKind::ExpectedStatement, "expected a statement"),
+    )
+}
+
+pub(crate) fn parse_block(code: &str, lua_version: LuaVersion) -> FragmentResult<Block> {
+    FragmentResult::parse(
+        code,
+        lua_version,
+        parsers::parse_block,
+        (AstErrorKind::ExpectedBlock, "expected a block"),
+    )
+}
+
+#[cfg(feature = "luau")]
+pub(crate) fn parse_type_info(code: &str, lua_version: LuaVersion) -> FragmentResult<TypeInfo> {
+    FragmentResult::parse(
+        code,
+        lua_version,
+        parsers::parse_type,
+        (AstErrorKind::ExpectedType, "expected a type"),
+    )
+}
+
+pub(crate) fn parse_function_body(
+    code: &str,
+    lua_version: LuaVersion,
+) -> FragmentResult<FunctionBody> {
+    FragmentResult::parse(
+        code,
+        lua_version,
+        parsers::parse_function_body,
+        (
+            AstErrorKind::ExpectedFunctionBody,
+            "expected a function body",
+        ),
+    )
+}
+
+macro_rules! impl_from_str {
+    ($($node:ty => $parser:ident,)+) => {
+        $(
+            impl FromStr for $node {
+                type Err = Vec<crate::Error>;
+
+                /// Parses the node using the most complete set of Lua versions enabled in your feature set.
+                fn from_str(code: &str) -> Result<Self, Self::Err> {
+                    $parser(code, LuaVersion::new()).into_result()
+                }
+            }
+        )+
+    };
+}
+
+impl_from_str! {
+    Expression => parse_expression,
+    Stmt => parse_statement,
+    Block => parse_block,
+    FunctionBody => parse_function_body,
+}
+
+#[cfg(feature = "luau")]
+impl_from_str! {
+    TypeInfo => parse_type_info,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ast::BinOp;
+
+    #[test]
+    fn test_parse_expression() {
+        let expression: Expression = "1 + 2".parse().unwrap();
+        assert!(matches!(
+            expression,
+            Expression::BinaryOperator {
+                binop: BinOp::Plus(_),
+                ..
+            }
+        ));
+        assert_eq!(expression.to_string(), "1 + 2");
+    }
+
+    #[test]
+    fn test_rejects_trailing_input() {
+        let result = parse_expression("1 + 2 3", LuaVersion::new());
+        assert!(result.node().is_some());
+        assert_eq!(result.errors().len(), 1);
+        assert_eq!(
+            result.errors()[0].error_message(),
+            "unexpected token, expected the end of the input"
+        );
+
+        assert!("local x = 1 end".parse::<Stmt>().is_err());
+        assert!("local x = 1 local y = 2".parse::<Stmt>().is_err());
+    }
+
+    #[test]
+    fn test_missing_node() {
+        let result = parse_expression("", LuaVersion::new());
+        assert!(result.node().is_none());
+        assert_eq!(result.errors()[0].error_message(), "expected an expression");
+
+        assert!("".parse::<FunctionBody>().is_err());
+        assert!("".parse::<Block>().unwrap().stmts().next().is_none());
+    }
+
+    #[test]
+    fn test_parse_fragments() {
+        let stmt: Stmt = "local x = 1".parse().unwrap();
+        assert!(matches!(stmt, Stmt::LocalAssignment(_)));
+
+        let block: Block = "local x = 1\nreturn x".parse().unwrap();
+        assert_eq!(block.stmts().count(), 1);
+        assert!(block.last_stmt().is_some());
+
+        let function_body: FunctionBody = "(a, b) return a + b end".parse().unwrap();
+        assert_eq!(function_body.parameters().len(), 2);
+    }
+
+    #[cfg(feature = "luau")]
+    #[test]
+    fn test_parse_type_info() {
+        let type_info: TypeInfo = "string | number".parse().unwrap();
+        assert!(matches!(type_info, TypeInfo::Union(_)));
+
+        assert!(parse_type_info("string number", LuaVersion::luau())
+            .into_result()
+            .is_err());
+    }
+}
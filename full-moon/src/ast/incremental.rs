@@ -0,0 +1,654 @@
+use std::{collections::HashSet, ops::Range};
+
+use super::{
+    diff::longest_increasing,
+    parser_structs::{AstResult, ParserResult, ParserState},
+    parsers::{parse_last_stmt, parse_stmt, StmtVariant},
+    Ast, Block, LastStmt, LuaVersion, Stmt,
+};
+use crate::{
+    node::Node,
+    tokenizer::{Lexer, Position, Symbol, Token, TokenReference, TriviaMode},
+    visitors::{Visit, VisitMut, VisitorMut, VisitorRef},
+};
+
+/// A replacement of a range of a source string with new text, such as a keystroke in an editor.
+/// Ranges are in bytes, and always refer to the original source string, even when used alongside other edits.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TextEdit {
+    range: Range<usize>,
+    replacement: String,
+}
+
+impl TextEdit {
+    /// Creates a new TextEdit replacing the given byte range with `replacement`
+    pub fn new(range: Range<usize>, replacement: impl Into<String>) -> Self {
+        Self {
+            range,
+            replacement: replacement.into(),
+        }
+    }
+
+    /// The byte range of the original source that is replaced
+    pub fn range(&self) -> Range<usize> {
+        self.range.clone()
+    }
+
+    /// The text the range is replaced with
+    pub fn replacement(&self) -> &str {
+        &self.replacement
+    }
+}
+
+// Applies the edits to the source, returning the new source along with the sorted edits
+fn apply_edits<'a>(source: &str, edits: &'a [TextEdit]) -> (String, Vec<&'a TextEdit>) {
+    let mut edits = edits.iter().collect::<Vec<_>>();
+    edits.sort_by_key(|edit| (edit.range.start, edit.range.end));
+
+    let mut new_source = String::with_capacity(source.len());
+    let mut copied_until = 0;
+
+    for edit in &edits {
+        assert!(
+            edit.range.start >= copied_until && edit.range.start <= edit.range.end,
+            "text edits must not overlap"
+        );
+
+        assert!(
+            source.is_char_boundary(edit.range.start) && source.is_char_boundary(edit.range.end),
+            "text edit range {:?} is not within the source",
+            edit.range
+        );
+
+        new_source.push_str(&source[copied_until..edit.range.start]);
+        new_source.push_str(&edit.replacement);
+        copied_until = edit.range.end;
+    }
+
+    new_source.push_str(&source[copied_until..]);
+
+    (new_source, edits)
+}
+
+// The line and character of a byte offset, as the lexer would count them
+fn line_and_character(source: &str, bytes: usize) -> (usize, usize) {
+    let before = &source[..bytes];
+    let line_start = before.rfind('\n').map_or(0, |index| index + 1);
+
+    (
+        bytecount::count(before.as_bytes(), b'\n') + 1,
+        before[line_start..].chars().count() + 1,
+    )
+}
+
+// Moves every token starting at or after the end of the edited region to where it is in the new source
+struct PositionShifter {
+    from_bytes: usize,
+    bytes_delta: isize,
+    from_line: usize,
+    line_delta: isize,
+    character_delta: isize,
+}
+
+impl PositionShifter {
+    fn shift_position(&self, position: Position) -> Position {
+        Position {
+            bytes: position.bytes.wrapping_add_signed(self.bytes_delta),
+            line: position.line.wrapping_add_signed(self.line_delta),
+            character: if position.line == self.from_line {
+                position.character.wrapping_add_signed(self.character_delta)
+            } else {
+                position.character
+            },
+        }
+    }
+}
+
+impl VisitorMut for PositionShifter {
+    fn visit_token(&mut self, token: Token) -> Token {
+        if token.start_position.bytes < self.from_bytes {
+            return token;
+        }
+
+        Token {
+            start_position: self.shift_position(token.start_position),
+            end_position: self.shift_position(token.end_position),
+            token_type: token.token_type,
+        }
+    }
+}
+
+// Where a token's leading trivia starts
+fn full_start(token: &TokenReference) -> Position {
+    token
+        .leading_trivia()
+        .next()
+        .unwrap_or(token.token())
+        .start_position()
+}
+
+fn node_full_start(node: &impl Node) -> Option<Position> {
+    node.tokens().next().map(full_start)
+}
+
+// The blocks directly inside a statement, along with the token that closes each of them
+fn nested_blocks(stmt: &Stmt) -> Vec<(&Block, &TokenReference)> {
+    match stmt {
+        Stmt::Do(do_block) => vec![(do_block.block(), do_block.end_token())],
+        Stmt::While(while_loop) => vec![(while_loop.block(), while_loop.end_token())],
+        Stmt::Repeat(repeat) => vec![(repeat.block(), repeat.until_token())],
+        Stmt::NumericFor(numeric_for) => vec![(numeric_for.block(), numeric_for.end_token())],
+        Stmt::GenericFor(generic_for) => vec![(generic_for.block(), generic_for.end_token())],
+
+        Stmt::FunctionDeclaration(declaration) => {
+            vec![(declaration.body().block(), declaration.body().end_token())]
+        }
+
+        Stmt::LocalFunction(local_function) => {
+            vec![(
+                local_function.body().block(),
+                local_function.body().end_token(),
+            )]
+        }
+
+        Stmt::If(if_stmt) => {
+            let else_ifs = if_stmt.else_if().map(Vec::as_slice).unwrap_or_default();
+
+            let mut blocks = Vec::new();
+            let mut block = if_stmt.block();
+
+            for else_if in else_ifs {
+                blocks.push((block, else_if.else_if_token()));
+                block = else_if.block();
+            }
+
+            match (if_stmt.else_token(), if_stmt.else_block()) {
+                (Some(else_token), Some(else_block)) => {
+                    blocks.push((block, else_token));
+                    blocks.push((else_block, if_stmt.end_token()));
+                }
+
+                _ => blocks.push((block, if_stmt.end_token())),
+            }
+
+            blocks
+        }
+
+        _ => Vec::new(),
+    }
+}
+
+// The same blocks as `nested_blocks`, but mutable
+fn nested_block_mut(stmt: &mut Stmt, index: usize) -> &mut Block {
+    match stmt {
+        Stmt::Do(do_block) => &mut do_block.block,
+        Stmt::While(while_loop) => &mut while_loop.block,
+        Stmt::Repeat(repeat) => &mut repeat.block,
+        Stmt::NumericFor(numeric_for) => &mut numeric_for.block,
+        Stmt::GenericFor(generic_for) => &mut generic_for.block,
+        Stmt::FunctionDeclaration(declaration) => &mut declaration.body.block,
+        Stmt::LocalFunction(local_function) => &mut local_function.body.block,
+
+        Stmt::If(if_stmt) => {
+            let else_if_count = if_stmt.else_if.as_ref().map_or(0, Vec::len);
+
+            if index == 0 {
+                &mut if_stmt.block
+            } else if index <= else_if_count {
+                &mut if_stmt.else_if.as_mut().unwrap()[index - 1].block
+            } else {
+                if_stmt.r#else.as_mut().unwrap()
+            }
+        }
+
+        _ => unreachable!("statement has no nested blocks"),
+    }
+}
+
+struct Edit<'a> {
+    // The region of the old source that was replaced
+    start: usize,
+    end: usize,
+    bytes_delta: isize,
+    old_source: &'a str,
+    new_source: &'a str,
+    lua_version: LuaVersion,
+}
+
+// The items of a block that have to be replaced, and what to replace them with.
+// Items are the statements of a block, followed by its last statement, if any.
+struct Plan {
+    // The statement index and nested block index to follow to get to the reparsed block
+    path: Vec<(usize, usize)>,
+    replace: Range<usize>,
+    stmts: Vec<(Stmt, Option<TokenReference>)>,
+    last_stmt: Option<(LastStmt, Option<TokenReference>)>,
+    eof: Option<TokenReference>,
+}
+
+fn plan_block(block: &Block, end: Position, is_root: bool, edit: &Edit) -> Option<Plan> {
+    let mut starts = block
+        .stmts
+        .iter()
+        .map(|(stmt, _)| node_full_start(stmt))
+        .chain(
+            block
+                .last_stmt
+                .iter()
+                .map(|(last_stmt, _)| node_full_start(last_stmt)),
+        )
+        .collect::<Option<Vec<_>>>()?;
+
+    starts.push(end);
+
+    let item_count = starts.len() - 1;
+    let first_affected = (0..item_count)
+        .find(|&index| starts[index + 1].bytes >= edit.start)
+        .unwrap_or(item_count);
+    let last_affected = (0..item_count)
+        .rev()
+        .find(|&index| starts[index].bytes <= edit.end)
+        .unwrap_or(0);
+
+    // If the edit is entirely within one statement, try to only reparse the block it is in
+    if first_affected == last_affected && first_affected < block.stmts.len() {
+        for (index, (nested_block, end_token)) in nested_blocks(&block.stmts[first_affected].0)
+            .into_iter()
+            .enumerate()
+        {
+            let nested_end = full_start(end_token);
+            let nested_start = match nested_block.tokens().next() {
+                Some(token) => full_start(token),
+                None => nested_end,
+            };
+
+            if nested_start.bytes <= edit.start && edit.end <= nested_end.bytes {
+                if let Some(mut plan) = plan_block(nested_block, nested_end, false, edit) {
+                    plan.path.insert(0, (first_affected, index));
+                    return Some(plan);
+                }
+
+                break;
+            }
+        }
+    }
+
+    reparse_items(&starts, first_affected, is_root, edit)
+}
+
+fn reparse_items(
+    starts: &[Position],
+    first_affected: usize,
+    is_root: bool,
+    edit: &Edit,
+) -> Option<Plan> {
+    let item_count = starts.len() - 1;
+
+    // The statement before the edit is reparsed too, as its end depends on the tokens after it
+    let first = first_affected.saturating_sub(1);
+    let start = starts[first];
+
+    // The token before the block collects trailing trivia up to the end of its line,
+    // which could change if the edit is on that line.
+    if first_affected == 0
+        && !is_root
+        && edit.old_source.as_bytes().get(start.bytes.wrapping_sub(1)) != Some(&b'\n')
+    {
+        return None;
+    }
+
+    let lexer = if start.bytes == 0 {
+        Lexer::new(edit.new_source, edit.lua_version)
+    } else {
+        Lexer::new_at(
+            &edit.new_source[start.bytes..],
+            start,
+            edit.lua_version,
+            TriviaMode::KeepAll,
+        )
+    };
+
+    let mut state = ParserState::new(lexer);
+    let mut stmts = Vec::new();
+    let mut last_stmt = None;
+    let mut block_ended = false;
+
+    let synced_at = loop {
+        let position = full_start(state.current().ok()?);
+
+        // Once we are past the edit, find an old item starting at the same place.
+        // Everything from there on is the same as before.
+        let old_bytes = position.bytes as isize - edit.bytes_delta;
+        if let Ok(index) = starts.binary_search_by_key(&old_bytes, |start| start.bytes as isize) {
+            if starts[index].bytes >= edit.end && (!block_ended || index == item_count) {
+                break index;
+            }
+        }
+
+        if block_ended {
+            return None;
+        }
+
+        match parse_stmt(&mut state) {
+            ParserResult::Value(StmtVariant::Stmt(stmt)) => {
+                let semicolon = state.consume_if(Symbol::Semicolon);
+                stmts.push((stmt, semicolon));
+            }
+
+            ParserResult::Value(StmtVariant::LastStmt(stmt)) => {
+                let semicolon = state.consume_if(Symbol::Semicolon);
+                last_stmt = Some((stmt, semicolon));
+                block_ended = true;
+            }
+
+            ParserResult::NotFound => {
+                if let ParserResult::Value(stmt) = parse_last_stmt(&mut state) {
+                    last_stmt = Some(stmt);
+                }
+
+                block_ended = true;
+            }
+
+            ParserResult::LexerMoved => return None,
+        }
+
+        if !state.errors.is_empty() {
+            return None;
+        }
+    };
+
+    let eof = if is_root && synced_at == item_count {
+        Some(state.consume().unwrap())
+    } else {
+        None
+    };
+
+    if !state.errors.is_empty() {
+        return None;
+    }
+
+    Some(Plan {
+        path: Vec::new(),
+        replace: first..synced_at,
+        stmts,
+        last_stmt,
+        eof,
+    })
+}
+
+fn splice(block: &mut Block, plan: Plan) {
+    let stmt_count = block.stmts.len();
+
+    if plan.replace.end > stmt_count
+        || (plan.replace.end == stmt_count && block.last_stmt.is_none())
+    {
+        block
+            .stmts
+            .splice(plan.replace.start.min(stmt_count)..stmt_count, plan.stmts);
+        block.last_stmt = plan.last_stmt;
+    } else {
+        debug_assert!(plan.last_stmt.is_none());
+        block.stmts.splice(plan.replace, plan.stmts);
+    }
+}
+
+// Every token and trivia that is printed, in the order it's printed
+#[derive(Default)]
+struct PrintedTokens<'a> {
+    tokens: Vec<&'a Token>,
+}
+
+impl<'a> VisitorRef<'a> for PrintedTokens<'a> {
+    fn visit_token_reference(&mut self, token: &'a TokenReference) {
+        if !token.is_phantom() {
+            self.tokens.extend(token.leading_trivia());
+            self.tokens.push(token.token());
+            self.tokens.extend(token.trailing_trivia());
+        }
+    }
+}
+
+// Adds an edit replacing the range of the source with the replacement, leaving out the text at
+// the start and end that is the same in both
+fn push_edit(source: &str, range: Range<usize>, replacement: &str, edits: &mut Vec<TextEdit>) {
+    let original = &source[range.clone()];
+
+    let same_length = |original: &mut dyn Iterator<Item = char>,
+                       replacement: &mut dyn Iterator<Item = char>| {
+        original
+            .zip(replacement)
+            .take_while(|(original, replacement)| original == replacement)
+            .map(|(character, _)| character.len_utf8())
+            .sum::<usize>()
+    };
+
+    let prefix = same_length(&mut original.chars(), &mut replacement.chars());
+    let suffix = same_length(
+        &mut original[prefix..].chars().rev(),
+        &mut replacement[prefix..].chars().rev(),
+    );
+
+    if original.len() == prefix + suffix && replacement.len() == prefix + suffix {
+        return;
+    }
+
+    edits.push(TextEdit::new(
+        range.start + prefix..range.end - suffix,
+        &replacement[prefix..replacement.len() - suffix],
+    ));
+}
+
+impl Ast {
+    /// Parses `source` after `edits` are applied to it, reusing the statements of this Ast
+    /// that the edits did not touch. `source` must be the code this Ast was parsed from
+    /// without errors, and `lua_version` must be the version it was parsed with.
+    ///
+    /// Only the smallest block around the edits is reparsed, and the positions of the
+    /// statements after it are moved to match the new source. Otherwise, this is the same as
+    /// calling [`parse_fallible`](crate::parse_fallible) on the new source.
+    ///
+    /// ```rust
+    /// # use full_moon::ast::{LuaVersion, TextEdit};
+    /// # fn main() -> Result<(), Vec<full_moon::Error>> {
+    /// let source = "local x = 1\nlocal y = 2\n";
+    /// let ast = full_moon::parse(source)?;
+    ///
+    /// let edits = [TextEdit::new(6..7, "renamed")];
+    /// let new_ast = ast.reparse(source, &edits, LuaVersion::new()).into_result()?;
+    /// assert_eq!(new_ast.to_string(), "local renamed = 1\nlocal y = 2\n");
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Panics
+    /// Panics if any of the edits overlap, or are not on character boundaries within `source`.
+    pub fn reparse(self, source: &str, edits: &[TextEdit], lua_version: LuaVersion) -> AstResult {
+        // The reused statements keep their node IDs, so only the new tokens need them
+        let node_ids = self.eof.id.is_some();
+        let mut result = self.reparse_edits(source, edits, lua_version);
+
+        if node_ids {
+            result.ast = result.ast.with_node_ids();
+        }
+
+        result
+    }
+
+    /// Returns the edits that turn `original_source` into the code of `new_ast`, such as after a
+    /// [`VisitorMut`] has changed an Ast parsed from it, so that only the code that changed is
+    /// replaced, rather than the whole file.
+    ///
+    /// Tokens and trivia are kept where their positions still point to the same text in
+    /// `original_source`. Everything else, such as tokens made with [`Token::new`], which have no
+    /// position, is replaced, along with tokens that were moved before others they were after. The
+    /// edits are sorted and never overlap, and can be given to [`Ast::reparse`].
+    ///
+    /// ```rust
+    /// # use full_moon::{ast::{Ast, TextEdit}, tokenizer::{Token, TokenReference, TokenType}, visitors::VisitorMut};
+    /// # fn main() -> Result<(), Vec<full_moon::Error>> {
+    /// struct Renamer;
+    ///
+    /// impl VisitorMut for Renamer {
+    ///     fn visit_token_reference(&mut self, token: TokenReference) -> TokenReference {
+    ///         if token.token().to_string() == "x" {
+    ///             token.with_token(Token::new(TokenType::Identifier {
+    ///                 identifier: "renamed".into(),
+    ///             }))
+    ///         } else {
+    ///             token
+    ///         }
+    ///     }
+    /// }
+    ///
+    /// let source = "local x = 1\nprint(x, y)\n";
+    /// let new_ast = Renamer.visit_ast(full_moon::parse(source)?);
+    ///
+    /// assert_eq!(
+    ///     Ast::diff_text(source, &new_ast),
+    ///     [TextEdit::new(6..7, "renamed"), TextEdit::new(18..19, "renamed")]
+    /// );
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn diff_text(original_source: &str, new_ast: &Ast) -> Vec<TextEdit> {
+        let mut printed = PrintedTokens::default();
+        new_ast.nodes().visit(&mut printed);
+        printed.visit_token_reference(new_ast.eof());
+
+        let texts: Vec<String> = printed.tokens.iter().map(ToString::to_string).collect();
+
+        // Where the tokens that haven't changed are in the original source
+        let original_ranges: Vec<Option<Range<usize>>> = printed
+            .tokens
+            .iter()
+            .zip(&texts)
+            .map(|(token, text)| {
+                let range = token.start_position().bytes()..token.end_position().bytes();
+                (!text.is_empty() && original_source.get(range.clone()) == Some(text.as_str()))
+                    .then_some(range)
+            })
+            .collect();
+
+        // Tokens can only be kept if they're in the same order as in the original source
+        let unchanged: Vec<usize> = (0..texts.len())
+            .filter(|&index| original_ranges[index].is_some())
+            .collect();
+
+        let starts: Vec<usize> = unchanged
+            .iter()
+            .map(|&index| original_ranges[index].as_ref().unwrap().start)
+            .collect();
+
+        let kept: HashSet<usize> = longest_increasing(&starts)
+            .into_iter()
+            .map(|index| unchanged[index])
+            .collect();
+
+        let mut edits = Vec::new();
+        let mut original_end = 0;
+        let mut replacement = String::new();
+
+        for (index, text) in texts.iter().enumerate() {
+            match &original_ranges[index] {
+                Some(range) if kept.contains(&index) && range.start >= original_end => {
+                    push_edit(
+                        original_source,
+                        original_end..range.start,
+                        &replacement,
+                        &mut edits,
+                    );
+
+                    replacement.clear();
+                    original_end = range.end;
+                }
+
+                _ => replacement.push_str(text),
+            }
+        }
+
+        push_edit(
+            original_source,
+            original_end..original_source.len(),
+            &replacement,
+            &mut edits,
+        );
+
+        edits
+    }
+
+    fn reparse_edits(self, source: &str, edits: &[TextEdit], lua_version: LuaVersion) -> AstResult {
+        let (new_source, edits) = apply_edits(source, edits);
+
+        let (Some(first_edit), Some(last_edit)) = (edits.first(), edits.last()) else {
+            return AstResult {
+                ast: self,
+                errors: Vec::new(),
+            };
+        };
+
+        let edit = Edit {
+            start: first_edit.range.start,
+            end: last_edit.range.end,
+            bytes_delta: new_source.len() as isize - source.len() as isize,
+            old_source: source,
+            new_source: &new_source,
+            lua_version,
+        };
+
+        let Some(mut plan) = plan_block(&self.nodes, full_start(&self.eof), true, &edit) else {
+            return AstResult::parse_fallible(&new_source, lua_version);
+        };
+
+        let (old_line, old_character) = line_and_character(source, edit.end);
+        let (new_line, new_character) =
+            line_and_character(&new_source, edit.end.wrapping_add_signed(edit.bytes_delta));
+
+        let mut shifter = PositionShifter {
+            from_bytes: edit.end,
+            bytes_delta: edit.bytes_delta,
+            from_line: old_line,
+            line_delta: new_line as isize - old_line as isize,
+            character_delta: new_character as isize - old_character as isize,
+        };
+
+        let (mut nodes, eof) = self.into_parts();
+
+        // Everything before the first item that changes is before the edit, and stays where it is
+        let shift_from = plan
+            .path
+            .first()
+            .map_or(plan.replace.start, |&(index, _)| index);
+
+        nodes.stmts = nodes
+            .stmts
+            .into_iter()
+            .enumerate()
+            .map(|(index, stmt)| {
+                if index >= shift_from {
+                    stmt.visit_mut(&mut shifter)
+                } else {
+                    stmt
+                }
+            })
+            .collect();
+
+        nodes.last_stmt = nodes.last_stmt.visit_mut(&mut shifter);
+
+        let eof = match plan.eof.take() {
+            Some(eof) => eof,
+            None => eof.visit_mut(&mut shifter),
+        };
+
+        let mut block = &mut nodes;
+        for &(index, nested_index) in &plan.path {
+            block = nested_block_mut(&mut block.stmts[index].0, nested_index);
+        }
+
+        splice(block, plan);
+
+        AstResult {
+            ast: Ast { nodes, eof },
+            errors: Vec::new(),
+        }
+    }
+}
@@ -0,0 +1,524 @@
+//! Finding the nodes at a position in the code, such as for hover or go to definition.
+//!
+//! [`Ast::node_at`] gives the innermost node covering a position, along with every node it is
+//! inside of, and [`Ast::nodes_in_range`] gives every node inside of a range, such as a selection.
+//! Both find nodes by their [`range`](Node::range), which doesn't include the trivia around them.
+//!
+//! ```rust
+//! # use full_moon::{ast::Expression, node::SyntaxKind};
+//! # fn main() -> Result<(), Vec<full_moon::Error>> {
+//! let ast = full_moon::parse("local x = 1\nprint(x + 2)")?;
+//!
+//! // The byte offset of `2`
+//! let found = ast.node_at(22).unwrap();
+//! assert_eq!(found.token().unwrap().to_string(), "2");
+//! assert_eq!(found.node().cast::<Expression>().unwrap().to_string(), "2");
+//!
+//! let kinds = found.ancestors().map(|node| node.kind()).collect::<Vec<_>>();
+//! assert_eq!(kinds[..3], [SyntaxKind::Expression, SyntaxKind::Expression, SyntaxKind::FunctionArgs]);
+//! assert_eq!(kinds.last(), Some(&SyntaxKind::Block));
+//! # Ok(())
+//! # }
+//! ```
+use std::{fmt, ops::Range};
+
+use super::borrowed::with_node_kinds;
+use crate::{
+    ast::{self, Ast},
+    node::{AnyNode, AnyNodeType, Node, SyntaxKind},
+    private::Sealed,
+    tokenizer::{Position, TokenReference},
+    visitors::{Visit, VisitorRef},
+};
+
+#[cfg(any(feature = "lua52", feature = "luajit"))]
+use crate::ast::lua52::*;
+#[cfg(feature = "lua54")]
+use crate::ast::lua54::*;
+#[cfg(feature = "luau")]
+use crate::ast::luau::*;
+#[cfg(any(feature = "cfxlua", feature = "luau"))]
+use crate::ast::{CompoundAssignment, CompoundOp};
+
+/// A place in the code, either a [`Position`] or a byte offset.
+///
+/// This trait is sealed and cannot be implemented for types outside of `full-moon`
+pub trait Offset: Sealed {
+    /// How many bytes into the code this is
+    fn bytes(self) -> usize;
+}
+
+impl Sealed for usize {}
+
+impl Offset for usize {
+    fn bytes(self) -> usize {
+        self
+    }
+}
+
+impl Sealed for Position {}
+
+impl Offset for Position {
+    fn bytes(self) -> usize {
+        self.bytes
+    }
+}
+
+/// A node found by [`Ast::node_at`] or [`Ast::nodes_in_range`].
+/// The typed node it was found from is gotten with [`cast`](FoundNode::cast).
+#[derive(Clone, Copy)]
+pub struct FoundNode<'a> {
+    node: AnyNode<'a>,
+    range: (Position, Position),
+}
+
+impl<'a> FoundNode<'a> {
+    /// The kind of node this was found from
+    pub fn kind(&self) -> SyntaxKind {
+        self.node.kind()
+    }
+
+    /// The typed node this was found from, for walking it without knowing its type
+    pub fn any_node(&self) -> AnyNode<'a> {
+        self.node
+    }
+
+    /// The typed node this was found from, if it's a `T`.
+    /// For example, `node.cast::<ast::Stmt>()` gives the statement if the node is one.
+    pub fn cast<T: AnyNodeType>(&self) -> Option<&'a T> {
+        self.node.cast()
+    }
+
+    /// The start and end position of the node, not including the trivia around it
+    pub fn range(&self) -> (Position, Position) {
+        self.range
+    }
+}
+
+impl fmt::Debug for FoundNode<'_> {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        let (start, end) = self.range;
+
+        write!(
+            formatter,
+            "{:?}@{}:{}..{}:{}",
+            self.kind(),
+            start.line(),
+            start.character(),
+            end.line(),
+            end.character()
+        )
+    }
+}
+
+/// The nodes at a position in the code, returned by [`Ast::node_at`]
+#[derive(Clone, Debug)]
+pub struct NodeAt<'a> {
+    token: Option<&'a TokenReference>,
+    // From the root block to the innermost node, never empty
+    nodes: Vec<FoundNode<'a>>,
+}
+
+impl<'a> NodeAt<'a> {
+    /// The token at the position, or `None` if the position is in trivia, such as a comment
+    pub fn token(&self) -> Option<&'a TokenReference> {
+        self.token
+    }
+
+    /// The innermost node covering the position
+    pub fn node(&self) -> FoundNode<'a> {
+        *self.nodes.last().expect("NodeAt has no nodes")
+    }
+
+    /// The innermost node covering the position, followed by the node it is inside of,
+    /// and so on up to the [`Block`](ast::Block) of the whole file
+    pub fn ancestors(&self) -> impl Iterator<Item = FoundNode<'a>> + '_ {
+        self.nodes.iter().rev().copied()
+    }
+}
+
+impl Ast {
+    /// The innermost node covering a [`Position`] or byte offset, along with every node it is
+    /// inside of and the token at the offset. Returns `None` if the offset is outside of the code,
+    /// such as in the comments before the first token.
+    ///
+    /// This visits every node in the Ast. [`node_at_fast`](Ast::node_at_fast) finds the same
+    /// nodes without doing so.
+    pub fn node_at(&self, offset: impl Offset) -> Option<NodeAt<'_>> {
+        let mut finder = Finder::new(Search::At(offset.bytes()));
+        self.nodes().visit(&mut finder);
+        finder.into_node_at()
+    }
+
+    /// The same as [`node_at`](Ast::node_at), but only visits the nodes covering the offset,
+    /// and the nodes directly inside of them. The statements of every block the offset is in are
+    /// binary searched, rather than each being looked at.
+    ///
+    /// This relies on the statements being in the order of their positions, which is always true
+    /// of a parsed Ast, but may not be if statements were moved around without
+    /// [updating positions](Ast::update_positions).
+    pub fn node_at_fast(&self, offset: impl Offset) -> Option<NodeAt<'_>> {
+        let offset = offset.bytes();
+        let mut finder = Finder::new(Search::At(offset));
+        let mut node = AnyNode::from(self.nodes());
+
+        loop {
+            if is_visited(node) {
+                finder.enter(node);
+            }
+
+            let mut children: Vec<_> = node.children().collect();
+
+            if let AnyNode::Block(_) = node {
+                let index = children.partition_point(|child| ends_before(child, offset));
+                children = children.get(index).copied().into_iter().collect();
+            }
+
+            match children.iter().find(|child| covers(**child, offset)) {
+                Some(AnyNode::TokenReference(token)) => {
+                    finder.token = Some(token);
+                    break;
+                }
+
+                Some(AnyNode::ContainedSpan(span)) => {
+                    finder.token = token_at(AnyNode::ContainedSpan(span), offset);
+                    break;
+                }
+
+                Some(child) => node = *child,
+
+                None => {
+                    finder.token = children
+                        .into_iter()
+                        .find_map(|child| token_at(child, offset));
+                    break;
+                }
+            }
+        }
+
+        finder.into_node_at()
+    }
+
+    /// Every node that is entirely inside of a range of [`Position`]s or byte offsets, with
+    /// parents before their children. Nodes only partly inside of the range are not included.
+    pub fn nodes_in_range<O: Offset>(&self, range: Range<O>) -> Vec<FoundNode<'_>> {
+        let mut finder = Finder::new(Search::In(range.start.bytes()..range.end.bytes()));
+        self.nodes().visit(&mut finder);
+        finder.nodes
+    }
+}
+
+fn ends_before(node: &impl Node, offset: usize) -> bool {
+    node.end_position()
+        .is_some_and(|end_position| end_position.bytes <= offset)
+}
+
+// The token at the offset in the node, for when it's in none of the nodes directly inside of it.
+// This is either because the offset is in trivia, or because the range of a node can leave out
+// its last token, such as the `]` of an index.
+fn token_at(node: AnyNode<'_>, offset: usize) -> Option<&TokenReference> {
+    match node {
+        AnyNode::TokenReference(token) => covers(node, offset).then_some(token),
+        _ => node.children().find_map(|child| token_at(child, offset)),
+    }
+}
+
+// Whether the offset is in the node. Only the brackets of a contained span are in it, and not
+// what they contain, which is a sibling of the span.
+fn covers(node: AnyNode, offset: usize) -> bool {
+    match node {
+        AnyNode::ContainedSpan(span) => {
+            let (start, end) = span.tokens();
+            covers(AnyNode::TokenReference(start), offset)
+                || covers(AnyNode::TokenReference(end), offset)
+        }
+
+        _ => node
+            .range()
+            .is_some_and(|(start, end)| start.bytes <= offset && offset < end.bytes),
+    }
+}
+
+macro_rules! finder_visitor {
+    (
+        $($kind:ident => $visit_name:ident,)+
+
+        $(#[$meta:meta] {
+            $($meta_kind:ident => $meta_visit_name:ident,)+
+        })+
+    ) => {
+        impl<'a> VisitorRef<'a> for Finder<'a> {
+            fn visit_token_reference(&mut self, token: &'a TokenReference) {
+                if let Search::At(offset) = self.search {
+                    if token.range().is_some_and(|(start, end)| start.bytes <= offset && offset < end.bytes) {
+                        self.token = Some(token);
+                    }
+                }
+            }
+
+            $(
+                fn $visit_name(&mut self, node: &'a ast::$kind) {
+                    self.enter(AnyNode::from(node));
+                }
+            )+
+
+            $($(
+                #[$meta]
+                fn $meta_visit_name(&mut self, node: &'a $meta_kind) {
+                    self.enter(AnyNode::from(node));
+                }
+            )+)+
+        }
+    };
+}
+
+with_node_kinds!(finder_visitor);
+
+macro_rules! is_visited {
+    (
+        $($kind:ident => $visit_name:ident,)+
+
+        $(#[$meta:meta] {
+            $($meta_kind:ident => $meta_visit_name:ident,)+
+        })+
+    ) => {
+        // Whether visitors enter the node, which are the nodes that can be found,
+        // rather than tokens and the like
+        fn is_visited(node: AnyNode) -> bool {
+            match node {
+                $(
+                    AnyNode::$kind(_) => true,
+                )+
+
+                $($(
+                    #[$meta]
+                    AnyNode::$meta_kind(_) => true,
+                )+)+
+
+                _ => false,
+            }
+        }
+    };
+}
+
+with_node_kinds!(is_visited);
+
+enum Search {
+    At(usize),
+    In(Range<usize>),
+}
+
+struct Finder<'a> {
+    search: Search,
+    token: Option<&'a TokenReference>,
+    nodes: Vec<FoundNode<'a>>,
+}
+
+impl<'a> Finder<'a> {
+    fn new(search: Search) -> Self {
+        Self {
+            search,
+            token: None,
+            nodes: Vec::new(),
+        }
+    }
+
+    // Nodes are entered outside in, and the nodes covering an offset are all inside of one
+    // another, so the nodes found for `Search::At` are always a chain from the root.
+    fn enter(&mut self, node: AnyNode<'a>) {
+        let Some(range) = node.range() else {
+            return;
+        };
+
+        let (start, end) = (range.0.bytes, range.1.bytes);
+
+        let found = match &self.search {
+            Search::At(offset) => start <= *offset && *offset < end,
+            Search::In(search_range) => search_range.start <= start && end <= search_range.end,
+        };
+
+        if found {
+            self.nodes.push(FoundNode { node, range });
+        }
+    }
+
+    fn into_node_at(self) -> Option<NodeAt<'a>> {
+        if self.nodes.is_empty() {
+            return None;
+        }
+
+        Some(NodeAt {
+            token: self.token,
+            nodes: self.nodes,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ast::{Expression, LocalAssignment, Stmt};
+
+    #[test]
+    fn test_node_at() {
+        let source = "local x = 1\n-- comment\nlocal y = x + 2";
+        let ast = crate::parse(source).unwrap();
+
+        let found = ast.node_at(source.find('+').unwrap()).unwrap();
+        assert_eq!(found.token().unwrap().to_string(), "+ ");
+        assert!(matches!(
+            found.node().cast::<Expression>(),
+            Some(Expression::BinaryOperator { .. })
+        ));
+
+        let kinds: Vec<_> = found.ancestors().map(|node| node.kind()).collect();
+        assert_eq!(
+            kinds,
+            [
+                SyntaxKind::Expression,
+                SyntaxKind::LocalAssignment,
+                SyntaxKind::Stmt,
+                SyntaxKind::Block
+            ]
+        );
+
+        let (start, end) = found.node().range();
+        assert_eq!((start.line(), start.character()), (3, 11));
+        assert_eq!((end.line(), end.character()), (3, 16));
+
+        let in_comment = ast.node_at(source.find("comment").unwrap()).unwrap();
+        assert!(in_comment.token().is_none());
+        assert_eq!(in_comment.node().kind(), SyntaxKind::Block);
+
+        let Some(Stmt::LocalAssignment(local_assignment)) = ast.nodes().stmts().next() else {
+            unreachable!()
+        };
+
+        let position = local_assignment
+            .names()
+            .iter()
+            .next()
+            .unwrap()
+            .start_position()
+            .unwrap();
+        let found = ast.node_at(position).unwrap();
+        assert_eq!(found.token().unwrap().to_string(), "x ");
+        assert!(std::ptr::eq(
+            found.node().cast::<LocalAssignment>().unwrap(),
+            local_assignment
+        ));
+
+        assert!(ast.node_at(source.len()).is_none());
+        assert!(crate::parse("").unwrap().node_at(0).is_none());
+    }
+
+    fn assert_node_at_fast_matches(source: &str) {
+        let Ok(ast) = crate::parse(source) else {
+            return;
+        };
+
+        for offset in 0..=source.len() {
+            let walked = ast.node_at(offset);
+            let fast = ast.node_at_fast(offset);
+
+            assert_eq!(
+                walked.as_ref().map(|found| found
+                    .ancestors()
+                    .map(|node| (node.kind(), node.range()))
+                    .collect::<Vec<_>>()),
+                fast.as_ref().map(|found| found
+                    .ancestors()
+                    .map(|node| (node.kind(), node.range()))
+                    .collect::<Vec<_>>()),
+                "different nodes at {offset} in {source}"
+            );
+
+            assert_eq!(
+                walked
+                    .and_then(|found| found.token())
+                    .map(ToString::to_string),
+                fast.and_then(|found| found.token())
+                    .map(ToString::to_string),
+                "different tokens at {offset} in {source}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_node_at_fast() {
+        assert_node_at_fast_matches("local x = 1; do\n\tprint(x)\nend\nlocal function f(a, b)\n\treturn a .. b\nend\nreturn f(x, 2);");
+        assert_node_at_fast_matches("return {\n\tf = function()\n\t\tif x then\n\t\t\tlocal y = (x[1])\n\t\tend\n\tend,\n}\n");
+
+        assert_node_at_fast_matches("x = t[1]\ny = { [1] = t[2] }\n");
+
+        let cases = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/cases/pass");
+
+        for entry in std::fs::read_dir(cases).unwrap() {
+            let path = entry.unwrap().path().join("source.lua");
+            assert_node_at_fast_matches(&std::fs::read_to_string(path).unwrap());
+        }
+    }
+
+    #[test]
+    fn test_node_at_fast_nested_blocks() {
+        // Each nested statement is a different number of levels deep
+        let mut source = String::new();
+        for index in 0..2000 {
+            source.push_str(&"do ".repeat(index % 10));
+            source.push_str("local x = 1 ");
+            source.push_str(&"end ".repeat(index % 10));
+        }
+
+        let ast = crate::parse(&source).unwrap();
+        let offset = source.rfind("local").unwrap();
+
+        let found = ast.node_at_fast(offset).unwrap();
+        assert_eq!(found.token().unwrap().to_string(), "local ");
+        assert_eq!(
+            found
+                .ancestors()
+                .map(|node| node.range())
+                .collect::<Vec<_>>(),
+            ast.node_at(offset)
+                .unwrap()
+                .ancestors()
+                .map(|node| node.range())
+                .collect::<Vec<_>>(),
+        );
+    }
+
+    #[test]
+    fn test_nodes_in_range() {
+        let source = "local x = 1\nlocal y = x + 2\nprint(y)";
+        let ast = crate::parse(source).unwrap();
+
+        let start = source.find("x +").unwrap();
+        let nodes = ast.nodes_in_range(start..start + "x + 2".len());
+
+        let kinds: Vec<_> = nodes.iter().map(|node| node.kind()).collect();
+        assert_eq!(
+            kinds,
+            [
+                SyntaxKind::Expression,
+                SyntaxKind::Expression,
+                SyntaxKind::Var,
+                SyntaxKind::Expression
+            ]
+        );
+        assert_eq!(
+            nodes[0].cast::<Expression>().unwrap().to_string(),
+            "x + 2\n"
+        );
+
+        let Some(Stmt::FunctionCall(call)) = ast.nodes().stmts().nth(2) else {
+            unreachable!()
+        };
+
+        let (start, end) = call.range().unwrap();
+        let nodes = ast.nodes_in_range(start..end);
+        assert_eq!(nodes[0].kind(), SyntaxKind::Stmt);
+        assert!(std::ptr::eq(nodes[1].cast().unwrap(), call));
+
+        assert!(ast.nodes_in_range(0..3).is_empty());
+    }
+}
@@ -3,13 +3,13 @@
 
 use crate::tokenizer::TokenReference;
 use derive_more::Display;
-use full_moon_derive::{Node, Visit};
+use full_moon_derive::{MutAccessors, Node, Visit};
 
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 
 /// A goto statement, such as `goto label`.
-#[derive(Clone, Debug, Display, PartialEq, Eq, Node, Visit)]
+#[derive(Clone, Debug, Display, PartialEq, Eq, MutAccessors, Node, Visit)]
 #[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 #[display("{goto_token}{label_name}")]
 pub struct Goto {
@@ -48,7 +48,7 @@ impl Goto {
 }
 
 /// A label, such as `::label::`.
-#[derive(Clone, Debug, Display, PartialEq, Eq, Node, Visit)]
+#[derive(Clone, Debug, Display, PartialEq, Eq, MutAccessors, Node, Visit)]
 #[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 #[display("{left_colons}{name}{right_colons}")]
 pub struct Label {
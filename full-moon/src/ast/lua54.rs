@@ -3,13 +3,13 @@
 
 use crate::{ast::ContainedSpan, tokenizer::TokenReference};
 use derive_more::Display;
-use full_moon_derive::{Node, Visit};
+use full_moon_derive::{MutAccessors, Node, Visit};
 
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 
 /// An attribute on a local variable, `<const>` in `local x <const>`
-#[derive(Clone, Debug, Display, PartialEq, Eq, Node, Visit)]
+#[derive(Clone, Debug, Display, PartialEq, Eq, MutAccessors, Node, Visit)]
 #[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 #[display("{}{}{}", brackets.tokens().0, name, brackets.tokens().1)]
 pub struct Attribute {
@@ -182,7 +182,7 @@ pub enum TypeInfo {
 }
 
 /// A union type, such as `string | number`.
-#[derive(Clone, Debug, Display, PartialEq, Node)]
+#[derive(Clone, Debug, Display, PartialEq, MutAccessors, Node)]
 #[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 #[display("{}{types}", display_option(leading))]
 pub struct TypeUnion {
@@ -218,7 +218,7 @@ impl TypeUnion {
 }
 
 /// An intersection type, such as `string & number`.
-#[derive(Clone, Debug, Display, PartialEq, Node)]
+#[derive(Clone, Debug, Display, PartialEq, MutAccessors, Node)]
 #[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 #[display("{}{types}", display_option(leading))]
 pub struct TypeIntersection {
@@ -276,7 +276,7 @@ pub enum IndexedTypeInfo {
 
 /// A type field used within table types.
 /// The `foo: number` in `{ foo: number }`.
-#[derive(Clone, Debug, Display, PartialEq, Node, Visit)]
+#[derive(Clone, Debug, Display, PartialEq, MutAccessors, Node, Visit)]
 #[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 #[display("{}{key}{colon}{value}", display_option(access))]
 pub struct TypeField {
@@ -286,6 +286,7 @@ pub struct TypeField {
     )]
     pub(crate) access: Option<TokenReference>,
     pub(crate) key: TypeFieldKey,
+    #[mut_accessor(name = "colon_token")]
     pub(crate) colon: TokenReference,
     pub(crate) value: TypeInfo,
 }
@@ -358,6 +359,7 @@ pub enum TypeFieldKey {
     #[display("{}{}{}", brackets.tokens().0, inner, brackets.tokens().1)]
     IndexSignature {
         /// The brackets (`[]`) used to contain the type.
+        #[node(full_range)]
         brackets: ContainedSpan,
 
         /// The type for the index signature, `number` in `[number]`.
@@ -366,7 +368,7 @@ pub enum TypeFieldKey {
 }
 
 /// A type assertion using `::`, such as `:: number`.
-#[derive(Clone, Debug, Display, PartialEq, Node, Visit)]
+#[derive(Clone, Debug, Display, PartialEq, MutAccessors, Node, Visit)]
 #[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 #[display("{assertion_op}{cast_to}")]
 pub struct TypeAssertion {
@@ -408,7 +410,7 @@ impl TypeAssertion {
 }
 
 /// A type declaration, such as `type Meters = number`
-#[derive(Clone, Debug, Display, PartialEq, Node, Visit)]
+#[derive(Clone, Debug, Display, PartialEq, MutAccessors, Node, Visit)]
 #[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 #[display(
     "{}{}{}{}{}",
@@ -420,9 +422,11 @@ impl TypeAssertion {
 )]
 pub struct TypeDeclaration {
     pub(crate) type_token: TokenReference,
+    #[mut_accessor(name = "type_name")]
     pub(crate) base: TokenReference,
     pub(crate) generics: Option<GenericDeclaration>,
     pub(crate) equal_token: TokenReference,
+    #[mut_accessor(name = "type_definition")]
     pub(crate) declare_as: TypeInfo,
 }
 
@@ -523,7 +527,7 @@ pub enum GenericParameterInfo {
     },
 }
 /// A generic declaration parameter us in [`GenericDeclaration`]. Consists of a [`GenericParameterInfo`] and an optional default type.
-#[derive(Clone, Debug, Display, PartialEq, Node, Visit)]
+#[derive(Clone, Debug, Display, PartialEq, MutAccessors, Node, Visit)]
 #[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 #[display(
     "{}{}{}",
@@ -572,7 +576,7 @@ impl GenericDeclarationParameter {
 }
 
 /// The generics used in a [`TypeDeclaration`].
-#[derive(Clone, Debug, Display, PartialEq, Node, Visit)]
+#[derive(Clone, Debug, Display, PartialEq, MutAccessors, Node, Visit)]
 #[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 #[display("{}{}{}", arrows.tokens().0, generics, arrows.tokens().1)]
 pub struct GenericDeclaration {
@@ -621,7 +625,7 @@ impl Default for GenericDeclaration {
 }
 
 /// A type specifier, the `: number` in `local foo: number`
-#[derive(Clone, Debug, Display, PartialEq, Node, Visit)]
+#[derive(Clone, Debug, Display, PartialEq, MutAccessors, Node, Visit)]
 #[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 #[display("{punctuation}{type_info}")]
 pub struct TypeSpecifier {
@@ -664,7 +668,7 @@ impl TypeSpecifier {
 }
 
 /// A type argument specified in a callback type, the `count: number` in `(count: number) -> ()`
-#[derive(Clone, Debug, PartialEq, Node, Visit)]
+#[derive(Clone, Debug, PartialEq, MutAccessors, Node, Visit)]
 #[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 pub struct TypeArgument {
     pub(crate) name: Option<(TokenReference, TokenReference)>,
@@ -712,7 +716,7 @@ impl fmt::Display for TypeArgument {
 }
 
 /// An exported type declaration, such as `export type Meters = number`
-#[derive(Clone, Debug, Display, PartialEq, Node, Visit)]
+#[derive(Clone, Debug, Display, PartialEq, MutAccessors, Node, Visit)]
 #[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 #[display("{export_token}{type_declaration}")]
 pub struct ExportedTypeDeclaration {
@@ -765,7 +769,7 @@ impl ExportedTypeDeclaration {
 /// A user defined type function, such as `type function foo() ... end`.
 ///
 /// See more: https://github.com/luau-lang/rfcs/blob/master/docs/user-defined-type-functions.md
-#[derive(Clone, Debug, Display, PartialEq, Node, Visit)]
+#[derive(Clone, Debug, Display, PartialEq, MutAccessors, Node, Visit)]
 #[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 #[display("{}{}{}{}", type_token, function_token, function_name, function_body)]
 pub struct TypeFunction {
@@ -843,7 +847,7 @@ impl TypeFunction {
 }
 
 /// An exported type function, such as `export type function Pairs() ... end`
-#[derive(Clone, Debug, Display, PartialEq, Node, Visit)]
+#[derive(Clone, Debug, Display, PartialEq, MutAccessors, Node, Visit)]
 #[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 #[display("{export_token}{type_function}")]
 pub struct ExportedTypeFunction {
@@ -910,7 +914,7 @@ pub type CompoundAssignment = crate::ast::compound::CompoundAssignment;
 pub type CompoundOp = crate::ast::compound::CompoundOp;
 
 /// An if statement
-#[derive(Clone, Debug, Display, PartialEq, Node, Visit)]
+#[derive(Clone, Debug, Display, PartialEq, MutAccessors, Node, Visit)]
 #[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 #[display(
     "{}{}{}{}{}{}{}",
@@ -1035,7 +1039,7 @@ impl IfExpression {
 }
 
 /// An elseif expression in a bigger [`IfExpression`] expression
-#[derive(Clone, Debug, Display, PartialEq, Node, Visit)]
+#[derive(Clone, Debug, Display, PartialEq, MutAccessors, Node, Visit)]
 #[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 #[display("{else_if_token}{condition}{then_token}{expression}")]
 pub struct ElseIfExpression {
@@ -1108,7 +1112,7 @@ impl ElseIfExpression {
 /// [`TokenType`](crate::tokenizer::TokenType) of `InterpolatedString { token: "1", kind: InterpolatedStringKind::Begin }`),
 /// and the expression `2`.
 /// The `last_string` would be the literal 3, with a backtick afterwards.
-#[derive(Clone, Debug, Display, PartialEq, Node, Visit)]
+#[derive(Clone, Debug, Display, PartialEq, MutAccessors, Node, Visit)]
 #[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 #[display("{}{}", join_vec(segments), last_string)]
 pub struct InterpolatedString {
@@ -1171,7 +1175,7 @@ pub struct InterpolatedStringSegment {
 }
 
 impl Visit for InterpolatedStringSegment {
-    fn visit<V: crate::visitors::Visitor>(&self, visitor: &mut V) {
+    fn visit<'ast, V: crate::visitors::VisitorRef<'ast>>(&'ast self, visitor: &mut V) {
         self.literal.visit(visitor);
         self.expression.visit(visitor);
     }
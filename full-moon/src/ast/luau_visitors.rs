@@ -1,7 +1,7 @@
 // Implementations of Visit and VisitMut that are not able to be automatically derived yet.
 // Ideally everything would be derived.
 use super::*;
-use crate::visitors::{Visit, VisitMut, Visitor, VisitorMut};
+use crate::visitors::{Visit, VisitMut, VisitorMut, VisitorRef};
 
 // The following have `ContainedSpan`, which when automatically derived will visit the tokens containing
 // before they visit what they're actually containing.
@@ -9,7 +9,7 @@ use crate::visitors::{Visit, VisitMut, Visitor, VisitorMut};
 // Then visitors will visit this as `()foo`.
 // This is fixed for structs with `#[visit(contains = "...")], but this is not supported on enums.
 impl Visit for TypeInfo {
-    fn visit<V: Visitor>(&self, visitor: &mut V) {
+    fn visit<'ast, V: VisitorRef<'ast>>(&'ast self, visitor: &mut V) {
         visitor.visit_type_info(self);
         match self {
             TypeInfo::Array {
@@ -274,7 +274,7 @@ impl VisitMut for TypeInfo {
 }
 
 impl Visit for TypeUnion {
-    fn visit<V: Visitor>(&self, visitor: &mut V) {
+    fn visit<'ast, V: VisitorRef<'ast>>(&'ast self, visitor: &mut V) {
         visitor.visit_type_union(self);
 
         self.leading.visit(visitor);
@@ -296,7 +296,7 @@ impl VisitMut for TypeUnion {
 }
 
 impl Visit for TypeIntersection {
-    fn visit<V: Visitor>(&self, visitor: &mut V) {
+    fn visit<'ast, V: VisitorRef<'ast>>(&'ast self, visitor: &mut V) {
         visitor.visit_type_intersection(self);
 
         self.leading.visit(visitor);
@@ -318,7 +318,7 @@ impl VisitMut for TypeIntersection {
 }
 
 impl Visit for IndexedTypeInfo {
-    fn visit<V: Visitor>(&self, visitor: &mut V) {
+    fn visit<'ast, V: VisitorRef<'ast>>(&'ast self, visitor: &mut V) {
         visitor.visit_indexed_type_info(self);
         match self {
             IndexedTypeInfo::Basic(__self_0) => {
@@ -368,7 +368,7 @@ impl VisitMut for IndexedTypeInfo {
 }
 
 impl Visit for TypeFieldKey {
-    fn visit<V: Visitor>(&self, visitor: &mut V) {
+    fn visit<'ast, V: VisitorRef<'ast>>(&'ast self, visitor: &mut V) {
         visitor.visit_type_field_key(self);
         match self {
             TypeFieldKey::Name(__self_0) => {
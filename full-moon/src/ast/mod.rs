@@ -5,7 +5,7 @@ use derive_more::Display;
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 
-use full_moon_derive::{Node, Visit};
+use full_moon_derive::{MutAccessors, Node, Visit};
 #[cfg(any(feature = "lua52", feature = "luajit"))]
 use lua52::*;
 #[cfg(feature = "lua54")]
@@ -19,22 +19,37 @@ mod compound;
 #[cfg(any(feature = "luau", feature = "cfxlua"))]
 pub use compound::*;
 
-pub use parser_structs::AstResult;
+pub use incremental::TextEdit;
+pub use parser_structs::{AstResult, FragmentResult, ParseOptions};
 use punctuated::{Pair, Punctuated};
 use span::ContainedSpan;
 pub use versions::*;
 
 use crate::{
-    tokenizer::{Position, Symbol, Token, TokenReference, TokenType},
+    node::Node,
+    tokenizer::{Position, Symbol, Token, TokenKind, TokenReference, TokenType},
     util::*,
+    visitors::{VisitMut, VisitorMut},
 };
 
+pub mod borrowed;
+pub(crate) mod constructors;
+pub mod diff;
+pub mod directives;
+mod duplicates;
+pub(crate) mod fragments;
+mod incremental;
+pub mod lookup;
+pub mod node_id;
 mod parser_structs;
 #[macro_use]
 mod parser_util;
 mod parsers;
 pub mod punctuated;
+pub(crate) mod separate_tokens;
 pub mod span;
+pub mod syntax;
+pub mod template;
 mod update_positions;
 mod visitors;
 
@@ -49,7 +64,7 @@ pub mod lua52;
 #[cfg(feature = "lua54")]
 pub mod lua54;
 /// A block of statements, such as in if/do/etc block
-#[derive(Clone, Debug, Default, Display, PartialEq, Node, Visit)]
+#[derive(Clone, Debug, Default, Display, PartialEq, MutAccessors, Node, Visit)]
 #[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 #[display(
     "{}{}",
@@ -114,6 +129,31 @@ impl Block {
             self.last_stmt = other.last_stmt;
         }
     }
+
+    // Adds an error statement with the given tokens, if there are any.
+    // Anything after a last statement can't be put in the block, so it becomes an error statement first.
+    pub(crate) fn push_error_stmt(&mut self, tokens: Vec<TokenReference>) {
+        if tokens.is_empty() {
+            return;
+        }
+
+        self.demote_last_stmt();
+        self.stmts.push((Stmt::Error(tokens), None));
+    }
+
+    // Turns the last statement into an error statement, so that statements can be put after it
+    pub(crate) fn demote_last_stmt(&mut self) {
+        if let Some((last_stmt, semicolon)) = self.last_stmt.take() {
+            let tokens = last_stmt
+                .tokens()
+                .chain(semicolon.iter())
+                .filter(|token| !token.is_phantom())
+                .cloned()
+                .collect();
+
+            self.stmts.push((Stmt::Error(tokens), None));
+        }
+    }
 }
 
 /// The last statement of a [`Block`]
@@ -132,7 +172,7 @@ pub enum LastStmt {
 }
 
 /// A `return` statement
-#[derive(Clone, Debug, Display, PartialEq, Node, Visit)]
+#[derive(Clone, Debug, Display, PartialEq, MutAccessors, Node, Visit)]
 #[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 #[display("{token}{returns}")]
 pub struct Return {
@@ -229,7 +269,7 @@ pub enum Field {
 }
 
 /// A table being constructed, such as `{ 1, 2, 3 }` or `{ a = 1 }`
-#[derive(Clone, Debug, Display, PartialEq, Node, Visit)]
+#[derive(Clone, Debug, Display, PartialEq, MutAccessors, Node, Visit)]
 #[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 #[display("{}{}{}", braces.tokens().0, fields, braces.tokens().1)]
 pub struct TableConstructor {
@@ -365,6 +405,19 @@ pub enum Expression {
     /// A more complex value, such as `call().x`
     #[display("{_0}")]
     Var(Var),
+
+    /// An expression that couldn't be parsed, holding any tokens that were skipped.
+    /// For example, the right hand side of `1 +` is an error with no tokens.
+    /// Only produced when there are errors, such as from [`parse_fallible`](crate::parse_fallible).
+    #[display("{}", join_vec(_0))]
+    Error(Vec<TokenReference>),
+}
+
+impl Expression {
+    // An empty expression that is cheap to create, used when moving expressions out of others
+    pub(crate) fn placeholder() -> Self {
+        Expression::Error(Vec::new())
+    }
 }
 
 /// A statement that stands alone
@@ -436,6 +489,12 @@ pub enum Stmt {
     /// Only available when the "lua52" or "luajit" feature flag is enabled.
     #[cfg(any(feature = "lua52", feature = "luajit"))]
     Label(Label),
+
+    /// Tokens that were skipped while recovering from an error, such as the stray `end` in `x = 1 end`,
+    /// kept so that the code can still be printed back exactly.
+    /// Only produced when there are errors, such as from [`parse_fallible`](crate::parse_fallible).
+    #[display("{}", join_vec(_0))]
+    Error(Vec<TokenReference>),
 }
 
 /// A node used before another in cases such as function calling
@@ -462,6 +521,7 @@ pub enum Index {
     #[display("{}{}{}", brackets.tokens().0, expression, brackets.tokens().1)]
     Brackets {
         /// The `[...]` part of `["y"]`
+        #[node(full_range)]
         brackets: ContainedSpan,
         /// The `"y"` part of `["y"]`
         expression: Expression,
@@ -518,7 +578,7 @@ impl FunctionArgs {
 }
 
 /// A numeric for loop, such as `for index = 1, 10 do end`
-#[derive(Clone, Debug, PartialEq, Node)]
+#[derive(Clone, Debug, PartialEq, MutAccessors, Node)]
 #[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 pub struct NumericFor {
     for_token: TokenReference,
@@ -745,12 +805,13 @@ impl fmt::Display for NumericFor {
 }
 
 /// A generic for loop, such as `for index, value in pairs(list) do end`
-#[derive(Clone, Debug, PartialEq, Node)]
+#[derive(Clone, Debug, PartialEq, MutAccessors, Node)]
 #[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 pub struct GenericFor {
     for_token: TokenReference,
     names: Punctuated<TokenReference>,
     in_token: TokenReference,
+    #[mut_accessor(name = "expressions")]
     expr_list: Punctuated<Expression>,
     do_token: TokenReference,
     block: Block,
@@ -904,7 +965,7 @@ impl fmt::Display for GenericFor {
 }
 
 /// An if statement
-#[derive(Clone, Debug, Display, PartialEq, Node, Visit)]
+#[derive(Clone, Debug, Display, PartialEq, MutAccessors, Node, Visit)]
 #[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 #[display(
     "{}{}{}{}{}{}{}{}",
@@ -925,6 +986,7 @@ pub struct If {
     else_if: Option<Vec<ElseIf>>,
     else_token: Option<TokenReference>,
     #[cfg_attr(feature = "serde", serde(rename = "else"))]
+    #[mut_accessor(name = "else_block")]
     r#else: Option<Block>,
     end_token: TokenReference,
 }
@@ -1028,7 +1090,7 @@ impl If {
 }
 
 /// An elseif block in a bigger [`If`] statement
-#[derive(Clone, Debug, Display, PartialEq, Node, Visit)]
+#[derive(Clone, Debug, Display, PartialEq, MutAccessors, Node, Visit)]
 #[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 #[display("{else_if_token}{condition}{then_token}{block}")]
 pub struct ElseIf {
@@ -1094,7 +1156,7 @@ impl ElseIf {
 }
 
 /// A while loop
-#[derive(Clone, Debug, Display, PartialEq, Node, Visit)]
+#[derive(Clone, Debug, Display, PartialEq, MutAccessors, Node, Visit)]
 #[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 #[display("{while_token}{condition}{do_token}{block}{end_token}")]
 pub struct While {
@@ -1172,7 +1234,7 @@ impl While {
 }
 
 /// A repeat loop
-#[derive(Clone, Debug, Display, PartialEq, Node, Visit)]
+#[derive(Clone, Debug, Display, PartialEq, MutAccessors, Node, Visit)]
 #[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 #[display("{repeat_token}{block}{until_token}{until}")]
 pub struct Repeat {
@@ -1241,7 +1303,7 @@ impl Repeat {
 }
 
 /// A method call, such as `x:y()`
-#[derive(Clone, Debug, Display, PartialEq, Node, Visit)]
+#[derive(Clone, Debug, Display, PartialEq, MutAccessors, Node, Visit)]
 #[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 #[display("{colon_token}{name}{args}")]
 pub struct MethodCall {
@@ -1308,7 +1370,7 @@ pub enum Call {
 }
 
 /// A function body, everything except `function x` in `function x(a, b, c) call() end`
-#[derive(Clone, Debug, PartialEq, Node)]
+#[derive(Clone, Debug, PartialEq, MutAccessors, Node)]
 #[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 pub struct FunctionBody {
     #[cfg(feature = "luau")]
@@ -1506,7 +1568,7 @@ pub enum Suffix {
 }
 
 /// A complex expression used by [`Var`], consisting of both a prefix and suffixes
-#[derive(Clone, Debug, Display, PartialEq, Node, Visit)]
+#[derive(Clone, Debug, Display, PartialEq, MutAccessors, Node, Visit)]
 #[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 #[display("{}{}", prefix, join_vec(suffixes))]
 pub struct VarExpression {
@@ -1558,12 +1620,14 @@ pub enum Var {
 }
 
 /// An assignment, such as `x = y`. Not used for [`LocalAssignment`s](LocalAssignment)
-#[derive(Clone, Debug, Display, PartialEq, Node, Visit)]
+#[derive(Clone, Debug, Display, PartialEq, MutAccessors, Node, Visit)]
 #[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 #[display("{var_list}{equal_token}{expr_list}")]
 pub struct Assignment {
+    #[mut_accessor(name = "variables")]
     var_list: Punctuated<Var>,
     equal_token: TokenReference,
+    #[mut_accessor(name = "expressions")]
     expr_list: Punctuated<Expression>,
 }
 
@@ -1614,7 +1678,7 @@ impl Assignment {
 }
 
 /// A declaration of a local function, such as `local function x() end`
-#[derive(Clone, Debug, Display, PartialEq, Node, Visit)]
+#[derive(Clone, Debug, Display, PartialEq, MutAccessors, Node, Visit)]
 #[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 #[cfg_attr(
     not(feature = "luau"),
@@ -1687,7 +1751,7 @@ impl LocalFunction {
 }
 
 /// An assignment to a local variable, such as `local x = 1`
-#[derive(Clone, Debug, PartialEq, Node)]
+#[derive(Clone, Debug, PartialEq, MutAccessors, Node)]
 #[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 pub struct LocalAssignment {
     local_token: TokenReference,
@@ -1697,6 +1761,7 @@ pub struct LocalAssignment {
         serde(skip_serializing_if = "empty_optional_vector")
     )]
     type_specifiers: Vec<Option<TypeSpecifier>>,
+    #[mut_accessor(name = "names")]
     name_list: Punctuated<TokenReference>,
     #[cfg(feature = "lua54")]
     #[cfg_attr(
@@ -1705,6 +1770,7 @@ pub struct LocalAssignment {
     )]
     attributes: Vec<Option<Attribute>>,
     equal_token: Option<TokenReference>,
+    #[mut_accessor(name = "expressions")]
     expr_list: Punctuated<Expression>,
 }
 
@@ -1829,7 +1895,7 @@ impl fmt::Display for LocalAssignment {
 
 /// A `do` block, such as `do ... end`
 /// This is not used for things like `while true do end`, only those on their own
-#[derive(Clone, Debug, Display, PartialEq, Node, Visit)]
+#[derive(Clone, Debug, Display, PartialEq, MutAccessors, Node, Visit)]
 #[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 #[display("{do_token}{block}{end_token}")]
 pub struct Do {
@@ -1886,7 +1952,7 @@ impl Default for Do {
 }
 
 /// A function being called, such as `call()`
-#[derive(Clone, Debug, Display, PartialEq, Node, Visit)]
+#[derive(Clone, Debug, Display, PartialEq, MutAccessors, Node, Visit)]
 #[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 #[display("{}{}", prefix, join_vec(suffixes))]
 pub struct FunctionCall {
@@ -1934,7 +2000,7 @@ impl FunctionCall {
 }
 
 /// A function name when being declared as [`FunctionDeclaration`]
-#[derive(Clone, Debug, Display, PartialEq, Eq, Node, Visit)]
+#[derive(Clone, Debug, Display, PartialEq, Eq, MutAccessors, Node, Visit)]
 #[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 #[display(
     "{}{}{}",
@@ -1944,6 +2010,7 @@ impl FunctionCall {
 )]
 pub struct FunctionName {
     names: Punctuated<TokenReference>,
+    #[mut_accessor(name = "method")]
     colon_name: Option<(TokenReference, TokenReference)>,
 }
 
@@ -1989,7 +2056,7 @@ impl FunctionName {
 
 /// A normal function declaration, supports simple declarations like `function x() end`
 /// as well as complicated declarations such as `function x.y.z:a() end`
-#[derive(Clone, Debug, Display, PartialEq, Node, Visit)]
+#[derive(Clone, Debug, Display, PartialEq, MutAccessors, Node, Visit)]
 #[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 #[cfg_attr(not(feature = "luau"), display("{function_token}{name}{body}"))]
 #[cfg_attr(feature = "luau", display("{function_token}{name}{body}"))]
@@ -2225,6 +2292,97 @@ impl UnOp {
     }
 }
 
+/// The kind of error that occurred when creating the AST, for when the error needs to be handled
+/// without reading the message, such as to offer a fix for it.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[non_exhaustive]
+pub enum AstErrorKind {
+    /// A specific symbol was expected, such as `end` to close a block or `)` to close a call
+    ExpectedSymbol(Symbol),
+    /// A name was expected, such as after `local` or `.`
+    ExpectedName,
+    /// An expression was expected, such as after `=` or a binary operator
+    ExpectedExpression,
+    /// Arguments to a call were expected, such as after `object:method`
+    ExpectedArguments,
+    /// A block was expected, such as after `then`
+    ExpectedBlock,
+    /// A function body was expected, such as after `function`
+    ExpectedFunctionBody,
+    /// A statement was expected
+    ExpectedStatement,
+    /// A type was expected, such as after `:` in a type annotation
+    ExpectedType,
+    /// A type pack was expected, such as for the default of a generic type pack
+    ExpectedTypePack,
+    /// The end of the input was expected, such as after parsing a single expression
+    ExpectedEndOfInput,
+    /// A token was found that can't start a statement
+    UnexpectedToken,
+    /// An expression was found where a statement was expected, such as `x + 1`
+    UnexpectedExpression,
+    /// A last statement was found where a statement was expected, such as when parsing a single statement
+    UnexpectedLastStatement,
+    /// A statement was found after a last statement, such as `return`
+    StatementAfterLastStatement,
+    /// A trailing comma was found where one isn't allowed, such as in function arguments
+    TrailingComma,
+    /// The result of a call was assigned to, such as `call() = 1`
+    AssignToCall,
+    /// A `{{` was found in an interpolated string, rather than an escaped `\{`
+    DoubleBrace,
+    /// An interpolated string was used as a type
+    InterpolatedStringType,
+    /// Union and intersection types were mixed without parentheses, such as `A | B & C`
+    MixedUnionAndIntersection,
+    /// A table type has more than one indexer, such as `{ [string]: A, [number]: B }`
+    MultipleTableIndexers,
+    /// A generic type was found after a generic type pack, such as `<T..., U>`
+    GenericTypeAfterTypePack,
+    /// A return type annotation used `->` rather than `:`
+    ReturnTypeArrow,
+    /// The code was nested deeper than [`ParseOptions::max_depth`](crate::ParseOptions::max_depth)
+    /// allows, such as `((((...))))`, or deeply enough to use more of the stack than
+    /// [`ParseOptions::max_stack_size`](crate::ParseOptions::max_stack_size) allows.
+    /// Parsing stops at this point.
+    TooDeeplyNested,
+    /// The code has more tokens than [`ParseOptions::max_tokens`](crate::ParseOptions::max_tokens)
+    /// allows. Parsing stops at this point.
+    TooManyTokens,
+    /// Parsing was cancelled through [`ParseOptions::with_cancellation`](crate::ParseOptions::with_cancellation).
+    /// Parsing stops at this point.
+    Cancelled,
+    /// The kind of error isn't known, such as for an error serialized by a version of full-moon
+    /// from before errors had kinds
+    #[default]
+    Unknown,
+}
+
+/// A token that would have been accepted where an [`AstError`] occurred.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[non_exhaustive]
+pub enum ExpectedToken {
+    /// A specific symbol, such as `end`
+    Symbol(Symbol),
+    /// Any token of a kind, such as an identifier
+    Kind(TokenKind),
+}
+
+impl fmt::Display for ExpectedToken {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ExpectedToken::Symbol(symbol) => write!(formatter, "`{symbol}`"),
+            ExpectedToken::Kind(TokenKind::Eof) => formatter.write_str("the end of the file"),
+            ExpectedToken::Kind(TokenKind::Identifier) => formatter.write_str("a name"),
+            ExpectedToken::Kind(TokenKind::Number) => formatter.write_str("a number"),
+            ExpectedToken::Kind(TokenKind::StringLiteral) => formatter.write_str("a string"),
+            ExpectedToken::Kind(kind) => write!(formatter, "{kind:?}"),
+        }
+    }
+}
+
 /// An error that occurs when creating the AST.
 #[derive(Clone, Debug, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
@@ -2238,6 +2396,24 @@ pub struct AstError {
     /// If set, this is the complete range of the error
     #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
     range: Option<(Position, Position)>,
+
+    /// The kind of error
+    #[cfg_attr(feature = "serde", serde(default))]
+    kind: AstErrorKind,
+
+    /// The tokens that would have been accepted instead
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    expected: Vec<ExpectedToken>,
+
+    /// If set, this is the range of a token the error relates to, such as the `if` that a missing `end` closes
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    related: Option<(Position, Position)>,
 }
 
 impl AstError {
@@ -2251,6 +2427,29 @@ impl AstError {
         self.additional.as_ref()
     }
 
+    /// Returns the kind of error
+    pub fn kind(&self) -> AstErrorKind {
+        self.kind
+    }
+
+    /// Returns the tokens that would have been accepted where the error occurred.
+    /// This is empty when the error isn't about a missing token, such as [`AstErrorKind::TrailingComma`].
+    /// ```rust
+    /// # use full_moon::{ast::{AstErrorKind, ExpectedToken}, tokenizer::Symbol};
+    /// let errors = full_moon::parse("if x then").unwrap_err();
+    /// let full_moon::Error::AstError(error) = &errors[0] else { unreachable!() };
+    /// assert_eq!(error.kind(), AstErrorKind::ExpectedSymbol(Symbol::End));
+    /// assert_eq!(error.expected(), &[ExpectedToken::Symbol(Symbol::End)]);
+    /// ```
+    pub fn expected(&self) -> &[ExpectedToken] {
+        &self.expected
+    }
+
+    /// Returns the range of a token the error relates to, such as the `if` that a missing `end` closes
+    pub fn related_range(&self) -> Option<(Position, Position)> {
+        self.related
+    }
+
     /// Returns the range of the error
     pub fn range(&self) -> (Position, Position) {
         self.range
@@ -2288,13 +2487,26 @@ pub struct Ast {
 
 impl Ast {
     /// Returns a new Ast with the given nodes
-    pub fn with_nodes(self, nodes: Block) -> Self {
-        Self { nodes, ..self }
+    pub fn with_nodes(mut self, nodes: Block) -> Self {
+        drop_iteratively(std::mem::replace(&mut self.nodes, nodes));
+        self
     }
 
     /// Returns a new Ast with the given EOF token
-    pub fn with_eof(self, eof: TokenReference) -> Self {
-        Self { eof, ..self }
+    pub fn with_eof(mut self, eof: TokenReference) -> Self {
+        self.eof = eof;
+        self
+    }
+
+    // Moves out the nodes and the EOF token, which can't be done by destructuring,
+    // as Ast implements Drop
+    pub(crate) fn into_parts(mut self) -> (Block, TokenReference) {
+        let eof = std::mem::replace(
+            &mut self.eof,
+            TokenReference::new(Vec::new(), Token::new(TokenType::Eof), Vec::new()),
+        );
+
+        (std::mem::take(&mut self.nodes), eof)
     }
 
     /// The entire code of the function
@@ -2320,6 +2532,62 @@ impl Ast {
     }
 }
 
+// Deeply nested code, such as `((((...))))` or `1 + 1 + 1 + ...`, is dropped iteratively rather
+// than recursively, so that dropping an Ast can't overflow the stack.
+impl Drop for Ast {
+    fn drop(&mut self) {
+        drop_iteratively(std::mem::take(&mut self.nodes));
+    }
+}
+
+fn drop_iteratively(block: Block) {
+    let mut dropper = Dropper::default();
+    block.visit_mut(&mut dropper);
+
+    loop {
+        if let Some(expression) = dropper.expressions.pop() {
+            dropper.root_expression = true;
+            expression.visit_mut(&mut dropper);
+        } else if let Some(stmt) = dropper.stmts.pop() {
+            stmt.visit_mut(&mut dropper);
+        } else if let Some(last_stmt) = dropper.last_stmts.pop() {
+            last_stmt.visit_mut(&mut dropper);
+        } else {
+            break;
+        }
+    }
+}
+
+// Moves every statement and expression nested in what it visits onto its own stack, so that
+// each can be dropped without first dropping everything nested in it
+#[derive(Default)]
+struct Dropper {
+    stmts: Vec<Stmt>,
+    last_stmts: Vec<LastStmt>,
+    expressions: Vec<Expression>,
+    // Whether the next expression visited is the one being dropped, rather than nested in it
+    root_expression: bool,
+}
+
+impl VisitorMut for Dropper {
+    fn visit_block(&mut self, block: Block) -> Block {
+        let Block { stmts, last_stmt } = block;
+        self.stmts.extend(stmts.into_iter().map(|(stmt, _)| stmt));
+        self.last_stmts
+            .extend(last_stmt.map(|(last_stmt, _)| last_stmt));
+        Block::new()
+    }
+
+    fn visit_expression(&mut self, expression: Expression) -> Expression {
+        if std::mem::take(&mut self.root_expression) {
+            expression
+        } else {
+            self.expressions.push(expression);
+            Expression::placeholder()
+        }
+    }
+}
+
 impl fmt::Display for Ast {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         write!(f, "{}", self.nodes())?;
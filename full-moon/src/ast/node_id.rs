@@ -0,0 +1,407 @@
+//! Identities for the nodes of an [`Ast`] that last across changes to it, and [`NodeMap`] for
+//! keeping information about nodes, such as their types or the results of lints.
+//!
+//! Nodes are compared by their contents, and move in memory whenever a [`VisitorMut`] rebuilds
+//! them, so neither tells whether two nodes are the same node. Once an Ast is given IDs, by
+//! [`Ast::with_node_ids`] or [`ParseOptions::with_node_ids`](crate::ParseOptions::with_node_ids),
+//! every node with tokens has a [`NodeId`]. It is kept when the Ast is cloned, and when a
+//! [`VisitorMut`] changes the node, such as renaming a variable in it, without replacing it.
+//!
+//! ```rust
+//! # use full_moon::{ast::{node_id::{HasNodeId, NodeMap}, punctuated::Pair, LocalAssignment, Stmt}, tokenizer::{Token, TokenType}, visitors::VisitorMut, LuaVersion, ParseOptions};
+//! # fn main() -> Result<(), Vec<full_moon::Error>> {
+//! let options = ParseOptions::new(LuaVersion::new()).with_node_ids(true);
+//! let ast = full_moon::parse_with_options("local x = 1 + 2", &options).into_result()?;
+//!
+//! let mut types = NodeMap::new();
+//! let Stmt::LocalAssignment(local_assignment) = ast.nodes().stmts().next().unwrap() else {
+//!     unreachable!()
+//! };
+//!
+//! let expression = local_assignment.expressions().iter().next().unwrap();
+//! types.insert(expression.node_id().unwrap(), "number");
+//!
+//! struct Rename;
+//!
+//! impl VisitorMut for Rename {
+//!     fn visit_local_assignment(&mut self, local_assignment: LocalAssignment) -> LocalAssignment {
+//!         let name = local_assignment.names().iter().next().unwrap();
+//!         let renamed = name.with_token(Token::new(TokenType::Identifier { identifier: "y".into() }));
+//!         local_assignment.with_names(std::iter::once(Pair::End(renamed)).collect())
+//!     }
+//! }
+//!
+//! let ast = Rename.visit_ast(ast);
+//! assert_eq!(ast.to_string(), "local y = 1 + 2");
+//!
+//! let Stmt::LocalAssignment(local_assignment) = ast.nodes().stmts().next().unwrap() else {
+//!     unreachable!()
+//! };
+//!
+//! let expression = local_assignment.expressions().iter().next().unwrap();
+//! assert_eq!(types.get(expression.node_id().unwrap()), Some(&"number"));
+//! # Ok(())
+//! # }
+//! ```
+use std::collections::{hash_map, HashMap};
+
+use crate::{
+    ast::Ast,
+    node::{AnyNode, AnyNodeType, Node, SyntaxKind},
+    tokenizer::TokenReference,
+    visitors::VisitorMut,
+};
+
+/// The identity of a node in an [`Ast`] that was given IDs with [`Ast::with_node_ids`].
+/// Read the [module documentation](self) for more information.
+///
+/// A node is identified by one of its tokens, preferring the ones that are its own rather than
+/// inside of the nodes in it, such as the `+` of `x + 2` or the `local` of a local assignment.
+/// Replacing the nodes inside of it, such as the `2`, doesn't change its ID, but replacing the
+/// token it is identified by does.
+///
+/// IDs are only unique within one Ast, and nodes in different Asts may have the same ID.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct NodeId {
+    kind: SyntaxKind,
+    token: u32,
+}
+
+impl NodeId {
+    /// The kind of node this is the ID of, which is [`SyntaxKind::TokenReference`] for tokens
+    pub fn kind(&self) -> SyntaxKind {
+        self.kind
+    }
+}
+
+// The token a node is identified by: one of its own tokens or brackets if it has any, otherwise
+// the first token that isn't inside of another node of the same kind. No two nodes of the same
+// kind can have the same one, since the nodes between them and the token would include one of them.
+fn anchor<'a>(node: AnyNode<'a>, kind: SyntaxKind) -> Option<&'a TokenReference> {
+    let own_token = node.children().find_map(|child| match child {
+        AnyNode::TokenReference(token) => Some(token),
+        AnyNode::ContainedSpan(span) => Some(span.tokens().0),
+        _ => None,
+    });
+
+    own_token.or_else(|| {
+        node.children()
+            .filter(|child| child.kind() != kind)
+            .find_map(|child| anchor(child, kind))
+    })
+}
+
+/// A node that has a [`NodeId`] once its [`Ast`] is given IDs.
+///
+/// This trait is sealed and cannot be implemented for types outside of `full-moon`
+pub trait HasNodeId: Node {
+    /// The ID of the node, or `None` if its Ast wasn't given IDs, if the node or the token it is
+    /// identified by was created or replaced after they were given, or if the node has no
+    /// tokens, such as an empty block.
+    fn node_id(&self) -> Option<NodeId>;
+}
+
+impl<T: AnyNodeType> HasNodeId for T {
+    fn node_id(&self) -> Option<NodeId> {
+        let node = self.as_any_node();
+
+        let token = match node {
+            AnyNode::TokenReference(token) => token,
+            _ => anchor(node, node.kind())?,
+        };
+
+        Some(NodeId {
+            kind: node.kind(),
+            token: token.id?,
+        })
+    }
+}
+
+/// Information about nodes, keyed by their [`NodeId`]. Because IDs are kept when an [`Ast`]
+/// is changed, the information stays with the nodes it is about.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct NodeMap<T> {
+    map: HashMap<NodeId, T>,
+}
+
+impl<T> NodeMap<T> {
+    /// Creates an empty NodeMap
+    pub fn new() -> Self {
+        Self {
+            map: HashMap::new(),
+        }
+    }
+
+    /// Sets the value for the node, returning the value it had before, if any
+    pub fn insert(&mut self, id: NodeId, value: T) -> Option<T> {
+        self.map.insert(id, value)
+    }
+
+    /// The value for the node, if it has one
+    pub fn get(&self, id: NodeId) -> Option<&T> {
+        self.map.get(&id)
+    }
+
+    /// A mutable reference to the value for the node, if it has one
+    pub fn get_mut(&mut self, id: NodeId) -> Option<&mut T> {
+        self.map.get_mut(&id)
+    }
+
+    /// Removes the value for the node, returning it if there was one
+    pub fn remove(&mut self, id: NodeId) -> Option<T> {
+        self.map.remove(&id)
+    }
+
+    /// Whether the node has a value
+    pub fn contains(&self, id: NodeId) -> bool {
+        self.map.contains_key(&id)
+    }
+
+    /// How many nodes have a value
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    /// Whether no nodes have a value
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    /// Every node that has a value, along with the value, in no particular order
+    pub fn iter(&self) -> impl Iterator<Item = (NodeId, &T)> {
+        self.map.iter().map(|(id, value)| (*id, value))
+    }
+}
+
+impl<T> Default for NodeMap<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Extend<(NodeId, T)> for NodeMap<T> {
+    fn extend<I: IntoIterator<Item = (NodeId, T)>>(&mut self, iter: I) {
+        self.map.extend(iter);
+    }
+}
+
+impl<T> FromIterator<(NodeId, T)> for NodeMap<T> {
+    fn from_iter<I: IntoIterator<Item = (NodeId, T)>>(iter: I) -> Self {
+        Self {
+            map: iter.into_iter().collect(),
+        }
+    }
+}
+
+impl<T> IntoIterator for NodeMap<T> {
+    type Item = (NodeId, T);
+    type IntoIter = hash_map::IntoIter<NodeId, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.map.into_iter()
+    }
+}
+
+struct IdGiver {
+    next_id: u32,
+}
+
+impl IdGiver {
+    fn give_id(&mut self, token: TokenReference) -> TokenReference {
+        if token.id.is_some() {
+            return token;
+        }
+
+        let id = self.next_id;
+        self.next_id += 1;
+
+        TokenReference {
+            id: Some(id),
+            ..token
+        }
+    }
+}
+
+impl VisitorMut for IdGiver {
+    fn visit_token_reference(&mut self, token: TokenReference) -> TokenReference {
+        self.give_id(token)
+    }
+
+    // `visit_ast` doesn't visit the end of file as a token reference
+    fn visit_eof(&mut self, token: TokenReference) -> TokenReference {
+        self.give_id(token)
+    }
+}
+
+impl Ast {
+    /// Gives [`NodeId`]s to the nodes of the Ast, such as after creating it or parsing it without
+    /// [`ParseOptions::with_node_ids`](crate::ParseOptions::with_node_ids).
+    /// Nodes that already have IDs keep them, so this can be called again after adding nodes.
+    pub fn with_node_ids(self) -> Self {
+        let next_id = self
+            .tokens()
+            .chain(std::iter::once(self.eof()))
+            .filter_map(|token| token.id)
+            .max()
+            .map_or(0, |id| id + 1);
+
+        IdGiver { next_id }.visit_ast(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        ast::{Expression, LuaVersion, Stmt, TextEdit},
+        ParseOptions,
+    };
+
+    fn parse_with_ids(code: &str) -> Ast {
+        let options = ParseOptions::new(LuaVersion::new()).with_node_ids(true);
+        crate::parse_with_options(code, &options)
+            .into_result()
+            .unwrap()
+    }
+
+    fn expression_ids(ast: &Ast) -> Vec<Option<NodeId>> {
+        struct Collector(Vec<Option<NodeId>>);
+
+        impl crate::visitors::Visitor for Collector {
+            fn visit_expression(&mut self, expression: &Expression) {
+                self.0.push(expression.node_id());
+            }
+        }
+
+        let mut collector = Collector(Vec::new());
+        crate::visitors::Visitor::visit_ast(&mut collector, ast);
+        collector.0
+    }
+
+    #[test]
+    fn test_unique() {
+        let ast = parse_with_ids("local x = (1 + 2) * f(3)\nlocal y = x\nprint(#y)");
+        let ids = expression_ids(&ast);
+
+        assert_eq!(ids.len(), 10);
+        assert!(ids.iter().all(Option::is_some));
+
+        let mut unique = ids.clone();
+        unique.sort_by_key(|id| id.map(|id| id.token));
+        unique.dedup();
+        assert_eq!(unique.len(), ids.len());
+
+        let stmt = ast.nodes().stmts().next().unwrap();
+        let Stmt::LocalAssignment(local_assignment) = stmt else {
+            unreachable!()
+        };
+
+        assert_eq!(stmt.node_id().unwrap().kind(), SyntaxKind::Stmt);
+        assert_ne!(stmt.node_id(), local_assignment.node_id());
+        assert_eq!(
+            local_assignment
+                .names()
+                .iter()
+                .next()
+                .unwrap()
+                .node_id()
+                .unwrap()
+                .kind(),
+            SyntaxKind::TokenReference
+        );
+    }
+
+    #[test]
+    fn test_unique_in_pass_cases() {
+        let cases = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/cases/pass");
+
+        for entry in std::fs::read_dir(cases).unwrap() {
+            let source = std::fs::read_to_string(entry.unwrap().path().join("source.lua")).unwrap();
+
+            let Ok(ast) = crate::parse_fallible(&source, LuaVersion::new()).into_result() else {
+                continue;
+            };
+
+            let ids = expression_ids(&ast.with_node_ids());
+            let unique = ids.iter().collect::<std::collections::HashSet<_>>();
+            assert_eq!(unique.len(), ids.len(), "{source}");
+        }
+    }
+
+    #[test]
+    fn test_optional() {
+        let ast = crate::parse("local x = 1").unwrap();
+        assert!(expression_ids(&ast).iter().all(Option::is_none));
+
+        let ast = ast.with_node_ids();
+        assert!(expression_ids(&ast).iter().all(Option::is_some));
+        assert!(ast.eof().node_id().is_some());
+
+        assert!(crate::ast::Block::new().node_id().is_none());
+    }
+
+    #[test]
+    fn test_kept() {
+        let ast = parse_with_ids("local x = 1\nlocal y = x + 2");
+        let ids = expression_ids(&ast);
+
+        let mut map: NodeMap<usize> = ids
+            .iter()
+            .enumerate()
+            .map(|(index, id)| (id.unwrap(), index))
+            .collect();
+
+        assert_eq!(expression_ids(&ast.clone()), ids);
+        assert_eq!(expression_ids(&ast.clone().update_positions()), ids);
+
+        for (index, id) in expression_ids(&ast).into_iter().enumerate() {
+            assert_eq!(map.get(id.unwrap()), Some(&index));
+        }
+
+        struct Replace;
+
+        impl VisitorMut for Replace {
+            fn visit_expression(&mut self, expression: Expression) -> Expression {
+                match expression {
+                    Expression::Number(_) => Expression::Number(TokenReference::new(
+                        Vec::new(),
+                        crate::tokenizer::Token::new(crate::tokenizer::TokenType::Number {
+                            text: "3".into(),
+                        }),
+                        Vec::new(),
+                    )),
+
+                    other => other,
+                }
+            }
+        }
+
+        let replaced = expression_ids(&Replace.visit_ast(ast.clone()));
+        assert_eq!(replaced[0], None);
+        assert_eq!(replaced[1], ids[1]);
+        assert_eq!(replaced[2], ids[2]);
+        assert_eq!(replaced[3], None);
+
+        assert_eq!(map.remove(ids[0].unwrap()), Some(0));
+        assert!(!map.contains(ids[0].unwrap()));
+        assert_eq!(map.len(), ids.len() - 1);
+    }
+
+    #[test]
+    fn test_reparse() {
+        let source = "local x = 1\nlocal y = 2\n";
+        let ast = parse_with_ids(source);
+        let ids = expression_ids(&ast);
+
+        let reparsed = ast
+            .reparse(source, &[TextEdit::new(22..23, "x + 3")], LuaVersion::new())
+            .into_result()
+            .unwrap();
+
+        let new_ids = expression_ids(&reparsed);
+        assert_eq!(new_ids[0], ids[0]);
+        assert_eq!(new_ids.len(), 4);
+        assert!(new_ids.iter().all(Option::is_some));
+        assert!(!ids.contains(&new_ids[1]));
+    }
+}
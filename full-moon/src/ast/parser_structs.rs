@@ -1,25 +1,213 @@
-use std::borrow::Cow;
+use std::{
+    borrow::Cow,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+};
 
 use crate::node::Node;
-use crate::tokenizer::{Lexer, LexerResult, Symbol, Token, TokenKind, TokenReference};
+use crate::tokenizer::{
+    Lexer, LexerResult, Position, Symbol, Token, TokenKind, TokenReference, TokenType, TriviaMode,
+};
 
-use super::{parsers::parse_block, Ast, Block, LuaVersion};
+use super::{parsers::parse_block, Ast, AstErrorKind, Block, ExpectedToken, LuaVersion};
 
 pub struct ParserState {
-    errors: Vec<crate::Error>,
+    pub(crate) errors: Vec<crate::Error>,
     lexer: Lexer,
+    consumed_tokens: usize,
     // Unused with no features enabled
     #[allow(unused)]
     lua_version: LuaVersion,
+    // How deeply nested the code being parsed is, such as inside parentheses or blocks
+    depth: usize,
+    max_depth: usize,
+    // Where the stack was when parsing started, to measure how much of it parsing has used
+    stack_start: usize,
+    max_stack_size: usize,
+    max_tokens: Option<usize>,
+    cancellation: Option<Arc<AtomicBool>>,
+    // Set when parsing stops early, such as when the code is nested too deeply.
+    // From then on, this is the only token left.
+    aborted_eof: Option<TokenReference>,
+}
+
+// The address of somewhere in the current stack frame. The stack can grow either up or down,
+// so only the distance between two of these is meaningful.
+#[inline(never)]
+fn stack_address() -> usize {
+    let marker = 0u8;
+    std::hint::black_box(&marker) as *const u8 as usize
+}
+
+// A point in parsing to recover the tokens consumed since, in case they were dropped
+// while recovering from an error
+#[derive(Clone, Copy)]
+pub struct Checkpoint {
+    consumed_tokens: usize,
+    errors: usize,
+    position: Option<Position>,
 }
 
 impl ParserState {
     pub fn new(lexer: Lexer) -> Self {
         Self {
             errors: Vec::new(),
+            consumed_tokens: 0,
             lua_version: lexer.lua_version,
             lexer,
+            depth: 0,
+            max_depth: usize::MAX,
+            stack_start: stack_address(),
+            max_stack_size: usize::MAX,
+            max_tokens: None,
+            cancellation: None,
+            aborted_eof: None,
+        }
+    }
+
+    pub fn with_options(self, options: &ParseOptions) -> Self {
+        Self {
+            max_depth: options.max_depth,
+            stack_start: stack_address(),
+            max_stack_size: options.max_stack_size,
+            max_tokens: options.max_tokens,
+            cancellation: options.cancellation.clone(),
+            ..self
+        }
+    }
+
+    pub fn is_aborted(&self) -> bool {
+        self.aborted_eof.is_some()
+    }
+
+    // Stops parsing at the current token, reporting why. Every token after is dropped.
+    fn abort(&mut self, kind: AstErrorKind, error: &'static str) {
+        if self.is_aborted() {
+            return;
+        }
+
+        let position = self
+            .current_full_start()
+            .unwrap_or_else(|| self.lexer.source.position());
+
+        let eof = TokenReference::new(
+            Vec::new(),
+            Token {
+                start_position: position,
+                end_position: position,
+                token_type: TokenType::Eof,
+            },
+            Vec::new(),
+        );
+
+        let token = match self.current() {
+            Ok(token) => token.clone(),
+            Err(()) => eof.clone(),
+        };
+
+        self.token_error(token, kind, error);
+        self.aborted_eof = Some(eof);
+    }
+
+    // Parses something nested in what is being parsed, such as the expression inside parentheses,
+    // aborting instead if that goes deeper than allowed, or uses more of the stack than allowed
+    pub fn nested<T>(
+        &mut self,
+        parser: impl FnOnce(&mut Self) -> ParserResult<T>,
+    ) -> ParserResult<T> {
+        if self.depth >= self.max_depth
+            || self.stack_start.abs_diff(stack_address()) > self.max_stack_size
+        {
+            self.abort(AstErrorKind::TooDeeplyNested, "code is nested too deeply");
+        }
+
+        if self.is_aborted() {
+            return ParserResult::LexerMoved;
+        }
+
+        self.depth += 1;
+        let result = parser(self);
+        self.depth -= 1;
+        result
+    }
+
+    fn check_limits(&mut self) {
+        if matches!(self.max_tokens, Some(max_tokens) if self.consumed_tokens > max_tokens) {
+            self.abort(AstErrorKind::TooManyTokens, "code has too many tokens");
+        } else if matches!(&self.cancellation, Some(cancelled) if cancelled.load(Ordering::Relaxed))
+        {
+            self.abort(AstErrorKind::Cancelled, "parsing was cancelled");
+        }
+    }
+
+    // The position of the current token, including its leading trivia
+    fn current_full_start(&self) -> Option<Position> {
+        let token = self.current().ok()?;
+
+        Some(
+            token
+                .leading_trivia()
+                .next()
+                .map_or_else(|| token.token().start_position(), Token::start_position),
+        )
+    }
+
+    pub fn checkpoint(&self) -> Checkpoint {
+        Checkpoint {
+            consumed_tokens: self.consumed_tokens,
+            errors: self.errors.len(),
+            position: self.current_full_start(),
+        }
+    }
+
+    // Whether every token consumed since the checkpoint is in the node (along with the extra tokens
+    // given, such as a semicolon), rather than having been dropped while recovering from an error
+    pub fn has_consumed_tokens(
+        &self,
+        checkpoint: Checkpoint,
+        node: &impl Node,
+        extra_tokens: usize,
+    ) -> bool {
+        // Tokens are only ever dropped when there's an error
+        if self.errors.len() == checkpoint.errors {
+            return true;
+        }
+
+        node.tokens().filter(|token| !token.is_phantom()).count() + extra_tokens
+            == self.consumed_tokens - checkpoint.consumed_tokens
+    }
+
+    // Returns the tokens consumed since the checkpoint by lexing their source again
+    pub fn consumed_tokens_since(&self, checkpoint: Checkpoint) -> Vec<TokenReference> {
+        if self.consumed_tokens == checkpoint.consumed_tokens {
+            return Vec::new();
         }
+
+        let (Some(start), Some(end)) = (checkpoint.position, self.current_full_start()) else {
+            return Vec::new();
+        };
+
+        let source = self.lexer.source.slice(start.bytes(), end.bytes());
+        let mut lexer = Lexer::new_at(&source, start, self.lua_version, self.lexer.trivia_mode);
+        let mut tokens: Vec<TokenReference> = Vec::new();
+
+        while let Some(result) = lexer.consume() {
+            let token = match result {
+                LexerResult::Ok(token) | LexerResult::Recovered(token, _) => token,
+                // Any errors were already reported the first time around
+                LexerResult::Fatal(_) => continue,
+            };
+
+            if token.token_kind() != TokenKind::Eof {
+                tokens.push(token);
+            } else if let Some(last_token) = tokens.last_mut() {
+                last_token.trailing_trivia.extend(token.leading_trivia);
+            }
+        }
+
+        tokens
     }
 
     // Unused with no features enabled
@@ -29,6 +217,10 @@ impl ParserState {
     }
 
     pub fn current(&self) -> Result<&TokenReference, ()> {
+        if let Some(eof) = &self.aborted_eof {
+            return Ok(eof);
+        }
+
         match self.lexer.current() {
             Some(LexerResult::Ok(token) | LexerResult::Recovered(token, _)) => Ok(token),
             Some(LexerResult::Fatal(_)) => Err(()),
@@ -37,6 +229,10 @@ impl ParserState {
     }
 
     pub fn peek(&self) -> Result<&TokenReference, ()> {
+        if let Some(eof) = &self.aborted_eof {
+            return Ok(eof);
+        }
+
         match self.lexer.peek() {
             Some(LexerResult::Ok(token) | LexerResult::Recovered(token, _)) => Ok(token),
             Some(LexerResult::Fatal(_)) => Err(()),
@@ -45,16 +241,26 @@ impl ParserState {
     }
 
     pub fn consume(&mut self) -> ParserResult<TokenReference> {
+        if let Some(eof) = &self.aborted_eof {
+            return ParserResult::Value(eof.clone());
+        }
+
         let token = self.lexer.consume();
 
         match token {
-            Some(LexerResult::Ok(token)) => ParserResult::Value(token),
+            Some(LexerResult::Ok(token)) => {
+                self.consumed_tokens += 1;
+                self.check_limits();
+                ParserResult::Value(token)
+            }
 
             Some(LexerResult::Recovered(token, errors)) => {
                 for error in errors {
                     self.errors.push(crate::Error::TokenizerError(error));
                 }
 
+                self.consumed_tokens += 1;
+                self.check_limits();
                 ParserResult::Value(token)
             }
 
@@ -104,7 +310,7 @@ impl ParserState {
                 if token.is_symbol(symbol) {
                     Some(self.consume().unwrap())
                 } else {
-                    self.token_error(token.clone(), error);
+                    self.token_error(token.clone(), AstErrorKind::ExpectedSymbol(symbol), error);
                     None
                 }
             }
@@ -124,7 +330,11 @@ impl ParserState {
                 if token.is_symbol(symbol) {
                     Some(self.consume().unwrap())
                 } else {
-                    self.token_error(reference_token.clone(), error);
+                    self.token_error(
+                        reference_token.clone(),
+                        AstErrorKind::ExpectedSymbol(symbol),
+                        error,
+                    );
                     None
                 }
             }
@@ -145,7 +355,13 @@ impl ParserState {
                 if token.is_symbol(symbol) {
                     Some(self.consume().unwrap())
                 } else {
-                    self.token_error_ranged(token.clone(), error.to_str(), start_token, end_token);
+                    self.token_error_ranged(
+                        token.clone(),
+                        AstErrorKind::ExpectedSymbol(symbol),
+                        error.to_str(),
+                        start_token,
+                        end_token,
+                    );
                     None
                 }
             }
@@ -169,6 +385,7 @@ impl ParserState {
 
                     self.token_error_ranged(
                         token.clone(),
+                        AstErrorKind::ExpectedSymbol(symbol),
                         error.to_str(),
                         &start_token,
                         &end_token,
@@ -185,13 +402,22 @@ impl ParserState {
     pub fn token_error<S: Into<Cow<'static, str>>>(
         &mut self,
         token_reference: TokenReference,
+        kind: AstErrorKind,
         error: S,
     ) {
+        // Anything after parsing stops is a result of stopping, not of the code
+        if self.is_aborted() {
+            return;
+        }
+
         self.errors
             .push(crate::Error::AstError(crate::ast::AstError {
                 token: token_reference.token,
                 additional: error.into(),
                 range: None,
+                kind,
+                expected: self.expected_tokens(kind),
+                related: None,
             }));
     }
 
@@ -199,10 +425,15 @@ impl ParserState {
     pub fn token_error_ranged<S: Into<Cow<'static, str>>>(
         &mut self,
         token_reference: TokenReference,
+        kind: AstErrorKind,
         error: S,
         start_token: &TokenReference,
         end_token: &TokenReference,
     ) {
+        if self.is_aborted() {
+            return;
+        }
+
         self.errors
             .push(crate::Error::AstError(crate::ast::AstError {
                 token: token_reference.token,
@@ -211,8 +442,177 @@ impl ParserState {
                     Token::start_position(start_token),
                     Token::end_position(end_token),
                 )),
+                kind,
+                expected: self.expected_tokens(kind),
+                related: None,
             }));
     }
+
+    // Requires a token closing `opening`, such as the `end` of an `if`, relating any error to it
+    pub fn require_closing(
+        &mut self,
+        opening: &TokenReference,
+        require: impl FnOnce(&mut Self) -> Option<TokenReference>,
+    ) -> Option<TokenReference> {
+        let errors = self.errors.len();
+        let closing = require(self);
+
+        if self.errors.len() > errors {
+            self.relate_last_error(opening);
+        }
+
+        closing
+    }
+
+    // Relates the last error to a token, such as the `if` that a missing `end` closes
+    pub fn relate_last_error(&mut self, token: &TokenReference) {
+        if self.is_aborted() {
+            return;
+        }
+
+        if let Some(crate::Error::AstError(error)) = self.errors.last_mut() {
+            error.related = Some((Token::start_position(token), Token::end_position(token)));
+        }
+    }
+
+    // Adds to the tokens that would have been accepted for the last error, for when a site
+    // accepts more than its error kind suggests
+    pub fn also_expected(&mut self, tokens: &[ExpectedToken]) {
+        if self.is_aborted() {
+            return;
+        }
+
+        if let Some(crate::Error::AstError(error)) = self.errors.last_mut() {
+            error.expected.extend_from_slice(tokens);
+        }
+    }
+
+    fn expected_tokens(&self, kind: AstErrorKind) -> Vec<ExpectedToken> {
+        use ExpectedToken::{Kind, Symbol as Sym};
+
+        let lua_version = self.lua_version;
+
+        let expression = || {
+            // Only pushed to with some features enabled
+            #[allow(unused_mut)]
+            let mut tokens = vec![
+                Kind(TokenKind::Identifier),
+                Kind(TokenKind::Number),
+                Kind(TokenKind::StringLiteral),
+                Sym(Symbol::Function),
+                Sym(Symbol::Nil),
+                Sym(Symbol::True),
+                Sym(Symbol::False),
+                Sym(Symbol::Ellipsis),
+                Sym(Symbol::LeftParen),
+                Sym(Symbol::LeftBrace),
+                Sym(Symbol::Minus),
+                Sym(Symbol::Not),
+                Sym(Symbol::Hash),
+            ];
+
+            #[cfg(feature = "lua53")]
+            if lua_version.has_lua53() {
+                tokens.push(Sym(Symbol::Tilde));
+            }
+
+            #[cfg(feature = "luau")]
+            if lua_version.has_luau() {
+                tokens.push(Sym(Symbol::If));
+                tokens.push(Kind(TokenKind::InterpolatedString));
+            }
+
+            tokens
+        };
+
+        let statement = || {
+            // Only pushed to with some features enabled
+            #[allow(unused_mut)]
+            let mut tokens = vec![
+                Kind(TokenKind::Identifier),
+                Sym(Symbol::LeftParen),
+                Sym(Symbol::Local),
+                Sym(Symbol::Function),
+                Sym(Symbol::Do),
+                Sym(Symbol::If),
+                Sym(Symbol::For),
+                Sym(Symbol::While),
+                Sym(Symbol::Repeat),
+                Sym(Symbol::Return),
+                Sym(Symbol::Break),
+            ];
+
+            #[cfg(any(feature = "lua52", feature = "luajit", feature = "cfxlua"))]
+            if lua_version.has_lua52() || lua_version.has_luajit() {
+                tokens.push(Sym(Symbol::Goto));
+            }
+
+            #[cfg(any(feature = "lua52", feature = "luajit"))]
+            if lua_version.has_lua52() || lua_version.has_luajit() {
+                tokens.push(Sym(Symbol::TwoColons));
+            }
+
+            tokens
+        };
+
+        let type_info = || {
+            vec![
+                Kind(TokenKind::Identifier),
+                Kind(TokenKind::StringLiteral),
+                Sym(Symbol::Nil),
+                Sym(Symbol::True),
+                Sym(Symbol::False),
+                Sym(Symbol::LeftParen),
+                Sym(Symbol::LeftBrace),
+                Sym(Symbol::LessThan),
+            ]
+        };
+
+        match kind {
+            AstErrorKind::ExpectedSymbol(symbol) => vec![Sym(symbol)],
+            AstErrorKind::ExpectedName => vec![Kind(TokenKind::Identifier)],
+            AstErrorKind::ExpectedExpression => expression(),
+            AstErrorKind::ExpectedArguments => vec![
+                Sym(Symbol::LeftParen),
+                Sym(Symbol::LeftBrace),
+                Kind(TokenKind::StringLiteral),
+            ],
+            AstErrorKind::ExpectedBlock
+            | AstErrorKind::ExpectedStatement
+            | AstErrorKind::UnexpectedToken => statement(),
+            AstErrorKind::ExpectedFunctionBody => {
+                let mut tokens = vec![Sym(Symbol::LeftParen)];
+
+                if lua_version.has_luau() {
+                    tokens.push(Sym(Symbol::LessThan));
+                }
+
+                tokens
+            }
+            AstErrorKind::ExpectedType => type_info(),
+            AstErrorKind::ExpectedTypePack => vec![
+                Kind(TokenKind::Identifier),
+                Sym(Symbol::LeftParen),
+                Sym(Symbol::Ellipsis),
+            ],
+            AstErrorKind::ExpectedEndOfInput => vec![Kind(TokenKind::Eof)],
+            AstErrorKind::ReturnTypeArrow => vec![Sym(Symbol::Colon)],
+            AstErrorKind::UnexpectedExpression
+            | AstErrorKind::UnexpectedLastStatement
+            | AstErrorKind::StatementAfterLastStatement
+            | AstErrorKind::TrailingComma
+            | AstErrorKind::AssignToCall
+            | AstErrorKind::DoubleBrace
+            | AstErrorKind::InterpolatedStringType
+            | AstErrorKind::MixedUnionAndIntersection
+            | AstErrorKind::MultipleTableIndexers
+            | AstErrorKind::GenericTypeAfterTypePack
+            | AstErrorKind::TooDeeplyNested
+            | AstErrorKind::TooManyTokens
+            | AstErrorKind::Cancelled
+            | AstErrorKind::Unknown => Vec::new(),
+        }
+    }
 }
 
 pub trait MaybeLazyString {
@@ -254,12 +654,178 @@ impl<T> ParserResult<T> {
     }
 }
 
+/// Options for [`parse_with_options`](crate::parse_with_options), such as limits on how much
+/// work parsing can do. These are useful when parsing untrusted code.
+///
+/// Every level of nesting, such as parentheses or a block, takes up stack space while parsing.
+/// This is up to tens of kilobytes per level in release builds, and more in debug builds, so
+/// as well as [`max_depth`](Self::max_depth), parsing stops once it has used
+/// [`max_stack_size`](Self::max_stack_size) bytes of the stack. The defaults are safe on threads
+/// with a stack of 2 MiB, which is the default for threads spawned by the standard library.
+/// Parsing on a thread with a smaller stack needs a smaller `max_stack_size`.
+///
+/// ```rust
+/// # use full_moon::{ast::AstErrorKind, LuaVersion, ParseOptions};
+/// let options = ParseOptions::new(LuaVersion::new()).with_max_depth(10);
+/// let code = format!("local x = {}1{}", "(".repeat(20), ")".repeat(20));
+///
+/// let result = full_moon::parse_with_options(&code, &options);
+/// let full_moon::Error::AstError(error) = &result.errors()[0] else {
+///     unreachable!();
+/// };
+///
+/// assert_eq!(error.kind(), AstErrorKind::TooDeeplyNested);
+/// ```
+#[derive(Clone, Debug)]
+pub struct ParseOptions {
+    lua_version: LuaVersion,
+    max_depth: usize,
+    max_stack_size: usize,
+    max_tokens: Option<usize>,
+    cancellation: Option<Arc<AtomicBool>>,
+    trivia_mode: TriviaMode,
+    node_ids: bool,
+}
+
+impl ParseOptions {
+    /// The maximum depth of nesting used by [`ParseOptions::new`].
+    pub const DEFAULT_MAX_DEPTH: usize = 100;
+
+    /// The maximum stack size, in bytes, used by [`ParseOptions::new`].
+    pub const DEFAULT_MAX_STACK_SIZE: usize = 1024 * 1024;
+
+    /// Creates options for parsing the given Lua version, with a maximum depth of
+    /// [`DEFAULT_MAX_DEPTH`](Self::DEFAULT_MAX_DEPTH), a maximum stack size of
+    /// [`DEFAULT_MAX_STACK_SIZE`](Self::DEFAULT_MAX_STACK_SIZE), and no other limits.
+    pub fn new(lua_version: LuaVersion) -> Self {
+        Self {
+            lua_version,
+            max_depth: Self::DEFAULT_MAX_DEPTH,
+            max_stack_size: Self::DEFAULT_MAX_STACK_SIZE,
+            max_tokens: None,
+            cancellation: None,
+            trivia_mode: TriviaMode::KeepAll,
+            node_ids: false,
+        }
+    }
+
+    // The options used by `parse` and `parse_fallible`
+    pub(crate) fn unlimited(lua_version: LuaVersion) -> Self {
+        Self {
+            max_depth: usize::MAX,
+            max_stack_size: usize::MAX,
+            ..Self::new(lua_version)
+        }
+    }
+
+    /// The Lua version to parse
+    pub fn lua_version(&self) -> LuaVersion {
+        self.lua_version
+    }
+
+    /// How deeply code can be nested before parsing stops with
+    /// [`AstErrorKind::TooDeeplyNested`]. Parentheses, unary operators, blocks,
+    /// and types all add a level of nesting, as do operators of higher precedence than the one
+    /// before them, such as the `*` in `1 + 2 * 3`.
+    pub fn max_depth(&self) -> usize {
+        self.max_depth
+    }
+
+    /// How many bytes of the stack parsing can use before it stops with
+    /// [`AstErrorKind::TooDeeplyNested`]. This is checked at every level of nesting, so
+    /// parsing can go over by up to one level.
+    pub fn max_stack_size(&self) -> usize {
+        self.max_stack_size
+    }
+
+    /// How many tokens can be parsed before parsing stops with [`AstErrorKind::TooManyTokens`],
+    /// if there is a limit.
+    pub fn max_tokens(&self) -> Option<usize> {
+        self.max_tokens
+    }
+
+    /// The flag that stops parsing with [`AstErrorKind::Cancelled`] once set, if there is one.
+    pub fn cancellation(&self) -> Option<&Arc<AtomicBool>> {
+        self.cancellation.as_ref()
+    }
+
+    /// Which trivia, such as whitespace and comments, is kept in the [`Ast`]. Anything but
+    /// [`TriviaMode::KeepAll`] makes parsing faster, but the Ast no longer prints back to the
+    /// original code.
+    pub fn trivia_mode(&self) -> TriviaMode {
+        self.trivia_mode
+    }
+
+    /// Whether the parsed [`Ast`] is given [`NodeId`](crate::ast::node_id::NodeId)s,
+    /// as if by [`Ast::with_node_ids`]. Off by default.
+    pub fn node_ids(&self) -> bool {
+        self.node_ids
+    }
+
+    /// Returns a new ParseOptions with the given Lua version
+    pub fn with_lua_version(self, lua_version: LuaVersion) -> Self {
+        Self {
+            lua_version,
+            ..self
+        }
+    }
+
+    /// Returns a new ParseOptions with the given maximum depth of nesting
+    pub fn with_max_depth(self, max_depth: usize) -> Self {
+        Self { max_depth, ..self }
+    }
+
+    /// Returns a new ParseOptions with the given maximum stack size, in bytes
+    pub fn with_max_stack_size(self, max_stack_size: usize) -> Self {
+        Self {
+            max_stack_size,
+            ..self
+        }
+    }
+
+    /// Returns a new ParseOptions with the given maximum number of tokens
+    pub fn with_max_tokens(self, max_tokens: usize) -> Self {
+        Self {
+            max_tokens: Some(max_tokens),
+            ..self
+        }
+    }
+
+    /// Returns a new ParseOptions that stops parsing once the given flag is set, such as from
+    /// another thread. The flag is checked after every token.
+    pub fn with_cancellation(self, cancellation: Arc<AtomicBool>) -> Self {
+        Self {
+            cancellation: Some(cancellation),
+            ..self
+        }
+    }
+
+    /// Returns a new ParseOptions with the given trivia mode
+    pub fn with_trivia_mode(self, trivia_mode: TriviaMode) -> Self {
+        Self {
+            trivia_mode,
+            ..self
+        }
+    }
+
+    /// Returns a new ParseOptions with node IDs turned on or off
+    pub fn with_node_ids(self, node_ids: bool) -> Self {
+        Self { node_ids, ..self }
+    }
+}
+
+impl Default for ParseOptions {
+    fn default() -> Self {
+        Self::new(LuaVersion::new())
+    }
+}
+
 /// A produced [`Ast`](crate::ast::Ast), along with any errors found during parsing.
 /// This Ast may not be exactly the same as the input code, as reconstruction may have occurred.
 /// For more information, read the documentation for [`parse_fallible`](crate::parse_fallible).
 pub struct AstResult {
-    ast: Ast,
-    errors: Vec<crate::Error>,
+    pub(crate) ast: Ast,
+    pub(crate) errors: Vec<crate::Error>,
 }
 
 impl AstResult {
@@ -285,65 +851,95 @@ impl AstResult {
     }
 
     pub(crate) fn parse_fallible(code: &str, lua_version: LuaVersion) -> Self {
+        Self::parse_with_options(code, &ParseOptions::unlimited(lua_version))
+    }
+
+    pub(crate) fn parse_with_options(code: &str, options: &ParseOptions) -> Self {
         const UNEXPECTED_TOKEN_ERROR: &str = "unexpected token, this needs to be a statement";
 
-        let lexer = Lexer::new(code, lua_version);
-        let mut parser_state = ParserState::new(lexer);
+        let lexer = Lexer::new_with_trivia_mode(code, options.lua_version, options.trivia_mode);
+        let mut parser_state = ParserState::new(lexer).with_options(options);
 
+        // Tokens that couldn't start a statement, kept in an error statement
+        let mut unexpected_tokens = Vec::new();
+
+        let checkpoint = parser_state.checkpoint();
         let mut block = match parse_block(&mut parser_state) {
             ParserResult::Value(block) => block,
-            _ => Block::new(),
+            _ => {
+                unexpected_tokens.extend(parser_state.consumed_tokens_since(checkpoint));
+                Block::new()
+            }
         };
 
         let block_has_last_stmt = block.last_stmt().is_some();
 
         loop {
+            if parser_state.is_aborted() {
+                break;
+            }
+
             match parser_state.lexer.current() {
                 Some(LexerResult::Ok(token)) if token.token_kind() == TokenKind::Eof => {
                     break;
                 }
 
                 Some(LexerResult::Ok(_) | LexerResult::Recovered(_, _)) => {
-                    if let ParserResult::Value(new_block) = parse_block(&mut parser_state) {
-                        if new_block.stmts.is_empty() {
-                            if let Ok(token) = parser_state.current() {
-                                if token.token_kind() == TokenKind::Eof {
-                                    break;
-                                }
+                    let checkpoint = parser_state.checkpoint();
+                    let new_block = match parse_block(&mut parser_state) {
+                        ParserResult::Value(new_block) => new_block,
+                        _ => {
+                            unexpected_tokens
+                                .extend(parser_state.consumed_tokens_since(checkpoint));
+                            continue;
+                        }
+                    };
+
+                    if new_block.stmts.is_empty() && new_block.last_stmt.is_none() {
+                        if let Ok(token) = parser_state.current() {
+                            if token.token_kind() == TokenKind::Eof {
+                                break;
                             }
+                        }
 
-                            match parser_state.consume() {
-                                ParserResult::Value(token) => {
-                                    if let Some(crate::Error::AstError(crate::ast::AstError {
-                                        additional,
-                                        ..
-                                    })) = parser_state.errors.last()
-                                    {
-                                        if additional == UNEXPECTED_TOKEN_ERROR {
-                                            continue;
-                                        }
-                                    }
-
-                                    parser_state.token_error(token, UNEXPECTED_TOKEN_ERROR);
+                        match parser_state.consume() {
+                            ParserResult::Value(token) => {
+                                let reported = matches!(
+                                    parser_state.errors.last(),
+                                    Some(crate::Error::AstError(error))
+                                        if error.kind() == AstErrorKind::UnexpectedToken
+                                );
+
+                                if !reported {
+                                    parser_state.token_error(
+                                        token.clone(),
+                                        AstErrorKind::UnexpectedToken,
+                                        UNEXPECTED_TOKEN_ERROR,
+                                    );
                                 }
 
-                                ParserResult::LexerMoved => {}
-
-                                ParserResult::NotFound => unreachable!(),
+                                unexpected_tokens.push(token);
                             }
 
-                            continue;
-                        }
+                            ParserResult::LexerMoved => {}
 
-                        if block_has_last_stmt {
-                            parser_state.token_error(
-                                new_block.tokens().next().unwrap().clone(),
-                                "unexpected statement after last statement",
-                            )
+                            ParserResult::NotFound => unreachable!(),
                         }
 
-                        block.merge_blocks(new_block);
+                        continue;
+                    }
+
+                    if block_has_last_stmt {
+                        parser_state.token_error(
+                            new_block.tokens().next().unwrap().clone(),
+                            AstErrorKind::StatementAfterLastStatement,
+                            "unexpected statement after last statement",
+                        )
                     }
+
+                    block.push_error_stmt(std::mem::take(&mut unexpected_tokens));
+                    block.demote_last_stmt();
+                    block.merge_blocks(new_block);
                 }
 
                 Some(LexerResult::Fatal(_)) => {
@@ -358,26 +954,37 @@ impl AstResult {
             }
         }
 
-        let eof = match parser_state.lexer.consume().unwrap() {
-            LexerResult::Ok(token) => token,
+        block.push_error_stmt(unexpected_tokens);
 
-            LexerResult::Recovered(token, errors) => {
-                for error in errors {
-                    parser_state
-                        .errors
-                        .push(crate::Error::TokenizerError(error));
-                }
+        let eof = match parser_state.aborted_eof.take() {
+            Some(eof) => eof,
+            None => match parser_state.lexer.consume().unwrap() {
+                LexerResult::Ok(token) => token,
 
-                token
-            }
+                LexerResult::Recovered(token, errors) => {
+                    for error in errors {
+                        parser_state
+                            .errors
+                            .push(crate::Error::TokenizerError(error));
+                    }
+
+                    token
+                }
 
-            LexerResult::Fatal(error) => unreachable!("error: {error:?}"),
+                LexerResult::Fatal(error) => unreachable!("error: {error:?}"),
+            },
         };
 
         debug_assert_eq!(eof.token_kind(), TokenKind::Eof);
 
+        let mut ast = Ast { nodes: block, eof };
+
+        if options.node_ids {
+            ast = ast.with_node_ids();
+        }
+
         Self {
-            ast: Ast { nodes: block, eof },
+            ast,
             errors: parser_state.errors,
         }
     }
@@ -397,3 +1004,112 @@ impl From<AstResult> for Result<Ast, Vec<crate::Error>> {
         }
     }
 }
+
+/// A node parsed from a fragment of code, such as with [`parse_expression`](crate::parse_expression),
+/// along with any errors found during parsing.
+/// Like [`AstResult`], the node may have been reconstructed if there were any errors.
+#[derive(Clone, Debug)]
+pub struct FragmentResult<T> {
+    pub(crate) node: Option<T>,
+    pub(crate) errors: Vec<crate::Error>,
+}
+
+impl<T> FragmentResult<T> {
+    /// Returns a reference to the node that was parsed, if any could be produced.
+    pub fn node(&self) -> Option<&T> {
+        self.node.as_ref()
+    }
+
+    /// Consumes the node that was parsed, if any could be produced.
+    pub fn into_node(self) -> Option<T> {
+        self.node
+    }
+
+    /// Returns all errors that occurred during parsing.
+    pub fn errors(&self) -> &[crate::Error] {
+        &self.errors
+    }
+
+    /// Consumes this FragmentResult, returning the node if there were no errors.
+    pub fn into_result(self) -> Result<T, Vec<crate::Error>> {
+        match self.node {
+            Some(node) if self.errors.is_empty() => Ok(node),
+            _ => Err(self.errors),
+        }
+    }
+
+    pub(crate) fn parse(
+        code: &str,
+        lua_version: LuaVersion,
+        parser: impl FnOnce(&mut ParserState) -> ParserResult<T>,
+        expected: (AstErrorKind, &'static str),
+    ) -> Self {
+        const TRAILING_INPUT_ERROR: &str = "unexpected token, expected the end of the input";
+
+        let lexer = Lexer::new(code, lua_version);
+        let mut parser_state = ParserState::new(lexer);
+
+        let node = match parser(&mut parser_state) {
+            ParserResult::Value(node) => Some(node),
+
+            ParserResult::NotFound => {
+                if let Ok(token) = parser_state.current() {
+                    parser_state.token_error(token.clone(), expected.0, expected.1);
+                }
+
+                None
+            }
+
+            ParserResult::LexerMoved => None,
+        };
+
+        loop {
+            if parser_state.is_aborted() {
+                break;
+            }
+
+            match parser_state.lexer.current() {
+                Some(LexerResult::Ok(token) | LexerResult::Recovered(token, _))
+                    if token.token_kind() == TokenKind::Eof =>
+                {
+                    // Consuming the eof reports any errors it was recovered from
+                    parser_state.consume();
+                    break;
+                }
+
+                Some(LexerResult::Ok(token) | LexerResult::Recovered(token, _)) => {
+                    if node.is_some() {
+                        parser_state.token_error(
+                            token.clone(),
+                            AstErrorKind::ExpectedEndOfInput,
+                            TRAILING_INPUT_ERROR,
+                        );
+                    }
+
+                    break;
+                }
+
+                Some(LexerResult::Fatal(_)) => {
+                    for error in parser_state.lexer.consume().unwrap().unwrap_errors() {
+                        parser_state
+                            .errors
+                            .push(crate::Error::TokenizerError(error));
+                    }
+                }
+
+                None => break,
+            }
+        }
+
+        Self {
+            node,
+            errors: parser_state.errors,
+        }
+    }
+}
+
+impl<T> From<FragmentResult<T>> for Result<T, Vec<crate::Error>> {
+    fn from(fragment_result: FragmentResult<T>) -> Self {
+        fragment_result.into_result()
+    }
+}
@@ -3,10 +3,10 @@
 // maybe keep parsing until there's an `end`???? but then global blocks...aaa
 
 use super::{
-    parser_structs::{ParserResult, ParserState},
+    parser_structs::{Checkpoint, ParserResult, ParserState},
     punctuated::{Pair, Punctuated},
     span::ContainedSpan,
-    Expression, FunctionBody, Parameter,
+    AstErrorKind, ExpectedToken, Expression, FunctionBody, Parameter,
 };
 
 #[cfg(any(feature = "cfxlua", feature = "luau"))]
@@ -26,48 +26,100 @@ static PARSE_NAME_ERROR: &str = "%error-id%";
 
 #[cfg(feature = "luau")]
 fn error_token() -> TokenReference {
-    TokenReference::new(
-        Vec::new(),
-        Token::new(TokenType::Identifier {
-            identifier: PARSE_NAME_ERROR.into(),
-        }),
-        Vec::new(),
-    )
+    TokenReference {
+        phantom: true,
+        ..TokenReference::new(
+            Vec::new(),
+            Token::new(TokenType::Identifier {
+                identifier: PARSE_NAME_ERROR.into(),
+            }),
+            Vec::new(),
+        )
+    }
 }
 
 pub fn parse_block(state: &mut ParserState) -> ParserResult<ast::Block> {
-    let mut stmts = Vec::new();
+    state.nested(|state| {
+        let mut stmts = Vec::new();
 
-    loop {
-        match parse_stmt(state) {
-            ParserResult::Value(StmtVariant::Stmt(stmt)) => {
-                let semicolon = state.consume_if(Symbol::Semicolon);
-                stmts.push((stmt, semicolon));
-            }
+        loop {
+            let checkpoint = state.checkpoint();
 
-            ParserResult::Value(StmtVariant::LastStmt(last_stmt)) => {
-                let semicolon = state.consume_if(Symbol::Semicolon);
-                let last_stmt = Some((last_stmt, semicolon));
-                return ParserResult::Value(ast::Block { stmts, last_stmt });
-            }
+            match parse_stmt(state) {
+                ParserResult::Value(StmtVariant::Stmt(stmt)) => {
+                    let semicolon = state.consume_if(Symbol::Semicolon);
+
+                    if state.has_consumed_tokens(checkpoint, &stmt, semicolon.is_some() as usize) {
+                        stmts.push((stmt, semicolon));
+                    } else {
+                        stmts.push(error_stmt(state, checkpoint));
+                    }
+                }
+
+                ParserResult::Value(StmtVariant::LastStmt(last_stmt)) => {
+                    let semicolon = state.consume_if(Symbol::Semicolon);
+
+                    if state.has_consumed_tokens(
+                        checkpoint,
+                        &last_stmt,
+                        semicolon.is_some() as usize,
+                    ) {
+                        let last_stmt = Some((last_stmt, semicolon));
+                        return ParserResult::Value(ast::Block { stmts, last_stmt });
+                    }
+
+                    stmts.push(error_stmt(state, checkpoint));
+                }
+
+                ParserResult::NotFound => break,
+                ParserResult::LexerMoved => {
+                    // Whatever holds this block keeps the tokens instead
+                    if stmts.is_empty() {
+                        return ParserResult::LexerMoved;
+                    }
+
+                    let tokens = state.consumed_tokens_since(checkpoint);
+
+                    if !tokens.is_empty() {
+                        stmts.push((ast::Stmt::Error(tokens), None));
+                    }
 
-            ParserResult::NotFound => break,
-            ParserResult::LexerMoved => {
-                if stmts.is_empty() {
-                    return ParserResult::LexerMoved;
-                } else {
                     break;
                 }
             }
         }
-    }
 
-    let last_stmt = match parse_last_stmt(state) {
-        ParserResult::Value(stmt) => Some(stmt),
-        ParserResult::LexerMoved | ParserResult::NotFound => None,
-    };
+        let checkpoint = state.checkpoint();
+        let last_stmt = match parse_last_stmt(state) {
+            ParserResult::Value(last_stmt)
+                if state.has_consumed_tokens(checkpoint, &last_stmt, 0) =>
+            {
+                Some(last_stmt)
+            }
+
+            ParserResult::Value(_) | ParserResult::LexerMoved => {
+                let tokens = state.consumed_tokens_since(checkpoint);
+
+                if !tokens.is_empty() {
+                    stmts.push((ast::Stmt::Error(tokens), None));
+                }
 
-    ParserResult::Value(ast::Block { stmts, last_stmt })
+                None
+            }
+
+            ParserResult::NotFound => None,
+        };
+
+        ParserResult::Value(ast::Block { stmts, last_stmt })
+    })
+}
+
+// Keeps the tokens of a statement that dropped some of them while recovering from an error
+fn error_stmt(state: &ParserState, checkpoint: Checkpoint) -> (ast::Stmt, Option<TokenReference>) {
+    (
+        ast::Stmt::Error(state.consumed_tokens_since(checkpoint)),
+        None,
+    )
 }
 
 // Blocks in general are not very fallible. This means, for instance, not finishing `function()`
@@ -98,19 +150,21 @@ fn expect_block_with_end(
         (start_for_errors, start_for_errors)
     };
 
-    let Some(end_token) = state.require_with_reference_range(
-        Symbol::End,
-        || format!("expected `end` to close {} block", name),
-        start,
-        end,
-    ) else {
-        return Ok((block, TokenReference::basic_symbol("end")));
+    let Some(end_token) = state.require_closing(start_for_errors, |state| {
+        state.require_with_reference_range(
+            Symbol::End,
+            || format!("expected `end` to close {} block", name),
+            start,
+            end,
+        )
+    }) else {
+        return Ok((block, TokenReference::phantom_symbol("end")));
     };
 
     Ok((block, end_token))
 }
 
-enum StmtVariant {
+pub enum StmtVariant {
     Stmt(ast::Stmt),
 
     // Used for things like Luau's `continue`, but nothing constructs it in Lua 5.1 alone.
@@ -123,7 +177,11 @@ fn parse_compound_assignment(state: &mut ParserState, var: Var) -> ParserResult<
     let compound_operator = state.consume().unwrap();
 
     let ParserResult::Value(expr) = parse_expression(state) else {
-        state.token_error(compound_operator, "expected expression to set to");
+        state.token_error(
+            compound_operator,
+            AstErrorKind::ExpectedExpression,
+            "expected expression to set to",
+        );
         return ParserResult::LexerMoved;
     };
 
@@ -136,491 +194,527 @@ fn parse_compound_assignment(state: &mut ParserState, var: Var) -> ParserResult<
     )))
 }
 
-fn parse_stmt(state: &mut ParserState) -> ParserResult<StmtVariant> {
+pub fn parse_stmt(state: &mut ParserState) -> ParserResult<StmtVariant> {
     let Ok(current_token) = state.current() else {
         return ParserResult::NotFound;
     };
 
-    match current_token.token_type() {
+    // Each kind of statement is parsed by its own function, so that parsing what is nested in a
+    // statement only needs the stack space of the kind of statement it is in
+    let parser: fn(&mut ParserState) -> ParserResult<StmtVariant> = match current_token.token_type()
+    {
         TokenType::Symbol {
             symbol: Symbol::Local,
-        } => {
-            let local_token = state.consume().unwrap();
-            let next_token = match state.current() {
-                Ok(token) => token,
-                Err(()) => return ParserResult::LexerMoved,
-            };
-
-            match next_token.token_type() {
-                TokenType::Identifier { .. } => ParserResult::Value(StmtVariant::Stmt(
-                    ast::Stmt::LocalAssignment(match expect_local_assignment(state, local_token) {
-                        Ok(local_assignment) => local_assignment,
-                        Err(()) => return ParserResult::LexerMoved,
-                    }),
-                )),
-
-                TokenType::Symbol {
-                    symbol: Symbol::Function,
-                } => {
-                    let function_token = state.consume().unwrap();
+        } => parse_local_stmt,
+        TokenType::Symbol {
+            symbol: Symbol::For,
+        } => parse_for_stmt,
+        TokenType::Symbol { symbol: Symbol::Do } => parse_do_stmt,
+        TokenType::Symbol { symbol: Symbol::If } => parse_if_stmt,
+        TokenType::Symbol {
+            symbol: Symbol::Function,
+        } => parse_function_declaration_stmt,
+        TokenType::Symbol {
+            symbol: Symbol::Repeat,
+        } => parse_repeat_stmt,
+        TokenType::Symbol {
+            symbol: Symbol::While,
+        } => parse_while_stmt,
+        TokenType::Symbol {
+            symbol: Symbol::LeftParen,
+        }
+        | TokenType::Identifier { .. } => parse_assignment_or_call_stmt,
+        #[cfg(any(feature = "lua52", feature = "luajit", feature = "cfxlua"))]
+        TokenType::Symbol {
+            symbol: Symbol::Goto,
+        } => parse_goto_stmt,
+        #[cfg(any(feature = "lua52", feature = "luajit"))]
+        TokenType::Symbol {
+            symbol: Symbol::TwoColons,
+        } if state.lua_version().has_lua52() || state.lua_version().has_luajit() => {
+            parse_label_stmt
+        }
+        _ => return ParserResult::NotFound,
+    };
 
-                    let function_name = match state.current() {
-                        Ok(token) if token.token_kind() == TokenKind::Identifier => {
-                            state.consume().unwrap()
-                        }
+    parser(state)
+}
 
-                        Ok(token) => {
-                            state.token_error(token.clone(), "expected a function name");
-                            return ParserResult::LexerMoved;
-                        }
+fn parse_local_stmt(state: &mut ParserState) -> ParserResult<StmtVariant> {
+    let local_token = state.consume().unwrap();
+    let next_token = match state.current() {
+        Ok(token) => token,
+        Err(()) => return ParserResult::LexerMoved,
+    };
 
-                        Err(()) => return ParserResult::LexerMoved,
-                    };
+    match next_token.token_type() {
+        TokenType::Identifier { .. } => ParserResult::Value(StmtVariant::Stmt(
+            ast::Stmt::LocalAssignment(match expect_local_assignment(state, local_token) {
+                Ok(local_assignment) => local_assignment,
+                Err(()) => return ParserResult::LexerMoved,
+            }),
+        )),
 
-                    let function_body = match parse_function_body(state) {
-                        ParserResult::Value(function_body) => function_body,
-                        ParserResult::NotFound => {
-                            state.token_error(function_token, "expected a function body");
-                            return ParserResult::LexerMoved;
-                        }
-                        ParserResult::LexerMoved => return ParserResult::LexerMoved,
-                    };
+        TokenType::Symbol {
+            symbol: Symbol::Function,
+        } => {
+            let function_token = state.consume().unwrap();
 
-                    ParserResult::Value(StmtVariant::Stmt(ast::Stmt::LocalFunction(
-                        ast::LocalFunction {
-                            local_token,
-                            function_token,
-                            name: function_name,
-                            body: function_body,
-                        },
-                    )))
+            let function_name = match state.current() {
+                Ok(token) if token.token_kind() == TokenKind::Identifier => {
+                    state.consume().unwrap()
                 }
 
-                _ => {
+                Ok(token) => {
                     state.token_error(
-                        next_token.clone(),
-                        "expected either a variable name or `function`",
+                        token.clone(),
+                        AstErrorKind::ExpectedName,
+                        "expected a function name",
                     );
-
-                    ParserResult::LexerMoved
+                    return ParserResult::LexerMoved;
                 }
-            }
-        }
 
-        TokenType::Symbol {
-            symbol: Symbol::For,
-        } => {
-            let for_token = state.consume().unwrap();
-
-            ParserResult::Value(StmtVariant::Stmt(match expect_for_stmt(state, for_token) {
-                Ok(for_stmt) => for_stmt,
                 Err(()) => return ParserResult::LexerMoved,
-            }))
-        }
+            };
 
-        TokenType::Symbol { symbol: Symbol::Do } => {
-            let do_token = state.consume().unwrap();
-            let (block, end_token) = match expect_block_with_end(state, "do", &do_token) {
-                Ok(block) => block,
-                Err(()) => return ParserResult::LexerMoved,
+            let function_body = match parse_function_body(state) {
+                ParserResult::Value(function_body) => function_body,
+                ParserResult::NotFound => {
+                    state.token_error(
+                        function_token,
+                        AstErrorKind::ExpectedFunctionBody,
+                        "expected a function body",
+                    );
+                    return ParserResult::LexerMoved;
+                }
+                ParserResult::LexerMoved => return ParserResult::LexerMoved,
             };
 
-            ParserResult::Value(StmtVariant::Stmt(ast::Stmt::Do(ast::Do {
-                do_token,
-                block,
-                end_token,
-            })))
+            ParserResult::Value(StmtVariant::Stmt(ast::Stmt::LocalFunction(
+                ast::LocalFunction {
+                    local_token,
+                    function_token,
+                    name: function_name,
+                    body: function_body,
+                },
+            )))
         }
 
-        TokenType::Symbol { symbol: Symbol::If } => {
-            let if_token = state.consume().unwrap();
+        _ => {
+            state.token_error(
+                next_token.clone(),
+                AstErrorKind::ExpectedName,
+                "expected either a variable name or `function`",
+            );
+            state.also_expected(&[ExpectedToken::Symbol(Symbol::Function)]);
 
-            ParserResult::Value(StmtVariant::Stmt(ast::Stmt::If(
-                match expect_if_stmt(state, if_token) {
-                    Ok(if_stmt) => if_stmt,
-                    Err(()) => return ParserResult::LexerMoved,
-                },
-            )))
+            ParserResult::LexerMoved
         }
+    }
+}
 
-        TokenType::Symbol {
-            symbol: Symbol::Function,
-        } => {
-            let function_token = state.consume().unwrap();
+fn parse_for_stmt(state: &mut ParserState) -> ParserResult<StmtVariant> {
+    let for_token = state.consume().unwrap();
 
-            let function_declaration = match expect_function_declaration(state, function_token) {
-                Ok(function_declaration) => function_declaration,
-                Err(()) => return ParserResult::LexerMoved,
-            };
+    ParserResult::Value(StmtVariant::Stmt(match expect_for_stmt(state, for_token) {
+        Ok(for_stmt) => for_stmt,
+        Err(()) => return ParserResult::LexerMoved,
+    }))
+}
 
-            ParserResult::Value(StmtVariant::Stmt(ast::Stmt::FunctionDeclaration(
-                function_declaration,
-            )))
-        }
+fn parse_do_stmt(state: &mut ParserState) -> ParserResult<StmtVariant> {
+    let do_token = state.consume().unwrap();
+    let (block, end_token) = match expect_block_with_end(state, "do", &do_token) {
+        Ok(block) => block,
+        Err(()) => return ParserResult::LexerMoved,
+    };
 
-        TokenType::Symbol {
-            symbol: Symbol::Repeat,
-        } => {
-            let repeat_token = state.consume().unwrap();
+    ParserResult::Value(StmtVariant::Stmt(ast::Stmt::Do(ast::Do {
+        do_token,
+        block,
+        end_token,
+    })))
+}
 
-            ParserResult::Value(StmtVariant::Stmt(
-                match expect_repeat_stmt(state, repeat_token) {
-                    Ok(repeat_stmt) => repeat_stmt,
-                    Err(()) => return ParserResult::LexerMoved,
-                },
-            ))
-        }
+fn parse_if_stmt(state: &mut ParserState) -> ParserResult<StmtVariant> {
+    let if_token = state.consume().unwrap();
 
-        TokenType::Symbol {
-            symbol: Symbol::While,
-        } => {
-            let while_token = state.consume().unwrap();
+    ParserResult::Value(StmtVariant::Stmt(ast::Stmt::If(
+        match expect_if_stmt(state, if_token) {
+            Ok(if_stmt) => if_stmt,
+            Err(()) => return ParserResult::LexerMoved,
+        },
+    )))
+}
 
-            ParserResult::Value(StmtVariant::Stmt(ast::Stmt::While(
-                match expect_while_stmt(state, while_token) {
-                    Ok(while_stmt) => while_stmt,
-                    Err(()) => return ParserResult::LexerMoved,
-                },
-            )))
+fn parse_function_declaration_stmt(state: &mut ParserState) -> ParserResult<StmtVariant> {
+    let function_token = state.consume().unwrap();
+
+    let function_declaration = match expect_function_declaration(state, function_token) {
+        Ok(function_declaration) => function_declaration,
+        Err(()) => return ParserResult::LexerMoved,
+    };
+
+    ParserResult::Value(StmtVariant::Stmt(ast::Stmt::FunctionDeclaration(
+        function_declaration,
+    )))
+}
+
+fn parse_repeat_stmt(state: &mut ParserState) -> ParserResult<StmtVariant> {
+    let repeat_token = state.consume().unwrap();
+
+    ParserResult::Value(StmtVariant::Stmt(
+        match expect_repeat_stmt(state, repeat_token) {
+            Ok(repeat_stmt) => repeat_stmt,
+            Err(()) => return ParserResult::LexerMoved,
+        },
+    ))
+}
+
+fn parse_while_stmt(state: &mut ParserState) -> ParserResult<StmtVariant> {
+    let while_token = state.consume().unwrap();
+
+    ParserResult::Value(StmtVariant::Stmt(ast::Stmt::While(
+        match expect_while_stmt(state, while_token) {
+            Ok(while_stmt) => while_stmt,
+            Err(()) => return ParserResult::LexerMoved,
+        },
+    )))
+}
+
+fn parse_assignment_or_call_stmt(state: &mut ParserState) -> ParserResult<StmtVariant> {
+    // unwrap() because we're always starting on the right path
+    let (prefix, suffixes) = try_parser!(parse_prefix_and_suffixes(state)).unwrap();
+
+    let var = match suffixes.last() {
+        Some(ast::Suffix::Call(_)) => {
+            return ParserResult::Value(StmtVariant::Stmt(ast::Stmt::FunctionCall(
+                ast::FunctionCall { prefix, suffixes },
+            )));
         }
 
-        TokenType::Symbol {
-            symbol: Symbol::LeftParen,
+        Some(ast::Suffix::Index(_)) => {
+            ast::Var::Expression(Box::new(ast::VarExpression { prefix, suffixes }))
         }
-        | TokenType::Identifier { .. } => {
-            // unwrap() because we're always starting on the right path
-            let (prefix, suffixes) = try_parser!(parse_prefix_and_suffixes(state)).unwrap();
 
-            let var = match suffixes.last() {
-                Some(ast::Suffix::Call(_)) => {
-                    return ParserResult::Value(StmtVariant::Stmt(ast::Stmt::FunctionCall(
-                        ast::FunctionCall { prefix, suffixes },
-                    )));
-                }
+        None => match prefix {
+            ast::Prefix::Name(name) => ast::Var::Name(name),
 
-                Some(ast::Suffix::Index(_)) => {
-                    ast::Var::Expression(Box::new(ast::VarExpression { prefix, suffixes }))
-                }
+            // I think this only happens in error cases
+            prefix @ ast::Prefix::Expression(_) => {
+                ast::Var::Expression(Box::new(ast::VarExpression { prefix, suffixes }))
+            }
+        },
+    };
 
-                None => match prefix {
-                    ast::Prefix::Name(name) => ast::Var::Name(name),
+    match state.current() {
+        #[cfg(feature = "luau")]
+        // Compound Assignment
+        Ok(token)
+            if state.lua_version().has_luau()
+                && (token.is_symbol(Symbol::PlusEqual)
+                    || token.is_symbol(Symbol::MinusEqual)
+                    || token.is_symbol(Symbol::StarEqual)
+                    || token.is_symbol(Symbol::SlashEqual)
+                    || token.is_symbol(Symbol::DoubleSlashEqual)
+                    || token.is_symbol(Symbol::PercentEqual)
+                    || token.is_symbol(Symbol::CaretEqual)
+                    || token.is_symbol(Symbol::TwoDotsEqual)) =>
+        {
+            return parse_compound_assignment(state, var);
+        }
 
-                    // I think this only happens in error cases
-                    prefix @ ast::Prefix::Expression(_) => {
-                        ast::Var::Expression(Box::new(ast::VarExpression { prefix, suffixes }))
-                    }
-                },
-            };
+        #[cfg(feature = "cfxlua")]
+        Ok(token)
+            if state.lua_version().has_cfxlua()
+                && (token.is_symbol(Symbol::PlusEqual)
+                    || token.is_symbol(Symbol::MinusEqual)
+                    || token.is_symbol(Symbol::StarEqual)
+                    || token.is_symbol(Symbol::SlashEqual)
+                    || token.is_symbol(Symbol::CaretEqual)
+                    || token.is_symbol(Symbol::DoubleLessThanEqual)
+                    || token.is_symbol(Symbol::DoubleGreaterThanEqual)
+                    || token.is_symbol(Symbol::AmpersandEqual)
+                    || token.is_symbol(Symbol::PipeEqual)) =>
+        {
+            return parse_compound_assignment(state, var);
+        }
 
-            match state.current() {
-                #[cfg(feature = "luau")]
-                // Compound Assignment
-                Ok(token)
-                    if state.lua_version().has_luau()
-                        && (token.is_symbol(Symbol::PlusEqual)
-                            || token.is_symbol(Symbol::MinusEqual)
-                            || token.is_symbol(Symbol::StarEqual)
-                            || token.is_symbol(Symbol::SlashEqual)
-                            || token.is_symbol(Symbol::DoubleSlashEqual)
-                            || token.is_symbol(Symbol::PercentEqual)
-                            || token.is_symbol(Symbol::CaretEqual)
-                            || token.is_symbol(Symbol::TwoDotsEqual)) =>
-                {
-                    return parse_compound_assignment(state, var);
-                }
+        Ok(token) if token.is_symbol(Symbol::Comma) || token.is_symbol(Symbol::Equal) => {}
 
-                #[cfg(feature = "cfxlua")]
-                Ok(token)
-                    if state.lua_version().has_cfxlua()
-                        && (token.is_symbol(Symbol::PlusEqual)
-                            || token.is_symbol(Symbol::MinusEqual)
-                            || token.is_symbol(Symbol::StarEqual)
-                            || token.is_symbol(Symbol::SlashEqual)
-                            || token.is_symbol(Symbol::CaretEqual)
-                            || token.is_symbol(Symbol::DoubleLessThanEqual)
-                            || token.is_symbol(Symbol::DoubleGreaterThanEqual)
-                            || token.is_symbol(Symbol::AmpersandEqual)
-                            || token.is_symbol(Symbol::PipeEqual)) =>
-                {
-                    return parse_compound_assignment(state, var);
-                }
+        Ok(token) => {
+            // Check if the consumed token is a potential context-sensitive keyword
+            #[cfg(feature = "luau")]
+            if state.lua_version().has_luau() {
+                if let ast::Var::Name(token) = var {
+                    match token.token_type() {
+                        TokenType::Identifier { identifier } if identifier.as_str() == "export" => {
+                            let export_token = token;
+
+                            let type_token = match state.current() {
+                                Ok(token) if matches!(token.token_type(), TokenType::Identifier { identifier } if identifier.as_str() == "type") => {
+                                    state.consume().unwrap()
+                                }
 
-                Ok(token) if token.is_symbol(Symbol::Comma) || token.is_symbol(Symbol::Equal) => {}
+                                Ok(token) => {
+                                    state.token_error_ranged(
+                                        token.clone(),
+                                        AstErrorKind::ExpectedName,
+                                        "expected `type` after `export`",
+                                        &export_token,
+                                        &token.clone(),
+                                    );
 
-                Ok(token) => {
-                    // Check if the consumed token is a potential context-sensitive keyword
-                    #[cfg(feature = "luau")]
-                    if state.lua_version().has_luau() {
-                        if let ast::Var::Name(token) = var {
-                            match token.token_type() {
-                                TokenType::Identifier { identifier }
-                                    if identifier.as_str() == "export" =>
-                                {
-                                    let export_token = token;
-
-                                    let type_token = match state.current() {
-                                        Ok(token) if matches!(token.token_type(), TokenType::Identifier { identifier } if identifier.as_str() == "type") => {
-                                            state.consume().unwrap()
-                                        }
-
-                                        Ok(token) => {
-                                            state.token_error_ranged(
-                                                token.clone(),
-                                                "expected `type` after `export`",
-                                                &export_token,
-                                                &token.clone(),
-                                            );
-
-                                            return ParserResult::LexerMoved;
-                                        }
+                                    return ParserResult::LexerMoved;
+                                }
+
+                                Err(()) => return ParserResult::LexerMoved,
+                            };
 
+                            if let Some(function_token) = state.consume_if(Symbol::Function) {
+                                return ParserResult::Value(StmtVariant::Stmt(
+                                    ast::Stmt::ExportedTypeFunction(ast::ExportedTypeFunction {
+                                        export_token,
+                                        type_function: match expect_type_function(
+                                            state,
+                                            type_token,
+                                            function_token,
+                                        ) {
+                                            Ok(type_function) => type_function,
+                                            Err(()) => return ParserResult::LexerMoved,
+                                        },
+                                    }),
+                                ));
+                            }
+
+                            return ParserResult::Value(StmtVariant::Stmt(
+                                ast::Stmt::ExportedTypeDeclaration(ast::ExportedTypeDeclaration {
+                                    export_token,
+                                    type_declaration: match expect_type_declaration(
+                                        state, type_token,
+                                    ) {
+                                        Ok(type_declaration) => type_declaration,
                                         Err(()) => return ParserResult::LexerMoved,
-                                    };
-
-                                    if let Some(function_token) = state.consume_if(Symbol::Function)
-                                    {
-                                        return ParserResult::Value(StmtVariant::Stmt(
-                                            ast::Stmt::ExportedTypeFunction(
-                                                ast::ExportedTypeFunction {
-                                                    export_token,
-                                                    type_function: match expect_type_function(
-                                                        state,
-                                                        type_token,
-                                                        function_token,
-                                                    ) {
-                                                        Ok(type_function) => type_function,
-                                                        Err(()) => return ParserResult::LexerMoved,
-                                                    },
-                                                },
-                                            ),
-                                        ));
-                                    }
-
-                                    return ParserResult::Value(StmtVariant::Stmt(
-                                        ast::Stmt::ExportedTypeDeclaration(
-                                            ast::ExportedTypeDeclaration {
-                                                export_token,
-                                                type_declaration: match expect_type_declaration(
-                                                    state, type_token,
-                                                ) {
-                                                    Ok(type_declaration) => type_declaration,
-                                                    Err(()) => return ParserResult::LexerMoved,
-                                                },
-                                            },
-                                        ),
-                                    ));
-                                }
-                                TokenType::Identifier { identifier }
-                                    if identifier.as_str() == "type" =>
-                                {
-                                    let type_token = token;
-
-                                    if let Some(function_token) = state.consume_if(Symbol::Function)
-                                    {
-                                        return ParserResult::Value(StmtVariant::Stmt(
-                                            ast::Stmt::TypeFunction(
-                                                match expect_type_function(
-                                                    state,
-                                                    type_token,
-                                                    function_token,
-                                                ) {
-                                                    Ok(type_function) => type_function,
-                                                    Err(()) => return ParserResult::LexerMoved,
-                                                },
-                                            ),
-                                        ));
-                                    }
-
-                                    return ParserResult::Value(StmtVariant::Stmt(
-                                        ast::Stmt::TypeDeclaration(
-                                            match expect_type_declaration(state, type_token) {
-                                                Ok(type_declaration) => type_declaration,
-                                                Err(()) => return ParserResult::LexerMoved,
-                                            },
-                                        ),
-                                    ));
-                                }
-                                TokenType::Identifier { identifier }
-                                    if identifier.as_str() == "continue" =>
-                                {
-                                    let continue_token = token;
-                                    return ParserResult::Value(StmtVariant::LastStmt(
-                                        ast::LastStmt::Continue(continue_token),
-                                    ));
-                                }
-                                _ => (),
+                                    },
+                                }),
+                            ));
+                        }
+                        TokenType::Identifier { identifier } if identifier.as_str() == "type" => {
+                            let type_token = token;
+
+                            if let Some(function_token) = state.consume_if(Symbol::Function) {
+                                return ParserResult::Value(StmtVariant::Stmt(
+                                    ast::Stmt::TypeFunction(
+                                        match expect_type_function(
+                                            state,
+                                            type_token,
+                                            function_token,
+                                        ) {
+                                            Ok(type_function) => type_function,
+                                            Err(()) => return ParserResult::LexerMoved,
+                                        },
+                                    ),
+                                ));
                             }
+
+                            return ParserResult::Value(StmtVariant::Stmt(
+                                ast::Stmt::TypeDeclaration(
+                                    match expect_type_declaration(state, type_token) {
+                                        Ok(type_declaration) => type_declaration,
+                                        Err(()) => return ParserResult::LexerMoved,
+                                    },
+                                ),
+                            ));
+                        }
+                        TokenType::Identifier { identifier }
+                            if identifier.as_str() == "continue" =>
+                        {
+                            let continue_token = token;
+                            return ParserResult::Value(StmtVariant::LastStmt(
+                                ast::LastStmt::Continue(continue_token),
+                            ));
                         }
+                        _ => (),
                     }
-
-                    state.token_error(
-                        token.clone(),
-                        "unexpected expression when looking for a statement",
-                    );
-
-                    return ParserResult::LexerMoved;
                 }
+            }
 
-                Err(()) => return ParserResult::LexerMoved,
-            };
+            state.token_error(
+                token.clone(),
+                AstErrorKind::UnexpectedExpression,
+                "unexpected expression when looking for a statement",
+            );
 
-            let mut var_list = Punctuated::new();
-            var_list.push(Pair::End(var));
+            return ParserResult::LexerMoved;
+        }
 
-            loop {
-                let next_comma = match state.current() {
-                    Ok(token) if token.is_symbol(Symbol::Comma) => state.consume().unwrap(),
-                    Ok(_) => break,
-                    Err(()) => return ParserResult::LexerMoved,
-                };
+        Err(()) => return ParserResult::LexerMoved,
+    };
 
-                let (next_prefix, next_suffixes) = match parse_prefix_and_suffixes(state) {
-                    ParserResult::Value((prefix, suffixes)) => (prefix, suffixes),
+    let mut var_list = Punctuated::new();
+    var_list.push(Pair::End(var));
 
-                    ParserResult::LexerMoved => {
-                        break;
-                    }
+    loop {
+        let next_comma = match state.current() {
+            Ok(token) if token.is_symbol(Symbol::Comma) => state.consume().unwrap(),
+            Ok(_) => break,
+            Err(()) => return ParserResult::LexerMoved,
+        };
 
-                    ParserResult::NotFound => {
-                        state.token_error(next_comma, "expected another variable");
-                        break;
-                    }
-                };
+        let (next_prefix, next_suffixes) = match parse_prefix_and_suffixes(state) {
+            ParserResult::Value((prefix, suffixes)) => (prefix, suffixes),
 
-                match next_suffixes.last() {
-                    Some(ast::Suffix::Call(call)) => {
-                        state.token_error(
-                            call.tokens().next_back().unwrap().clone(),
-                            "can't assign to the result of a call",
-                        );
-                        break;
-                    }
+            ParserResult::LexerMoved => {
+                break;
+            }
 
-                    Some(ast::Suffix::Index(_)) => {
-                        var_list.push_punctuated(
-                            ast::Var::Expression(Box::new(ast::VarExpression {
-                                prefix: next_prefix,
-                                suffixes: next_suffixes,
-                            })),
-                            next_comma,
-                        );
-                    }
+            ParserResult::NotFound => {
+                state.token_error(
+                    next_comma,
+                    AstErrorKind::ExpectedName,
+                    "expected another variable",
+                );
+                break;
+            }
+        };
 
-                    None => match next_prefix {
-                        ast::Prefix::Name(name) => {
-                            var_list.push_punctuated(ast::Var::Name(name), next_comma);
-                        }
+        match next_suffixes.last() {
+            Some(ast::Suffix::Call(call)) => {
+                state.token_error(
+                    call.tokens().next_back().unwrap().clone(),
+                    AstErrorKind::AssignToCall,
+                    "can't assign to the result of a call",
+                );
+                break;
+            }
 
-                        prefix @ ast::Prefix::Expression(_) => var_list.push_punctuated(
-                            ast::Var::Expression(Box::new(ast::VarExpression {
-                                prefix,
-                                suffixes: next_suffixes,
-                            })),
-                            next_comma,
-                        ),
-                    },
-                }
+            Some(ast::Suffix::Index(_)) => {
+                var_list.push_punctuated(
+                    ast::Var::Expression(Box::new(ast::VarExpression {
+                        prefix: next_prefix,
+                        suffixes: next_suffixes,
+                    })),
+                    next_comma,
+                );
             }
 
-            let Some(equal_token) = state.require(Symbol::Equal, "expected `=` after name") else {
-                return ParserResult::LexerMoved;
-            };
+            None => match next_prefix {
+                ast::Prefix::Name(name) => {
+                    var_list.push_punctuated(ast::Var::Name(name), next_comma);
+                }
 
-            let expr_list = match parse_expression_list(state) {
-                ParserResult::Value(expr_list) => expr_list,
+                prefix @ ast::Prefix::Expression(_) => var_list.push_punctuated(
+                    ast::Var::Expression(Box::new(ast::VarExpression {
+                        prefix,
+                        suffixes: next_suffixes,
+                    })),
+                    next_comma,
+                ),
+            },
+        }
+    }
 
-                ParserResult::NotFound => {
-                    state.token_error(equal_token.clone(), "expected values to set to");
-                    Punctuated::new()
-                }
+    let Some(equal_token) = state.require(Symbol::Equal, "expected `=` after name") else {
+        return ParserResult::LexerMoved;
+    };
 
-                ParserResult::LexerMoved => Punctuated::new(),
-            };
+    let expr_list = match parse_expression_list(state) {
+        ParserResult::Value(expr_list) => expr_list,
 
-            ParserResult::Value(StmtVariant::Stmt(ast::Stmt::Assignment(ast::Assignment {
-                var_list,
-                equal_token,
-                expr_list,
-            })))
+        ParserResult::NotFound => {
+            state.token_error(
+                equal_token.clone(),
+                AstErrorKind::ExpectedExpression,
+                "expected values to set to",
+            );
+            Punctuated::new()
         }
 
-        #[cfg(any(feature = "lua52", feature = "luajit", feature = "cfxlua"))]
-        TokenType::Symbol {
-            symbol: Symbol::Goto,
-        } => {
-            debug_assert!(state.lua_version().has_lua52() || state.lua_version().has_luajit());
-
-            let goto_token = state.consume().unwrap();
+        ParserResult::LexerMoved => Punctuated::new(),
+    };
 
-            match state.current() {
-                Ok(token) if matches!(token.token_type(), TokenType::Identifier { .. }) => {
-                    let label_name = state.consume().unwrap();
-                    ParserResult::Value(StmtVariant::Stmt(ast::Stmt::Goto(ast::Goto {
-                        goto_token,
-                        label_name,
-                    })))
-                }
+    ParserResult::Value(StmtVariant::Stmt(ast::Stmt::Assignment(ast::Assignment {
+        var_list,
+        equal_token,
+        expr_list,
+    })))
+}
 
-                Ok(token) => {
-                    state.token_error_ranged(
-                        token.clone(),
-                        "expected label name after `goto`",
-                        &goto_token,
-                        &token.clone(),
-                    );
+#[cfg(any(feature = "lua52", feature = "luajit", feature = "cfxlua"))]
+fn parse_goto_stmt(state: &mut ParserState) -> ParserResult<StmtVariant> {
+    debug_assert!(state.lua_version().has_lua52() || state.lua_version().has_luajit());
 
-                    ParserResult::LexerMoved
-                }
+    let goto_token = state.consume().unwrap();
 
-                Err(()) => {
-                    state.token_error(goto_token, "expected label name after `goto`");
-                    ParserResult::LexerMoved
-                }
-            }
+    match state.current() {
+        Ok(token) if matches!(token.token_type(), TokenType::Identifier { .. }) => {
+            let label_name = state.consume().unwrap();
+            ParserResult::Value(StmtVariant::Stmt(ast::Stmt::Goto(ast::Goto {
+                goto_token,
+                label_name,
+            })))
         }
 
-        #[cfg(any(feature = "lua52", feature = "luajit"))]
-        TokenType::Symbol {
-            symbol: Symbol::TwoColons,
-        } if state.lua_version().has_lua52() || state.lua_version().has_luajit() => {
-            let left_colons = state.consume().unwrap();
+        Ok(token) => {
+            state.token_error_ranged(
+                token.clone(),
+                AstErrorKind::ExpectedName,
+                "expected label name after `goto`",
+                &goto_token,
+                &token.clone(),
+            );
 
-            let name = match state.current() {
-                Ok(token) if matches!(token.token_type(), TokenType::Identifier { .. }) => {
-                    state.consume().unwrap()
-                }
+            ParserResult::LexerMoved
+        }
 
-                Ok(token) => {
-                    state.token_error_ranged(
-                        token.clone(),
-                        "expected label name after `::`",
-                        &left_colons,
-                        &token.clone(),
-                    );
+        Err(()) => {
+            state.token_error(
+                goto_token,
+                AstErrorKind::ExpectedName,
+                "expected label name after `goto`",
+            );
+            ParserResult::LexerMoved
+        }
+    }
+}
 
-                    return ParserResult::LexerMoved;
-                }
+#[cfg(any(feature = "lua52", feature = "luajit"))]
+fn parse_label_stmt(state: &mut ParserState) -> ParserResult<StmtVariant> {
+    let left_colons = state.consume().unwrap();
 
-                Err(()) => return ParserResult::LexerMoved,
-            };
+    let name = match state.current() {
+        Ok(token) if matches!(token.token_type(), TokenType::Identifier { .. }) => {
+            state.consume().unwrap()
+        }
 
-            let right_colons = match state.require(Symbol::TwoColons, "expected `::` after label") {
-                Some(token) => token,
-                None => TokenReference::symbol("::").unwrap(),
-            };
+        Ok(token) => {
+            state.token_error_ranged(
+                token.clone(),
+                AstErrorKind::ExpectedName,
+                "expected label name after `::`",
+                &left_colons,
+                &token.clone(),
+            );
 
-            ParserResult::Value(StmtVariant::Stmt(ast::Stmt::Label(ast::Label {
-                left_colons,
-                name,
-                right_colons,
-            })))
+            return ParserResult::LexerMoved;
         }
 
-        _ => ParserResult::NotFound,
-    }
+        Err(()) => return ParserResult::LexerMoved,
+    };
+
+    let right_colons = match state.require(Symbol::TwoColons, "expected `::` after label") {
+        Some(token) => token,
+        None => TokenReference::phantom_symbol("::"),
+    };
+
+    ParserResult::Value(StmtVariant::Stmt(ast::Stmt::Label(ast::Label {
+        left_colons,
+        name,
+        right_colons,
+    })))
 }
 
-fn parse_last_stmt(
+pub fn parse_last_stmt(
     state: &mut ParserState,
 ) -> ParserResult<(ast::LastStmt, Option<TokenReference>)> {
     let last_stmt = match state.current() {
@@ -660,7 +754,11 @@ fn expect_function_name(state: &mut ParserState) -> ParserResult<ast::FunctionNa
         }
 
         Ok(token) => {
-            state.token_error(token.clone(), "expected function name");
+            state.token_error(
+                token.clone(),
+                AstErrorKind::ExpectedName,
+                "expected function name",
+            );
             return ParserResult::NotFound;
         }
 
@@ -686,6 +784,7 @@ fn expect_function_name(state: &mut ParserState) -> ParserResult<ast::FunctionNa
             Ok(token) => {
                 state.token_error(
                     token.clone(),
+                    AstErrorKind::ExpectedName,
                     format!("expected name after `{}`", middle_token.token()),
                 );
                 return ParserResult::NotFound;
@@ -729,7 +828,11 @@ fn expect_function_declaration(
         ParserResult::LexerMoved => ast::FunctionBody::new(),
 
         ParserResult::NotFound => {
-            state.token_error(function_token.clone(), "expected a function body");
+            state.token_error(
+                function_token.clone(),
+                AstErrorKind::ExpectedFunctionBody,
+                "expected a function body",
+            );
             ast::FunctionBody::new()
         }
     };
@@ -745,7 +848,11 @@ fn expect_for_stmt(state: &mut ParserState, for_token: TokenReference) -> Result
     let name_list = match parse_name_list(state) {
         ParserResult::Value(name_list) => name_list,
         ParserResult::NotFound => {
-            state.token_error(for_token, "expected name after `for`");
+            state.token_error(
+                for_token,
+                AstErrorKind::ExpectedName,
+                "expected name after `for`",
+            );
             return Err(());
         }
         ParserResult::LexerMoved => return Err(()),
@@ -769,7 +876,11 @@ fn expect_for_stmt(state: &mut ParserState, for_token: TokenReference) -> Result
     let in_token = match current_token {
         token if token.is_symbol(Symbol::In) => state.consume().unwrap(),
         token => {
-            state.token_error(token.clone(), "expected `in` after name list");
+            state.token_error(
+                token.clone(),
+                AstErrorKind::ExpectedSymbol(Symbol::In),
+                "expected `in` after name list",
+            );
             return Err(());
         }
     };
@@ -777,7 +888,11 @@ fn expect_for_stmt(state: &mut ParserState, for_token: TokenReference) -> Result
     let expressions = match parse_expression_list(state) {
         ParserResult::Value(expressions) => expressions,
         ParserResult::NotFound => {
-            state.token_error(in_token, "expected expressions after `in`");
+            state.token_error(
+                in_token,
+                AstErrorKind::ExpectedExpression,
+                "expected expressions after `in`",
+            );
             return Err(());
         }
         ParserResult::LexerMoved => return Err(()),
@@ -798,15 +913,15 @@ fn expect_for_stmt(state: &mut ParserState, for_token: TokenReference) -> Result
                 .collect(),
             in_token,
             expr_list: expressions,
-            do_token: TokenReference::basic_symbol("do"),
+            do_token: TokenReference::phantom_symbol("do"),
             block: ast::Block::new(),
-            end_token: TokenReference::basic_symbol("end"),
+            end_token: TokenReference::phantom_symbol("end"),
         }));
     };
 
     let (block, end) = match expect_block_with_end(state, "for loop", &do_token) {
         Ok(block) => block,
-        Err(()) => (ast::Block::new(), TokenReference::basic_symbol("end")),
+        Err(()) => (ast::Block::new(), TokenReference::phantom_symbol("end")),
     };
 
     Ok(ast::Stmt::GenericFor(ast::GenericFor {
@@ -840,7 +955,11 @@ fn expect_numeric_for_stmt(
     let start = match parse_expression(state) {
         ParserResult::Value(start) => start,
         ParserResult::NotFound => {
-            state.token_error(equal_token, "expected start expression after `=`");
+            state.token_error(
+                equal_token,
+                AstErrorKind::ExpectedExpression,
+                "expected start expression after `=`",
+            );
             return Err(());
         }
         ParserResult::LexerMoved => return Err(()),
@@ -854,7 +973,11 @@ fn expect_numeric_for_stmt(
     let end = match parse_expression(state) {
         ParserResult::Value(end) => end,
         ParserResult::NotFound => {
-            state.token_error(start_end_comma, "expected end expression after `,`");
+            state.token_error(
+                start_end_comma,
+                AstErrorKind::ExpectedExpression,
+                "expected end expression after `,`",
+            );
             return Err(());
         }
         ParserResult::LexerMoved => return Err(()),
@@ -865,7 +988,11 @@ fn expect_numeric_for_stmt(
         Some(end_step_comma) => match parse_expression(state) {
             ParserResult::Value(step) => (Some(end_step_comma), Some(step)),
             ParserResult::NotFound => {
-                state.token_error(start_end_comma, "expected step expression after `,`");
+                state.token_error(
+                    start_end_comma,
+                    AstErrorKind::ExpectedExpression,
+                    "expected step expression after `,`",
+                );
                 return Err(());
             }
             ParserResult::LexerMoved => return Err(()),
@@ -880,7 +1007,7 @@ fn expect_numeric_for_stmt(
 
     let (block, end_token) = match expect_block_with_end(state, "numeric for loop", &do_token) {
         Ok(block) => block,
-        Err(()) => (ast::Block::new(), TokenReference::basic_symbol("end")),
+        Err(()) => (ast::Block::new(), TokenReference::phantom_symbol("end")),
     };
 
     Ok(ast::NumericFor {
@@ -904,7 +1031,11 @@ fn expect_if_stmt(state: &mut ParserState, if_token: TokenReference) -> Result<a
     let condition = match parse_expression(state) {
         ParserResult::Value(condition) => condition,
         ParserResult::NotFound => {
-            state.token_error(if_token, "expected condition after `if`");
+            state.token_error(
+                if_token,
+                AstErrorKind::ExpectedExpression,
+                "expected condition after `if`",
+            );
             return Err(());
         }
         ParserResult::LexerMoved => return Err(()),
@@ -917,7 +1048,11 @@ fn expect_if_stmt(state: &mut ParserState, if_token: TokenReference) -> Result<a
     let then_block = match parse_block(state) {
         ParserResult::Value(block) => block,
         ParserResult::NotFound => {
-            state.token_error(then_token, "expected block after `then`");
+            state.token_error(
+                then_token,
+                AstErrorKind::ExpectedBlock,
+                "expected block after `then`",
+            );
             return Ok(ast::If::new(condition));
         }
         ParserResult::LexerMoved => {
@@ -954,7 +1089,11 @@ fn expect_if_stmt(state: &mut ParserState, if_token: TokenReference) -> Result<a
         let condition = match parse_expression(state) {
             ParserResult::Value(condition) => condition,
             ParserResult::NotFound => {
-                state.token_error(else_if_token, "expected condition after `elseif`");
+                state.token_error(
+                    else_if_token,
+                    AstErrorKind::ExpectedExpression,
+                    "expected condition after `elseif`",
+                );
                 return unfinished_if(condition, else_if);
             }
             ParserResult::LexerMoved => {
@@ -970,7 +1109,11 @@ fn expect_if_stmt(state: &mut ParserState, if_token: TokenReference) -> Result<a
         let then_block = match parse_block(state) {
             ParserResult::Value(block) => block,
             ParserResult::NotFound => {
-                state.token_error(then_token, "expected block after `then`");
+                state.token_error(
+                    then_token,
+                    AstErrorKind::ExpectedBlock,
+                    "expected block after `then`",
+                );
                 return unfinished_if(condition, else_if);
             }
             ParserResult::LexerMoved => {
@@ -990,7 +1133,11 @@ fn expect_if_stmt(state: &mut ParserState, if_token: TokenReference) -> Result<a
         Some(else_token) => match parse_block(state) {
             ParserResult::Value(block) => (Some(block), Some(else_token)),
             ParserResult::NotFound => {
-                state.token_error(else_token.clone(), "expected block after `else`");
+                state.token_error(
+                    else_token.clone(),
+                    AstErrorKind::ExpectedBlock,
+                    "expected block after `else`",
+                );
                 (Some(ast::Block::new()), Some(else_token))
             }
             ParserResult::LexerMoved => (Some(ast::Block::new()), Some(else_token)),
@@ -1002,11 +1149,16 @@ fn expect_if_stmt(state: &mut ParserState, if_token: TokenReference) -> Result<a
     let end_token = match state.current() {
         Ok(token) if token.is_symbol(Symbol::End) => state.consume().unwrap(),
         Ok(token) => {
-            state.token_error(token.clone(), "expected `end` to conclude `if`");
-            TokenReference::basic_symbol("end")
+            state.token_error(
+                token.clone(),
+                AstErrorKind::ExpectedSymbol(Symbol::End),
+                "expected `end` to conclude `if`",
+            );
+            state.relate_last_error(&if_token);
+            TokenReference::phantom_symbol("end")
         }
 
-        Err(()) => TokenReference::basic_symbol("end"),
+        Err(()) => TokenReference::phantom_symbol("end"),
     };
 
     Ok(ast::If {
@@ -1083,6 +1235,7 @@ fn expect_local_assignment(
         ParserResult::NotFound => {
             state.token_error(
                 local_assignment.equal_token.clone().unwrap(),
+                AstErrorKind::ExpectedExpression,
                 "expected an expression",
             );
         }
@@ -1101,7 +1254,11 @@ fn expect_expression_key(
         ParserResult::Value(expression) => expression,
 
         ParserResult::NotFound => {
-            state.token_error(left_bracket, "expected an expression after `[`");
+            state.token_error(
+                left_bracket,
+                AstErrorKind::ExpectedExpression,
+                "expected an expression after `[`",
+            );
 
             return Err(());
         }
@@ -1139,7 +1296,11 @@ fn expect_expression_key(
         ParserResult::Value(expression) => expression,
 
         ParserResult::NotFound => {
-            state.token_error(equal_token, "expected an expression after `=`");
+            state.token_error(
+                equal_token,
+                AstErrorKind::ExpectedExpression,
+                "expected an expression after `=`",
+            );
 
             return Err(());
         }
@@ -1165,7 +1326,7 @@ fn force_table_constructor(
 
     let unfinished_table =
         |left_brace: TokenReference, fields: Punctuated<ast::Field>| ast::TableConstructor {
-            braces: ContainedSpan::new(left_brace, TokenReference::basic_symbol("}")),
+            braces: ContainedSpan::new(left_brace, TokenReference::phantom_symbol("}")),
             fields,
         };
 
@@ -1207,7 +1368,11 @@ fn force_table_constructor(
                     ParserResult::Value(expression) => expression,
 
                     ParserResult::NotFound => {
-                        state.token_error(equal_token, "expected an expression after `=`");
+                        state.token_error(
+                            equal_token,
+                            AstErrorKind::ExpectedExpression,
+                            "expected an expression after `=`",
+                        );
 
                         return unfinished_table(left_brace, fields);
                     }
@@ -1236,7 +1401,11 @@ fn force_table_constructor(
                         state.consume().unwrap()
                     }
                     Ok(token) => {
-                        state.token_error(token.clone(), "expected identifier after `.`");
+                        state.token_error(
+                            token.clone(),
+                            AstErrorKind::ExpectedName,
+                            "expected identifier after `.`",
+                        );
                         return unfinished_table(left_brace, fields);
                     }
                     Err(()) => {
@@ -1262,8 +1431,13 @@ fn force_table_constructor(
                                 }
                                 None => left_brace.clone(),
                             },
+                            AstErrorKind::ExpectedExpression,
                             "expected a field",
                         );
+                        state.also_expected(&[
+                            ExpectedToken::Symbol(Symbol::LeftBracket),
+                            ExpectedToken::Symbol(Symbol::RightBrace),
+                        ]);
 
                         return unfinished_table(left_brace, fields);
                     }
@@ -1294,9 +1468,11 @@ fn force_table_constructor(
         };
     }
 
-    let right_brace = match state.require(Symbol::RightBrace, "expected `}` after last field") {
+    let right_brace = match state.require_closing(&left_brace, |state| {
+        state.require(Symbol::RightBrace, "expected `}` after last field")
+    }) {
         Some(right_brace) => right_brace,
-        None => TokenReference::basic_symbol("}"),
+        None => TokenReference::phantom_symbol("}"),
     };
 
     ast::TableConstructor {
@@ -1313,7 +1489,11 @@ fn expect_repeat_stmt(
         ParserResult::Value(block) => block,
 
         ParserResult::NotFound => {
-            state.token_error(repeat_token, "expected a block after `repeat`");
+            state.token_error(
+                repeat_token,
+                AstErrorKind::ExpectedBlock,
+                "expected a block after `repeat`",
+            );
 
             return Err(());
         }
@@ -1323,21 +1503,30 @@ fn expect_repeat_stmt(
         }
     };
 
-    let Some(until_token) = state.require(Symbol::Until, "expected `until` after block") else {
-        return Ok(ast::Stmt::Do(ast::Do::new().with_block(block)));
+    let Some(until_token) = state.require_closing(&repeat_token, |state| {
+        state.require(Symbol::Until, "expected `until` after block")
+    }) else {
+        return Ok(ast::Stmt::Repeat(ast::Repeat {
+            repeat_token,
+            block,
+            until: ast::Expression::Error(Vec::new()),
+            until_token: TokenReference::phantom_symbol("until"),
+        }));
     };
 
     let condition = match parse_expression(state) {
         ParserResult::Value(expression) => expression,
 
         ParserResult::NotFound => {
-            state.token_error(until_token, "expected a condition after `until`");
-            return Ok(ast::Stmt::Do(ast::Do::new().with_block(block)));
+            state.token_error(
+                until_token.clone(),
+                AstErrorKind::ExpectedExpression,
+                "expected a condition after `until`",
+            );
+            ast::Expression::Error(Vec::new())
         }
 
-        ParserResult::LexerMoved => {
-            return Ok(ast::Stmt::Do(ast::Do::new().with_block(block)));
-        }
+        ParserResult::LexerMoved => ast::Expression::Error(Vec::new()),
     };
 
     Ok(ast::Stmt::Repeat(ast::Repeat {
@@ -1356,7 +1545,11 @@ fn expect_while_stmt(
         ParserResult::Value(expression) => expression,
 
         ParserResult::NotFound => {
-            state.token_error(while_token, "expected a condition after `while`");
+            state.token_error(
+                while_token,
+                AstErrorKind::ExpectedExpression,
+                "expected a condition after `while`",
+            );
 
             return Err(());
         }
@@ -1395,7 +1588,11 @@ fn expect_type_declaration(
     let base = match state.current()? {
         token if token.token_kind() == TokenKind::Identifier => state.consume().unwrap(),
         token => {
-            state.token_error(token.clone(), "expected type name");
+            state.token_error(
+                token.clone(),
+                AstErrorKind::ExpectedName,
+                "expected type name",
+            );
             // rewrite todo (in future if needed): maybe we can add an error name here to continue parsing?
             return Err(());
         }
@@ -1409,7 +1606,7 @@ fn expect_type_declaration(
 
     let equal_token = state
         .require(Symbol::Equal, "expected `=` after type name")
-        .unwrap_or_else(|| TokenReference::basic_symbol("="));
+        .unwrap_or_else(|| TokenReference::phantom_symbol("="));
 
     let ParserResult::Value(declare_as) = parse_type(state) else {
         return Err(());
@@ -1434,7 +1631,11 @@ fn expect_type_function(
         Ok(token) if token.token_kind() == TokenKind::Identifier => state.consume().unwrap(),
 
         Ok(token) => {
-            state.token_error(token.clone(), "expected a type function name");
+            state.token_error(
+                token.clone(),
+                AstErrorKind::ExpectedName,
+                "expected a type function name",
+            );
             return Err(());
         }
 
@@ -1445,7 +1646,11 @@ fn expect_type_function(
         ParserResult::Value(body) => body,
         ParserResult::LexerMoved => return Err(()),
         ParserResult::NotFound => {
-            state.token_error(function_token, "expected a type function body");
+            state.token_error(
+                function_token,
+                AstErrorKind::ExpectedFunctionBody,
+                "expected a type function body",
+            );
             return Err(());
         }
     };
@@ -1474,19 +1679,23 @@ fn parse_prefix(state: &mut ParserState) -> ParserResult<ast::Prefix> {
                 Some(expression) => expression,
 
                 None => {
-                    state.token_error(left_parenthesis, "expected an expression after `(`");
+                    state.token_error(
+                        left_parenthesis,
+                        AstErrorKind::ExpectedExpression,
+                        "expected an expression after `(`",
+                    );
                     return ParserResult::LexerMoved;
                 }
             });
 
-            let Some(right_parenthesis) =
+            let Some(right_parenthesis) = state.require_closing(&left_parenthesis, |state| {
                 state.require(Symbol::RightParen, "expected `)` after expression")
-            else {
+            }) else {
                 return ParserResult::Value(ast::Prefix::Expression(Box::new(
                     ast::Expression::Parentheses {
                         contained: ContainedSpan::new(
                             left_parenthesis,
-                            TokenReference::basic_symbol(")"),
+                            TokenReference::phantom_symbol(")"),
                         ),
                         expression,
                     },
@@ -1521,14 +1730,16 @@ fn parse_arguments(state: &mut ParserState) -> ParserResult<ast::FunctionArgs> {
         } => {
             let left_parenthesis = state.consume().unwrap();
             let arguments = try_parser!(parse_expression_list(state)).unwrap_or_default();
-            let right_parenthesis = match state.require_with_reference_token(
-                Symbol::RightParen,
-                "expected `)` to close function call",
-                &left_parenthesis,
-            ) {
+            let right_parenthesis = match state.require_closing(&left_parenthesis, |state| {
+                state.require_with_reference_token(
+                    Symbol::RightParen,
+                    "expected `)` to close function call",
+                    &left_parenthesis,
+                )
+            }) {
                 Some(token) => token,
 
-                None => TokenReference::basic_symbol(")"),
+                None => TokenReference::phantom_symbol(")"),
             };
 
             ParserResult::Value(ast::FunctionArgs::Parentheses {
@@ -1572,7 +1783,11 @@ fn parse_suffix(state: &mut ParserState) -> ParserResult<ast::Suffix> {
                 }
 
                 Ok(_) => {
-                    state.token_error(safe_navigation, "expected identifier after `?.`");
+                    state.token_error(
+                        safe_navigation,
+                        AstErrorKind::ExpectedName,
+                        "expected identifier after `?.`",
+                    );
                     return ParserResult::LexerMoved;
                 }
 
@@ -1595,7 +1810,11 @@ fn parse_suffix(state: &mut ParserState) -> ParserResult<ast::Suffix> {
                 }
 
                 Ok(_) => {
-                    state.token_error(dot, "expected identifier after `.`");
+                    state.token_error(
+                        dot,
+                        AstErrorKind::ExpectedName,
+                        "expected identifier after `.`",
+                    );
                     return ParserResult::LexerMoved;
                 }
 
@@ -1614,20 +1833,26 @@ fn parse_suffix(state: &mut ParserState) -> ParserResult<ast::Suffix> {
                 ParserResult::Value(expression) => expression,
                 ParserResult::LexerMoved => return ParserResult::LexerMoved,
                 ParserResult::NotFound => {
-                    state.token_error(left_bracket, "expected expression after `[`");
+                    state.token_error(
+                        left_bracket,
+                        AstErrorKind::ExpectedExpression,
+                        "expected expression after `[`",
+                    );
                     return ParserResult::LexerMoved;
                 }
             };
 
-            let right_bracket = match state.require_with_reference_range(
-                Symbol::RightBracket,
-                "expected `]` to close index expression",
-                &left_bracket,
-                expression.tokens().next_back().unwrap(),
-            ) {
+            let right_bracket = match state.require_closing(&left_bracket, |state| {
+                state.require_with_reference_range(
+                    Symbol::RightBracket,
+                    "expected `]` to close index expression",
+                    &left_bracket,
+                    expression.tokens().next_back().unwrap(),
+                )
+            }) {
                 Some(right_bracket) => right_bracket,
 
-                None => TokenReference::basic_symbol("]"),
+                None => TokenReference::phantom_symbol("]"),
             };
 
             ParserResult::Value(ast::Suffix::Index(ast::Index::Brackets {
@@ -1655,7 +1880,11 @@ fn parse_suffix(state: &mut ParserState) -> ParserResult<ast::Suffix> {
                 }
 
                 Ok(_) => {
-                    state.token_error(colon_token, "expected identifier after `:`");
+                    state.token_error(
+                        colon_token,
+                        AstErrorKind::ExpectedName,
+                        "expected identifier after `:`",
+                    );
                     return ParserResult::LexerMoved;
                 }
 
@@ -1666,7 +1895,11 @@ fn parse_suffix(state: &mut ParserState) -> ParserResult<ast::Suffix> {
                 ParserResult::Value(args) => args,
                 ParserResult::LexerMoved => ast::FunctionArgs::empty(),
                 ParserResult::NotFound => {
-                    state.token_error(name.clone(), "expected arguments after `:`");
+                    state.token_error(
+                        name.clone(),
+                        AstErrorKind::ExpectedArguments,
+                        "expected arguments after `:`",
+                    );
                     ast::FunctionArgs::empty()
                 }
             };
@@ -1712,14 +1945,16 @@ fn parse_prefix_and_suffixes(
     ParserResult::Value((prefix, suffixes))
 }
 
-fn parse_expression(state: &mut ParserState) -> ParserResult<Expression> {
-    let primary_expression = match parse_primary_expression(state) {
-        ParserResult::Value(expression) => expression,
-        ParserResult::NotFound => return ParserResult::NotFound,
-        ParserResult::LexerMoved => return ParserResult::LexerMoved,
-    };
+pub fn parse_expression(state: &mut ParserState) -> ParserResult<Expression> {
+    state.nested(|state| {
+        let primary_expression = match parse_primary_expression(state) {
+            ParserResult::Value(expression) => expression,
+            ParserResult::NotFound => return ParserResult::NotFound,
+            ParserResult::LexerMoved => return ParserResult::LexerMoved,
+        };
 
-    parse_expression_with_precedence(state, primary_expression, 0)
+        parse_expression_with_precedence(state, primary_expression, 0)
+    })
 }
 
 fn parse_primary_expression(state: &mut ParserState) -> ParserResult<Expression> {
@@ -1737,7 +1972,11 @@ fn parse_primary_expression(state: &mut ParserState) -> ParserResult<Expression>
                 ParserResult::Value(body) => body,
                 ParserResult::LexerMoved => return ParserResult::LexerMoved,
                 ParserResult::NotFound => {
-                    state.token_error(function_token, "expected a function body");
+                    state.token_error(
+                        function_token,
+                        AstErrorKind::ExpectedFunctionBody,
+                        "expected a function body",
+                    );
                     return ParserResult::LexerMoved;
                 }
             };
@@ -1896,48 +2135,87 @@ fn parse_expression_with_precedence(
             return ParserResult::Value(lhs);
         }
 
-        let bin_op = ast::BinOp::consume(state).unwrap();
+        // The operator may exist with the enabled features, but not in the Lua version being parsed
+        let Some(bin_op) = ast::BinOp::consume(state) else {
+            return ParserResult::Value(lhs);
+        };
 
         let mut rhs = match parse_primary_expression(state) {
             ParserResult::Value(expression) => expression,
             ParserResult::NotFound => {
                 state.token_error(
                     bin_op.token().clone(),
+                    AstErrorKind::ExpectedExpression,
                     "expected expression after binary operator",
                 );
-                return ParserResult::Value(lhs);
+
+                return ParserResult::Value(Expression::BinaryOperator {
+                    lhs: Box::new(lhs),
+                    binop: bin_op,
+                    rhs: Box::new(Expression::Error(Vec::new())),
+                });
             }
             ParserResult::LexerMoved => return ParserResult::LexerMoved,
         };
 
+        // The operands and operators before the last operand of a chain of right associative
+        // operators, such as `..`, which are parsed in a loop rather than nesting for each operator
+        let mut right_associative = Vec::new();
+
         while let Ok(next_bin_op_token) = state.current() {
             let Some(next_bin_op_precedence) = ast::BinOp::precedence_of_token(next_bin_op_token)
             else {
                 break;
             };
 
-            let precedence_to_search;
-
             if next_bin_op_precedence > bin_op_precedence {
-                precedence_to_search = bin_op_precedence + 1;
+                rhs = match state.nested(|state| {
+                    parse_expression_with_precedence(state, rhs, bin_op_precedence + 1)
+                }) {
+                    ParserResult::Value(expression) => expression,
+                    ParserResult::NotFound => {
+                        state.token_error(
+                            bin_op.token().clone(),
+                            AstErrorKind::ExpectedExpression,
+                            "expected expression after binary operator",
+                        );
+                        return ParserResult::Value(lhs);
+                    }
+                    ParserResult::LexerMoved => return ParserResult::Value(lhs),
+                };
             } else if ast::BinOp::is_right_associative_token(next_bin_op_token)
                 && next_bin_op_precedence == bin_op_precedence
             {
-                precedence_to_search = bin_op_precedence;
+                let Some(next_bin_op) = ast::BinOp::consume(state) else {
+                    break;
+                };
+
+                let next_rhs = match parse_primary_expression(state) {
+                    ParserResult::Value(expression) => expression,
+                    ParserResult::NotFound => {
+                        state.token_error(
+                            next_bin_op.token().clone(),
+                            AstErrorKind::ExpectedExpression,
+                            "expected expression after binary operator",
+                        );
+
+                        Expression::Error(Vec::new())
+                    }
+                    ParserResult::LexerMoved => return ParserResult::LexerMoved,
+                };
+
+                right_associative.push((std::mem::replace(&mut rhs, next_rhs), next_bin_op));
             } else {
                 break;
             }
+        }
 
-            rhs = match parse_expression_with_precedence(state, rhs, precedence_to_search) {
-                ParserResult::Value(expression) => expression,
-                ParserResult::NotFound => {
-                    state.token_error(
-                        bin_op.token().clone(),
-                        "expected expression after binary operator",
-                    );
-                    return ParserResult::Value(lhs);
-                }
-                ParserResult::LexerMoved => return ParserResult::Value(lhs),
+        while let Some((right_associative_lhs, right_associative_bin_op)) = right_associative.pop()
+        {
+            rhs = Expression::BinaryOperator {
+                lhs: Box::new(right_associative_lhs),
+                binop: right_associative_bin_op,
+                rhs: Box::new(rhs),
             };
         }
 
@@ -1968,17 +2246,22 @@ fn parse_unary_expression(
         _ => unreachable!(),
     };
 
-    let primary_expression = match parse_primary_expression(state) {
+    let primary_expression = match state.nested(parse_primary_expression) {
         ParserResult::Value(expression) => expression,
         ParserResult::NotFound => {
             state.token_error(
                 unary_operator.token().clone(),
+                AstErrorKind::ExpectedExpression,
                 format!(
                     "expected an expression after {}",
                     unary_operator.token().token()
                 ),
             );
-            return ParserResult::NotFound;
+
+            return ParserResult::Value(Expression::UnaryOperator {
+                unop: unary_operator,
+                expression: Box::new(Expression::Error(Vec::new())),
+            });
         }
         ParserResult::LexerMoved => return ParserResult::LexerMoved,
     };
@@ -1993,6 +2276,7 @@ fn parse_unary_expression(
         ParserResult::NotFound => {
             state.token_error(
                 unary_operator.token().clone(),
+                AstErrorKind::ExpectedExpression,
                 format!(
                     "expected an expression after {}",
                     unary_operator.token().token()
@@ -2008,7 +2292,7 @@ fn parse_unary_expression(
     })
 }
 
-fn parse_function_body(state: &mut ParserState) -> ParserResult<FunctionBody> {
+pub fn parse_function_body(state: &mut ParserState) -> ParserResult<FunctionBody> {
     const NO_TRAILING_COMMAS_ERROR: &str = "trailing commas in arguments are not allowed";
 
     #[cfg(feature = "luau")]
@@ -2041,7 +2325,7 @@ fn parse_function_body(state: &mut ParserState) -> ParserResult<FunctionBody> {
                 generics: None, // rewrite todo: fix
                 parameters_parentheses: ContainedSpan::new(
                     left_parenthesis,
-                    TokenReference::basic_symbol(")"),
+                    TokenReference::phantom_symbol(")"),
                 ),
                 parameters,
                 #[cfg(feature = "luau")]
@@ -2049,7 +2333,7 @@ fn parse_function_body(state: &mut ParserState) -> ParserResult<FunctionBody> {
                 #[cfg(feature = "luau")]
                 return_type: None,
                 block: ast::Block::new(),
-                end_token: TokenReference::basic_symbol("end"),
+                end_token: TokenReference::phantom_symbol("end"),
             })
         };
 
@@ -2065,8 +2349,10 @@ fn parse_function_body(state: &mut ParserState) -> ParserResult<FunctionBody> {
                 parameters.push(Pair::End(ast::Parameter::Ellipsis(ellipsis)));
 
                 #[cfg(feature = "luau")]
-                if state.lua_version().has_luau() {
-                    let type_specifier = if let Some(colon) = state.consume_if(Symbol::Colon) {
+                {
+                    let type_specifier = if !state.lua_version().has_luau() {
+                        None
+                    } else if let Some(colon) = state.consume_if(Symbol::Colon) {
                         // varargs can also be annotated using generic packs: T...
                         let type_info = if matches!(state.current(), Ok(token) if token.token_kind() == TokenKind::Identifier)
                             && matches!(state.peek(), Ok(token) if token.is_symbol(Symbol::Ellipsis))
@@ -2100,7 +2386,9 @@ fn parse_function_body(state: &mut ParserState) -> ParserResult<FunctionBody> {
                     type_specifiers.push(type_specifier);
                 }
 
-                right_parenthesis = match state.require(Symbol::RightParen, "expected a `)`") {
+                right_parenthesis = match state.require_closing(&left_parenthesis, |state| {
+                    state.require(Symbol::RightParen, "expected a `)`")
+                }) {
                     Some(right_parenthesis) => right_parenthesis,
                     None => return unfinished_function_body(left_parenthesis, parameters),
                 };
@@ -2128,7 +2416,9 @@ fn parse_function_body(state: &mut ParserState) -> ParserResult<FunctionBody> {
                 let Some(comma) = state.consume_if(Symbol::Comma) else {
                     parameters.push(Pair::End(name_parameter));
 
-                    match state.require(Symbol::RightParen, "expected a `)`") {
+                    match state.require_closing(&left_parenthesis, |state| {
+                        state.require(Symbol::RightParen, "expected a `)`")
+                    }) {
                         Some(new_right_parenthesis) => {
                             right_parenthesis = new_right_parenthesis;
                             break;
@@ -2142,7 +2432,15 @@ fn parse_function_body(state: &mut ParserState) -> ParserResult<FunctionBody> {
             }
 
             Ok(token) => {
-                state.token_error(token.clone(), "expected a parameter name or `)`");
+                state.token_error(
+                    token.clone(),
+                    AstErrorKind::ExpectedName,
+                    "expected a parameter name or `)`",
+                );
+                state.also_expected(&[
+                    ExpectedToken::Symbol(Symbol::Ellipsis),
+                    ExpectedToken::Symbol(Symbol::RightParen),
+                ]);
 
                 return unfinished_function_body(left_parenthesis, parameters);
             }
@@ -2158,6 +2456,7 @@ fn parse_function_body(state: &mut ParserState) -> ParserResult<FunctionBody> {
 
         state.token_error(
             last_parameter.punctuation().unwrap().clone(),
+            AstErrorKind::TrailingComma,
             NO_TRAILING_COMMAS_ERROR,
         );
 
@@ -2177,6 +2476,7 @@ fn parse_function_body(state: &mut ParserState) -> ParserResult<FunctionBody> {
         } else if let Some(punctuation) = state.consume_if(Symbol::ThinArrow) {
             state.token_error(
                 punctuation.clone(),
+                AstErrorKind::ReturnTypeArrow,
                 "function return type annotations should use `:` instead of `->`",
             );
             match parse_return_type(state) {
@@ -2289,10 +2589,16 @@ fn expect_interpolated_string(
     let first_string = current.clone();
 
     loop {
-        let has_double_brace = if current.trailing_trivia.is_empty() {
+        // Compared by position rather than by trivia, which may have been dropped
+        let is_adjacent = state
+            .current()
+            .is_ok_and(|next| next.token().start_position() == current.token().end_position());
+
+        let has_double_brace = if is_adjacent {
             if let Some(double_brace) = state.consume_if(Symbol::LeftBrace) {
                 state.token_error(
                     double_brace,
+                    AstErrorKind::DoubleBrace,
                     "unexpected double brace, try \\{ if you meant to escape",
                 );
 
@@ -2307,7 +2613,11 @@ fn expect_interpolated_string(
         let expression = match parse_expression(state) {
             ParserResult::Value(expression) => expression,
             ParserResult::NotFound => {
-                state.token_error(current, "expected expression after `{`");
+                state.token_error(
+                    current,
+                    AstErrorKind::ExpectedExpression,
+                    "expected expression after `{`",
+                );
                 break;
             }
             ParserResult::LexerMoved => break,
@@ -2344,6 +2654,7 @@ fn expect_interpolated_string(
         if next.token_kind() != TokenKind::InterpolatedString {
             state.token_error(
                 next.clone(),
+                AstErrorKind::ExpectedExpression,
                 "interpolated string parameter can only contain an expression",
             );
             break;
@@ -2359,14 +2670,17 @@ fn expect_interpolated_string(
         last_string: if segments.is_empty() {
             first_string
         } else {
-            TokenReference::new(
-                Vec::new(),
-                Token::new(TokenType::InterpolatedString {
-                    literal: ShortString::default(),
-                    kind: InterpolatedStringKind::End,
-                }),
-                Vec::new(),
-            )
+            TokenReference {
+                phantom: true,
+                ..TokenReference::new(
+                    Vec::new(),
+                    Token::new(TokenType::InterpolatedString {
+                        literal: ShortString::default(),
+                        kind: InterpolatedStringKind::End,
+                    }),
+                    Vec::new(),
+                )
+            }
         },
 
         segments,
@@ -2374,7 +2688,7 @@ fn expect_interpolated_string(
 }
 
 #[cfg(feature = "luau")]
-fn parse_type(state: &mut ParserState) -> ParserResult<ast::TypeInfo> {
+pub fn parse_type(state: &mut ParserState) -> ParserResult<ast::TypeInfo> {
     let Ok(current_token) = state.current() else {
         return ParserResult::NotFound;
     };
@@ -2385,7 +2699,8 @@ fn parse_type(state: &mut ParserState) -> ParserResult<ast::TypeInfo> {
     {
         parse_type_suffix(state, None)
     } else {
-        let ParserResult::Value(simple_type) = parse_simple_type(state, SimpleTypeStyle::Default)
+        let ParserResult::Value(simple_type) =
+            state.nested(|state| parse_simple_type(state, SimpleTypeStyle::Default))
         else {
             return ParserResult::LexerMoved;
         };
@@ -2396,7 +2711,8 @@ fn parse_type(state: &mut ParserState) -> ParserResult<ast::TypeInfo> {
 
 #[cfg(feature = "luau")]
 fn parse_type_or_pack(state: &mut ParserState) -> ParserResult<ast::TypeInfo> {
-    let ParserResult::Value(simple_type) = parse_simple_type(state, SimpleTypeStyle::AllowPack)
+    let ParserResult::Value(simple_type) =
+        state.nested(|state| parse_simple_type(state, SimpleTypeStyle::AllowPack))
     else {
         return ParserResult::LexerMoved;
     };
@@ -2488,6 +2804,7 @@ fn parse_simple_type(
             let token = state.consume().unwrap();
             state.token_error(
                 token,
+                AstErrorKind::InterpolatedStringType,
                 "interpolated string literals cannot be used as types",
             );
             ParserResult::LexerMoved
@@ -2499,20 +2816,22 @@ fn parse_simple_type(
                 let left_parenthesis =
                     match state.require(Symbol::LeftParen, "expected `(` after `typeof`") {
                         Some(token) => token,
-                        None => TokenReference::basic_symbol("("),
+                        None => TokenReference::phantom_symbol("("),
                     };
 
                 let ParserResult::Value(expression) = parse_expression(state) else {
                     return ParserResult::LexerMoved;
                 };
 
-                let right_parenthesis = match state.require_with_reference_token(
-                    Symbol::RightParen,
-                    "expected `)` to close typeof call",
-                    &left_parenthesis,
-                ) {
+                let right_parenthesis = match state.require_closing(&left_parenthesis, |state| {
+                    state.require_with_reference_token(
+                        Symbol::RightParen,
+                        "expected `)` to close typeof call",
+                        &left_parenthesis,
+                    )
+                }) {
                     Some(token) => token,
-                    None => TokenReference::basic_symbol(")"),
+                    None => TokenReference::phantom_symbol(")"),
                 };
 
                 ParserResult::Value(ast::TypeInfo::Typeof {
@@ -2530,6 +2849,7 @@ fn parse_simple_type(
                             ParserResult::NotFound => {
                                 state.token_error(
                                     state.current().unwrap().clone(),
+                                    AstErrorKind::ExpectedName,
                                     "expected identifier after `.`",
                                 );
                                 return ParserResult::LexerMoved;
@@ -2653,6 +2973,7 @@ fn parse_type_suffix(
                 if is_intersection {
                     state.token_error(
                         current_token.clone(),
+                        AstErrorKind::MixedUnionAndIntersection,
                         "cannot mix union and intersection types",
                     );
                     return ParserResult::LexerMoved;
@@ -2674,6 +2995,7 @@ fn parse_type_suffix(
                 if is_intersection {
                     state.token_error(
                         current_token.clone(),
+                        AstErrorKind::MixedUnionAndIntersection,
                         "cannot mix union and intersection types",
                     );
                     return ParserResult::LexerMoved;
@@ -2690,6 +3012,7 @@ fn parse_type_suffix(
                 if is_union {
                     state.token_error(
                         current_token.clone(),
+                        AstErrorKind::MixedUnionAndIntersection,
                         "cannot mix union and intersection types",
                     );
                     return ParserResult::LexerMoved;
@@ -2755,10 +3078,12 @@ fn expect_type_table(
         {
             let left_brace = state.consume().unwrap();
             let property = state.consume().unwrap();
-            let Some(right_brace) = state.require(
-                Symbol::RightBracket,
-                "expected `]` to close `[` for type table field",
-            ) else {
+            let Some(right_brace) = state.require_closing(&left_brace, |state| {
+                state.require(
+                    Symbol::RightBracket,
+                    "expected `]` to close `[` for type table field",
+                )
+            }) else {
                 return Err(());
             };
             let Some(colon) = state.require(Symbol::Colon, "expected `:` after type field key")
@@ -2771,6 +3096,7 @@ fn expect_type_table(
                 ParserResult::LexerMoved | ParserResult::NotFound => {
                     state.token_error(
                         state.current().unwrap().clone(),
+                        AstErrorKind::ExpectedType,
                         "expected type after type field key",
                     );
                     return Err(());
@@ -2793,16 +3119,19 @@ fn expect_type_table(
                 ParserResult::NotFound => {
                     state.token_error(
                         state.current().unwrap().clone(),
+                        AstErrorKind::ExpectedType,
                         "expected type for type field key",
                     );
                     return Err(());
                 }
                 ParserResult::LexerMoved => return Err(()),
             };
-            let Some(right_brace) = state.require(
-                Symbol::RightBracket,
-                "expected `]` to close `[` for type table field",
-            ) else {
+            let Some(right_brace) = state.require_closing(&left_brace, |state| {
+                state.require(
+                    Symbol::RightBracket,
+                    "expected `]` to close `[` for type table field",
+                )
+            }) else {
                 return Err(());
             };
             let Some(colon) = state.require(Symbol::Colon, "expected `:` after type field key")
@@ -2815,6 +3144,7 @@ fn expect_type_table(
                 ParserResult::NotFound => {
                     state.token_error(
                         state.current().unwrap().clone(),
+                        AstErrorKind::ExpectedType,
                         "expected type after type field key",
                     );
                     return Err(());
@@ -2825,6 +3155,7 @@ fn expect_type_table(
             if has_indexer {
                 state.token_error_ranged(
                     left_brace.clone(),
+                    AstErrorKind::MultipleTableIndexers,
                     "cannot have more than one table indexer",
                     &left_brace,
                     value.tokens().next_back().unwrap(),
@@ -2853,6 +3184,7 @@ fn expect_type_table(
                     ParserResult::NotFound => {
                         state.token_error(
                             state.current().unwrap().clone(),
+                            AstErrorKind::ExpectedType,
                             "expected type for table array",
                         );
                         return Err(());
@@ -2874,6 +3206,7 @@ fn expect_type_table(
                         ParserResult::NotFound => {
                             state.token_error(
                                 state.current().unwrap().clone(),
+                                AstErrorKind::ExpectedType,
                                 "expected type after type field key",
                             );
                             return Err(());
@@ -2911,8 +3244,10 @@ fn expect_type_table(
     }
 
     let right_brace = state
-        .require(Symbol::RightBrace, "expected `}` to close type table")
-        .unwrap_or_else(|| TokenReference::basic_symbol("}"));
+        .require_closing(&left_brace, |state| {
+            state.require(Symbol::RightBrace, "expected `}` to close type table")
+        })
+        .unwrap_or_else(|| TokenReference::phantom_symbol("}"));
 
     let braces = ContainedSpan::new(left_brace, right_brace);
 
@@ -2990,13 +3325,16 @@ fn expect_function_type(
         if matches!(state.current(), Ok(token) if token.is_symbol(Symbol::RightParen)) {
             state.token_error(
                 state.current().unwrap().clone(),
+                AstErrorKind::ExpectedType,
                 "expected type after `,` but got `)` instead",
             );
             break;
         }
     }
 
-    let Some(right_paren) = state.require(Symbol::RightParen, "expected `)` to close `(`") else {
+    let Some(right_paren) = state.require_closing(&left_paren, |state| {
+        state.require(Symbol::RightParen, "expected `)` to close `(`")
+    }) else {
         return Err(());
     };
 
@@ -3058,7 +3396,11 @@ fn expect_type_specifier(
     punctuation: TokenReference,
 ) -> Result<ast::TypeSpecifier, ()> {
     let ParserResult::Value(type_info) = parse_type(state) else {
-        state.token_error(punctuation, "expected type info after `:`");
+        state.token_error(
+            punctuation,
+            AstErrorKind::ExpectedType,
+            "expected type info after `:`",
+        );
         return Err(());
     };
 
@@ -3108,7 +3450,11 @@ fn parse_generic_type_list(
             Ok(token) if token.token_kind() == TokenKind::Identifier => state.consume().unwrap(),
 
             Ok(token) => {
-                state.token_error(token.clone(), "expected a generic type name");
+                state.token_error(
+                    token.clone(),
+                    AstErrorKind::ExpectedName,
+                    "expected a generic type name",
+                );
                 error_token()
             }
 
@@ -3125,8 +3471,12 @@ fn parse_generic_type_list(
             let ellipsis = match state.consume_if(Symbol::Ellipsis) {
                 Some(token) => token,
                 None => {
-                    state.token_error(name.clone(), "generic types come before generic type packs");
-                    TokenReference::basic_symbol("...")
+                    state.token_error(
+                        name.clone(),
+                        AstErrorKind::GenericTypeAfterTypePack,
+                        "generic types come before generic type packs",
+                    );
+                    TokenReference::phantom_symbol("...")
                 }
             };
 
@@ -3148,6 +3498,7 @@ fn parse_generic_type_list(
                         ParserResult::Value(type_info) => {
                             state.token_error_ranged(
                                 equal_token.clone(),
+                                AstErrorKind::ExpectedTypePack,
                                 "expected type pack after `=` but got type instead",
                                 type_info.tokens().next().unwrap(),
                                 type_info.tokens().next_back().unwrap(),
@@ -3164,6 +3515,7 @@ fn parse_generic_type_list(
                 if seen_default {
                     state.token_error(
                         state.current().unwrap().clone(),
+                        AstErrorKind::ExpectedType,
                         "expected default type after type name",
                     )
                 }
@@ -3191,6 +3543,7 @@ fn parse_generic_type_list(
                 if seen_default {
                     state.token_error(
                         state.current().unwrap().clone(),
+                        AstErrorKind::ExpectedType,
                         "expected default type after type name",
                     )
                 }
@@ -3213,6 +3566,7 @@ fn parse_generic_type_list(
                     if token.is_symbol(Symbol::GreaterThan) {
                         state.token_error(
                             token.clone(),
+                            AstErrorKind::ExpectedType,
                             "expected type after `,` but got `>` instead",
                         );
                     }
@@ -3230,8 +3584,10 @@ fn parse_generic_type_list(
     }
 
     let right_angle_bracket = state
-        .require(Symbol::GreaterThan, "expected `>` to close generic list")
-        .unwrap_or_else(|| TokenReference::basic_symbol(">"));
+        .require_closing(&left_angle_bracket, |state| {
+            state.require(Symbol::GreaterThan, "expected `>` to close generic list")
+        })
+        .unwrap_or_else(|| TokenReference::phantom_symbol(">"));
 
     ParserResult::Value(ast::GenericDeclaration {
         arrows: ContainedSpan::new(left_angle_bracket, right_angle_bracket),
@@ -3271,6 +3627,7 @@ fn expect_generic_type_params(
             if state.current()?.is_symbol(Symbol::GreaterThan) {
                 state.token_error(
                     state.current()?.clone(),
+                    AstErrorKind::ExpectedType,
                     "expected type after ',' but got '>' instead",
                 );
                 break;
@@ -3281,10 +3638,12 @@ fn expect_generic_type_params(
         }
     }
 
-    let Some(right_arrow) = state.require(
-        Symbol::GreaterThan,
-        "expected '>' to close generic type parameter list",
-    ) else {
+    let Some(right_arrow) = state.require_closing(&left_arrow, |state| {
+        state.require(
+            Symbol::GreaterThan,
+            "expected '>' to close generic type parameter list",
+        )
+    }) else {
         return Err(());
     };
 
@@ -3380,6 +3739,7 @@ fn force_name_with_attributes(state: &mut ParserState, name: TokenReference) ->
             Ok(token) => {
                 state.token_error_ranged(
                     token.clone(),
+                    AstErrorKind::ExpectedName,
                     ERROR_INVALID_ATTRIBUTE,
                     &left_angle_bracket,
                     &token.clone(),
@@ -3394,7 +3754,11 @@ fn force_name_with_attributes(state: &mut ParserState, name: TokenReference) ->
             }
 
             Err(()) => {
-                state.token_error(left_angle_bracket, ERROR_INVALID_ATTRIBUTE);
+                state.token_error(
+                    left_angle_bracket,
+                    AstErrorKind::ExpectedName,
+                    ERROR_INVALID_ATTRIBUTE,
+                );
 
                 return Name {
                     name,
@@ -3405,15 +3769,15 @@ fn force_name_with_attributes(state: &mut ParserState, name: TokenReference) ->
             }
         };
 
-        let Some(right_angle_bracket) =
+        let Some(right_angle_bracket) = state.require_closing(&left_angle_bracket, |state| {
             state.require(Symbol::GreaterThan, "expected `>` to close attribute")
-        else {
+        }) else {
             return Name {
                 name,
                 attribute: Some(super::lua54::Attribute {
                     brackets: ContainedSpan::new(
                         left_angle_bracket,
-                        TokenReference::basic_symbol(">"),
+                        TokenReference::phantom_symbol(">"),
                     ),
                     name: attribute_name,
                 }),
@@ -3507,6 +3871,7 @@ fn one_or_more<T, F: Fn(&mut ParserState) -> ParserResult<T>>(
 
         state.token_error(
             last_value.punctuation().unwrap().clone(),
+            AstErrorKind::TrailingComma,
             "trailing commas are not allowed",
         );
 
@@ -19,12 +19,12 @@ use crate::{
     private::Sealed,
     tokenizer::{Position, TokenReference},
     util,
-    visitors::{Visit, VisitMut, Visitor, VisitorMut},
+    visitors::{Visit, VisitMut, VisitorMut, VisitorRef},
 };
 use derive_more::Display as DeriveDisplay;
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
-use std::{fmt::Display, iter::FromIterator};
+use std::{fmt::Display, hash::Hasher, iter::FromIterator};
 
 /// A punctuated sequence of node `T` separated by
 /// [`TokenReference`](crate::tokenizer::TokenReference).
@@ -224,13 +224,19 @@ impl<T: Node> Node for Punctuated<T> {
             .similar(&other.into_iter().collect::<Vec<_>>())
     }
 
-    fn tokens(&self) -> Tokens {
+    fn hash_similar(&self, state: &mut dyn Hasher) {
+        for value in self {
+            value.hash_similar(state);
+        }
+    }
+
+    fn tokens(&self) -> Tokens<'_> {
         self.pairs.tokens()
     }
 }
 
 impl<T: Visit> Visit for Punctuated<T> {
-    fn visit<V: Visitor>(&self, visitor: &mut V) {
+    fn visit<'ast, V: VisitorRef<'ast>>(&'ast self, visitor: &mut V) {
         self.pairs.visit(visitor);
     }
 }
@@ -457,7 +463,11 @@ impl<T: Node> Node for Pair<T> {
         self.value().similar(other.value())
     }
 
-    fn tokens(&self) -> Tokens {
+    fn hash_similar(&self, state: &mut dyn Hasher) {
+        self.value().hash_similar(state);
+    }
+
+    fn tokens(&self) -> Tokens<'_> {
         match self {
             Pair::Punctuated(node, separator) => {
                 let mut items = node.tokens().items;
@@ -472,7 +482,7 @@ impl<T: Node> Node for Pair<T> {
 }
 
 impl<T: Visit> Visit for Pair<T> {
-    fn visit<V: Visitor>(&self, visitor: &mut V) {
+    fn visit<'ast, V: VisitorRef<'ast>>(&'ast self, visitor: &mut V) {
         match self {
             Pair::End(value) => value.visit(visitor),
             Pair::Punctuated(value, punctuation) => {
@@ -0,0 +1,234 @@
+use std::mem;
+
+use crate::{
+    ast::{Ast, LuaVersion},
+    tokenizer::{Lexer, LexerResult, Token, TokenKind, TokenReference, TokenType},
+    visitors::VisitorMut,
+};
+
+#[cfg(feature = "luau")]
+use crate::tokenizer::InterpolatedStringKind;
+
+// Goes through everything printed in order, keeping the last token or trivia that was printed
+#[derive(Default)]
+struct TokenSeparator {
+    previous: Option<Token>,
+}
+
+impl TokenSeparator {
+    // Adds whitespace before `next` if it would run into what was printed before it
+    fn separate(&mut self, next: Token, separated: &mut Vec<Token>) {
+        if let Some(previous) = &self.previous {
+            if let Some(whitespace) = separator(previous, &next) {
+                separated.push(Token::new(TokenType::Whitespace {
+                    characters: whitespace.into(),
+                }));
+            }
+        }
+
+        self.previous = Some(next);
+    }
+
+    fn separate_trivia(&mut self, trivia: Vec<Token>, separated: &mut Vec<Token>) {
+        for token in trivia {
+            self.separate(token.clone(), separated);
+            separated.push(token);
+        }
+    }
+}
+
+impl VisitorMut for TokenSeparator {
+    fn visit_token_reference(&mut self, mut token: TokenReference) -> TokenReference {
+        // Phantom tokens aren't printed, so they can't run into anything
+        if token.is_phantom() {
+            return token;
+        }
+
+        let mut leading_trivia = Vec::with_capacity(token.leading_trivia.len());
+        self.separate_trivia(mem::take(&mut token.leading_trivia), &mut leading_trivia);
+
+        if token.token_kind() != TokenKind::Eof {
+            self.separate(token.token.clone(), &mut leading_trivia);
+        }
+
+        let mut trailing_trivia = Vec::with_capacity(token.trailing_trivia.len());
+        self.separate_trivia(mem::take(&mut token.trailing_trivia), &mut trailing_trivia);
+
+        TokenReference {
+            leading_trivia,
+            trailing_trivia,
+            ..token
+        }
+    }
+
+    fn visit_eof(&mut self, token: TokenReference) -> TokenReference {
+        self.visit_token_reference(token)
+    }
+}
+
+// The token types the code lexes as, or None if it can't be lexed on its own
+fn lex(code: &str) -> Option<Vec<TokenType>> {
+    match Lexer::new(code, LuaVersion::new()).collect() {
+        LexerResult::Ok(tokens) => Some(
+            tokens
+                .into_iter()
+                .map(|token| token.token_type)
+                .filter(|token_type| !matches!(token_type, TokenType::Eof))
+                .collect(),
+        ),
+
+        _ => None,
+    }
+}
+
+// The whitespace that needs to go between two tokens so that they lex the same when printed
+// next to each other, such as the space in `local x` or `- -x`
+pub(crate) fn separator(previous: &Token, next: &Token) -> Option<&'static str> {
+    if previous.token_kind() == TokenKind::Whitespace || next.token_kind() == TokenKind::Whitespace
+    {
+        return None;
+    }
+
+    // Nothing can come after a single line comment on the same line
+    if matches!(
+        previous.token_kind(),
+        TokenKind::SingleLineComment | TokenKind::Shebang
+    ) {
+        return Some("\n");
+    }
+
+    let previous_text = previous.to_string();
+    let next_text = next.to_string();
+
+    // An interpolation starting with `{`, as in `{ {}}`, lexes the same without the space, but
+    // is not allowed, as it looks like an escaped brace
+    #[cfg(feature = "luau")]
+    if matches!(
+        previous.token_type(),
+        TokenType::InterpolatedString {
+            kind: InterpolatedStringKind::Begin | InterpolatedStringKind::Middle,
+            ..
+        }
+    ) && next_text.starts_with('{')
+    {
+        return Some(" ");
+    }
+
+    // Lua reads letters, digits, and dots straight after a number as part of it, so `1 end` and
+    // `0x1 ..x` can't be written as `1end` and `0x1..x`, even though they lex the same here
+    if previous.token_kind() == TokenKind::Number
+        && next_text.starts_with(|character: char| {
+            character.is_ascii_alphanumeric() || matches!(character, '_' | '.')
+        })
+    {
+        return Some(" ");
+    }
+
+    // Tokens that can't be lexed on their own, such as the end of an interpolated string,
+    // depend on what's around them, and are left as they are
+    let (Some(mut apart), Some(next_types)) = (lex(&previous_text), lex(&next_text)) else {
+        return None;
+    };
+
+    apart.extend(next_types);
+
+    if lex(&(previous_text + &next_text)) == Some(apart) {
+        None
+    } else {
+        Some(" ")
+    }
+}
+
+impl Ast {
+    /// Returns the Ast with the least whitespace added so that every token prints apart from the
+    /// ones around it, for after a [`VisitorMut`] has removed trivia. Without it, removing the
+    /// whitespace from `local function f() end` prints `localfunctionf()end`, but with it, the
+    /// Ast prints `local function f()end`.
+    ///
+    /// Whitespace is only added between tokens that would lex differently when printed next to
+    /// each other, such as a name next to a keyword, `-` next to `-`, or `[` next to `[`. After a
+    /// single line comment, a new line is added instead.
+    ///
+    /// ```rust
+    /// # use full_moon::{tokenizer::TokenReference, visitors::VisitorMut};
+    /// # fn main() -> Result<(), Vec<full_moon::Error>> {
+    /// struct TriviaRemover;
+    ///
+    /// impl VisitorMut for TriviaRemover {
+    ///     fn visit_token_reference(&mut self, token: TokenReference) -> TokenReference {
+    ///         TokenReference::new(Vec::new(), token.token().clone(), Vec::new())
+    ///     }
+    /// }
+    ///
+    /// let ast = TriviaRemover.visit_ast(full_moon::parse("local x = a - -b -- negate")?);
+    /// assert_eq!(ast.to_string(), "localx=a--b");
+    /// assert_eq!(ast.separate_tokens().to_string(), "local x=a- -b");
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn separate_tokens(self) -> Self {
+        TokenSeparator::default().visit_ast(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        node::Node,
+        parse,
+        tokenizer::{Token, TokenKind, TokenReference},
+        visitors::VisitorMut,
+    };
+    use pretty_assertions::assert_eq;
+
+    struct WhitespaceRemover;
+
+    impl VisitorMut for WhitespaceRemover {
+        fn visit_token_reference(&mut self, token: TokenReference) -> TokenReference {
+            let without_whitespace = |trivia: &mut dyn Iterator<Item = &Token>| {
+                trivia
+                    .filter(|trivia| trivia.token_kind() != TokenKind::Whitespace)
+                    .cloned()
+                    .collect()
+            };
+
+            TokenReference::new(
+                without_whitespace(&mut token.leading_trivia()),
+                token.token().clone(),
+                without_whitespace(&mut token.trailing_trivia()),
+            )
+        }
+
+        fn visit_eof(&mut self, token: TokenReference) -> TokenReference {
+            self.visit_token_reference(token)
+        }
+    }
+
+    #[test]
+    fn test_separate_tokens() {
+        for (code, expected) in [
+            ("local function f() end", "local function f()end"),
+            ("x = a - -b", "x=a- -b"),
+            ("x = t[ [[s]] ]", "x=t[ [[s]]]"),
+            ("x = t[ [=[s]=] ]", "x=t[ [=[s]=]]"),
+            ("x = 1 .. 2", "x=1 ..2"),
+            ("x = a .. .5", "x=a.. .5"),
+            ("x = a.b", "x=a.b"),
+            ("return not x", "return not x"),
+            ("x = 1 -- one\ny = 2", "x=1-- one\ny=2"),
+            ("x = - --[[ comment ]] y", "x=- --[[ comment ]]y"),
+            ("f() -- end", "f()-- end"),
+            ("x = 1 y = 0x2 z = 3", "x=1 y=0x2 z=3"),
+            ("x = 0x1 .. y", "x=0x1 ..y"),
+        ] {
+            let ast = WhitespaceRemover.visit_ast(parse(code).unwrap());
+            let separated = ast.clone().separate_tokens();
+
+            assert_eq!(separated.to_string(), expected, "separating {code:?}");
+            assert!(
+                parse(&separated.to_string()).unwrap().similar(&ast),
+                "{expected:?} parsed differently"
+            );
+        }
+    }
+}
@@ -15,6 +15,7 @@ use crate::{
 use full_moon_derive::Visit;
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
+use std::hash::Hasher;
 
 /// A contained span with the beginning and ending bounds.
 /// Refer to the [module documentation](index.html) for more details.
@@ -51,7 +52,12 @@ impl Node for ContainedSpan {
         self.tokens.0.similar(&other.tokens.0) && self.tokens.1.similar(&other.tokens.1)
     }
 
-    fn tokens(&self) -> Tokens {
+    fn hash_similar(&self, state: &mut dyn Hasher) {
+        self.tokens.0.hash_similar(state);
+        self.tokens.1.hash_similar(state);
+    }
+
+    fn tokens(&self) -> Tokens<'_> {
         self.tokens.tokens()
     }
 }
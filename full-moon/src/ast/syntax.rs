@@ -0,0 +1,512 @@
+//! A read-only view over an [`Ast`] whose nodes know their parent, siblings, and depth.
+//!
+//! Nodes in an [`Ast`] only know their children, so questions such as "what function is this
+//! statement in?" can't be answered from the node alone. A [`SyntaxTree`] walks the Ast once,
+//! recording where every node is, and then hands out [`SyntaxNode`]s that can be navigated in any
+//! direction, or with a [`SyntaxCursor`]. The typed node a syntax node was made from can be
+//! gotten back with [`SyntaxNode::cast`], and the syntax node of a typed node with
+//! [`SyntaxTree::find`].
+//!
+//! ```rust
+//! # use full_moon::ast::{syntax::SyntaxTree, FunctionBody, LocalAssignment};
+//! # fn main() -> Result<(), Vec<full_moon::Error>> {
+//! let ast = full_moon::parse("local function f()\n\tlocal x = 1\nend")?;
+//! let tree = SyntaxTree::new(&ast);
+//!
+//! let local_assignment = tree
+//!     .root()
+//!     .descendants()
+//!     .find(|node| node.cast::<LocalAssignment>().is_some())
+//!     .unwrap();
+//!
+//! let function_body = local_assignment
+//!     .ancestors()
+//!     .find_map(|node| node.cast::<FunctionBody>())
+//!     .unwrap();
+//!
+//! assert_eq!(function_body.block().stmts().count(), 1);
+//! # Ok(())
+//! # }
+//! ```
+use std::{collections::HashMap, fmt};
+
+use super::borrowed::with_node_kinds;
+use crate::{
+    ast::{self, Ast},
+    node::{AnyNode, AnyNodeType, SyntaxKind},
+    tokenizer::{Position, TokenReference},
+    visitors::{Visit, VisitorRef},
+};
+
+#[cfg(any(feature = "lua52", feature = "luajit"))]
+use crate::ast::lua52::*;
+#[cfg(feature = "lua54")]
+use crate::ast::lua54::*;
+#[cfg(feature = "luau")]
+use crate::ast::luau::*;
+#[cfg(any(feature = "cfxlua", feature = "luau"))]
+use crate::ast::{CompoundAssignment, CompoundOp};
+
+macro_rules! syntax_visitor {
+    (
+        $($kind:ident => $visit_name:ident,)+
+
+        $(#[$meta:meta] {
+            $($meta_kind:ident => $meta_visit_name:ident,)+
+        })+
+    ) => {
+        impl<'a> VisitorRef<'a> for Builder<'a> {
+            fn visit_token_reference(&mut self, token: &'a TokenReference) {
+                self.tokens.push(token);
+            }
+
+            paste::item! {
+                $(
+                    fn $visit_name(&mut self, node: &'a ast::$kind) {
+                        self.enter(AnyNode::from(node));
+                    }
+
+                    fn [<$visit_name _end>](&mut self, _node: &'a ast::$kind) {
+                        self.exit();
+                    }
+                )+
+
+                $($(
+                    #[$meta]
+                    fn $meta_visit_name(&mut self, node: &'a $meta_kind) {
+                        self.enter(AnyNode::from(node));
+                    }
+
+                    #[$meta]
+                    fn [<$meta_visit_name _end>](&mut self, _node: &'a $meta_kind) {
+                        self.exit();
+                    }
+                )+)+
+            }
+        }
+    };
+}
+
+with_node_kinds!(syntax_visitor);
+
+struct NodeData<'a> {
+    node: AnyNode<'a>,
+    parent: Option<u32>,
+    previous_sibling: Option<u32>,
+    depth: u32,
+    // The range of tokens in the node
+    first_token: u32,
+    token_end: u32,
+    // Nodes are stored in preorder, so every node after this one up until `subtree_end` is inside it
+    subtree_end: u32,
+}
+
+/// A read-only view over an [`Ast`], made of [`SyntaxNode`]s that know their parent.
+/// Read the [module documentation](self) for more information.
+pub struct SyntaxTree<'a> {
+    ast: &'a Ast,
+    nodes: Vec<NodeData<'a>>,
+    // Every token other than the end of file, in the order they appear in the code
+    tokens: Vec<&'a TokenReference>,
+    // The syntax nodes made from typed nodes, by the kind and address of the typed node
+    typed_nodes: HashMap<(SyntaxKind, usize), u32>,
+}
+
+impl<'a> SyntaxTree<'a> {
+    /// Creates a syntax tree over the Ast, visiting every node in it once
+    pub fn new(ast: &'a Ast) -> Self {
+        let mut builder = Builder {
+            nodes: Vec::new(),
+            tokens: Vec::new(),
+            stack: Vec::new(),
+        };
+
+        ast.nodes().visit(&mut builder);
+
+        let typed_nodes = builder
+            .nodes
+            .iter()
+            .enumerate()
+            .map(|(index, data)| (typed_node_key(data.node), index as u32))
+            .collect();
+
+        Self {
+            ast,
+            nodes: builder.nodes,
+            tokens: builder.tokens,
+            typed_nodes,
+        }
+    }
+
+    /// The Ast this is a view over
+    pub fn ast(&self) -> &'a Ast {
+        self.ast
+    }
+
+    /// The [`Block`](ast::Block) of the whole file
+    pub fn root(&self) -> SyntaxNode<'_> {
+        self.node(0)
+    }
+
+    /// Every node, with parents before their children
+    pub fn nodes(&self) -> impl Iterator<Item = SyntaxNode<'_>> {
+        (0..self.nodes.len() as u32).map(move |index| self.node(index))
+    }
+
+    /// Every token other than the end of file, in the order they appear in the code
+    pub fn tokens(&self) -> impl Iterator<Item = &'a TokenReference> + '_ {
+        self.tokens.iter().copied()
+    }
+
+    /// The syntax node made from the typed node, if it is in the Ast.
+    /// Nodes are found by their address, so a node equal to one in the Ast, such as a clone, isn't found.
+    pub fn find<T: AnyNodeType>(&self, node: &T) -> Option<SyntaxNode<'_>> {
+        let index = *self.typed_nodes.get(&typed_node_key(node.as_any_node()))?;
+        Some(self.node(index))
+    }
+
+    fn node(&self, index: u32) -> SyntaxNode<'_> {
+        SyntaxNode { tree: self, index }
+    }
+}
+
+impl fmt::Debug for SyntaxTree<'_> {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.debug_list().entries(self.nodes()).finish()
+    }
+}
+
+fn typed_node_key(node: AnyNode) -> (SyntaxKind, usize) {
+    (node.kind(), node.address())
+}
+
+/// A node in a [`SyntaxTree`], which knows where it is in the tree.
+/// The typed node it was made from is gotten with [`cast`](SyntaxNode::cast).
+#[derive(Clone, Copy)]
+pub struct SyntaxNode<'a> {
+    tree: &'a SyntaxTree<'a>,
+    index: u32,
+}
+
+impl<'a> SyntaxNode<'a> {
+    fn data(&self) -> &'a NodeData<'a> {
+        &self.tree.nodes[self.index as usize]
+    }
+
+    /// The kind of node this was made from
+    pub fn kind(&self) -> SyntaxKind {
+        self.data().node.kind()
+    }
+
+    /// The typed node this was made from, for walking it without knowing its type
+    pub fn any_node(&self) -> AnyNode<'a> {
+        self.data().node
+    }
+
+    /// The typed node this was made from, if it's a `T`.
+    /// For example, `node.cast::<ast::Stmt>()` gives the statement if the node is one.
+    pub fn cast<T: AnyNodeType>(&self) -> Option<&'a T> {
+        self.data().node.cast()
+    }
+
+    /// How many nodes this is inside of, which is 0 for the [root](SyntaxTree::root)
+    pub fn depth(&self) -> usize {
+        self.data().depth as usize
+    }
+
+    /// The node this is directly inside of, which is `None` for the [root](SyntaxTree::root)
+    pub fn parent(&self) -> Option<SyntaxNode<'a>> {
+        Some(self.tree.node(self.data().parent?))
+    }
+
+    /// This node, followed by the node it is inside of, and so on up to the [root](SyntaxTree::root)
+    pub fn ancestors(&self) -> impl Iterator<Item = SyntaxNode<'a>> {
+        std::iter::successors(Some(*self), SyntaxNode::parent)
+    }
+
+    /// The nodes directly inside of this one, in the order they appear in the code
+    pub fn children(&self) -> impl Iterator<Item = SyntaxNode<'a>> {
+        std::iter::successors(self.first_child(), SyntaxNode::next_sibling)
+    }
+
+    /// The nodes inside of this one at any depth, with parents before their children
+    pub fn descendants(&self) -> impl Iterator<Item = SyntaxNode<'a>> {
+        let tree = self.tree;
+        (self.index + 1..self.data().subtree_end).map(move |index| tree.node(index))
+    }
+
+    /// The first node directly inside of this one
+    pub fn first_child(&self) -> Option<SyntaxNode<'a>> {
+        let index = self.index + 1;
+        (index < self.data().subtree_end).then(|| self.tree.node(index))
+    }
+
+    /// The last node directly inside of this one
+    pub fn last_child(&self) -> Option<SyntaxNode<'a>> {
+        self.children().last()
+    }
+
+    /// The node after this one with the same parent
+    pub fn next_sibling(&self) -> Option<SyntaxNode<'a>> {
+        let parent = self.parent()?;
+        let index = self.data().subtree_end;
+        (index < parent.data().subtree_end).then(|| self.tree.node(index))
+    }
+
+    /// The node before this one with the same parent
+    pub fn previous_sibling(&self) -> Option<SyntaxNode<'a>> {
+        Some(self.tree.node(self.data().previous_sibling?))
+    }
+
+    /// The tokens in the node, in the order they appear in the code
+    pub fn tokens(&self) -> impl Iterator<Item = &'a TokenReference> {
+        let data = self.data();
+        self.tree.tokens[data.first_token as usize..data.token_end as usize]
+            .iter()
+            .copied()
+    }
+
+    /// The start and end position of the node, not including the trivia around it,
+    /// or `None` if it has no tokens, such as an empty block
+    pub fn range(&self) -> Option<(Position, Position)> {
+        let data = self.data();
+
+        if data.first_token == data.token_end {
+            return None;
+        }
+
+        let first_token = self.tree.tokens[data.first_token as usize];
+        let last_token = self.tree.tokens[data.token_end as usize - 1];
+        Some((first_token.start_position(), last_token.end_position()))
+    }
+
+    /// A cursor starting at this node
+    pub fn cursor(&self) -> SyntaxCursor<'a> {
+        SyntaxCursor { node: *self }
+    }
+}
+
+impl fmt::Debug for SyntaxNode<'_> {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        match self.range() {
+            Some((start, end)) => write!(
+                formatter,
+                "{:?}@{}:{}..{}:{}",
+                self.kind(),
+                start.line(),
+                start.character(),
+                end.line(),
+                end.character()
+            ),
+
+            None => write!(formatter, "{:?}", self.kind()),
+        }
+    }
+}
+
+impl fmt::Display for SyntaxNode<'_> {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        self.tokens().try_for_each(|token| token.fmt(formatter))
+    }
+}
+
+impl PartialEq for SyntaxNode<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        std::ptr::eq(self.tree, other.tree) && self.index == other.index
+    }
+}
+
+impl Eq for SyntaxNode<'_> {}
+
+/// Walks a [`SyntaxTree`] one step at a time, starting from [`SyntaxNode::cursor`].
+/// Every `goto_` method returns whether it moved, and stays put if it didn't.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SyntaxCursor<'a> {
+    node: SyntaxNode<'a>,
+}
+
+impl<'a> SyntaxCursor<'a> {
+    /// The node the cursor is at
+    pub fn node(&self) -> SyntaxNode<'a> {
+        self.node
+    }
+
+    /// Moves to the parent of the current node
+    pub fn goto_parent(&mut self) -> bool {
+        self.goto(SyntaxNode::parent)
+    }
+
+    /// Moves to the first child of the current node
+    pub fn goto_first_child(&mut self) -> bool {
+        self.goto(SyntaxNode::first_child)
+    }
+
+    /// Moves to the last child of the current node
+    pub fn goto_last_child(&mut self) -> bool {
+        self.goto(SyntaxNode::last_child)
+    }
+
+    /// Moves to the next sibling of the current node
+    pub fn goto_next_sibling(&mut self) -> bool {
+        self.goto(SyntaxNode::next_sibling)
+    }
+
+    /// Moves to the previous sibling of the current node
+    pub fn goto_previous_sibling(&mut self) -> bool {
+        self.goto(SyntaxNode::previous_sibling)
+    }
+
+    fn goto(&mut self, step: impl FnOnce(&SyntaxNode<'a>) -> Option<SyntaxNode<'a>>) -> bool {
+        match step(&self.node) {
+            Some(node) => {
+                self.node = node;
+                true
+            }
+
+            None => false,
+        }
+    }
+}
+
+struct Builder<'a> {
+    nodes: Vec<NodeData<'a>>,
+    tokens: Vec<&'a TokenReference>,
+    // The nodes being built, and the last child found in each
+    stack: Vec<(u32, Option<u32>)>,
+}
+
+impl<'a> Builder<'a> {
+    fn enter(&mut self, node: AnyNode<'a>) {
+        let index = self.nodes.len() as u32;
+
+        let (parent, previous_sibling) = match self.stack.last_mut() {
+            Some((parent, last_child)) => (Some(*parent), last_child.replace(index)),
+            None => (None, None),
+        };
+
+        self.nodes.push(NodeData {
+            node,
+            parent,
+            previous_sibling,
+            depth: self.stack.len() as u32,
+            first_token: self.tokens.len() as u32,
+            token_end: 0,
+            subtree_end: 0,
+        });
+
+        self.stack.push((index, None));
+    }
+
+    fn exit(&mut self) {
+        let (index, _) = self.stack.pop().expect("exited a node that wasn't entered");
+        let token_end = self.tokens.len() as u32;
+        let subtree_end = self.nodes.len() as u32;
+
+        let data = &mut self.nodes[index as usize];
+        data.token_end = token_end;
+        data.subtree_end = subtree_end;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ast::{Block, Expression, LocalAssignment, Stmt};
+
+    #[test]
+    fn test_navigation() {
+        let ast = crate::parse("local x = 1\nlocal y = x + 2\nprint(y)").unwrap();
+        let tree = SyntaxTree::new(&ast);
+        let root = tree.root();
+
+        assert_eq!(root.kind(), SyntaxKind::Block);
+        assert_eq!(root.depth(), 0);
+        assert!(root.parent().is_none());
+        assert!(std::ptr::eq(root.cast::<Block>().unwrap(), ast.nodes()));
+
+        let stmts: Vec<_> = root.children().collect();
+        assert_eq!(stmts.len(), 3);
+        assert!(stmts.iter().all(|stmt| stmt.kind() == SyntaxKind::Stmt));
+        assert_eq!(stmts[1].to_string(), "local y = x + 2\n");
+        assert_eq!(stmts[1].next_sibling(), Some(stmts[2]));
+        assert_eq!(stmts[1].previous_sibling(), Some(stmts[0]));
+        assert_eq!(stmts[0].previous_sibling(), None);
+        assert_eq!(stmts[2].next_sibling(), None);
+        assert_eq!(root.last_child(), Some(stmts[2]));
+
+        let addition = stmts[1]
+            .descendants()
+            .find(|node| {
+                matches!(
+                    node.cast::<Expression>(),
+                    Some(Expression::BinaryOperator { .. })
+                )
+            })
+            .unwrap();
+
+        assert_eq!(addition.depth(), 3);
+        assert_eq!(addition.to_string(), "x + 2\n");
+
+        let ancestors: Vec<_> = addition.ancestors().map(|node| node.kind()).collect();
+        assert_eq!(
+            ancestors,
+            [
+                SyntaxKind::Expression,
+                SyntaxKind::LocalAssignment,
+                SyntaxKind::Stmt,
+                SyntaxKind::Block
+            ]
+        );
+
+        let (start, end) = addition.range().unwrap();
+        assert_eq!((start.line(), start.character()), (2, 11));
+        assert_eq!((end.line(), end.character()), (2, 16));
+    }
+
+    #[test]
+    fn test_find() {
+        let ast = crate::parse("local x = 1\nlocal y = 2").unwrap();
+        let tree = SyntaxTree::new(&ast);
+
+        let Some(Stmt::LocalAssignment(local_assignment)) = ast.nodes().stmts().nth(1) else {
+            unreachable!()
+        };
+
+        let node = tree.find(local_assignment).unwrap();
+        assert_eq!(node.kind(), SyntaxKind::LocalAssignment);
+        assert!(std::ptr::eq(
+            node.cast::<LocalAssignment>().unwrap(),
+            local_assignment
+        ));
+        assert_eq!(
+            node.parent()
+                .unwrap()
+                .previous_sibling()
+                .unwrap()
+                .to_string(),
+            "local x = 1\n"
+        );
+
+        assert!(tree.find(&local_assignment.clone()).is_none());
+    }
+
+    #[test]
+    fn test_cursor() {
+        let ast = crate::parse("do\n\tlocal x = 1\nend\nreturn").unwrap();
+        let tree = SyntaxTree::new(&ast);
+        let mut cursor = tree.root().cursor();
+
+        assert!(!cursor.goto_parent());
+        assert!(cursor.goto_first_child());
+        assert_eq!(cursor.node().kind(), SyntaxKind::Stmt);
+        assert!(cursor.goto_first_child());
+        assert_eq!(cursor.node().kind(), SyntaxKind::Do);
+        assert!(!cursor.goto_next_sibling());
+        assert!(cursor.goto_parent());
+        assert!(cursor.goto_next_sibling());
+        assert_eq!(cursor.node().kind(), SyntaxKind::LastStmt);
+        assert!(cursor.goto_previous_sibling());
+        assert_eq!(cursor.node().kind(), SyntaxKind::Stmt);
+        assert_eq!(cursor.node().depth(), 1);
+    }
+}
@@ -0,0 +1,990 @@
+//! Templates for building nodes from Lua code with named holes, such as
+//! `local $name = require($path)`.
+//!
+//! A template is parsed once, and can then be filled in any number of times by substituting
+//! [`Binding`]s into its holes. The formatting of the template is kept, with each hole's whitespace
+//! and comments moved onto the node substituted into it.
+//!
+//! What a hole can be filled with depends on where it is:
+//! - Holes where an expression is expected take an [`Expression`], or a [`TokenReference`] that is
+//!   used as a name.
+//! - Holes on their own as a statement take a [`Block`], whose statements are spliced in.
+#![cfg_attr(
+    feature = "luau",
+    doc = "- Holes where a type is expected take a [`TypeInfo`], or a [`TokenReference`] that is used as a name."
+)]
+//! - Any other hole, such as the name of a local, takes a [`TokenReference`].
+//!
+//! ```rust
+//! # use full_moon::ast::{template::{self, Bindings}, Expression};
+//! # use full_moon::tokenizer::{Token, TokenReference, TokenType};
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let name = TokenReference::new(
+//!     Vec::new(),
+//!     Token::new(TokenType::Identifier { identifier: "Roact".into() }),
+//!     Vec::new(),
+//! );
+//!
+//! let path = "script.Parent.Roact".parse::<Expression>().unwrap();
+//!
+//! let stmt = template::stmt(
+//!     "local $name = require($path)",
+//!     &Bindings::new().with("name", name).with("path", path),
+//! )?;
+//!
+//! assert_eq!(stmt.to_string(), "local Roact = require(script.Parent.Roact)");
+//! # Ok(())
+//! # }
+//! ```
+use std::{fmt, mem, ops::Range};
+
+use super::{
+    fragments, span::ContainedSpan, Block, Expression, FragmentResult, LuaVersion, Prefix, Stmt,
+    Var,
+};
+use crate::{
+    node::Node,
+    tokenizer::{Lexer, LexerResult, Symbol, Token, TokenReference, TokenType},
+    visitors::{VisitMut, VisitorMut},
+};
+
+#[cfg(feature = "luau")]
+use super::luau::TypeInfo;
+
+const HOLE_PREFIX: &str = "__full_moon_hole_";
+const BLOCK_HOLE_PREFIX: &str = "__full_moon_block_hole_";
+const FILLED_HOLE: &str = "__full_moon_filled_hole";
+
+/// A value that can be substituted into a hole of a [`Template`]
+#[derive(Clone, Debug, PartialEq)]
+#[non_exhaustive]
+pub enum Binding {
+    /// An expression, such as `1 + 2`
+    Expression(Expression),
+    /// A single token, such as a name. The trivia of the hole is used instead of the token's own.
+    Token(TokenReference),
+    /// A block of statements
+    Block(Block),
+    /// A type, such as `string | number`.
+    /// Only available when the "luau" feature flag is enabled.
+    #[cfg(feature = "luau")]
+    TypeInfo(TypeInfo),
+}
+
+impl Binding {
+    /// The kind of node this binding is
+    pub fn kind(&self) -> HoleKind {
+        match self {
+            Binding::Expression(_) => HoleKind::Expression,
+            Binding::Token(_) => HoleKind::Token,
+            Binding::Block(_) => HoleKind::Block,
+            #[cfg(feature = "luau")]
+            Binding::TypeInfo(_) => HoleKind::TypeInfo,
+        }
+    }
+}
+
+impl From<Expression> for Binding {
+    fn from(expression: Expression) -> Self {
+        Binding::Expression(expression)
+    }
+}
+
+impl From<TokenReference> for Binding {
+    fn from(token: TokenReference) -> Self {
+        Binding::Token(token)
+    }
+}
+
+impl From<Block> for Binding {
+    fn from(block: Block) -> Self {
+        Binding::Block(block)
+    }
+}
+
+#[cfg(feature = "luau")]
+impl From<TypeInfo> for Binding {
+    fn from(type_info: TypeInfo) -> Self {
+        Binding::TypeInfo(type_info)
+    }
+}
+
+/// The kind of node a hole of a [`Template`] expects
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum HoleKind {
+    /// An expression, such as `require($path)`
+    Expression,
+    /// A single token, such as `local $name`
+    Token,
+    /// A block of statements, written as a hole on its own as a statement
+    Block,
+    /// A type, such as `local x: $type`.
+    /// Only available when the "luau" feature flag is enabled.
+    #[cfg(feature = "luau")]
+    TypeInfo,
+}
+
+impl HoleKind {
+    fn accepts(self, binding: HoleKind) -> bool {
+        match (self, binding) {
+            (HoleKind::Expression, HoleKind::Token) => true,
+            #[cfg(feature = "luau")]
+            (HoleKind::TypeInfo, HoleKind::Token) => true,
+            (hole, binding) => hole == binding,
+        }
+    }
+}
+
+impl fmt::Display for HoleKind {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            HoleKind::Expression => write!(formatter, "an expression"),
+            HoleKind::Token => write!(formatter, "a token"),
+            HoleKind::Block => write!(formatter, "a block"),
+            #[cfg(feature = "luau")]
+            HoleKind::TypeInfo => write!(formatter, "a type"),
+        }
+    }
+}
+
+/// The named values to substitute into a [`Template`]
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Bindings {
+    bindings: Vec<(String, Binding)>,
+}
+
+impl Bindings {
+    /// Creates an empty set of bindings
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns a new set of bindings with the given binding, replacing any with the same name
+    pub fn with(mut self, name: impl Into<String>, binding: impl Into<Binding>) -> Self {
+        self.insert(name, binding);
+        self
+    }
+
+    /// Adds the given binding, replacing any with the same name
+    pub fn insert(&mut self, name: impl Into<String>, binding: impl Into<Binding>) {
+        let name = name.into();
+        let binding = binding.into();
+
+        match self
+            .bindings
+            .iter_mut()
+            .find(|(existing_name, _)| *existing_name == name)
+        {
+            Some((_, existing_binding)) => *existing_binding = binding,
+            None => self.bindings.push((name, binding)),
+        }
+    }
+
+    /// Returns the binding with the given name, if any
+    pub fn get(&self, name: &str) -> Option<&Binding> {
+        self.bindings
+            .iter()
+            .find(|(existing_name, _)| existing_name == name)
+            .map(|(_, binding)| binding)
+    }
+}
+
+/// An error that occurs when parsing or filling in a [`Template`]
+#[derive(Clone, Debug, PartialEq)]
+#[non_exhaustive]
+pub enum TemplateError {
+    /// The template was not valid Lua
+    Parse(Vec<crate::Error>),
+    /// No binding was given for a hole
+    MissingBinding {
+        /// The name of the hole
+        name: String,
+    },
+    /// The binding given for a hole was not of a kind the hole accepts
+    MismatchedKind {
+        /// The name of the hole
+        name: String,
+        /// The kind of node the hole expects
+        expected: HoleKind,
+        /// The kind of node that was given
+        found: HoleKind,
+    },
+    /// A binding was given that the template has no hole for
+    UnusedBinding {
+        /// The name of the binding
+        name: String,
+    },
+    /// A block ending in a last statement, such as `return`, was given for a hole
+    /// that is not at the end of its block
+    MisplacedLastStmt {
+        /// The name of the hole
+        name: String,
+    },
+}
+
+impl fmt::Display for TemplateError {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TemplateError::Parse(errors) => {
+                write!(formatter, "couldn't parse template")?;
+
+                for error in errors {
+                    write!(formatter, "\n{error}")?;
+                }
+
+                Ok(())
+            }
+
+            TemplateError::MissingBinding { name } => {
+                write!(formatter, "no binding was given for hole `${name}`")
+            }
+
+            TemplateError::MismatchedKind {
+                name,
+                expected,
+                found,
+            } => write!(
+                formatter,
+                "hole `${name}` expects {expected}, but was given {found}"
+            ),
+
+            TemplateError::UnusedBinding { name } => {
+                write!(formatter, "the template has no hole for binding `{name}`")
+            }
+
+            TemplateError::MisplacedLastStmt { name } => write!(
+                formatter,
+                "the block given for hole `${name}` ends in a last statement, but the hole is not at the end of its block"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for TemplateError {}
+
+/// Lua code with named holes, parsed once and filled in with [`Template::substitute`].
+/// See the [module documentation](self) for what each hole accepts.
+#[derive(Clone, Debug)]
+pub struct Template<T> {
+    node: T,
+    holes: Vec<(String, HoleKind)>,
+}
+
+impl Template<Stmt> {
+    /// Parses a template for a single statement, such as `local $name = require($path)`
+    pub fn stmt(code: &str) -> Result<Self, TemplateError> {
+        Self::parse(code, fragments::parse_statement)
+    }
+}
+
+impl Template<Expression> {
+    /// Parses a template for a single expression, such as `$value + 1`
+    pub fn expression(code: &str) -> Result<Self, TemplateError> {
+        Self::parse(code, fragments::parse_expression)
+    }
+}
+
+impl Template<Block> {
+    /// Parses a template for a block, such as `local x = $value\n$body`
+    pub fn block(code: &str) -> Result<Self, TemplateError> {
+        Self::parse(code, fragments::parse_block)
+    }
+}
+
+impl<T: Clone + VisitMut> Template<T> {
+    fn parse(
+        code: &str,
+        parser: fn(&str, LuaVersion) -> FragmentResult<T>,
+    ) -> Result<Self, TemplateError> {
+        let lua_version = LuaVersion::new();
+        let node = parser(&replace_holes(code, lua_version), lua_version)
+            .into_result()
+            .map_err(TemplateError::Parse)?;
+
+        let mut substituter = Substituter::new(None);
+        node.clone().visit_mut(&mut substituter);
+
+        let mut holes = Vec::new();
+        for hole in substituter.holes {
+            if !holes.contains(&hole) {
+                holes.push(hole);
+            }
+        }
+
+        Ok(Self { node, holes })
+    }
+
+    /// The name and kind of every hole in the template, in the order they first appear
+    pub fn holes(&self) -> impl Iterator<Item = (&str, HoleKind)> {
+        self.holes.iter().map(|(name, kind)| (name.as_str(), *kind))
+    }
+
+    /// Fills in every hole of the template with the binding of the same name.
+    /// Every hole must have a binding of a kind it accepts, and every binding must be used.
+    pub fn substitute(&self, bindings: &Bindings) -> Result<T, TemplateError> {
+        let mut substituter = Substituter::new(Some(bindings));
+        let node = self.node.clone().visit_mut(&mut substituter);
+
+        if let Some(error) = substituter.errors.into_iter().next() {
+            return Err(error);
+        }
+
+        if let Some((name, _)) = bindings
+            .bindings
+            .iter()
+            .find(|(name, _)| !self.holes.iter().any(|(hole, _)| hole == name))
+        {
+            return Err(TemplateError::UnusedBinding { name: name.clone() });
+        }
+
+        Ok(node)
+    }
+}
+
+/// Parses a statement template and fills it in with the given bindings.
+/// See [`Template::stmt`] to reuse a template.
+pub fn stmt(code: &str, bindings: &Bindings) -> Result<Stmt, TemplateError> {
+    Template::stmt(code)?.substitute(bindings)
+}
+
+/// Parses an expression template and fills it in with the given bindings.
+/// See [`Template::expression`] to reuse a template.
+pub fn expression(code: &str, bindings: &Bindings) -> Result<Expression, TemplateError> {
+    Template::expression(code)?.substitute(bindings)
+}
+
+/// Parses a block template and fills it in with the given bindings.
+/// See [`Template::block`] to reuse a template.
+pub fn block(code: &str, bindings: &Bindings) -> Result<Block, TemplateError> {
+    Template::block(code)?.substitute(bindings)
+}
+
+fn find_holes(code: &str) -> Vec<Range<usize>> {
+    let mut holes = Vec::new();
+    let mut characters = code.char_indices().peekable();
+
+    while let Some((start, character)) = characters.next() {
+        if character != '$' {
+            continue;
+        }
+
+        if !matches!(characters.peek(), Some((_, next)) if next.is_ascii_alphabetic() || *next == '_')
+        {
+            continue;
+        }
+
+        let mut end = start + 1;
+        while let Some((index, next)) =
+            characters.next_if(|(_, next)| next.is_ascii_alphanumeric() || *next == '_')
+        {
+            end = index + next.len_utf8();
+        }
+
+        holes.push(start..end);
+    }
+
+    holes
+}
+
+fn ends_statement(token: Option<&Token>) -> bool {
+    let Some(token) = token else {
+        return true;
+    };
+
+    match token.token_type() {
+        TokenType::Identifier { .. }
+        | TokenType::Number { .. }
+        | TokenType::StringLiteral { .. } => true,
+
+        TokenType::Symbol { symbol } => matches!(
+            symbol,
+            Symbol::Break
+                | Symbol::Do
+                | Symbol::Else
+                | Symbol::End
+                | Symbol::Ellipsis
+                | Symbol::False
+                | Symbol::Nil
+                | Symbol::Repeat
+                | Symbol::RightBrace
+                | Symbol::RightBracket
+                | Symbol::RightParen
+                | Symbol::Semicolon
+                | Symbol::Then
+                | Symbol::True
+        ),
+
+        _ => false,
+    }
+}
+
+fn starts_statement(token: Option<&Token>) -> bool {
+    let Some(token) = token else {
+        return true;
+    };
+
+    match token.token_type() {
+        TokenType::Eof | TokenType::Identifier { .. } => true,
+
+        TokenType::Symbol { symbol } => matches!(
+            symbol,
+            Symbol::Break
+                | Symbol::Do
+                | Symbol::Else
+                | Symbol::ElseIf
+                | Symbol::End
+                | Symbol::For
+                | Symbol::Function
+                | Symbol::If
+                | Symbol::Local
+                | Symbol::Repeat
+                | Symbol::Return
+                | Symbol::Semicolon
+                | Symbol::Until
+                | Symbol::While
+        ),
+
+        _ => false,
+    }
+}
+
+// Turns every `$name` into an identifier so that the template can be parsed.
+// Holes on their own as a statement become calls, as names alone aren't statements.
+// Holes inside of strings and comments are left alone.
+fn replace_holes(code: &str, lua_version: LuaVersion) -> String {
+    let holes = find_holes(code);
+
+    let mut replaced = String::with_capacity(code.len());
+    let mut starts = Vec::with_capacity(holes.len());
+    let mut last_end = 0;
+
+    for hole in &holes {
+        replaced.push_str(&code[last_end..hole.start]);
+        starts.push(replaced.len());
+        replaced.push_str(HOLE_PREFIX);
+        replaced.push_str(&code[hole.start + 1..hole.end]);
+        last_end = hole.end;
+    }
+
+    replaced.push_str(&code[last_end..]);
+
+    let tokens = match Lexer::new(&replaced, lua_version).collect() {
+        LexerResult::Ok(tokens) | LexerResult::Recovered(tokens, _) => tokens,
+        // The error will be reported when parsing
+        LexerResult::Fatal(_) => return replaced,
+    };
+
+    let tokens = tokens
+        .into_iter()
+        .filter(|token| !token.token_type().is_trivia())
+        .collect::<Vec<_>>();
+
+    let mut output = String::with_capacity(replaced.len());
+    let mut last_end = 0;
+
+    for (hole, start) in holes.into_iter().zip(starts) {
+        output.push_str(&code[last_end..hole.start]);
+        last_end = hole.end;
+
+        let name = &code[hole.start + 1..hole.end];
+
+        let Ok(index) = tokens.binary_search_by_key(&start, |token| token.start_position().bytes())
+        else {
+            output.push_str(&code[hole.clone()]);
+            continue;
+        };
+
+        if !matches!(tokens[index].token_type(), TokenType::Identifier { .. }) {
+            output.push_str(&code[hole.clone()]);
+            continue;
+        }
+
+        let previous = index.checked_sub(1).map(|index| &tokens[index]);
+        if ends_statement(previous) && starts_statement(tokens.get(index + 1)) {
+            output.push_str(BLOCK_HOLE_PREFIX);
+            output.push_str(name);
+            output.push_str("()");
+        } else {
+            output.push_str(HOLE_PREFIX);
+            output.push_str(name);
+        }
+    }
+
+    output.push_str(&code[last_end..]);
+    output
+}
+
+fn hole_name(token: &TokenReference) -> Option<&str> {
+    match token.token_type() {
+        TokenType::Identifier { identifier } => identifier.as_str().strip_prefix(HOLE_PREFIX),
+        _ => None,
+    }
+}
+
+// A statement is a block hole if it is exactly a call to a block hole, as produced by `replace_holes`
+fn block_hole_name(stmt: &Stmt) -> Option<&str> {
+    let Stmt::FunctionCall(function_call) = stmt else {
+        return None;
+    };
+
+    let Prefix::Name(name) = function_call.prefix() else {
+        return None;
+    };
+
+    if function_call.suffixes().count() != 1 {
+        return None;
+    }
+
+    match name.token_type() {
+        TokenType::Identifier { identifier } => identifier.as_str().strip_prefix(BLOCK_HOLE_PREFIX),
+        _ => None,
+    }
+}
+
+fn trivia(node: &impl Node) -> (Vec<Token>, Vec<Token>) {
+    // Not `surrounding_trivia`, as a node can be a single token that has both
+    match (node.tokens().next(), node.tokens().last()) {
+        (Some(first), Some(last)) => (
+            first.leading_trivia().cloned().collect(),
+            last.trailing_trivia().cloned().collect(),
+        ),
+        _ => (Vec::new(), Vec::new()),
+    }
+}
+
+fn substitute_token(hole: &TokenReference, binding: &TokenReference) -> TokenReference {
+    TokenReference::new(
+        hole.leading_trivia().cloned().collect(),
+        binding.token().clone(),
+        hole.trailing_trivia().cloned().collect(),
+    )
+}
+
+// Used for holes that weren't filled in, so that they aren't treated as holes again
+// when the visitor continues into them
+fn fill_hole(hole: &TokenReference) -> TokenReference {
+    TokenReference::new(
+        hole.leading_trivia().cloned().collect(),
+        Token::new(TokenType::Identifier {
+            identifier: FILLED_HOLE.into(),
+        }),
+        hole.trailing_trivia().cloned().collect(),
+    )
+}
+
+pub(crate) fn parenthesize(expression: Expression) -> Expression {
+    Expression::Parentheses {
+        contained: ContainedSpan::new(
+            TokenReference::basic_symbol("("),
+            TokenReference::basic_symbol(")"),
+        ),
+        expression: Box::new(expression),
+    }
+}
+
+// Whether an expression needs parentheses to be used as an operand of an operator
+fn is_operation(expression: &Expression) -> bool {
+    match expression {
+        Expression::BinaryOperator { .. } | Expression::UnaryOperator { .. } => true,
+        #[cfg(feature = "luau")]
+        Expression::IfExpression(_) | Expression::TypeAssertion { .. } => true,
+        _ => false,
+    }
+}
+
+// Visitors go through tokens in the order they're printed, so the first and last tokens visited
+// are the first and last tokens of the node.
+struct TokenCounter {
+    count: usize,
+}
+
+impl VisitorMut for TokenCounter {
+    fn visit_token_reference(&mut self, token: TokenReference) -> TokenReference {
+        self.count += 1;
+        token
+    }
+}
+
+struct TriviaWrapper {
+    index: usize,
+    last: usize,
+    leading_trivia: Vec<Token>,
+    trailing_trivia: Vec<Token>,
+}
+
+impl VisitorMut for TriviaWrapper {
+    fn visit_token_reference(&mut self, mut token: TokenReference) -> TokenReference {
+        if self.index == 0 {
+            token
+                .leading_trivia
+                .splice(0..0, mem::take(&mut self.leading_trivia));
+        }
+
+        if self.index == self.last {
+            token.trailing_trivia.append(&mut self.trailing_trivia);
+        }
+
+        self.index += 1;
+        token
+    }
+}
+
+// Moves the trivia of a hole onto the first and last tokens of the node filling it
+pub(crate) fn with_trivia<T: VisitMut>(
+    node: T,
+    (leading_trivia, trailing_trivia): (Vec<Token>, Vec<Token>),
+) -> T {
+    let mut counter = TokenCounter { count: 0 };
+    let node = node.visit_mut(&mut counter);
+
+    if counter.count == 0 {
+        return node;
+    }
+
+    node.visit_mut(&mut TriviaWrapper {
+        index: 0,
+        last: counter.count - 1,
+        leading_trivia,
+        trailing_trivia,
+    })
+}
+
+struct Substituter<'a> {
+    // None when only looking for holes
+    bindings: Option<&'a Bindings>,
+    holes: Vec<(String, HoleKind)>,
+    errors: Vec<TemplateError>,
+}
+
+impl<'a> Substituter<'a> {
+    fn new(bindings: Option<&'a Bindings>) -> Self {
+        Self {
+            bindings,
+            holes: Vec::new(),
+            errors: Vec::new(),
+        }
+    }
+
+    fn binding(&mut self, name: &str, kind: HoleKind) -> Option<&'a Binding> {
+        self.holes.push((name.to_owned(), kind));
+
+        let Some(binding) = self.bindings?.get(name) else {
+            self.errors.push(TemplateError::MissingBinding {
+                name: name.to_owned(),
+            });
+
+            return None;
+        };
+
+        if !kind.accepts(binding.kind()) {
+            self.errors.push(TemplateError::MismatchedKind {
+                name: name.to_owned(),
+                expected: kind,
+                found: binding.kind(),
+            });
+
+            return None;
+        }
+
+        Some(binding)
+    }
+
+    fn substitute_expression(&mut self, expression: Expression, operand: bool) -> Expression {
+        let Expression::Var(Var::Name(hole)) = &expression else {
+            return expression;
+        };
+
+        let Some(name) = hole_name(hole) else {
+            return expression;
+        };
+
+        match self.binding(name, HoleKind::Expression) {
+            Some(Binding::Expression(binding)) if operand && is_operation(binding) => {
+                with_trivia(parenthesize(binding.clone()), trivia(hole))
+            }
+
+            Some(Binding::Expression(binding)) => with_trivia(binding.clone(), trivia(hole)),
+
+            Some(Binding::Token(binding)) => {
+                Expression::Var(Var::Name(substitute_token(hole, binding)))
+            }
+
+            _ => Expression::Var(Var::Name(fill_hole(hole))),
+        }
+    }
+}
+
+impl VisitorMut for Substituter<'_> {
+    fn visit_block(&mut self, block: Block) -> Block {
+        let stmt_count = block.stmts.len();
+        let mut stmts = Vec::with_capacity(stmt_count);
+        let mut last_stmt = block.last_stmt;
+
+        for (index, (stmt, semicolon)) in block.stmts.into_iter().enumerate() {
+            let Some(name) = block_hole_name(&stmt) else {
+                stmts.push((stmt, semicolon));
+                continue;
+            };
+
+            let Some(Binding::Block(binding)) = self.binding(name, HoleKind::Block) else {
+                stmts.push((stmt, semicolon));
+                continue;
+            };
+
+            let binding = with_trivia(binding.clone(), trivia(&stmt));
+            stmts.extend(binding.stmts);
+
+            if let Some(binding_last_stmt) = binding.last_stmt {
+                if index + 1 == stmt_count && last_stmt.is_none() {
+                    last_stmt = Some(binding_last_stmt);
+                } else {
+                    self.errors.push(TemplateError::MisplacedLastStmt {
+                        name: name.to_owned(),
+                    });
+                }
+            }
+        }
+
+        Block { stmts, last_stmt }
+    }
+
+    fn visit_expression(&mut self, expression: Expression) -> Expression {
+        match expression {
+            Expression::BinaryOperator { lhs, binop, rhs } => Expression::BinaryOperator {
+                lhs: Box::new(self.substitute_expression(*lhs, true)),
+                binop,
+                rhs: Box::new(self.substitute_expression(*rhs, true)),
+            },
+
+            Expression::UnaryOperator { unop, expression } => Expression::UnaryOperator {
+                unop,
+                expression: Box::new(self.substitute_expression(*expression, true)),
+            },
+
+            expression => self.substitute_expression(expression, false),
+        }
+    }
+
+    fn visit_prefix(&mut self, prefix: Prefix) -> Prefix {
+        let Prefix::Name(hole) = &prefix else {
+            return prefix;
+        };
+
+        let Some(name) = hole_name(hole) else {
+            return prefix;
+        };
+
+        match self.binding(name, HoleKind::Expression) {
+            Some(Binding::Token(binding)) => Prefix::Name(substitute_token(hole, binding)),
+
+            Some(Binding::Expression(Expression::Var(Var::Name(binding)))) => {
+                Prefix::Name(substitute_token(hole, binding))
+            }
+
+            Some(Binding::Expression(
+                binding @ (Expression::Parentheses { .. }
+                | Expression::Var(_)
+                | Expression::FunctionCall(_)),
+            )) => Prefix::Expression(Box::new(with_trivia(binding.clone(), trivia(hole)))),
+
+            // Anything else can only be called or indexed inside parentheses
+            Some(Binding::Expression(binding)) => Prefix::Expression(Box::new(with_trivia(
+                parenthesize(binding.clone()),
+                trivia(hole),
+            ))),
+
+            _ => Prefix::Name(fill_hole(hole)),
+        }
+    }
+
+    #[cfg(feature = "luau")]
+    fn visit_type_info(&mut self, type_info: TypeInfo) -> TypeInfo {
+        let TypeInfo::Basic(hole) = &type_info else {
+            return type_info;
+        };
+
+        let Some(name) = hole_name(hole) else {
+            return type_info;
+        };
+
+        match self.binding(name, HoleKind::TypeInfo) {
+            Some(Binding::TypeInfo(binding)) => with_trivia(binding.clone(), trivia(hole)),
+            Some(Binding::Token(binding)) => TypeInfo::Basic(substitute_token(hole, binding)),
+            _ => TypeInfo::Basic(fill_hole(hole)),
+        }
+    }
+
+    fn visit_token_reference(&mut self, token: TokenReference) -> TokenReference {
+        let Some(name) = hole_name(&token) else {
+            return token;
+        };
+
+        match self.binding(name, HoleKind::Token) {
+            Some(Binding::Token(binding)) => substitute_token(&token, binding),
+            _ => fill_hole(&token),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn identifier(name: &str) -> TokenReference {
+        TokenReference::new(
+            Vec::new(),
+            Token::new(TokenType::Identifier {
+                identifier: name.into(),
+            }),
+            Vec::new(),
+        )
+    }
+
+    fn expression(code: &str) -> Expression {
+        code.parse().unwrap()
+    }
+
+    #[test]
+    fn test_template_holes() {
+        let template = Template::block(
+            "local $name = $value + 1 -- $comment\nprint(\"$string\")\nif $name then\n\t$body\nend",
+        )
+        .unwrap();
+
+        assert_eq!(
+            template.holes().collect::<Vec<_>>(),
+            vec![
+                ("name", HoleKind::Token),
+                ("value", HoleKind::Expression),
+                ("name", HoleKind::Expression),
+                ("body", HoleKind::Block),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_template_substitute() {
+        let template = Template::block(
+            "local $name = $value * 2 -- $comment\nprint(\"$string\")\nif $name then\n\t$body\nend",
+        )
+        .unwrap();
+
+        let block = template
+            .substitute(
+                &Bindings::new()
+                    .with("name", identifier("x"))
+                    .with("value", expression("a + b"))
+                    .with("body", "call(x)\nreturn x".parse::<Block>().unwrap()),
+            )
+            .unwrap();
+
+        assert_eq!(
+            block.to_string(),
+            "local x = (a + b) * 2 -- $comment\nprint(\"$string\")\nif x then\n\tcall(x)\nreturn x\nend"
+        );
+
+        // Templates can be filled in more than once
+        let block = template
+            .substitute(
+                &Bindings::new()
+                    .with("name", identifier("y"))
+                    .with("value", expression("1"))
+                    .with("body", Block::new()),
+            )
+            .unwrap();
+
+        assert_eq!(
+            block.to_string(),
+            "local y = 1 * 2 -- $comment\nprint(\"$string\")\nif y then\nend"
+        );
+    }
+
+    #[test]
+    fn test_template_prefix() {
+        let expression = super::expression(
+            "$object:method($argument)",
+            &Bindings::new()
+                .with("object", expression("\"string\""))
+                .with("argument", identifier("x")),
+        )
+        .unwrap();
+
+        assert_eq!(expression.to_string(), "(\"string\"):method(x)");
+    }
+
+    #[test]
+    fn test_template_trivia() {
+        // The trivia around a hole goes around the code filling it, whatever that code contains
+        let template = Template::block("call(  $value  )").unwrap();
+        let block = template
+            .substitute(&Bindings::new().with("value", expression("(\"\\0\0\" --[[\0]] .. x)")))
+            .unwrap();
+
+        assert_eq!(block.to_string(), "call(  (\"\\0\0\" --[[\0]] .. x)  )");
+    }
+
+    #[test]
+    fn test_template_errors() {
+        assert_eq!(
+            stmt("local $name = 1", &Bindings::new()),
+            Err(TemplateError::MissingBinding {
+                name: "name".to_owned()
+            })
+        );
+
+        assert_eq!(
+            stmt(
+                "local $name = 1",
+                &Bindings::new().with("name", expression("1 + 2"))
+            ),
+            Err(TemplateError::MismatchedKind {
+                name: "name".to_owned(),
+                expected: HoleKind::Token,
+                found: HoleKind::Expression,
+            })
+        );
+
+        assert_eq!(
+            stmt(
+                "local x = $value",
+                &Bindings::new()
+                    .with("value", identifier("y"))
+                    .with("other", identifier("z"))
+            ),
+            Err(TemplateError::UnusedBinding {
+                name: "other".to_owned()
+            })
+        );
+
+        assert_eq!(
+            block(
+                "$body\nprint(1)",
+                &Bindings::new().with("body", "return 1".parse::<Block>().unwrap())
+            ),
+            Err(TemplateError::MisplacedLastStmt {
+                name: "body".to_owned()
+            })
+        );
+
+        assert!(matches!(
+            stmt("local = $value", &Bindings::new()),
+            Err(TemplateError::Parse(_))
+        ));
+    }
+
+    #[cfg(feature = "luau")]
+    #[test]
+    fn test_template_type_info() {
+        let stmt = stmt(
+            "local x: $type = $value",
+            &Bindings::new()
+                .with("type", "string | number".parse::<TypeInfo>().unwrap())
+                .with("value", expression("1")),
+        )
+        .unwrap();
+
+        assert_eq!(stmt.to_string(), "local x: string | number = 1");
+    }
+}
@@ -54,6 +54,18 @@ impl UpdatePositionsRewriter {
 
 impl VisitorMut for UpdatePositionsRewriter {
     fn visit_token_reference(&mut self, token: TokenReference) -> TokenReference {
+        // Phantom tokens aren't printed, so they take up no space
+        if token.is_phantom() {
+            return TokenReference {
+                token: Token {
+                    start_position: self.start_position,
+                    end_position: self.start_position,
+                    token_type: token.token.token_type,
+                },
+                ..token
+            };
+        }
+
         TokenReference::new(
             token
                 .leading_trivia()
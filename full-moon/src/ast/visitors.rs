@@ -1,7 +1,7 @@
 // Implementations of Visit and VisitMut that are not able to be automatically derived yet.
 // Ideally everything would be derived.
 use super::*;
-use crate::visitors::{Visit, VisitMut, Visitor, VisitorMut};
+use crate::visitors::{Visit, VisitMut, VisitorMut, VisitorRef};
 
 // The following have `ContainedSpan`, which when automatically derived will visit the tokens containing
 // before they visit what they're actually containing.
@@ -10,7 +10,7 @@ use crate::visitors::{Visit, VisitMut, Visitor, VisitorMut};
 // This is fixed for structs with `#[visit(contains = "...")], but this is not supported on enums.
 
 impl Visit for Field {
-    fn visit<V: Visitor>(&self, visitor: &mut V) {
+    fn visit<'ast, V: VisitorRef<'ast>>(&'ast self, visitor: &mut V) {
         visitor.visit_field(self);
         match self {
             Field::ExpressionKey {
@@ -91,7 +91,7 @@ impl VisitMut for Field {
 }
 
 impl Visit for Expression {
-    fn visit<V: Visitor>(&self, visitor: &mut V) {
+    fn visit<'ast, V: VisitorRef<'ast>>(&'ast self, visitor: &mut V) {
         visitor.visit_expression(self);
         match self {
             Expression::BinaryOperator { lhs, binop, rhs } => {
@@ -153,6 +153,10 @@ impl Visit for Expression {
             Expression::Var(var) => {
                 var.visit(visitor);
             }
+
+            Expression::Error(tokens) => {
+                tokens.visit(visitor);
+            }
         };
 
         visitor.visit_expression_end(self);
@@ -227,6 +231,8 @@ impl VisitMut for Expression {
             Expression::Symbol(token) => Expression::Symbol(token.visit_mut(visitor)),
 
             Expression::Var(var) => Expression::Var(var.visit_mut(visitor)),
+
+            Expression::Error(tokens) => Expression::Error(tokens.visit_mut(visitor)),
         };
 
         self = visitor.visit_expression_end(self);
@@ -235,7 +241,7 @@ impl VisitMut for Expression {
 }
 
 impl Visit for Index {
-    fn visit<V: Visitor>(&self, visitor: &mut V) {
+    fn visit<'ast, V: VisitorRef<'ast>>(&'ast self, visitor: &mut V) {
         visitor.visit_index(self);
         match self {
             Index::Brackets {
@@ -286,7 +292,7 @@ impl VisitMut for Index {
 }
 
 impl Visit for FunctionArgs {
-    fn visit<V: Visitor>(&self, visitor: &mut V) {
+    fn visit<'ast, V: VisitorRef<'ast>>(&'ast self, visitor: &mut V) {
         visitor.visit_function_args(self);
         match self {
             FunctionArgs::Parentheses {
@@ -338,7 +344,7 @@ impl VisitMut for FunctionArgs {
 
 // The following contain type signatures, which are addendums to previous identities
 impl Visit for FunctionBody {
-    fn visit<V: Visitor>(&self, visitor: &mut V) {
+    fn visit<'ast, V: VisitorRef<'ast>>(&'ast self, visitor: &mut V) {
         visitor.visit_function_body(self);
 
         #[cfg(feature = "luau")]
@@ -356,12 +362,19 @@ impl Visit for FunctionBody {
         #[cfg(not(feature = "luau"))]
         {
             // TODO: Option<!>, and implement Visit for !
-            type_specifiers = std::iter::repeat::<Option<Self>>(None);
+            type_specifiers = std::iter::repeat::<Option<&Self>>(None);
         }
 
-        for parameter in &self.parameters {
-            parameter.visit(visitor);
-            type_specifiers.next().visit(visitor);
+        for parameter_pair in self.parameters.pairs() {
+            parameter_pair.value().visit(visitor);
+
+            if let Some(type_specifier) = type_specifiers.next().flatten() {
+                type_specifier.visit(visitor);
+            }
+
+            if let Some(punctuation) = parameter_pair.punctuation() {
+                punctuation.visit(visitor);
+            }
         }
 
         self.parameters_parentheses.tokens.1.visit(visitor);
@@ -440,7 +453,7 @@ impl VisitMut for FunctionBody {
 }
 
 impl Visit for LocalAssignment {
-    fn visit<V: Visitor>(&self, visitor: &mut V) {
+    fn visit<'ast, V: VisitorRef<'ast>>(&'ast self, visitor: &mut V) {
         visitor.visit_local_assignment(self);
         self.local_token.visit(visitor);
 
@@ -455,7 +468,7 @@ impl Visit for LocalAssignment {
         #[cfg(not(feature = "lua54"))]
         {
             // TODO: Option<!>, and implement Visit for !
-            attributes = std::iter::repeat::<Option<Self>>(None);
+            attributes = std::iter::repeat::<Option<&Self>>(None);
         }
 
         #[cfg(feature = "luau")]
@@ -466,13 +479,23 @@ impl Visit for LocalAssignment {
         #[cfg(not(feature = "luau"))]
         {
             // TODO: Option<!>, and implement Visit for !
-            type_specifiers = std::iter::repeat::<Option<Self>>(None);
+            type_specifiers = std::iter::repeat::<Option<&Self>>(None);
         }
 
-        for name in &self.name_list {
-            name.visit(visitor);
-            attributes.next().visit(visitor);
-            type_specifiers.next().visit(visitor);
+        for name_pair in self.name_list.pairs() {
+            name_pair.value().visit(visitor);
+
+            if let Some(attribute) = attributes.next().flatten() {
+                attribute.visit(visitor);
+            }
+
+            if let Some(type_specifier) = type_specifiers.next().flatten() {
+                type_specifier.visit(visitor);
+            }
+
+            if let Some(punctuation) = name_pair.punctuation() {
+                punctuation.visit(visitor);
+            }
         }
 
         self.equal_token.visit(visitor);
@@ -553,7 +576,7 @@ impl VisitMut for LocalAssignment {
 }
 
 impl Visit for GenericFor {
-    fn visit<V: Visitor>(&self, visitor: &mut V) {
+    fn visit<'ast, V: VisitorRef<'ast>>(&'ast self, visitor: &mut V) {
         visitor.visit_generic_for(self);
         self.for_token.visit(visitor);
 
@@ -567,12 +590,19 @@ impl Visit for GenericFor {
         #[cfg(not(feature = "luau"))]
         {
             // TODO: Option<!>, and implement Visit for !
-            type_specifiers = std::iter::repeat::<Option<Self>>(None);
+            type_specifiers = std::iter::repeat::<Option<&Self>>(None);
         }
 
-        for name in &self.names {
-            name.visit(visitor);
-            type_specifiers.next().visit(visitor);
+        for name_pair in self.names.pairs() {
+            name_pair.value().visit(visitor);
+
+            if let Some(type_specifier) = type_specifiers.next().flatten() {
+                type_specifier.visit(visitor);
+            }
+
+            if let Some(punctuation) = name_pair.punctuation() {
+                punctuation.visit(visitor);
+            }
         }
 
         self.in_token.visit(visitor);
@@ -638,7 +668,7 @@ impl VisitMut for GenericFor {
 }
 
 impl Visit for NumericFor {
-    fn visit<V: Visitor>(&self, visitor: &mut V) {
+    fn visit<'ast, V: VisitorRef<'ast>>(&'ast self, visitor: &mut V) {
         visitor.visit_numeric_for(self);
         self.for_token.visit(visitor);
         self.index_variable.visit(visitor);
@@ -0,0 +1,501 @@
+use std::{fmt, io, ops::Range};
+
+use codespan_reporting::{
+    diagnostic::{Diagnostic as CodespanDiagnostic, Label as CodespanLabel},
+    files::{self, SimpleFile},
+    term::{self, termcolor::NoColor},
+};
+
+use crate::{
+    ast::{AstError, AstErrorKind, ExpectedToken, TextEdit},
+    tokenizer::{Position, Symbol, TokenType, TokenizerError, TokenizerErrorType},
+    Error,
+};
+
+pub use codespan_reporting::term::termcolor;
+
+/// Whether a [`Label`] points at the cause of a [`Diagnostic`], or at something related to it
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LabelStyle {
+    /// The cause of the diagnostic, such as the token where an `end` was expected
+    Primary,
+    /// Something related to the diagnostic, such as the `if` that a missing `end` closes
+    Secondary,
+}
+
+/// A range of the source code that is pointed at by a [`Diagnostic`]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Label {
+    style: LabelStyle,
+    range: Range<usize>,
+    message: String,
+}
+
+impl Label {
+    /// Whether the label is the cause of the diagnostic
+    pub fn style(&self) -> LabelStyle {
+        self.style
+    }
+
+    /// The byte range of the source code the label points at
+    pub fn range(&self) -> Range<usize> {
+        self.range.clone()
+    }
+
+    /// The message shown alongside the label, which can be empty
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+}
+
+/// A suggested fix for a [`Diagnostic`], made of edits to the source code
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Suggestion {
+    message: String,
+    edits: Vec<TextEdit>,
+}
+
+impl Suggestion {
+    /// A human readable description of the fix, such as "insert `end`"
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    /// The edits to make to the source code to apply the fix
+    pub fn edits(&self) -> &[TextEdit] {
+        &self.edits
+    }
+}
+
+/// A rich diagnostic for an [`Error`], with labels pointing at the source code, notes, and
+/// suggested fixes. Created with `Diagnostic::from(&error)`.
+/// ```rust
+/// # use full_moon::diagnostics::Diagnostic;
+/// let source = "if x then\n\tcall()\n";
+/// let errors = full_moon::parse(source).unwrap_err();
+///
+/// let diagnostic = Diagnostic::from(&errors[0]);
+/// assert_eq!(diagnostic.message(), "expected `end` to conclude `if`");
+/// assert_eq!(diagnostic.suggestions()[0].message(), "insert `end`");
+///
+/// let rendered = diagnostic.render("source.lua", source);
+/// assert!(rendered.contains("opened here"));
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Diagnostic {
+    message: String,
+    code: &'static str,
+    labels: Vec<Label>,
+    notes: Vec<String>,
+    suggestions: Vec<Suggestion>,
+}
+
+impl Diagnostic {
+    fn new(message: impl Into<String>, code: &'static str) -> Self {
+        Self {
+            message: message.into(),
+            code,
+            labels: Vec::new(),
+            notes: Vec::new(),
+            suggestions: Vec::new(),
+        }
+    }
+
+    fn with_label(
+        mut self,
+        style: LabelStyle,
+        range: (Position, Position),
+        message: impl Into<String>,
+    ) -> Self {
+        self.labels.push(Label {
+            style,
+            range: range.0.bytes()..range.1.bytes(),
+            message: message.into(),
+        });
+
+        self
+    }
+
+    fn with_note(mut self, note: impl Into<String>) -> Self {
+        self.notes.push(note.into());
+        self
+    }
+
+    fn with_suggestion(mut self, message: impl Into<String>, edit: TextEdit) -> Self {
+        self.suggestions.push(Suggestion {
+            message: message.into(),
+            edits: vec![edit],
+        });
+
+        self
+    }
+
+    /// The human readable error message
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    /// A code for where the error came from, either `ast` or `tokenizer`
+    pub fn code(&self) -> &'static str {
+        self.code
+    }
+
+    /// The ranges of the source code the diagnostic points at
+    pub fn labels(&self) -> &[Label] {
+        &self.labels
+    }
+
+    /// Additional information about the error
+    pub fn notes(&self) -> &[String] {
+        &self.notes
+    }
+
+    /// Suggested fixes for the error
+    pub fn suggestions(&self) -> &[Suggestion] {
+        &self.suggestions
+    }
+
+    fn to_codespan(&self) -> CodespanDiagnostic<()> {
+        CodespanDiagnostic::error()
+            .with_message(&self.message)
+            .with_code(self.code)
+            .with_labels(
+                self.labels
+                    .iter()
+                    .map(|label| {
+                        match label.style {
+                            LabelStyle::Primary => CodespanLabel::primary((), label.range()),
+                            LabelStyle::Secondary => CodespanLabel::secondary((), label.range()),
+                        }
+                        .with_message(&label.message)
+                    })
+                    .collect(),
+            )
+            .with_notes(
+                self.notes
+                    .iter()
+                    .cloned()
+                    .chain(
+                        self.suggestions
+                            .iter()
+                            .map(|suggestion| format!("help: {}", suggestion.message)),
+                    )
+                    .collect(),
+            )
+    }
+
+    /// Writes the diagnostic to a terminal, along with snippets of the source code it points at.
+    /// Use [`termcolor::StandardStream`] to write to stdout or stderr with colors.
+    pub fn emit(
+        &self,
+        writer: &mut dyn termcolor::WriteColor,
+        file_name: &str,
+        source: &str,
+    ) -> io::Result<()> {
+        let file = SimpleFile::new(file_name, source);
+
+        term::emit(writer, &term::Config::default(), &file, &self.to_codespan()).map_err(|error| {
+            match error {
+                files::Error::Io(error) => error,
+                error => io::Error::other(error),
+            }
+        })
+    }
+
+    /// Renders the diagnostic without colors, along with snippets of the source code it points at
+    pub fn render(&self, file_name: &str, source: &str) -> String {
+        let mut output = NoColor::new(Vec::new());
+
+        self.emit(&mut output, file_name, source)
+            .expect("couldn't render diagnostic");
+
+        String::from_utf8(output.into_inner()).expect("rendered diagnostic isn't valid utf-8")
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str(&self.message)
+    }
+}
+
+impl std::error::Error for Diagnostic {}
+
+// Symbols that are safe to insert on their own, as they only close or continue something
+fn is_insertable(symbol: Symbol) -> bool {
+    matches!(
+        symbol,
+        Symbol::End
+            | Symbol::Then
+            | Symbol::Do
+            | Symbol::RightParen
+            | Symbol::RightBracket
+            | Symbol::RightBrace
+            | Symbol::GreaterThan
+    )
+}
+
+fn expected_message(expected: &[ExpectedToken]) -> String {
+    let mut message = String::from("expected one of ");
+
+    for (index, token) in expected.iter().enumerate() {
+        if index > 0 {
+            message.push_str(if index + 1 == expected.len() {
+                ", or "
+            } else {
+                ", "
+            });
+        }
+
+        message.push_str(&token.to_string());
+    }
+
+    message
+}
+
+impl From<&AstError> for Diagnostic {
+    fn from(error: &AstError) -> Self {
+        let token = error.token();
+
+        let label = match error.expected() {
+            [expected] => format!("expected {expected}"),
+            _ => String::new(),
+        };
+
+        let mut diagnostic = Diagnostic::new(error.error_message(), "ast").with_label(
+            LabelStyle::Primary,
+            error.range(),
+            label,
+        );
+
+        if let Some(related_range) = error.related_range() {
+            diagnostic = diagnostic.with_label(LabelStyle::Secondary, related_range, "opened here");
+        }
+
+        if error.expected().len() > 1 {
+            diagnostic = diagnostic.with_note(expected_message(error.expected()));
+        }
+
+        let start = token.start_position().bytes();
+        let token_range = start..token.end_position().bytes();
+
+        match error.kind() {
+            AstErrorKind::ExpectedSymbol(symbol) if is_insertable(symbol) => {
+                let text = if matches!(token.token_type(), TokenType::Eof) {
+                    format!("\n{symbol}")
+                } else {
+                    format!("{symbol} ")
+                };
+
+                diagnostic.with_suggestion(
+                    format!("insert `{symbol}`"),
+                    TextEdit::new(start..start, text),
+                )
+            }
+
+            AstErrorKind::ReturnTypeArrow => {
+                diagnostic.with_suggestion("replace `->` with `:`", TextEdit::new(token_range, ":"))
+            }
+
+            AstErrorKind::TrailingComma => {
+                diagnostic.with_suggestion("remove the comma", TextEdit::new(token_range, ""))
+            }
+
+            AstErrorKind::DoubleBrace => {
+                diagnostic.with_note("braces in interpolated strings are escaped with `\\{`")
+            }
+
+            AstErrorKind::MixedUnionAndIntersection => {
+                diagnostic.with_note("use parentheses to group union or intersection types")
+            }
+
+            AstErrorKind::GenericTypeAfterTypePack => {
+                diagnostic.with_note("generic types have to come before generic type packs")
+            }
+
+            AstErrorKind::AssignToCall => {
+                diagnostic.with_note("only variables, fields, and indexes can be assigned to")
+            }
+
+            AstErrorKind::StatementAfterLastStatement => diagnostic
+                .with_note("statements such as `return` and `break` have to end their block"),
+
+            _ => diagnostic,
+        }
+    }
+}
+
+impl From<&TokenizerError> for Diagnostic {
+    fn from(error: &TokenizerError) -> Self {
+        let diagnostic = Diagnostic::new(error.error().to_string(), "tokenizer").with_label(
+            LabelStyle::Primary,
+            error.range(),
+            "",
+        );
+
+        match error.error() {
+            TokenizerErrorType::UnclosedComment => diagnostic
+                .with_note("multi-line comments are closed with `]]`, with as many `=` as they were opened with"),
+
+            TokenizerErrorType::UnclosedString => diagnostic
+                .with_note("strings can't span multiple lines, unless they are opened with `[[`"),
+
+            _ => diagnostic,
+        }
+    }
+}
+
+impl From<&Error> for Diagnostic {
+    fn from(error: &Error) -> Self {
+        match error {
+            Error::AstError(error) => error.into(),
+            Error::TokenizerError(error) => error.into(),
+        }
+    }
+}
+
+impl miette::Diagnostic for Diagnostic {
+    fn code<'a>(&'a self) -> Option<Box<dyn fmt::Display + 'a>> {
+        Some(Box::new(self.code))
+    }
+
+    fn help<'a>(&'a self) -> Option<Box<dyn fmt::Display + 'a>> {
+        let help = self
+            .notes
+            .iter()
+            .cloned()
+            .chain(
+                self.suggestions
+                    .iter()
+                    .map(|suggestion| suggestion.message.clone()),
+            )
+            .collect::<Vec<_>>();
+
+        if help.is_empty() {
+            None
+        } else {
+            Some(Box::new(help.join("\n")))
+        }
+    }
+
+    fn labels(&self) -> Option<Box<dyn Iterator<Item = miette::LabeledSpan> + '_>> {
+        Some(Box::new(self.labels.iter().map(|label| {
+            let message = Some(label.message.clone()).filter(|message| !message.is_empty());
+
+            match label.style {
+                LabelStyle::Primary => {
+                    miette::LabeledSpan::new_primary_with_span(message, label.range())
+                }
+                LabelStyle::Secondary => miette::LabeledSpan::new_with_span(message, label.range()),
+            }
+        })))
+    }
+}
+
+// Errors are reported through miette by converting them to a Diagnostic every time,
+// so that `miette::Report::new(error)` works without converting first
+macro_rules! impl_miette_diagnostic {
+    ($($error:ty),+) => {
+        $(
+            impl miette::Diagnostic for $error {
+                fn code<'a>(&'a self) -> Option<Box<dyn fmt::Display + 'a>> {
+                    Some(Box::new(Diagnostic::from(self).code))
+                }
+
+                fn help<'a>(&'a self) -> Option<Box<dyn fmt::Display + 'a>> {
+                    miette::Diagnostic::help(&Diagnostic::from(self))
+                        .map(|help| Box::new(help.to_string()) as Box<dyn fmt::Display>)
+                }
+
+                fn labels(&self) -> Option<Box<dyn Iterator<Item = miette::LabeledSpan> + '_>> {
+                    let labels = miette::Diagnostic::labels(&Diagnostic::from(self))?
+                        .collect::<Vec<_>>();
+
+                    Some(Box::new(labels.into_iter()))
+                }
+            }
+        )+
+    };
+}
+
+impl_miette_diagnostic!(Error, AstError, TokenizerError);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::LuaVersion;
+
+    fn diagnostics(source: &str) -> Vec<Diagnostic> {
+        crate::parse_fallible(source, LuaVersion::new())
+            .errors()
+            .iter()
+            .map(Diagnostic::from)
+            .collect()
+    }
+
+    #[test]
+    fn test_missing_end_labels() {
+        let source = "if x then\n\tcall()\n";
+        let diagnostics = diagnostics(source);
+        assert_eq!(diagnostics.len(), 1);
+
+        let labels = diagnostics[0].labels();
+        assert_eq!(labels[0].style(), LabelStyle::Primary);
+        assert_eq!(labels[0].message(), "expected `end`");
+        assert_eq!(labels[1].style(), LabelStyle::Secondary);
+        assert_eq!(&source[labels[1].range()], "if");
+
+        let edits = diagnostics[0].suggestions()[0].edits();
+        assert_eq!(edits[0].range(), source.len()..source.len());
+        assert_eq!(edits[0].replacement(), "\nend");
+    }
+
+    #[test]
+    fn test_unclosed_call() {
+        let source = "print(1, 2 local x = 1";
+        let diagnostics = diagnostics(source);
+
+        let diagnostic = &diagnostics[0];
+        assert_eq!(diagnostic.message(), "expected `)` to close function call");
+        assert_eq!(&source[diagnostic.labels()[1].range()], "(");
+        assert_eq!(diagnostic.suggestions()[0].message(), "insert `)`");
+    }
+
+    #[test]
+    fn test_expected_notes() {
+        let diagnostics = diagnostics("local x = ");
+        assert!(diagnostics[0].notes()[0].starts_with("expected one of a name, a number"));
+        assert!(diagnostics[0].suggestions().is_empty());
+    }
+
+    #[test]
+    fn test_tokenizer_error() {
+        let diagnostics = diagnostics("local x = \"hello");
+        assert_eq!(diagnostics[0].code(), "tokenizer");
+        assert_eq!(diagnostics[0].labels()[0].range(), 10..16);
+    }
+
+    #[test]
+    fn test_render() {
+        let source = "local x = (1 + 2";
+        let errors = crate::parse(source).unwrap_err();
+        let rendered = Diagnostic::from(&errors[0]).render("source.lua", source);
+
+        assert!(rendered.starts_with("error[ast]: expected `)` after expression"));
+        assert!(rendered.contains("source.lua:1:"));
+        assert!(rendered.contains("help: insert `)`"));
+    }
+
+    #[test]
+    fn test_miette() {
+        let errors = crate::parse("if x then").unwrap_err();
+        let error = &errors[0];
+
+        assert_eq!(miette::Diagnostic::code(error).unwrap().to_string(), "ast");
+        assert_eq!(miette::Diagnostic::labels(error).unwrap().count(), 2);
+        assert_eq!(
+            miette::Diagnostic::help(error).unwrap().to_string(),
+            "insert `end`"
+        );
+    }
+}
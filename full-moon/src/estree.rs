@@ -0,0 +1,1343 @@
+use serde_json::{json, Map, Value};
+
+use crate::{
+    ast::{punctuated::Punctuated, *},
+    minify::unescape,
+    node::Node,
+    tokenizer::{Position, StringLiteralQuoteType, Symbol, TokenReference, TokenType},
+};
+
+#[cfg(feature = "luau")]
+use crate::ast::luau::{IfExpression, InterpolatedString, TypeSpecifier};
+
+/// Options for [`to_json`], made with [`EstreeOptions::new`] and changed with its `with_*` methods.
+/// These are named after, and default the same as, the options of luaparse.
+#[derive(Clone, Debug)]
+pub struct EstreeOptions {
+    comments: bool,
+    locations: bool,
+    ranges: bool,
+}
+
+impl EstreeOptions {
+    /// Creates options for giving comments, but not locations or ranges.
+    pub fn new() -> Self {
+        Self {
+            comments: true,
+            locations: false,
+            ranges: false,
+        }
+    }
+
+    /// Whether the chunk has a `comments` array with every comment in the code. On by default.
+    pub fn comments(&self) -> bool {
+        self.comments
+    }
+
+    /// Whether nodes have a `loc`, with the line and column they start and end at. Off by default.
+    pub fn locations(&self) -> bool {
+        self.locations
+    }
+
+    /// Whether nodes have a `range`, with the offsets they start and end at. Off by default.
+    pub fn ranges(&self) -> bool {
+        self.ranges
+    }
+
+    /// Returns a new EstreeOptions with comments given or not
+    pub fn with_comments(self, comments: bool) -> Self {
+        Self { comments, ..self }
+    }
+
+    /// Returns a new EstreeOptions with locations given or not
+    pub fn with_locations(self, locations: bool) -> Self {
+        Self { locations, ..self }
+    }
+
+    /// Returns a new EstreeOptions with ranges given or not
+    pub fn with_ranges(self, ranges: bool) -> Self {
+        Self { ranges, ..self }
+    }
+}
+
+impl Default for EstreeOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Returns the Ast as JSON in the same shape as the trees made by [luaparse], so that tools made
+/// for it can be used on code parsed by full-moon.
+///
+/// Nodes have the same types and fields as in luaparse, such as `LocalStatement` with `variables`
+/// and `init`. Offsets in a `range` and columns in a `loc` are counted in UTF-16 code units, like
+/// indexes into JavaScript strings, and columns start from 0. Positions are those of the tokens, so
+/// an Ast that was changed after it was parsed should have
+/// [`Ast::update_positions`](crate::ast::Ast::update_positions) called on it first.
+///
+/// Syntax that luaparse doesn't have is given nodes named after where it's from, so that it can't
+/// be mistaken for anything luaparse makes:
+/// - Luau statements and expressions are `LuauContinueStatement`, `LuauTypeDeclaration`,
+///   `LuauTypeFunction`, `LuauIfExpression`, `LuauInterpolatedString`, and `LuauTypeAssertion`.
+///   Compound assignments, which CfxLua has too, are `LuauCompoundAssignmentStatement`.
+/// - Types are `LuauType` nodes with their `raw` code. Type annotations, generics, and return
+///   types go in a `luau` object on the identifier or function they're on, which tools that don't
+///   know about it will skip over.
+/// - Lua 5.4 attributes, such as `<const>`, are the `attribute` of the identifier.
+/// - CfxLua set constructors, such as `.a` in `{ .a }`, are `CfxLuaTableSetConstructor`.
+/// - Code that couldn't be parsed, in an Ast from [`parse_fallible`](crate::parse_fallible), is an
+///   `Error` node with its `raw` code.
+///
+/// Strings have the `value` they decode to, unless they have an escape that means different things
+/// in different versions of Lua or aren't valid UTF-8, in which case it's `null`.
+///
+/// ```rust
+/// # use full_moon::estree::{self, EstreeOptions};
+/// # use serde_json::json;
+/// # fn main() -> Result<(), Vec<full_moon::Error>> {
+/// let ast = full_moon::parse("local x = 1 -- one")?;
+///
+/// assert_eq!(
+///     estree::to_json(&ast, &EstreeOptions::new()),
+///     json!({
+///         "type": "Chunk",
+///         "body": [{
+///             "type": "LocalStatement",
+///             "variables": [{ "type": "Identifier", "name": "x" }],
+///             "init": [{ "type": "NumericLiteral", "value": 1, "raw": "1" }],
+///         }],
+///         "comments": [{ "type": "Comment", "value": " one", "raw": "-- one" }],
+///     }),
+/// );
+/// # Ok(())
+/// # }
+/// ```
+///
+/// [luaparse]: https://github.com/fstirlitz/luaparse
+pub fn to_json(ast: &Ast, options: &EstreeOptions) -> Value {
+    let source = ast.to_string();
+
+    let mut utf16_offsets = Vec::with_capacity(source.len() + 1);
+    let mut offset = 0;
+
+    for character in source.chars() {
+        utf16_offsets.extend(std::iter::repeat_n(offset, character.len_utf8()));
+        offset += character.len_utf16();
+    }
+
+    utf16_offsets.push(offset);
+
+    let line_starts = std::iter::once(0)
+        .chain(source.match_indices('\n').map(|(index, _)| index + 1))
+        .collect();
+
+    let exporter = Exporter {
+        options,
+        source: &source,
+        utf16_offsets,
+        line_starts,
+    };
+
+    let start = Position {
+        bytes: 0,
+        line: 1,
+        character: 1,
+    };
+
+    let mut fields = json!({ "body": exporter.block(ast.nodes()) });
+
+    if options.comments() {
+        let mut comments: Vec<_> = ast
+            .tokens()
+            .chain(std::iter::once(ast.eof()))
+            .flat_map(|token| token.leading_trivia().chain(token.trailing_trivia()))
+            .collect();
+
+        comments.sort_by_key(|trivia| trivia.start_position().bytes());
+
+        fields["comments"] = comments
+            .into_iter()
+            .filter_map(|trivia| {
+                let (value, raw) = match trivia.token_type() {
+                    TokenType::SingleLineComment { comment } => {
+                        (comment.to_string(), format!("--{comment}"))
+                    }
+
+                    TokenType::MultiLineComment { comment, .. } => {
+                        (comment.to_string(), trivia.to_string())
+                    }
+
+                    #[cfg(feature = "cfxlua")]
+                    TokenType::CStyleComment { comment } => {
+                        (comment.to_string(), trivia.to_string())
+                    }
+
+                    _ => return None,
+                };
+
+                Some(exporter.node(
+                    "Comment",
+                    Some((trivia.start_position(), trivia.end_position())),
+                    json!({ "value": value, "raw": raw }),
+                ))
+            })
+            .collect();
+    }
+
+    exporter.node(
+        "Chunk",
+        Some((start, ast.eof().token().end_position())),
+        fields,
+    )
+}
+
+struct Exporter<'a> {
+    options: &'a EstreeOptions,
+    source: &'a str,
+    // The UTF-16 offset of every byte in the source, and the end of it
+    utf16_offsets: Vec<usize>,
+    line_starts: Vec<usize>,
+}
+
+// The range of a node's tokens. This isn't `Node::range`, as it ends at the last token it gives,
+// which for nodes such as tables is the closing brace instead of what's inside them.
+fn full_range(node: impl Node) -> Option<(Position, Position)> {
+    let positions: Vec<_> = node
+        .tokens()
+        .filter(|token| !token.is_phantom())
+        .map(|token| (token.token().start_position(), token.token().end_position()))
+        .collect();
+
+    let start = positions
+        .iter()
+        .map(|(start, _)| *start)
+        .min_by_key(|position| position.bytes())?;
+    let end = positions
+        .iter()
+        .map(|(_, end)| *end)
+        .max_by_key(|position| position.bytes())?;
+
+    Some((start, end))
+}
+
+// The range from the start of the first range to the end of the second
+fn join(
+    start: Option<(Position, Position)>,
+    end: Option<(Position, Position)>,
+) -> Option<(Position, Position)> {
+    Some((start?.0, end?.1))
+}
+
+// The value of a number, if it can be worked out. Integers are given as integers, so that `1` isn't
+// given as `1.0`.
+fn number_value(text: &str) -> Value {
+    let lowercase = text.replace('_', "").to_lowercase();
+
+    let value = if let Some(digits) = lowercase.strip_prefix("0x") {
+        let (mantissa, exponent) = match digits.split_once('p') {
+            Some((mantissa, exponent)) => match exponent.parse::<i32>() {
+                Ok(exponent) => (mantissa, exponent),
+                Err(_) => return Value::Null,
+            },
+            None => (digits, 0),
+        };
+
+        let (whole, fraction) = mantissa.split_once('.').unwrap_or((mantissa, ""));
+        let mut value = 0.0;
+        let mut scale = 1.0;
+
+        for (digit, is_fraction) in whole
+            .chars()
+            .map(|digit| (digit, false))
+            .chain(fraction.chars().map(|digit| (digit, true)))
+        {
+            let Some(digit) = digit.to_digit(16) else {
+                return Value::Null;
+            };
+
+            if is_fraction {
+                scale /= 16.0;
+                value += f64::from(digit) * scale;
+            } else {
+                value = value * 16.0 + f64::from(digit);
+            }
+        }
+
+        if whole.is_empty() && fraction.is_empty() {
+            return Value::Null;
+        }
+
+        value * 2f64.powi(exponent)
+    } else if let Some(digits) = lowercase.strip_prefix("0b") {
+        match u64::from_str_radix(digits, 2) {
+            Ok(value) => value as f64,
+            Err(_) => return Value::Null,
+        }
+    } else {
+        // Rust also parses `inf` and `nan`, which aren't numbers in Lua
+        if !lowercase.starts_with(|character: char| character.is_ascii_digit() || character == '.')
+        {
+            return Value::Null;
+        }
+
+        match lowercase.parse::<f64>() {
+            Ok(value) => value,
+            Err(_) => return Value::Null,
+        }
+    };
+
+    if value.fract() == 0.0 && value.abs() < 2f64.powi(53) {
+        json!(value as i64)
+    } else {
+        json!(value)
+    }
+}
+
+// The value of a string, unless it can't be decoded the same in every version of Lua
+fn string_value(literal: &str, quote_type: StringLiteralQuoteType) -> Value {
+    let bytes = match quote_type {
+        StringLiteralQuoteType::Brackets => {
+            // The first new line of a string in brackets isn't part of it
+            let literal = ["\r\n", "\n\r", "\n", "\r"]
+                .iter()
+                .find_map(|new_line| literal.strip_prefix(new_line))
+                .unwrap_or(literal);
+
+            literal.as_bytes().to_vec()
+        }
+
+        _ => match unescape(literal) {
+            Some(bytes) => bytes,
+            None => return Value::Null,
+        },
+    };
+
+    String::from_utf8(bytes).map_or(Value::Null, Value::String)
+}
+
+impl Exporter<'_> {
+    fn offset(&self, bytes: usize) -> usize {
+        self.utf16_offsets
+            .get(bytes)
+            .or(self.utf16_offsets.last())
+            .copied()
+            .unwrap_or_default()
+    }
+
+    fn location(&self, bytes: usize) -> Value {
+        let line = self
+            .line_starts
+            .partition_point(|&line_start| line_start <= bytes);
+        let line_start = self.line_starts[line.saturating_sub(1)];
+
+        json!({
+            "line": line.max(1),
+            "column": self.offset(bytes) - self.offset(line_start),
+        })
+    }
+
+    // A node with the given type and fields, and the range and location asked for
+    fn node(&self, kind: &str, range: Option<(Position, Position)>, fields: Value) -> Value {
+        let mut node = Map::new();
+        node.insert("type".to_owned(), kind.into());
+
+        if let Value::Object(fields) = fields {
+            node.extend(fields);
+        }
+
+        if let Some((start, end)) = range {
+            if self.options.ranges() {
+                node.insert(
+                    "range".to_owned(),
+                    json!([self.offset(start.bytes()), self.offset(end.bytes())]),
+                );
+            }
+
+            if self.options.locations() {
+                node.insert(
+                    "loc".to_owned(),
+                    json!({
+                        "start": self.location(start.bytes()),
+                        "end": self.location(end.bytes()),
+                    }),
+                );
+            }
+        }
+
+        Value::Object(node)
+    }
+
+    // The code in the range, if it has one
+    fn source_text(&self, range: Option<(Position, Position)>) -> Option<String> {
+        range
+            .and_then(|(start, end)| self.source.get(start.bytes()..end.bytes()))
+            .map(ToOwned::to_owned)
+    }
+
+    // The code of a node without the trivia around it
+    #[cfg(feature = "luau")]
+    fn raw(&self, node: &(impl Node + std::fmt::Display)) -> String {
+        self.source_text(full_range(node))
+            .unwrap_or_else(|| node.to_string().trim().to_owned())
+    }
+
+    fn error(&self, tokens: &[TokenReference]) -> Value {
+        let range = join(
+            tokens.first().and_then(full_range),
+            tokens.last().and_then(full_range),
+        );
+
+        let raw = self.source_text(range).unwrap_or_else(|| {
+            tokens
+                .iter()
+                .map(|token| token.token().to_string())
+                .collect::<Vec<_>>()
+                .join(" ")
+        });
+
+        self.node("Error", range, json!({ "raw": raw }))
+    }
+
+    fn identifier(&self, token: &TokenReference) -> Value {
+        self.node(
+            "Identifier",
+            full_range(token),
+            json!({ "name": token.token().to_string() }),
+        )
+    }
+
+    fn vararg(&self, token: &TokenReference) -> Value {
+        self.node(
+            "VarargLiteral",
+            full_range(token),
+            json!({ "value": "...", "raw": "..." }),
+        )
+    }
+
+    #[cfg(feature = "luau")]
+    fn luau_type(&self, node: &(impl Node + std::fmt::Display)) -> Value {
+        self.node(
+            "LuauType",
+            full_range(node),
+            json!({ "raw": self.raw(node) }),
+        )
+    }
+
+    // Adds a field to the `luau` object of a node
+    #[cfg(feature = "luau")]
+    fn add_luau(value: &mut Value, key: &str, field: Value) {
+        if let Value::Object(node) = value {
+            node.entry("luau")
+                .or_insert_with(|| json!({}))
+                .as_object_mut()
+                .expect("luau field should be an object")
+                .insert(key.to_owned(), field);
+        }
+    }
+
+    #[cfg(feature = "luau")]
+    fn annotate(&self, value: &mut Value, type_specifier: Option<&TypeSpecifier>) {
+        if let Some(type_specifier) = type_specifier {
+            let type_annotation = self.luau_type(type_specifier.type_info());
+            Self::add_luau(value, "typeAnnotation", type_annotation);
+        }
+    }
+
+    fn block(&self, block: &Block) -> Vec<Value> {
+        block
+            .stmts()
+            .map(|stmt| self.stmt(stmt))
+            .chain(block.last_stmt().map(|last_stmt| self.last_stmt(last_stmt)))
+            .collect()
+    }
+
+    fn expressions(&self, expressions: &Punctuated<Expression>) -> Vec<Value> {
+        expressions
+            .iter()
+            .map(|expression| self.expression(expression))
+            .collect()
+    }
+
+    fn last_stmt(&self, last_stmt: &LastStmt) -> Value {
+        match last_stmt {
+            LastStmt::Break(token) => self.node("BreakStatement", full_range(token), json!({})),
+
+            #[cfg(feature = "luau")]
+            LastStmt::Continue(token) => {
+                self.node("LuauContinueStatement", full_range(token), json!({}))
+            }
+
+            LastStmt::Return(r#return) => self.node(
+                "ReturnStatement",
+                full_range(r#return),
+                json!({ "arguments": self.expressions(r#return.returns()) }),
+            ),
+        }
+    }
+
+    fn stmt(&self, stmt: &Stmt) -> Value {
+        match stmt {
+            Stmt::Assignment(assignment) => self.node(
+                "AssignmentStatement",
+                full_range(stmt),
+                json!({
+                    "variables": assignment.variables().iter().map(|var| self.var(var)).collect::<Vec<_>>(),
+                    "init": self.expressions(assignment.expressions()),
+                }),
+            ),
+
+            Stmt::Do(r#do) => self.node(
+                "DoStatement",
+                full_range(stmt),
+                json!({ "body": self.block(r#do.block()) }),
+            ),
+
+            Stmt::FunctionCall(function_call) => self.node(
+                "CallStatement",
+                full_range(stmt),
+                json!({ "expression": self.suffixes(function_call.prefix(), function_call.suffixes()) }),
+            ),
+
+            Stmt::FunctionDeclaration(declaration) => self.function(
+                full_range(stmt),
+                self.function_name(declaration.name()),
+                false,
+                declaration.body(),
+            ),
+
+            Stmt::GenericFor(generic_for) => {
+                #[cfg_attr(not(feature = "luau"), allow(unused_mut))]
+                let mut variables: Vec<_> = generic_for
+                    .names()
+                    .iter()
+                    .map(|name| self.identifier(name))
+                    .collect();
+
+                #[cfg(feature = "luau")]
+                for (variable, type_specifier) in
+                    variables.iter_mut().zip(generic_for.type_specifiers())
+                {
+                    self.annotate(variable, type_specifier);
+                }
+
+                self.node(
+                    "ForGenericStatement",
+                    full_range(stmt),
+                    json!({
+                        "variables": variables,
+                        "iterators": self.expressions(generic_for.expressions()),
+                        "body": self.block(generic_for.block()),
+                    }),
+                )
+            }
+
+            Stmt::If(r#if) => {
+                let clause = |kind, start: &TokenReference, condition, block: &Block, end| {
+                    let mut clause = json!({ "body": self.block(block) });
+
+                    if let Some(condition) = condition {
+                        clause["condition"] = self.expression(condition);
+                    }
+
+                    self.node(
+                        kind,
+                        join(full_range(start), full_range(block).or(end)),
+                        clause,
+                    )
+                };
+
+                let mut clauses = vec![clause(
+                    "IfClause",
+                    r#if.if_token(),
+                    Some(r#if.condition()),
+                    r#if.block(),
+                    full_range(r#if.then_token()),
+                )];
+
+                for else_if in r#if.else_if().into_iter().flatten() {
+                    clauses.push(clause(
+                        "ElseifClause",
+                        else_if.else_if_token(),
+                        Some(else_if.condition()),
+                        else_if.block(),
+                        full_range(else_if.then_token()),
+                    ));
+                }
+
+                if let (Some(else_token), Some(else_block)) = (r#if.else_token(), r#if.else_block())
+                {
+                    clauses.push(clause(
+                        "ElseClause",
+                        else_token,
+                        None,
+                        else_block,
+                        full_range(else_token),
+                    ));
+                }
+
+                self.node("IfStatement", full_range(stmt), json!({ "clauses": clauses }))
+            }
+
+            Stmt::LocalAssignment(local_assignment) => {
+                #[cfg_attr(not(feature = "luau"), allow(unused_mut))]
+                let mut variables: Vec<_> = local_assignment
+                    .names()
+                    .iter()
+                    .map(|name| self.identifier(name))
+                    .collect();
+
+                #[cfg(feature = "lua54")]
+                for (variable, attribute) in variables.iter_mut().zip(local_assignment.attributes())
+                {
+                    if let Some(attribute) = attribute {
+                        variable["attribute"] = attribute.name().token().to_string().into();
+                    }
+                }
+
+                #[cfg(feature = "luau")]
+                for (variable, type_specifier) in
+                    variables.iter_mut().zip(local_assignment.type_specifiers())
+                {
+                    self.annotate(variable, type_specifier);
+                }
+
+                self.node(
+                    "LocalStatement",
+                    full_range(stmt),
+                    json!({
+                        "variables": variables,
+                        "init": self.expressions(local_assignment.expressions()),
+                    }),
+                )
+            }
+
+            Stmt::LocalFunction(local_function) => self.function(
+                full_range(stmt),
+                self.identifier(local_function.name()),
+                true,
+                local_function.body(),
+            ),
+
+            Stmt::NumericFor(numeric_for) => {
+                #[cfg_attr(not(feature = "luau"), allow(unused_mut))]
+                let mut variable = self.identifier(numeric_for.index_variable());
+
+                #[cfg(feature = "luau")]
+                self.annotate(&mut variable, numeric_for.type_specifier());
+
+                self.node(
+                    "ForNumericStatement",
+                    full_range(stmt),
+                    json!({
+                        "variable": variable,
+                        "start": self.expression(numeric_for.start()),
+                        "end": self.expression(numeric_for.end()),
+                        "step": numeric_for.step().map(|step| self.expression(step)),
+                        "body": self.block(numeric_for.block()),
+                    }),
+                )
+            }
+
+            Stmt::Repeat(repeat) => self.node(
+                "RepeatStatement",
+                full_range(stmt),
+                json!({
+                    "condition": self.expression(repeat.until()),
+                    "body": self.block(repeat.block()),
+                }),
+            ),
+
+            Stmt::While(r#while) => self.node(
+                "WhileStatement",
+                full_range(stmt),
+                json!({
+                    "condition": self.expression(r#while.condition()),
+                    "body": self.block(r#while.block()),
+                }),
+            ),
+
+            #[cfg(any(feature = "luau", feature = "cfxlua"))]
+            Stmt::CompoundAssignment(compound_assignment) => self.node(
+                "LuauCompoundAssignmentStatement",
+                full_range(stmt),
+                json!({
+                    "operator": compound_assignment.compound_operator().token().token().to_string(),
+                    "variable": self.var(compound_assignment.lhs()),
+                    "init": self.expression(compound_assignment.rhs()),
+                }),
+            ),
+
+            #[cfg(feature = "luau")]
+            Stmt::ExportedTypeDeclaration(exported) => {
+                self.type_declaration(full_range(stmt), exported.type_declaration(), true)
+            }
+
+            #[cfg(feature = "luau")]
+            Stmt::TypeDeclaration(type_declaration) => {
+                self.type_declaration(full_range(stmt), type_declaration, false)
+            }
+
+            #[cfg(feature = "luau")]
+            Stmt::ExportedTypeFunction(exported) => {
+                self.type_function(full_range(stmt), exported.type_function(), true)
+            }
+
+            #[cfg(feature = "luau")]
+            Stmt::TypeFunction(type_function) => {
+                self.type_function(full_range(stmt), type_function, false)
+            }
+
+            #[cfg(any(feature = "lua52", feature = "luajit"))]
+            Stmt::Goto(goto) => self.node(
+                "GotoStatement",
+                full_range(stmt),
+                json!({ "label": self.identifier(goto.label_name()) }),
+            ),
+
+            #[cfg(any(feature = "lua52", feature = "luajit"))]
+            Stmt::Label(label) => self.node(
+                "LabelStatement",
+                full_range(stmt),
+                json!({ "label": self.identifier(label.name()) }),
+            ),
+
+            Stmt::Error(tokens) => self.error(tokens),
+        }
+    }
+
+    #[cfg(feature = "luau")]
+    fn type_declaration(
+        &self,
+        range: Option<(Position, Position)>,
+        type_declaration: &luau::TypeDeclaration,
+        exported: bool,
+    ) -> Value {
+        let generics = type_declaration.generics().map(|generics| {
+            self.node(
+                "LuauGenerics",
+                full_range(generics),
+                json!({ "raw": self.raw(generics) }),
+            )
+        });
+
+        self.node(
+            "LuauTypeDeclaration",
+            range,
+            json!({
+                "name": self.identifier(type_declaration.type_name()),
+                "exported": exported,
+                "generics": generics,
+                "value": self.luau_type(type_declaration.type_definition()),
+            }),
+        )
+    }
+
+    #[cfg(feature = "luau")]
+    fn type_function(
+        &self,
+        range: Option<(Position, Position)>,
+        type_function: &luau::TypeFunction,
+        exported: bool,
+    ) -> Value {
+        let body = type_function.function_body();
+
+        self.node(
+            "LuauTypeFunction",
+            range,
+            json!({
+                "name": self.identifier(type_function.function_name()),
+                "exported": exported,
+                "parameters": self.parameters(body),
+                "body": self.block(body.block()),
+            }),
+        )
+    }
+
+    // `a.b:c` as the member expressions luaparse gives it as
+    fn function_name(&self, function_name: &FunctionName) -> Value {
+        let mut names = function_name.names().iter();
+        let Some(first) = names.next() else {
+            return Value::Null;
+        };
+
+        let start = full_range(first);
+        let mut base = self.identifier(first);
+
+        let method = function_name
+            .method_name()
+            .map(|method_name| (":", method_name));
+
+        for (indexer, name) in names.map(|name| (".", name)).chain(method) {
+            base = self.node(
+                "MemberExpression",
+                join(start, full_range(name)),
+                json!({
+                    "indexer": indexer,
+                    "identifier": self.identifier(name),
+                    "base": base,
+                }),
+            );
+        }
+
+        base
+    }
+
+    fn parameters(&self, body: &FunctionBody) -> Vec<Value> {
+        #[cfg(feature = "luau")]
+        let mut type_specifiers = body.type_specifiers();
+
+        body.parameters()
+            .iter()
+            .map(|parameter| {
+                #[cfg_attr(not(feature = "luau"), allow(unused_mut))]
+                let mut value = match parameter {
+                    Parameter::Ellipsis(token) => self.vararg(token),
+                    Parameter::Name(name) => self.identifier(name),
+                };
+
+                #[cfg(feature = "luau")]
+                self.annotate(&mut value, type_specifiers.next().flatten());
+
+                value
+            })
+            .collect()
+    }
+
+    fn function(
+        &self,
+        range: Option<(Position, Position)>,
+        identifier: Value,
+        is_local: bool,
+        body: &FunctionBody,
+    ) -> Value {
+        #[cfg_attr(not(feature = "luau"), allow(unused_mut))]
+        let mut function = self.node(
+            "FunctionDeclaration",
+            range,
+            json!({
+                "identifier": identifier,
+                "isLocal": is_local,
+                "parameters": self.parameters(body),
+                "body": self.block(body.block()),
+            }),
+        );
+
+        #[cfg(feature = "luau")]
+        {
+            if let Some(generics) = body.generics() {
+                let generics = self.node(
+                    "LuauGenerics",
+                    full_range(generics),
+                    json!({ "raw": self.raw(generics) }),
+                );
+                Self::add_luau(&mut function, "generics", generics);
+            }
+
+            if let Some(return_type) = body.return_type() {
+                let return_type = self.luau_type(return_type.type_info());
+                Self::add_luau(&mut function, "returnType", return_type);
+            }
+        }
+
+        function
+    }
+
+    fn var(&self, var: &Var) -> Value {
+        match var {
+            Var::Expression(var_expression) => {
+                self.suffixes(var_expression.prefix(), var_expression.suffixes())
+            }
+            Var::Name(name) => self.identifier(name),
+        }
+    }
+
+    // A prefix and its suffixes, such as `a.b(c)`, as the nested expressions luaparse gives them as
+    fn suffixes<'b>(&self, prefix: &Prefix, suffixes: impl Iterator<Item = &'b Suffix>) -> Value {
+        let start = full_range(prefix);
+
+        let mut base = match prefix {
+            Prefix::Expression(expression) => self.expression(expression),
+            Prefix::Name(name) => self.identifier(name),
+        };
+
+        for suffix in suffixes {
+            let suffix_range = join(start, full_range(suffix));
+
+            base = match suffix {
+                Suffix::Index(Index::Brackets { expression, .. }) => self.node(
+                    "IndexExpression",
+                    suffix_range,
+                    json!({ "base": base, "index": self.expression(expression) }),
+                ),
+
+                Suffix::Index(Index::Dot { name, .. }) => self.node(
+                    "MemberExpression",
+                    suffix_range,
+                    json!({
+                        "indexer": ".",
+                        "identifier": self.identifier(name),
+                        "base": base,
+                    }),
+                ),
+
+                Suffix::Call(Call::AnonymousCall(args)) => self.call(suffix_range, base, args),
+
+                Suffix::Call(Call::MethodCall(method_call)) => {
+                    let method = self.node(
+                        "MemberExpression",
+                        join(start, full_range(method_call.name())),
+                        json!({
+                            "indexer": ":",
+                            "identifier": self.identifier(method_call.name()),
+                            "base": base,
+                        }),
+                    );
+
+                    self.call(suffix_range, method, method_call.args())
+                }
+            };
+        }
+
+        base
+    }
+
+    fn call(&self, range: Option<(Position, Position)>, base: Value, args: &FunctionArgs) -> Value {
+        match args {
+            FunctionArgs::Parentheses { arguments, .. } => self.node(
+                "CallExpression",
+                range,
+                json!({ "base": base, "arguments": self.expressions(arguments) }),
+            ),
+
+            FunctionArgs::String(string) => self.node(
+                "StringCallExpression",
+                range,
+                json!({ "base": base, "argument": self.string(string) }),
+            ),
+
+            FunctionArgs::TableConstructor(table_constructor) => self.node(
+                "TableCallExpression",
+                range,
+                json!({ "base": base, "arguments": self.table_constructor(table_constructor) }),
+            ),
+        }
+    }
+
+    fn string(&self, token: &TokenReference) -> Value {
+        let value = match token.token_type() {
+            TokenType::StringLiteral {
+                literal,
+                quote_type,
+                ..
+            } => string_value(literal, *quote_type),
+            _ => Value::Null,
+        };
+
+        self.node(
+            "StringLiteral",
+            full_range(token),
+            json!({ "value": value, "raw": token.token().to_string() }),
+        )
+    }
+
+    fn table_constructor(&self, table_constructor: &TableConstructor) -> Value {
+        let fields: Vec<_> = table_constructor
+            .fields()
+            .iter()
+            .map(|field| match field {
+                Field::ExpressionKey { key, value, .. } => self.node(
+                    "TableKey",
+                    full_range(field),
+                    json!({ "key": self.expression(key), "value": self.expression(value) }),
+                ),
+
+                Field::NameKey { key, value, .. } => self.node(
+                    "TableKeyString",
+                    full_range(field),
+                    json!({ "key": self.identifier(key), "value": self.expression(value) }),
+                ),
+
+                Field::NoKey(value) => self.node(
+                    "TableValue",
+                    full_range(field),
+                    json!({ "value": self.expression(value) }),
+                ),
+
+                #[cfg(feature = "cfxlua")]
+                Field::SetConstructor { name, .. } => self.node(
+                    "CfxLuaTableSetConstructor",
+                    full_range(field),
+                    json!({ "key": self.identifier(name) }),
+                ),
+            })
+            .collect();
+
+        self.node(
+            "TableConstructorExpression",
+            full_range(table_constructor),
+            json!({ "fields": fields }),
+        )
+    }
+
+    fn expression(&self, expression: &Expression) -> Value {
+        let expression_range = full_range(expression);
+
+        match expression {
+            Expression::BinaryOperator { lhs, binop, rhs } => {
+                let operator = binop.token().token().to_string();
+                let kind = if matches!(binop, BinOp::And(_) | BinOp::Or(_)) {
+                    "LogicalExpression"
+                } else {
+                    "BinaryExpression"
+                };
+
+                self.node(
+                    kind,
+                    expression_range,
+                    json!({
+                        "operator": operator,
+                        "left": self.expression(lhs),
+                        "right": self.expression(rhs),
+                    }),
+                )
+            }
+
+            Expression::Parentheses { expression, .. } => {
+                let mut value = self.expression(expression);
+                value["inParens"] = true.into();
+                value
+            }
+
+            Expression::UnaryOperator { unop, expression } => self.node(
+                "UnaryExpression",
+                expression_range,
+                json!({
+                    "operator": unop.token().token().to_string(),
+                    "argument": self.expression(expression),
+                }),
+            ),
+
+            Expression::Function(function) => {
+                self.function(expression_range, Value::Null, false, &function.1)
+            }
+
+            Expression::FunctionCall(function_call) => {
+                self.suffixes(function_call.prefix(), function_call.suffixes())
+            }
+
+            #[cfg(feature = "luau")]
+            Expression::IfExpression(if_expression) => self.if_expression(if_expression),
+
+            #[cfg(feature = "luau")]
+            Expression::InterpolatedString(interpolated_string) => {
+                self.interpolated_string(interpolated_string)
+            }
+
+            Expression::TableConstructor(table_constructor) => {
+                self.table_constructor(table_constructor)
+            }
+
+            Expression::Number(token) => {
+                let raw = token.token().to_string();
+
+                self.node(
+                    "NumericLiteral",
+                    expression_range,
+                    json!({ "value": number_value(&raw), "raw": raw }),
+                )
+            }
+
+            Expression::String(token) => self.string(token),
+
+            Expression::Symbol(token) => match token.token_type() {
+                TokenType::Symbol {
+                    symbol: Symbol::True,
+                } => self.node(
+                    "BooleanLiteral",
+                    expression_range,
+                    json!({ "value": true, "raw": "true" }),
+                ),
+
+                TokenType::Symbol {
+                    symbol: Symbol::False,
+                } => self.node(
+                    "BooleanLiteral",
+                    expression_range,
+                    json!({ "value": false, "raw": "false" }),
+                ),
+
+                TokenType::Symbol {
+                    symbol: Symbol::Ellipsis,
+                } => self.vararg(token),
+
+                _ => self.node(
+                    "NilLiteral",
+                    expression_range,
+                    json!({ "value": null, "raw": "nil" }),
+                ),
+            },
+
+            #[cfg(feature = "luau")]
+            Expression::TypeAssertion {
+                expression,
+                type_assertion,
+            } => self.node(
+                "LuauTypeAssertion",
+                expression_range,
+                json!({
+                    "expression": self.expression(expression),
+                    "typeAnnotation": self.luau_type(type_assertion.cast_to()),
+                }),
+            ),
+
+            Expression::Var(var) => self.var(var),
+
+            Expression::Error(tokens) => self.error(tokens),
+        }
+    }
+
+    // Like `if` statements, but with `elseif` as another `LuauIfExpression` in the `alternate`
+    #[cfg(feature = "luau")]
+    fn if_expression(&self, if_expression: &IfExpression) -> Value {
+        let else_expression = if_expression.else_expression();
+        let mut alternate = self.expression(else_expression);
+
+        for else_if in if_expression
+            .else_if_expressions()
+            .into_iter()
+            .flatten()
+            .rev()
+        {
+            alternate = self.node(
+                "LuauIfExpression",
+                join(full_range(else_if), full_range(else_expression)),
+                json!({
+                    "condition": self.expression(else_if.condition()),
+                    "consequent": self.expression(else_if.expression()),
+                    "alternate": alternate,
+                }),
+            );
+        }
+
+        self.node(
+            "LuauIfExpression",
+            full_range(if_expression),
+            json!({
+                "condition": self.expression(if_expression.condition()),
+                "consequent": self.expression(if_expression.if_expression()),
+                "alternate": alternate,
+            }),
+        )
+    }
+
+    // The literal parts of the string, around the expressions between them
+    #[cfg(feature = "luau")]
+    fn interpolated_string(&self, interpolated_string: &InterpolatedString) -> Value {
+        let literals: Vec<_> = interpolated_string
+            .segments()
+            .map(|segment| &segment.literal)
+            .chain(std::iter::once(interpolated_string.last_string()))
+            .map(|token| match token.token_type() {
+                TokenType::InterpolatedString { literal, .. } => {
+                    string_value(literal, StringLiteralQuoteType::Double)
+                }
+                _ => Value::Null,
+            })
+            .collect();
+
+        let expressions: Vec<_> = interpolated_string
+            .expressions()
+            .map(|expression| self.expression(expression))
+            .collect();
+
+        self.node(
+            "LuauInterpolatedString",
+            full_range(interpolated_string),
+            json!({
+                "literals": literals,
+                "expressions": expressions,
+                "raw": self.raw(interpolated_string),
+            }),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse;
+    use pretty_assertions::assert_eq;
+
+    fn export(code: &str, options: &EstreeOptions) -> Value {
+        to_json(&parse(code).unwrap(), options)
+    }
+
+    #[test]
+    fn test_statements() {
+        let options = EstreeOptions::new().with_comments(false);
+
+        assert_eq!(
+            export("function a.b:c(x, ...) return x end", &options)["body"][0],
+            json!({
+                "type": "FunctionDeclaration",
+                "identifier": {
+                    "type": "MemberExpression",
+                    "indexer": ":",
+                    "identifier": { "type": "Identifier", "name": "c" },
+                    "base": {
+                        "type": "MemberExpression",
+                        "indexer": ".",
+                        "identifier": { "type": "Identifier", "name": "b" },
+                        "base": { "type": "Identifier", "name": "a" },
+                    },
+                },
+                "isLocal": false,
+                "parameters": [
+                    { "type": "Identifier", "name": "x" },
+                    { "type": "VarargLiteral", "value": "...", "raw": "..." },
+                ],
+                "body": [{
+                    "type": "ReturnStatement",
+                    "arguments": [{ "type": "Identifier", "name": "x" }],
+                }],
+            })
+        );
+
+        assert_eq!(
+            export("if a then elseif b then else end", &options)["body"][0]["clauses"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|clause| clause["type"].as_str().unwrap())
+                .collect::<Vec<_>>(),
+            ["IfClause", "ElseifClause", "ElseClause"]
+        );
+
+        assert_eq!(
+            export("x:y 'z' { 1 }", &options)["body"][0],
+            json!({
+                "type": "CallStatement",
+                "expression": {
+                    "type": "TableCallExpression",
+                    "base": {
+                        "type": "StringCallExpression",
+                        "base": {
+                            "type": "MemberExpression",
+                            "indexer": ":",
+                            "identifier": { "type": "Identifier", "name": "y" },
+                            "base": { "type": "Identifier", "name": "x" },
+                        },
+                        "argument": { "type": "StringLiteral", "value": "z", "raw": "'z'" },
+                    },
+                    "arguments": {
+                        "type": "TableConstructorExpression",
+                        "fields": [{
+                            "type": "TableValue",
+                            "value": { "type": "NumericLiteral", "value": 1, "raw": "1" },
+                        }],
+                    },
+                },
+            })
+        );
+    }
+
+    #[test]
+    fn test_literals() {
+        let init = |code: &str| {
+            export(&format!("local _ = {code}"), &EstreeOptions::new())["body"][0]["init"][0]
+                .clone()
+        };
+
+        assert_eq!(init("0x10")["value"], json!(16));
+        assert_eq!(init("0xa")["value"], json!(10));
+        assert_eq!(init("1.5e1")["value"], json!(15));
+        assert_eq!(init(".25")["value"], json!(0.25));
+        assert_eq!(init(r#""a\n\65""#)["value"], json!("a\nA"));
+        assert_eq!(init(r#""\x41""#)["value"], Value::Null);
+        assert_eq!(init("[[\nline]]")["value"], json!("line"));
+        assert_eq!(init("a and not b")["type"], json!("LogicalExpression"));
+        assert_eq!(init("a .. b")["type"], json!("BinaryExpression"));
+        assert_eq!(init("(nil)")["inParens"], json!(true));
+    }
+
+    #[test]
+    fn test_positions() {
+        let options = EstreeOptions::new().with_locations(true).with_ranges(true);
+
+        // `é` is one code unit in UTF-16, but two bytes
+        let chunk = export("local s = 'é'\n  --[[ x ]] f()", &options);
+        let call = &chunk["body"][1]["expression"];
+
+        assert_eq!(call["range"], json!([26, 29]));
+        assert_eq!(
+            call["loc"],
+            json!({
+                "start": { "line": 2, "column": 12 },
+                "end": { "line": 2, "column": 15 },
+            })
+        );
+
+        assert_eq!(chunk["body"][0]["init"][0]["range"], json!([10, 13]));
+        assert_eq!(chunk["range"], json!([0, 29]));
+        assert_eq!(
+            chunk["comments"],
+            json!([{
+                "type": "Comment",
+                "value": " x ",
+                "raw": "--[[ x ]]",
+                "range": [16, 25],
+                "loc": {
+                    "start": { "line": 2, "column": 2 },
+                    "end": { "line": 2, "column": 11 },
+                },
+            }])
+        );
+    }
+
+    #[cfg(feature = "luau")]
+    #[test]
+    fn test_luau() {
+        let options = EstreeOptions::new().with_comments(false);
+        let body = export(
+            "export type T<U> = { U }\nlocal x: number = if a then `{b}!` else c :: T<number>",
+            &options,
+        )["body"]
+            .clone();
+
+        assert_eq!(
+            body,
+            json!([
+                {
+                    "type": "LuauTypeDeclaration",
+                    "name": { "type": "Identifier", "name": "T" },
+                    "exported": true,
+                    "generics": { "type": "LuauGenerics", "raw": "<U>" },
+                    "value": { "type": "LuauType", "raw": "{ U }" },
+                },
+                {
+                    "type": "LocalStatement",
+                    "variables": [{
+                        "type": "Identifier",
+                        "name": "x",
+                        "luau": { "typeAnnotation": { "type": "LuauType", "raw": "number" } },
+                    }],
+                    "init": [{
+                        "type": "LuauIfExpression",
+                        "condition": { "type": "Identifier", "name": "a" },
+                        "consequent": {
+                            "type": "LuauInterpolatedString",
+                            "literals": ["", "!"],
+                            "expressions": [{ "type": "Identifier", "name": "b" }],
+                            "raw": "`{b}!`",
+                        },
+                        "alternate": {
+                            "type": "LuauTypeAssertion",
+                            "expression": { "type": "Identifier", "name": "c" },
+                            "typeAnnotation": { "type": "LuauType", "raw": "T<number>" },
+                        },
+                    }],
+                },
+            ])
+        );
+    }
+}
@@ -0,0 +1,1354 @@
+use std::{collections::HashMap, mem, ops::Range};
+
+use crate::{
+    ast::{punctuated::Punctuated, separate_tokens::separator, span::ContainedSpan, *},
+    node::Node,
+    tokenizer::{StringLiteralQuoteType, Symbol, Token, TokenKind, TokenReference, TokenType},
+    visitors::{Visit, VisitorRef},
+};
+
+#[cfg(any(feature = "lua52", feature = "luajit"))]
+use crate::ast::lua52::Label;
+#[cfg(feature = "lua54")]
+use crate::ast::lua54::Attribute;
+#[cfg(feature = "luau")]
+use crate::{
+    ast::luau::{GenericParameterInfo, TypeInfo},
+    tokenizer::InterpolatedStringKind,
+};
+
+/// How blocks and broken up lists are indented
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum IndentStyle {
+    /// One tab per level, counted as 4 columns wide for the line width
+    Tabs,
+    /// The given number of spaces per level
+    Spaces(usize),
+}
+
+/// Which quotes strings are written with. Strings in brackets, such as `[[string]]`, are left alone.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum QuoteStyle {
+    /// Double quotes, unless the string has more double quotes than single quotes in it
+    PreferDouble,
+    /// Single quotes, unless the string has more single quotes than double quotes in it
+    PreferSingle,
+    /// The quotes the string was written with
+    Keep,
+}
+
+/// When the parentheses around the arguments of a call are written, for calls such as `f "string"`
+/// and `f { table }` that can leave them out.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CallParentheses {
+    /// Parentheses are always written: `f("string")` and `f({ table })`
+    Always,
+    /// Parentheses are left out when the only argument is a string: `f "string"` and `f({ table })`
+    NoSingleString,
+    /// Parentheses are left out when the only argument is a table: `f("string")` and `f { table }`
+    NoSingleTable,
+    /// Parentheses are left out when the only argument is a string or a table
+    None,
+    /// Parentheses are written where they were
+    Keep,
+}
+
+/// When a separator is written after the last field of a table, as in `{ 1, 2, }`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TrailingSeparator {
+    /// Only when the table is broken over multiple lines
+    Multiline,
+    /// Always, even when the table fits on one line
+    Always,
+    /// Never
+    Never,
+    /// Only where there was one
+    Keep,
+}
+
+/// Options for [`format`], such as the indentation and line width to format with.
+///
+/// ```rust
+/// # use full_moon::format::{self, FormatOptions, IndentStyle, QuoteStyle};
+/// # fn main() -> Result<(), Vec<full_moon::Error>> {
+/// let ast = full_moon::parse("if x then print( {'a','b'} ) end")?;
+/// let options = FormatOptions::new()
+///     .with_indent_style(IndentStyle::Spaces(2))
+///     .with_quote_style(QuoteStyle::PreferSingle);
+///
+/// assert_eq!(
+///     format::format(&ast, &options),
+///     "if x then\n  print({ 'a', 'b' })\nend\n"
+/// );
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct FormatOptions {
+    indent_style: IndentStyle,
+    line_width: usize,
+    quote_style: QuoteStyle,
+    call_parentheses: CallParentheses,
+    trailing_separator: TrailingSeparator,
+    range: Option<Range<usize>>,
+}
+
+impl FormatOptions {
+    /// The line width used by [`FormatOptions::new`].
+    pub const DEFAULT_LINE_WIDTH: usize = 120;
+
+    /// Creates options for formatting with tabs, a line width of
+    /// [`DEFAULT_LINE_WIDTH`](Self::DEFAULT_LINE_WIDTH), double quotes, parentheses around every
+    /// call's arguments, and trailing separators in tables broken over multiple lines.
+    pub fn new() -> Self {
+        Self {
+            indent_style: IndentStyle::Tabs,
+            line_width: Self::DEFAULT_LINE_WIDTH,
+            quote_style: QuoteStyle::PreferDouble,
+            call_parentheses: CallParentheses::Always,
+            trailing_separator: TrailingSeparator::Multiline,
+            range: None,
+        }
+    }
+
+    /// How blocks and broken up lists are indented
+    pub fn indent_style(&self) -> IndentStyle {
+        self.indent_style
+    }
+
+    /// How many columns wide lines can be before lists, such as the fields of a table or the
+    /// arguments of a call, are broken over multiple lines. Lines that can't be broken, such as a
+    /// long expression, can still be wider.
+    pub fn line_width(&self) -> usize {
+        self.line_width
+    }
+
+    /// Which quotes strings are written with
+    pub fn quote_style(&self) -> QuoteStyle {
+        self.quote_style
+    }
+
+    /// When the parentheses around the arguments of a call are written
+    pub fn call_parentheses(&self) -> CallParentheses {
+        self.call_parentheses
+    }
+
+    /// When a separator is written after the last field of a table
+    pub fn trailing_separator(&self) -> TrailingSeparator {
+        self.trailing_separator
+    }
+
+    /// The byte range of the code that is formatted, if only part of it is. Statements outside of
+    /// the range are printed as they were.
+    pub fn range(&self) -> Option<&Range<usize>> {
+        self.range.as_ref()
+    }
+
+    /// Returns a new FormatOptions with the given indent style
+    pub fn with_indent_style(self, indent_style: IndentStyle) -> Self {
+        Self {
+            indent_style,
+            ..self
+        }
+    }
+
+    /// Returns a new FormatOptions with the given line width
+    pub fn with_line_width(self, line_width: usize) -> Self {
+        Self { line_width, ..self }
+    }
+
+    /// Returns a new FormatOptions with the given quote style
+    pub fn with_quote_style(self, quote_style: QuoteStyle) -> Self {
+        Self {
+            quote_style,
+            ..self
+        }
+    }
+
+    /// Returns a new FormatOptions with the given call parentheses
+    pub fn with_call_parentheses(self, call_parentheses: CallParentheses) -> Self {
+        Self {
+            call_parentheses,
+            ..self
+        }
+    }
+
+    /// Returns a new FormatOptions with the given trailing separators
+    pub fn with_trailing_separator(self, trailing_separator: TrailingSeparator) -> Self {
+        Self {
+            trailing_separator,
+            ..self
+        }
+    }
+
+    /// Returns a new FormatOptions that only formats statements in the given byte range
+    pub fn with_range(self, range: Range<usize>) -> Self {
+        Self {
+            range: Some(range),
+            ..self
+        }
+    }
+}
+
+impl Default for FormatOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Prints the Ast formatted with the given options.
+///
+/// Every statement is put on its own line, with blocks indented and a blank line kept wherever
+/// there was at least one. Tables, call arguments, and function parameters are kept on one line
+/// if they fit in the [line width](FormatOptions::line_width), and are otherwise broken up with
+/// one item per line. Comments are kept where they were.
+///
+/// A statement with a `-- fmt: skip` comment before it is printed as it was, as is every statement
+/// outside of the [range](FormatOptions::range) being formatted. Formatting code that has already
+/// been formatted with the same options doesn't change it.
+///
+/// ```rust
+/// # use full_moon::format::{self, FormatOptions};
+/// # fn main() -> Result<(), Vec<full_moon::Error>> {
+/// let ast = full_moon::parse("local x={1,2}  -- numbers\n-- fmt: skip\nlocal  y  =  3")?;
+///
+/// assert_eq!(
+///     format::format(&ast, &FormatOptions::new()),
+///     "local x = { 1, 2 } -- numbers\n-- fmt: skip\nlocal  y  =  3\n"
+/// );
+/// # Ok(())
+/// # }
+/// ```
+pub fn format(ast: &Ast, options: &FormatOptions) -> String {
+    let mut formatter = Formatter::new(options);
+    ast.nodes().visit(&mut formatter);
+    formatter.trivia(ast.eof().leading_trivia());
+
+    Printer::new(options).print(&formatter.events)
+}
+
+// What the formatter decides to print, before the printer decides which groups fit on one line
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Event {
+    Text(String),
+    Comment { text: String, single_line: bool },
+    // Code that isn't formatted, printed as it is without being indented
+    Raw(String),
+    Space,
+    // New lines are only added if the line isn't already empty
+    Line,
+    BlankLine,
+    Indent,
+    Dedent,
+    // A list, such as the fields of a table, that is either all on one line or one item per line
+    GroupStart { spaced: bool, broken: bool },
+    GroupSeparator,
+    // Only printed when the group is broken, such as a trailing comma
+    IfBroken(String),
+    GroupEnd,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+enum Break {
+    None,
+    Line,
+    // Before a statement, which keeps a blank line before it if there was one
+    Statement,
+}
+
+// How a token is printed, decided by the node it's in
+#[derive(Clone, Copy, Debug, Default)]
+struct Role {
+    // Whether there's no space before or after the token, when it's not up to the tokens alone
+    tight_before: Option<bool>,
+    tight_after: Option<bool>,
+    opens_group: Option<bool>,
+    closes_group: bool,
+    separator: bool,
+    trailing_separator: bool,
+    missing_trailing_separator: bool,
+    // Parentheses added around call arguments, or the parentheses of a call that are left out
+    parenthesize_before: bool,
+    parenthesize_after: bool,
+    hidden: bool,
+}
+
+const OPEN_PARENTHESIS: Role = Role {
+    tight_before: Some(true),
+    tight_after: Some(true),
+    opens_group: None,
+    closes_group: false,
+    separator: false,
+    trailing_separator: false,
+    missing_trailing_separator: false,
+    parenthesize_before: false,
+    parenthesize_after: false,
+    hidden: false,
+};
+
+const CLOSE_PARENTHESIS: Role = Role {
+    tight_after: None,
+    ..OPEN_PARENTHESIS
+};
+
+struct Formatter<'o> {
+    options: &'o FormatOptions,
+    events: Vec<Event>,
+    roles: HashMap<*const TokenReference, Role>,
+    // The statement being printed as it was, until it's done being visited
+    unformatted: Option<*const ()>,
+    block_depth: usize,
+    first_in_block: bool,
+    dedents: usize,
+    break_before: Break,
+    // New lines in the trivia since the last thing printed
+    newlines: usize,
+    after_comment: bool,
+    previous: Option<(Token, Role)>,
+    // Groups and separators are started at the next token, so that comments after them stay put
+    pending_group: Option<(bool, bool)>,
+    pending_separator: bool,
+}
+
+impl<'o> Formatter<'o> {
+    fn new(options: &'o FormatOptions) -> Self {
+        Self {
+            options,
+            events: Vec::new(),
+            roles: HashMap::new(),
+            unformatted: None,
+            block_depth: 0,
+            first_in_block: false,
+            dedents: 0,
+            break_before: Break::None,
+            newlines: 0,
+            after_comment: false,
+            previous: None,
+            pending_group: None,
+            pending_separator: false,
+        }
+    }
+
+    fn role_mut(&mut self, token: &TokenReference) -> &mut Role {
+        self.roles.entry(token as *const _).or_default()
+    }
+
+    fn group(&mut self, span: &ContainedSpan, spaced: bool) {
+        // Groups put spaces inside themselves when they aren't broken up
+        let (start, end) = span.tokens();
+        let start = self.role_mut(start);
+        start.opens_group = Some(spaced);
+        start.tight_after = Some(true);
+
+        let end = self.role_mut(end);
+        end.closes_group = true;
+        end.tight_before = Some(true);
+    }
+
+    fn separators<T>(&mut self, punctuated: &Punctuated<T>) {
+        for pair in punctuated.pairs() {
+            if let Some(punctuation) = pair.punctuation() {
+                self.role_mut(punctuation).separator = true;
+            }
+        }
+    }
+
+    fn fields<T>(&mut self, braces: &ContainedSpan, fields: &Punctuated<T>) {
+        if fields.is_empty() {
+            return;
+        }
+
+        self.group(braces, true);
+        self.separators(fields);
+
+        match fields.last().and_then(|pair| pair.punctuation()) {
+            Some(punctuation) => {
+                let role = self.role_mut(punctuation);
+                role.separator = false;
+                role.trailing_separator = true;
+            }
+
+            None => self.role_mut(braces.tokens().1).missing_trailing_separator = true,
+        }
+    }
+
+    fn is_unformatted(&self, node: &impl Node) -> bool {
+        let skipped = node.tokens().next().is_some_and(|token| {
+            token
+                .leading_trivia()
+                .any(|trivia| match trivia.token_type() {
+                    TokenType::SingleLineComment { comment }
+                    | TokenType::MultiLineComment { comment, .. } => comment.trim() == "fmt: skip",
+                    _ => false,
+                })
+        });
+
+        let outside_range = match (&self.options.range, node.range()) {
+            (Some(range), Some((start, end))) => {
+                end.bytes() <= range.start || start.bytes() >= range.end
+            }
+            _ => false,
+        };
+
+        skipped || outside_range
+    }
+
+    fn start_statement(&mut self, node: &(impl Node + std::fmt::Display), unformatted: bool) {
+        self.break_before = Break::Statement;
+
+        if unformatted || self.is_unformatted(node) {
+            let code = node.to_string();
+
+            self.flush(true);
+            self.events.push(Event::Line);
+            self.break_before = Break::None;
+            self.newlines = code.len() - code.trim_end_matches('\n').len();
+            self.after_comment = false;
+            self.previous = None;
+            self.first_in_block = false;
+            self.events.push(Event::Raw(code));
+            self.unformatted = Some(node as *const _ as *const ());
+        }
+    }
+
+    fn end_statement(&mut self, node: &impl Node) {
+        if self.unformatted == Some(node as *const _ as *const ()) {
+            self.unformatted = None;
+        }
+    }
+
+    fn flush(&mut self, broken: bool) {
+        if let Some((spaced, forced)) = self.pending_group.take() {
+            self.events.push(Event::GroupStart {
+                spaced,
+                broken: broken || forced,
+            });
+        }
+
+        if mem::take(&mut self.pending_separator) {
+            self.events.push(Event::GroupSeparator);
+        }
+    }
+
+    fn line_before(&self) -> Option<Event> {
+        let blank = self.newlines >= 2 && !self.first_in_block;
+
+        match self.break_before {
+            Break::Statement if blank => Some(Event::BlankLine),
+            Break::Statement | Break::Line => Some(Event::Line),
+            Break::None => (self.after_comment && self.newlines > 0).then_some(Event::Line),
+        }
+    }
+
+    fn trivia<'a>(&mut self, trivia: impl Iterator<Item = &'a Token>) {
+        for trivia in trivia {
+            match trivia.token_type() {
+                TokenType::Whitespace { characters } => {
+                    self.newlines += characters.matches('\n').count();
+                }
+
+                token_type
+                    if token_type.is_comment() || token_type.kind() == TokenKind::Shebang =>
+                {
+                    self.comment(trivia)
+                }
+
+                _ => {}
+            }
+        }
+    }
+
+    fn comment(&mut self, comment: &Token) {
+        let single_line = matches!(
+            comment.token_kind(),
+            TokenKind::SingleLineComment | TokenKind::Shebang
+        );
+
+        let own_line = self.newlines > 0 && !self.events.is_empty();
+
+        match self.line_before() {
+            Some(line) => {
+                self.flush(true);
+                self.events.push(line);
+            }
+
+            None if own_line => {
+                self.flush(true);
+                self.events.push(Event::Line);
+            }
+
+            None => {
+                if let Some((_, forced)) = &mut self.pending_group {
+                    *forced |= single_line;
+                }
+
+                self.events.push(Event::Space);
+            }
+        }
+
+        self.events.push(Event::Comment {
+            text: comment.to_string(),
+            single_line,
+        });
+
+        if single_line {
+            self.break_before = self.break_before.max(Break::Line);
+        }
+
+        self.newlines = 0;
+        self.after_comment = true;
+        self.first_in_block = false;
+    }
+
+    fn token(&mut self, token: &TokenReference) {
+        if self.unformatted.is_some() || token.is_phantom() {
+            return;
+        }
+
+        let role = self
+            .roles
+            .get(&(token as *const _))
+            .copied()
+            .unwrap_or_default();
+
+        self.trivia(token.leading_trivia());
+
+        for _ in 0..mem::take(&mut self.dedents) {
+            self.events.push(Event::Dedent);
+        }
+
+        if role.missing_trailing_separator {
+            self.add_trailing_separator();
+        }
+
+        if role.closes_group {
+            self.flush(false);
+            self.events.push(Event::GroupEnd);
+        }
+
+        if !role.hidden {
+            if role.parenthesize_before {
+                self.print(&symbol(Symbol::LeftParen), OPEN_PARENTHESIS);
+            }
+
+            if role.trailing_separator {
+                match self.options.trailing_separator {
+                    TrailingSeparator::Multiline => {
+                        self.flush(false);
+                        self.events.push(Event::IfBroken(token.token().to_string()));
+                    }
+
+                    TrailingSeparator::Always | TrailingSeparator::Keep => {
+                        self.print(token.token(), role)
+                    }
+
+                    TrailingSeparator::Never => {}
+                }
+            } else {
+                self.print(token.token(), role);
+            }
+
+            if role.parenthesize_after {
+                self.print(&symbol(Symbol::RightParen), CLOSE_PARENTHESIS);
+            }
+        }
+
+        if let Some(spaced) = role.opens_group {
+            self.pending_group = Some((spaced, false));
+        }
+
+        self.pending_separator |= role.separator;
+        self.trivia(token.trailing_trivia());
+    }
+
+    fn add_trailing_separator(&mut self) {
+        let separator = match self.options.trailing_separator {
+            TrailingSeparator::Multiline => Event::IfBroken(",".to_owned()),
+            TrailingSeparator::Always => Event::Text(",".to_owned()),
+            TrailingSeparator::Never | TrailingSeparator::Keep => return,
+        };
+
+        // The separator goes right after the last field, before any comments after it
+        let index = self
+            .events
+            .iter()
+            .rposition(|event| matches!(event, Event::Text(_)))
+            .map_or(0, |index| index + 1);
+
+        self.events.insert(index, separator);
+    }
+
+    fn print(&mut self, token: &Token, role: Role) {
+        self.flush(false);
+
+        match self.line_before() {
+            Some(line) => self.events.push(line),
+
+            None => {
+                if let Some((previous, previous_role)) = &self.previous {
+                    let tight = match (previous_role.tight_after, role.tight_before) {
+                        (Some(false), _) | (_, Some(false)) => false,
+                        (Some(true), _) | (_, Some(true)) => true,
+                        _ => tight_between(previous, token),
+                    };
+
+                    if self.after_comment || !tight || separator(previous, token).is_some() {
+                        self.events.push(Event::Space);
+                    }
+                } else if self.after_comment {
+                    self.events.push(Event::Space);
+                }
+            }
+        }
+
+        self.events.push(Event::Text(self.text(token)));
+        self.break_before = Break::None;
+        self.newlines = 0;
+        self.after_comment = false;
+        self.first_in_block = false;
+        self.previous = Some((token.clone(), role));
+    }
+
+    fn text(&self, token: &Token) -> String {
+        let TokenType::StringLiteral {
+            literal,
+            quote_type:
+                quote_type @ (StringLiteralQuoteType::Double | StringLiteralQuoteType::Single),
+            ..
+        } = token.token_type()
+        else {
+            return token.to_string();
+        };
+
+        let (preferred, other) = match self.options.quote_style {
+            QuoteStyle::PreferDouble => ('"', '\''),
+            QuoteStyle::PreferSingle => ('\'', '"'),
+            QuoteStyle::Keep => return token.to_string(),
+        };
+
+        let from = match quote_type {
+            StringLiteralQuoteType::Double => '"',
+            _ => '\'',
+        };
+
+        let to = if literal.matches(preferred).count() > literal.matches(other).count() {
+            other
+        } else {
+            preferred
+        };
+
+        if from == to {
+            return token.to_string();
+        }
+
+        let mut text = String::with_capacity(literal.len() + 2);
+        let mut characters = literal.chars();
+        text.push(to);
+
+        while let Some(character) = characters.next() {
+            match character {
+                '\\' => match characters.next() {
+                    Some(escaped) if escaped == from => text.push(escaped),
+                    Some(escaped) => {
+                        text.push('\\');
+                        text.push(escaped);
+                    }
+                    None => text.push('\\'),
+                },
+
+                character if character == to => {
+                    text.push('\\');
+                    text.push(character);
+                }
+
+                character => text.push(character),
+            }
+        }
+
+        text.push(to);
+        text
+    }
+
+    fn call_arguments(&mut self, arguments: &FunctionArgs) {
+        // Whether the parentheses are left out around a single string or table
+        let omitted = |table: bool| match self.options.call_parentheses {
+            CallParentheses::Always => Some(false),
+            CallParentheses::NoSingleString => Some(!table),
+            CallParentheses::NoSingleTable => Some(table),
+            CallParentheses::None => Some(true),
+            CallParentheses::Keep => None,
+        };
+
+        match arguments {
+            FunctionArgs::Parentheses {
+                parentheses,
+                arguments,
+            } => {
+                let (start, end) = parentheses.tokens();
+                self.role_mut(start).tight_before = Some(true);
+
+                let single = match arguments.iter().next() {
+                    Some(Expression::String(_)) if arguments.len() == 1 => Some(false),
+                    Some(Expression::TableConstructor(_)) if arguments.len() == 1 => Some(true),
+                    _ => None,
+                };
+
+                let has_comments = |token: &TokenReference| {
+                    token
+                        .leading_trivia()
+                        .chain(token.trailing_trivia())
+                        .any(|trivia| trivia.token_type().is_comment())
+                };
+
+                if let Some(table) = single {
+                    if omitted(table) == Some(true) && !has_comments(start) && !has_comments(end) {
+                        self.role_mut(start).hidden = true;
+                        self.role_mut(end).hidden = true;
+                        return;
+                    }
+                }
+
+                // A lone table or function keeps its parentheses around it, as in `f({`
+                let hugged = arguments.len() == 1
+                    && matches!(
+                        arguments.iter().next(),
+                        Some(Expression::TableConstructor(_) | Expression::Function(_))
+                    );
+
+                if !hugged {
+                    self.group(parentheses, false);
+                    self.separators(arguments);
+                }
+            }
+
+            FunctionArgs::String(string) => {
+                if omitted(false) == Some(false) {
+                    let role = self.role_mut(string);
+                    role.parenthesize_before = true;
+                    role.parenthesize_after = true;
+                }
+            }
+
+            FunctionArgs::TableConstructor(table) => {
+                if omitted(true) == Some(false) {
+                    let (start, end) = table.braces().tokens();
+                    self.role_mut(start).parenthesize_before = true;
+                    self.role_mut(end).parenthesize_after = true;
+                }
+            }
+        }
+    }
+}
+
+impl<'a> VisitorRef<'a> for Formatter<'_> {
+    fn visit_token_reference(&mut self, token: &'a TokenReference) {
+        self.token(token);
+    }
+
+    fn visit_block(&mut self, _block: &'a Block) {
+        if self.unformatted.is_some() {
+            return;
+        }
+
+        if self.block_depth > 0 {
+            self.events.push(Event::Indent);
+        }
+
+        self.block_depth += 1;
+        self.first_in_block = true;
+    }
+
+    fn visit_block_end(&mut self, block: &'a Block) {
+        if self.unformatted.is_some() {
+            return;
+        }
+
+        self.block_depth -= 1;
+
+        // Dedenting waits for the token after the block, so comments at the end of it are indented
+        if self.block_depth > 0 {
+            self.dedents += 1;
+        }
+
+        let empty = block.stmts().next().is_none() && block.last_stmt().is_none();
+
+        self.break_before = self.break_before.max(match (empty, self.block_depth) {
+            (true, _) => Break::None,
+            (false, 0) => Break::Statement,
+            (false, _) => Break::Line,
+        });
+    }
+
+    fn visit_stmt(&mut self, stmt: &'a Stmt) {
+        if self.unformatted.is_none() {
+            self.start_statement(stmt, matches!(stmt, Stmt::Error(_)));
+        }
+    }
+
+    fn visit_stmt_end(&mut self, stmt: &'a Stmt) {
+        self.end_statement(stmt);
+    }
+
+    fn visit_last_stmt(&mut self, last_stmt: &'a LastStmt) {
+        if self.unformatted.is_none() {
+            self.start_statement(last_stmt, false);
+        }
+    }
+
+    fn visit_last_stmt_end(&mut self, last_stmt: &'a LastStmt) {
+        self.end_statement(last_stmt);
+    }
+
+    fn visit_expression(&mut self, expression: &'a Expression) {
+        if let Expression::BinaryOperator { binop, .. } = expression {
+            let role = self.role_mut(binop.token());
+            role.tight_before = Some(false);
+            role.tight_after = Some(false);
+        }
+    }
+
+    fn visit_un_op(&mut self, unop: &'a UnOp) {
+        if !matches!(unop, UnOp::Not(_)) {
+            self.role_mut(unop.token()).tight_after = Some(true);
+        }
+    }
+
+    fn visit_function_args(&mut self, arguments: &'a FunctionArgs) {
+        self.call_arguments(arguments);
+    }
+
+    fn visit_function_body(&mut self, body: &'a FunctionBody) {
+        let parentheses = body.parameters_parentheses();
+        self.role_mut(parentheses.tokens().0).tight_before = Some(true);
+        self.group(parentheses, false);
+        self.separators(body.parameters());
+    }
+
+    fn visit_table_constructor(&mut self, table: &'a TableConstructor) {
+        self.fields(table.braces(), table.fields());
+    }
+
+    fn visit_method_call(&mut self, method_call: &'a MethodCall) {
+        let role = self.role_mut(method_call.colon_token());
+        role.tight_before = Some(true);
+        role.tight_after = Some(true);
+    }
+
+    fn visit_function_name(&mut self, name: &'a FunctionName) {
+        if let Some(colon) = name.method_colon() {
+            let role = self.role_mut(colon);
+            role.tight_before = Some(true);
+            role.tight_after = Some(true);
+        }
+    }
+
+    fn visit_index(&mut self, index: &'a Index) {
+        if let Index::Brackets { brackets, .. } = index {
+            let (start, end) = brackets.tokens();
+            *self.role_mut(start) = OPEN_PARENTHESIS;
+            self.role_mut(end).tight_before = Some(true);
+        }
+    }
+
+    #[cfg(feature = "luau")]
+    fn visit_type_info(&mut self, type_info: &'a TypeInfo) {
+        match type_info {
+            TypeInfo::Table { braces, fields } => self.fields(braces, fields),
+
+            TypeInfo::Typeof { parentheses, .. } => {
+                self.role_mut(parentheses.tokens().0).tight_before = Some(true);
+            }
+
+            TypeInfo::Callback {
+                generics: Some(_),
+                parentheses,
+                ..
+            } => {
+                self.role_mut(parentheses.tokens().0).tight_before = Some(true);
+            }
+
+            TypeInfo::Variadic { ellipsis, .. } | TypeInfo::VariadicPack { ellipsis, .. } => {
+                self.role_mut(ellipsis).tight_after = Some(true);
+            }
+
+            TypeInfo::GenericPack { ellipsis, .. } => {
+                self.role_mut(ellipsis).tight_before = Some(true);
+            }
+
+            _ => {}
+        }
+    }
+
+    #[cfg(feature = "luau")]
+    fn visit_generic_parameter_info(&mut self, parameter: &'a GenericParameterInfo) {
+        if let GenericParameterInfo::Variadic { ellipsis, .. } = parameter {
+            self.role_mut(ellipsis).tight_before = Some(true);
+        }
+    }
+
+    #[cfg(any(feature = "lua52", feature = "luajit"))]
+    fn visit_label(&mut self, label: &'a Label) {
+        self.role_mut(label.left_colons()).tight_after = Some(true);
+        self.role_mut(label.right_colons()).tight_before = Some(true);
+    }
+
+    #[cfg(feature = "lua54")]
+    fn visit_attribute(&mut self, attribute: &'a Attribute) {
+        self.role_mut(attribute.brackets().tokens().0).tight_before = Some(false);
+    }
+}
+
+// Whether two tokens are printed without a space between them when the nodes they're in don't say
+fn tight_between(previous: &Token, next: &Token) -> bool {
+    #[cfg(feature = "luau")]
+    if let TokenType::InterpolatedString { kind, .. } = previous.token_type() {
+        if matches!(
+            kind,
+            InterpolatedStringKind::Begin | InterpolatedStringKind::Middle
+        ) {
+            return true;
+        }
+    }
+
+    #[cfg(feature = "luau")]
+    if let TokenType::InterpolatedString { kind, .. } = next.token_type() {
+        if matches!(
+            kind,
+            InterpolatedStringKind::Middle | InterpolatedStringKind::End
+        ) {
+            return true;
+        }
+    }
+
+    let symbol = |token: &Token| match token.token_type() {
+        TokenType::Symbol { symbol } => Some(*symbol),
+        _ => None,
+    };
+
+    // The `<` of generics, as in `Array<T>`, goes right after the name
+    let generics =
+        previous.token_kind() == TokenKind::Identifier && symbol(next) == Some(Symbol::LessThan);
+
+    let (previous, next) = (symbol(previous), symbol(next));
+
+    if previous == Some(Symbol::LeftBrace) && next == Some(Symbol::RightBrace) {
+        return true;
+    }
+
+    let tight_after = matches!(
+        previous,
+        Some(Symbol::LeftParen | Symbol::LeftBracket | Symbol::Dot | Symbol::LessThan)
+    );
+
+    let tight_before = matches!(
+        next,
+        Some(
+            Symbol::RightParen
+                | Symbol::RightBracket
+                | Symbol::Comma
+                | Symbol::Semicolon
+                | Symbol::Dot
+                | Symbol::Colon
+                | Symbol::GreaterThan
+        )
+    );
+
+    #[cfg(feature = "luau")]
+    let tight_before = tight_before || next == Some(Symbol::QuestionMark);
+
+    #[cfg(feature = "cfxlua")]
+    let (tight_after, tight_before) = (
+        tight_after || previous == Some(Symbol::QuestionMarkDot),
+        tight_before || next == Some(Symbol::QuestionMarkDot),
+    );
+
+    generics || tight_after || tight_before
+}
+
+fn symbol(symbol: Symbol) -> Token {
+    Token::new(TokenType::Symbol { symbol })
+}
+
+// Prints the events, breaking every group that doesn't fit on one line
+struct Printer<'o> {
+    options: &'o FormatOptions,
+    output: String,
+    indent_level: usize,
+    column: usize,
+    // Whether each group being printed is broken and spaced
+    groups: Vec<(bool, bool)>,
+}
+
+impl<'o> Printer<'o> {
+    fn new(options: &'o FormatOptions) -> Self {
+        Self {
+            options,
+            output: String::new(),
+            indent_level: 0,
+            column: 0,
+            groups: Vec::new(),
+        }
+    }
+
+    fn print(mut self, events: &[Event]) -> String {
+        for (index, event) in events.iter().enumerate() {
+            match event {
+                Event::Text(text) | Event::Comment { text, .. } => self.text(text),
+
+                Event::Raw(code) => {
+                    self.output.push_str(code);
+                    self.advance(code);
+                }
+
+                Event::Space => {
+                    if !self.at_line_start() && !self.output.ends_with(' ') {
+                        self.output.push(' ');
+                        self.column += 1;
+                    }
+                }
+
+                Event::Line => self.line(),
+
+                Event::BlankLine => {
+                    self.line();
+
+                    if !self.output.is_empty() && !self.output.ends_with("\n\n") {
+                        self.output.push('\n');
+                    }
+                }
+
+                Event::Indent => self.indent_level += 1,
+                Event::Dedent => self.indent_level = self.indent_level.saturating_sub(1),
+
+                Event::GroupStart { spaced, broken } => {
+                    let empty = events.get(index + 1) == Some(&Event::GroupEnd);
+                    let broken = *broken || !(empty || self.fits(*spaced, &events[index + 1..]));
+                    self.groups.push((broken, *spaced));
+
+                    if broken {
+                        self.indent_level += 1;
+                        self.line();
+                    } else if *spaced && !empty {
+                        self.text(" ");
+                    }
+                }
+
+                Event::GroupSeparator => match self.groups.last() {
+                    Some((true, _)) => self.line(),
+                    _ => self.text(" "),
+                },
+
+                Event::IfBroken(text) => {
+                    if let Some((true, _)) = self.groups.last() {
+                        self.text(text);
+                    }
+                }
+
+                Event::GroupEnd => match self.groups.pop() {
+                    Some((true, _)) => {
+                        self.indent_level = self.indent_level.saturating_sub(1);
+                        self.line();
+                    }
+
+                    Some((false, true))
+                        if index > 0 && !matches!(events[index - 1], Event::GroupStart { .. }) =>
+                    {
+                        self.text(" ")
+                    }
+
+                    _ => {}
+                },
+            }
+        }
+
+        let length = self.output.trim_end().len();
+        self.output.truncate(length);
+
+        if !self.output.is_empty() {
+            self.output.push('\n');
+        }
+
+        self.output
+    }
+
+    fn at_line_start(&self) -> bool {
+        self.output.is_empty() || self.output.ends_with('\n')
+    }
+
+    fn indent_width(&self) -> usize {
+        match self.options.indent_style {
+            IndentStyle::Tabs => 4,
+            IndentStyle::Spaces(spaces) => spaces,
+        }
+    }
+
+    fn text(&mut self, text: &str) {
+        if self.at_line_start() {
+            if text == " " {
+                return;
+            }
+
+            for _ in 0..self.indent_level {
+                match self.options.indent_style {
+                    IndentStyle::Tabs => self.output.push('\t'),
+                    IndentStyle::Spaces(spaces) => self.output.extend((0..spaces).map(|_| ' ')),
+                }
+            }
+
+            self.column = self.indent_level * self.indent_width();
+        }
+
+        self.output.push_str(text);
+        self.advance(text);
+    }
+
+    fn advance(&mut self, text: &str) {
+        match text.rsplit_once('\n') {
+            Some((_, last_line)) => self.column = last_line.chars().count(),
+            None => self.column += text.chars().count(),
+        }
+    }
+
+    fn line(&mut self) {
+        if !self.at_line_start() {
+            let length = self.output.trim_end_matches([' ', '\t']).len();
+            self.output.truncate(length);
+            self.output.push('\n');
+            self.column = 0;
+        }
+    }
+
+    // Whether the group starting right before the events fits on the rest of the line. Only the
+    // first line of anything with new lines in it, such as a function, has to fit.
+    fn fits(&self, spaced: bool, events: &[Event]) -> bool {
+        let mut width = self.column;
+        // Spaces next to each other are printed as one, and only before something else
+        let mut space = spaced;
+        let mut groups = Vec::new();
+        let mut blocks = 0;
+        let mut closed = false;
+
+        for event in events {
+            match event {
+                Event::Text(text) | Event::Raw(text) | Event::Comment { text, .. } => {
+                    if let Event::Comment {
+                        single_line: true, ..
+                    } = event
+                    {
+                        if blocks == 0 {
+                            return false;
+                        }
+                    }
+
+                    width += usize::from(mem::take(&mut space));
+
+                    match text.split_once('\n') {
+                        Some((first_line, _)) => {
+                            return width + first_line.chars().count() <= self.options.line_width
+                        }
+                        None => width += text.chars().count(),
+                    }
+
+                    if closed {
+                        break;
+                    }
+                }
+
+                Event::Space | Event::GroupSeparator => space = true,
+                Event::IfBroken(_) => {}
+
+                // Tables with functions in them are always broken up
+                Event::Indent if spaced && groups.is_empty() => return false,
+                Event::Indent => blocks += 1,
+                Event::Dedent => blocks -= 1,
+
+                Event::Line | Event::BlankLine => break,
+
+                Event::GroupStart { spaced, broken } => {
+                    if *broken && blocks == 0 {
+                        return false;
+                    }
+
+                    groups.push(*spaced);
+                    space |= *spaced;
+                }
+
+                Event::GroupEnd => match groups.pop() {
+                    Some(spaced) => space |= spaced,
+                    None => {
+                        closed = true;
+                        space |= spaced;
+                    }
+                },
+            }
+
+            if width > self.options.line_width {
+                return false;
+            }
+        }
+
+        width <= self.options.line_width
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse;
+    use pretty_assertions::assert_eq;
+
+    fn format_with(code: &str, options: &FormatOptions) -> String {
+        let formatted = format(&parse(code).unwrap(), options);
+        assert_eq!(
+            format(&parse(&formatted).unwrap(), options),
+            formatted,
+            "formatting {code:?} again changed it"
+        );
+
+        formatted
+    }
+
+    #[test]
+    fn test_line_width() {
+        let code = "local t = {first, second, third}\ncall(first, second, third)";
+        let options = FormatOptions::new()
+            .with_indent_style(IndentStyle::Spaces(2))
+            .with_line_width(20);
+
+        assert_eq!(
+            format_with(code, &options),
+            "local t = {\n  first,\n  second,\n  third,\n}\ncall(\n  first,\n  second,\n  third\n)\n"
+        );
+
+        assert_eq!(
+            format_with(code, &options.with_line_width(40)),
+            "local t = { first, second, third }\ncall(first, second, third)\n"
+        );
+    }
+
+    #[test]
+    fn test_quote_style() {
+        let code = r#"x = 'a' .. "b" .. 'it\'s' .. "\"quoted\"" .. [[brackets]]"#;
+
+        assert_eq!(
+            format_with(code, &FormatOptions::new()),
+            "x = \"a\" .. \"b\" .. \"it's\" .. '\"quoted\"' .. [[brackets]]\n"
+        );
+
+        assert_eq!(
+            format_with(
+                code,
+                &FormatOptions::new().with_quote_style(QuoteStyle::PreferSingle)
+            ),
+            "x = 'a' .. 'b' .. \"it's\" .. '\"quoted\"' .. [[brackets]]\n"
+        );
+
+        assert_eq!(
+            format_with(
+                code,
+                &FormatOptions::new().with_quote_style(QuoteStyle::Keep)
+            ),
+            format!("{code}\n")
+        );
+    }
+
+    #[test]
+    fn test_call_parentheses() {
+        let code = "f('a') f {1} f'b' f{2} f(x, 'c')";
+
+        for (call_parentheses, expected) in [
+            (
+                CallParentheses::Always,
+                "f(\"a\")\nf({ 1 })\nf(\"b\")\nf({ 2 })\nf(x, \"c\")\n",
+            ),
+            (
+                CallParentheses::NoSingleString,
+                "f \"a\"\nf({ 1 })\nf \"b\"\nf({ 2 })\nf(x, \"c\")\n",
+            ),
+            (
+                CallParentheses::NoSingleTable,
+                "f(\"a\")\nf { 1 }\nf(\"b\")\nf { 2 }\nf(x, \"c\")\n",
+            ),
+            (
+                CallParentheses::None,
+                "f \"a\"\nf { 1 }\nf \"b\"\nf { 2 }\nf(x, \"c\")\n",
+            ),
+            (
+                CallParentheses::Keep,
+                "f(\"a\")\nf { 1 }\nf \"b\"\nf { 2 }\nf(x, \"c\")\n",
+            ),
+        ] {
+            let options = FormatOptions::new().with_call_parentheses(call_parentheses);
+            assert_eq!(
+                format_with(code, &options),
+                expected,
+                "{call_parentheses:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_trailing_separator() {
+        let code = "a = {1, 2,}\nb = {1, 2}\nc = {first, second}";
+        let options = FormatOptions::new().with_line_width(14);
+
+        for (trailing_separator, expected) in [
+            (
+                TrailingSeparator::Multiline,
+                "a = { 1, 2 }\nb = { 1, 2 }\nc = {\n\tfirst,\n\tsecond,\n}\n",
+            ),
+            (
+                TrailingSeparator::Always,
+                "a = { 1, 2, }\nb = { 1, 2, }\nc = {\n\tfirst,\n\tsecond,\n}\n",
+            ),
+            (
+                TrailingSeparator::Never,
+                "a = { 1, 2 }\nb = { 1, 2 }\nc = {\n\tfirst,\n\tsecond\n}\n",
+            ),
+            (
+                TrailingSeparator::Keep,
+                "a = { 1, 2, }\nb = { 1, 2 }\nc = {\n\tfirst,\n\tsecond\n}\n",
+            ),
+        ] {
+            let options = options.clone().with_trailing_separator(trailing_separator);
+            assert_eq!(
+                format_with(code, &options),
+                expected,
+                "{trailing_separator:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_comments() {
+        assert_eq!(
+            format_with(
+                "local t = { -- numbers\n1, --[[ one ]] 2 }\n\n\n-- end",
+                &FormatOptions::new()
+            ),
+            "local t = { -- numbers\n\t1, --[[ one ]]\n\t2,\n}\n\n-- end\n"
+        );
+
+        assert_eq!(
+            format_with("do\n-- nothing\nend", &FormatOptions::new()),
+            "do\n\t-- nothing\nend\n"
+        );
+    }
+
+    #[test]
+    fn test_unformatted() {
+        let code =
+            "local  a  =  1\n-- fmt: skip\nlocal  b  =  2\nif  x  then\n\tlocal  c  =  3\nend\n";
+
+        assert_eq!(
+            format_with(code, &FormatOptions::new()),
+            "local a = 1\n-- fmt: skip\nlocal  b  =  2\nif x then\n\tlocal c = 3\nend\n"
+        );
+
+        // Only the `if`, which starts in the range, is formatted
+        assert_eq!(
+            format_with(code, &FormatOptions::new().with_range(42..45)),
+            "local  a  =  1\n-- fmt: skip\nlocal  b  =  2\nif x then\n\tlocal  c  =  3\nend\n"
+        );
+    }
+}
@@ -0,0 +1,1578 @@
+use crate::{
+    ast::{
+        constructors::needs_semicolon_before,
+        punctuated::{Pair, Punctuated},
+        span::ContainedSpan,
+        *,
+    },
+    tokenizer::{StringLiteralQuoteType, Symbol, Token, TokenReference, TokenType},
+    ShortString,
+};
+
+#[cfg(any(feature = "lua52", feature = "luajit"))]
+use crate::ast::lua52::{Goto, Label};
+#[cfg(feature = "lua54")]
+use crate::ast::lua54::Attribute;
+#[cfg(feature = "luau")]
+use crate::{
+    ast::luau::*,
+    node::Node,
+    tokenizer::{InterpolatedStringKind, TokenKind},
+};
+
+/// The default for [`Generator::with_max_depth`]
+pub const DEFAULT_MAX_DEPTH: usize = 6;
+
+// The most statements, arguments, fields, and such that are put in one node
+const MAX_ITEMS: usize = 3;
+
+const NAMES: &[&str] = &["a", "b", "c", "x", "y", "foo", "bar", "value"];
+const NUMBERS: &[&str] = &["0", "1", "42", "3.5", ".5", "0x1F", "1e10", "2E-3"];
+#[cfg(feature = "luau")]
+const LUAU_NUMBERS: &[&str] = &["0b101", "1_000"];
+
+/// Generates random, well-formed nodes from bytes.
+///
+/// A [`Generator`] makes every decision by reading bytes from the entropy it is given, the same way
+/// [`arbitrary`] does, so that it can be driven by a fuzzer. [`Ast`] implements
+/// [`Arbitrary`](arbitrary::Arbitrary) using a Generator, and [`Generator::from_unstructured`]
+/// reads from an [`Unstructured`](arbitrary::Unstructured) for a chosen Lua version. When the bytes
+/// run out, it takes the smallest choice left, so any input gives a finished Ast. Code that prints
+/// the same is always generated the same way, with parentheses added wherever the printed code
+/// would otherwise parse differently.
+///
+/// Only the syntax of the Lua versions given is used, including Luau types. CfxLua syntax is not generated.
+///
+/// ```rust
+/// # use full_moon::{generate::Generator, node::Node, LuaVersion};
+/// let ast = Generator::new(b"fuzzer input", LuaVersion::lua51()).ast();
+/// let code = ast.to_string();
+///
+/// let parsed = full_moon::parse_fallible(&code, LuaVersion::lua51())
+///     .into_result()
+///     .unwrap();
+///
+/// assert!(ast.similar(&parsed));
+/// ```
+#[derive(Clone, Debug)]
+pub struct Generator<'a> {
+    entropy: &'a [u8],
+    lua_version: LuaVersion,
+    depth: usize,
+    max_depth: usize,
+}
+
+impl<'a> Generator<'a> {
+    /// Creates a new Generator reading from the given bytes, generating code for the given Lua version
+    pub fn new(entropy: &'a [u8], lua_version: LuaVersion) -> Self {
+        Self {
+            entropy,
+            lua_version,
+            depth: 0,
+            max_depth: DEFAULT_MAX_DEPTH,
+        }
+    }
+
+    /// Creates a new Generator reading from bytes taken from `unstructured`, generating code for
+    /// the given Lua version
+    pub fn from_unstructured(
+        unstructured: &mut arbitrary::Unstructured<'a>,
+        lua_version: LuaVersion,
+    ) -> arbitrary::Result<Self> {
+        let length = unstructured.arbitrary_len::<u8>()?;
+        Ok(Self::new(unstructured.bytes(length)?, lua_version))
+    }
+
+    /// Returns a new Generator that nests blocks, expressions, and types at most this deeply
+    pub fn with_max_depth(self, max_depth: usize) -> Self {
+        Self { max_depth, ..self }
+    }
+
+    /// The Lua version code is generated for
+    pub fn lua_version(&self) -> LuaVersion {
+        self.lua_version
+    }
+
+    /// Generates an entire Ast
+    pub fn ast(&mut self) -> Ast {
+        Ast {
+            nodes: self.block(),
+            eof: TokenReference::new(Vec::new(), Token::new(TokenType::Eof), Vec::new()),
+        }
+    }
+
+    /// Generates a block of statements, which might end in a last statement such as `return`
+    pub fn block(&mut self) -> Block {
+        self.nested(|generator| {
+            let stmt_count = generator.count();
+            let mut stmts: Vec<(Stmt, Option<TokenReference>)> = Vec::with_capacity(stmt_count);
+
+            for _ in 0..stmt_count {
+                let stmt = generator.stmt();
+
+                if let Some((_, semicolon)) = stmts.last_mut() {
+                    if semicolon.is_none() && needs_semicolon_before(&stmt) {
+                        *semicolon = Some(generator.symbol(Symbol::Semicolon));
+                    }
+                }
+
+                let semicolon = generator
+                    .chance()
+                    .then(|| generator.symbol(Symbol::Semicolon));
+
+                stmts.push((stmt, semicolon));
+            }
+
+            let last_stmt = generator.chance().then(|| {
+                let last_stmt = generator.last_stmt();
+                let semicolon = generator
+                    .chance()
+                    .then(|| generator.symbol(Symbol::Semicolon));
+
+                (last_stmt, semicolon)
+            });
+
+            Block::new().with_stmts(stmts).with_last_stmt(last_stmt)
+        })
+    }
+
+    /// Generates a statement
+    pub fn stmt(&mut self) -> Stmt {
+        #[allow(unused_mut)]
+        let mut generators: Vec<fn(&mut Self) -> Stmt> = vec![
+            Self::local_assignment,
+            Self::assignment,
+            Self::function_call_stmt,
+            Self::do_stmt,
+            Self::while_stmt,
+            Self::repeat_stmt,
+            Self::if_stmt,
+            Self::numeric_for,
+            Self::generic_for,
+            Self::function_declaration,
+            Self::local_function,
+        ];
+
+        #[cfg(feature = "luau")]
+        if self.lua_version.has_luau() {
+            generators.extend([
+                Self::compound_assignment as fn(&mut Self) -> Stmt,
+                Self::type_declaration,
+                Self::exported_type_declaration,
+                Self::type_function,
+            ]);
+        }
+
+        #[cfg(any(feature = "lua52", feature = "luajit"))]
+        if self.lua_version.has_lua52() || self.lua_version.has_luajit() {
+            generators.extend([Self::goto as fn(&mut Self) -> Stmt, Self::label]);
+        }
+
+        let generator = generators[self.choose(generators.len())];
+        generator(self)
+    }
+
+    /// Generates an expression
+    pub fn expression(&mut self) -> Expression {
+        self.nested(|generator| {
+            #[allow(unused_mut)]
+            let mut generators: Vec<fn(&mut Self) -> Expression> = vec![
+                Self::symbol_expression,
+                Self::number_expression,
+                Self::string_expression,
+                Self::name_expression,
+            ];
+
+            #[cfg(feature = "luau")]
+            if generator.lua_version.has_luau() {
+                generators.push(Self::simple_interpolated_string);
+            }
+
+            if generator.can_nest() {
+                generators.extend([
+                    Self::binary_operator as fn(&mut Self) -> Expression,
+                    Self::unary_operator,
+                    Self::parentheses_expression,
+                    Self::function_expression,
+                    Self::function_call_expression,
+                    Self::table_constructor_expression,
+                    Self::var_expression,
+                ]);
+
+                #[cfg(feature = "luau")]
+                if generator.lua_version.has_luau() {
+                    generators.extend([
+                        Self::if_expression as fn(&mut Self) -> Expression,
+                        Self::type_assertion,
+                        Self::interpolated_string,
+                    ]);
+                }
+            }
+
+            let expression_generator = generators[generator.choose(generators.len())];
+            expression_generator(generator)
+        })
+    }
+
+    /// Generates a type, such as `string | number`.
+    /// Only available when the "luau" feature flag is enabled.
+    #[cfg(feature = "luau")]
+    pub fn type_info(&mut self) -> TypeInfo {
+        self.nested(|generator| {
+            if !generator.can_nest() {
+                return generator.simple_type();
+            }
+
+            match generator.choose(6) {
+                0 | 1 => generator.simple_type(),
+                2 => TypeInfo::Optional {
+                    base: Box::new(generator.union_member()),
+                    question_mark: generator.symbol(Symbol::QuestionMark),
+                },
+                3 => {
+                    let count = 2 + generator.choose(MAX_ITEMS - 1);
+
+                    TypeInfo::Union(TypeUnion::new(
+                        None,
+                        generator.punctuated(count, Symbol::Pipe, Self::union_member),
+                    ))
+                }
+
+                4 => {
+                    let count = 2 + generator.choose(MAX_ITEMS - 1);
+
+                    TypeInfo::Intersection(TypeIntersection::new(
+                        None,
+                        generator.punctuated(count, Symbol::Ampersand, Self::simple_type),
+                    ))
+                }
+
+                _ => generator.callback_type(),
+            }
+        })
+    }
+
+    // Entropy
+
+    fn byte(&mut self) -> u8 {
+        match self.entropy.split_first() {
+            Some((&byte, rest)) => {
+                self.entropy = rest;
+                byte
+            }
+
+            None => 0,
+        }
+    }
+
+    // Picks a number in `0..count`, which is 0 once the entropy runs out
+    fn choose(&mut self, count: usize) -> usize {
+        debug_assert!(count > 0 && count <= 256);
+        self.byte() as usize % count
+    }
+
+    fn chance(&mut self) -> bool {
+        self.byte() % 2 == 1
+    }
+
+    // How many items to put in a node, which is none when nested as deep as possible
+    fn count(&mut self) -> usize {
+        if self.can_nest() {
+            self.choose(MAX_ITEMS + 1)
+        } else {
+            0
+        }
+    }
+
+    fn can_nest(&self) -> bool {
+        self.depth < self.max_depth
+    }
+
+    fn nested<T>(&mut self, generate: impl FnOnce(&mut Self) -> T) -> T {
+        self.depth += 1;
+        let result = generate(self);
+        self.depth -= 1;
+        result
+    }
+
+    // Tokens
+
+    fn token(&mut self, token_type: TokenType) -> TokenReference {
+        let whitespace = |characters| {
+            Token::new(TokenType::Whitespace {
+                characters: ShortString::new(characters),
+            })
+        };
+
+        // Tokens are always followed by some trivia, so that they can't run into each other
+        let trailing_trivia = match self.choose(6) {
+            0..=2 => vec![whitespace(" ")],
+            3 => vec![whitespace("\n")],
+
+            4 => vec![
+                whitespace(" "),
+                Token::new(TokenType::MultiLineComment {
+                    blocks: 0,
+                    comment: ShortString::new(" comment "),
+                }),
+                whitespace(" "),
+            ],
+
+            _ => vec![
+                whitespace(" "),
+                Token::new(TokenType::SingleLineComment {
+                    comment: ShortString::new(" comment"),
+                }),
+                whitespace("\n"),
+            ],
+        };
+
+        TokenReference::new(Vec::new(), Token::new(token_type), trailing_trivia)
+    }
+
+    fn symbol(&mut self, symbol: Symbol) -> TokenReference {
+        self.token(TokenType::Symbol { symbol })
+    }
+
+    fn identifier(&mut self, identifier: &str) -> TokenReference {
+        self.token(TokenType::Identifier {
+            identifier: ShortString::new(identifier),
+        })
+    }
+
+    fn name(&mut self) -> TokenReference {
+        let name = NAMES[self.choose(NAMES.len())];
+        self.identifier(name)
+    }
+
+    fn number(&mut self) -> TokenReference {
+        #[allow(unused_mut)]
+        let mut numbers = NUMBERS.to_vec();
+
+        #[cfg(feature = "luau")]
+        if self.lua_version.has_luau() {
+            numbers.extend(LUAU_NUMBERS);
+        }
+
+        let number = numbers[self.choose(numbers.len())];
+        self.token(TokenType::Number {
+            text: ShortString::new(number),
+        })
+    }
+
+    fn string(&mut self) -> TokenReference {
+        let (literal, multi_line_depth, quote_type) = match self.choose(5) {
+            0 => ("hello", 0, StringLiteralQuoteType::Double),
+            1 => ("it's", 0, StringLiteralQuoteType::Double),
+            2 => ("say \"hi\"\\n", 0, StringLiteralQuoteType::Single),
+            3 => ("long\nstring", 0, StringLiteralQuoteType::Brackets),
+            _ => ("a]]b", 1, StringLiteralQuoteType::Brackets),
+        };
+
+        self.token(TokenType::StringLiteral {
+            literal: ShortString::new(literal),
+            multi_line_depth,
+            quote_type,
+        })
+    }
+
+    fn contained_span(&mut self, start: Symbol, end: Symbol) -> ContainedSpan {
+        ContainedSpan::new(self.symbol(start), self.symbol(end))
+    }
+
+    fn punctuated<T>(
+        &mut self,
+        count: usize,
+        punctuation: Symbol,
+        mut generate: impl FnMut(&mut Self) -> T,
+    ) -> Punctuated<T> {
+        let mut punctuated = Punctuated::new();
+
+        for index in 0..count {
+            let value = generate(self);
+            let punctuation = (index + 1 < count).then(|| self.symbol(punctuation));
+            punctuated.push(Pair::new(value, punctuation));
+        }
+
+        punctuated
+    }
+
+    fn expression_list(&mut self, count: usize) -> Punctuated<Expression> {
+        self.punctuated(count, Symbol::Comma, Self::expression)
+    }
+
+    // Statements
+
+    fn last_stmt(&mut self) -> LastStmt {
+        match self.choose(3) {
+            0 => {
+                let count = self.count();
+
+                LastStmt::Return(
+                    Return::new()
+                        .with_token(self.symbol(Symbol::Return))
+                        .with_returns(self.expression_list(count)),
+                )
+            }
+
+            #[cfg(feature = "luau")]
+            1 if self.lua_version.has_luau() => LastStmt::Continue(self.identifier("continue")),
+
+            _ => LastStmt::Break(self.symbol(Symbol::Break)),
+        }
+    }
+
+    fn local_assignment(&mut self) -> Stmt {
+        let local_token = self.symbol(Symbol::Local);
+        let name_count = 1 + self.choose(MAX_ITEMS);
+        let names = self.punctuated(name_count, Symbol::Comma, Self::name);
+
+        #[cfg(feature = "lua54")]
+        let mut attributes = Vec::with_capacity(name_count);
+        #[cfg(feature = "luau")]
+        let mut type_specifiers = Vec::with_capacity(name_count);
+
+        // Names can have either an attribute or a type specifier, but never both
+        for _ in 0..name_count {
+            #[cfg(feature = "lua54")]
+            let attribute = (self.lua_version.has_lua54() && self.chance()).then(|| {
+                let name = match self.chance() {
+                    true => "const",
+                    false => "close",
+                };
+
+                Attribute::new(self.identifier(name))
+                    .with_brackets(self.contained_span(Symbol::LessThan, Symbol::GreaterThan))
+            });
+
+            #[cfg(feature = "luau")]
+            {
+                #[cfg(feature = "lua54")]
+                let has_attribute = attribute.is_some();
+                #[cfg(not(feature = "lua54"))]
+                let has_attribute = false;
+
+                type_specifiers.push(
+                    (!has_attribute && self.lua_version.has_luau() && self.chance())
+                        .then(|| self.type_specifier()),
+                );
+            }
+
+            #[cfg(feature = "lua54")]
+            attributes.push(attribute);
+        }
+
+        let expression_count = self.count();
+        let equal_token = (expression_count > 0).then(|| self.symbol(Symbol::Equal));
+
+        let local_assignment = LocalAssignment::new(names)
+            .with_local_token(local_token)
+            .with_equal_token(equal_token)
+            .with_expressions(self.expression_list(expression_count));
+
+        #[cfg(feature = "lua54")]
+        let local_assignment = local_assignment.with_attributes(attributes);
+        #[cfg(feature = "luau")]
+        let local_assignment = local_assignment.with_type_specifiers(type_specifiers);
+
+        Stmt::LocalAssignment(local_assignment)
+    }
+
+    fn assignment(&mut self) -> Stmt {
+        let var_count = 1 + self.choose(MAX_ITEMS);
+        let variables = self.punctuated(var_count, Symbol::Comma, Self::var);
+        let equal_token = self.symbol(Symbol::Equal);
+        let expression_count = 1 + self.choose(MAX_ITEMS);
+
+        Stmt::Assignment(
+            Assignment::new(variables, self.expression_list(expression_count))
+                .with_equal_token(equal_token),
+        )
+    }
+
+    fn function_call_stmt(&mut self) -> Stmt {
+        Stmt::FunctionCall(self.function_call())
+    }
+
+    fn do_stmt(&mut self) -> Stmt {
+        Stmt::Do(
+            Do::new()
+                .with_do_token(self.symbol(Symbol::Do))
+                .with_block(self.block())
+                .with_end_token(self.symbol(Symbol::End)),
+        )
+    }
+
+    fn while_stmt(&mut self) -> Stmt {
+        Stmt::While(
+            While::new(Expression::placeholder())
+                .with_while_token(self.symbol(Symbol::While))
+                .with_condition(self.expression())
+                .with_do_token(self.symbol(Symbol::Do))
+                .with_block(self.block())
+                .with_end_token(self.symbol(Symbol::End)),
+        )
+    }
+
+    fn repeat_stmt(&mut self) -> Stmt {
+        Stmt::Repeat(
+            Repeat::new(Expression::placeholder())
+                .with_repeat_token(self.symbol(Symbol::Repeat))
+                .with_block(self.block())
+                .with_until_token(self.symbol(Symbol::Until))
+                .with_until(self.expression()),
+        )
+    }
+
+    fn if_stmt(&mut self) -> Stmt {
+        let if_stmt = If::new(Expression::placeholder())
+            .with_if_token(self.symbol(Symbol::If))
+            .with_condition(self.expression())
+            .with_then_token(self.symbol(Symbol::Then))
+            .with_block(self.block());
+
+        let else_if_count = self.count();
+        let else_ifs: Vec<_> = (0..else_if_count)
+            .map(|_| {
+                ElseIf::new(Expression::placeholder())
+                    .with_else_if_token(self.symbol(Symbol::ElseIf))
+                    .with_condition(self.expression())
+                    .with_then_token(self.symbol(Symbol::Then))
+                    .with_block(self.block())
+            })
+            .collect();
+
+        let (else_token, else_block) = match self.chance() {
+            true => (Some(self.symbol(Symbol::Else)), Some(self.block())),
+            false => (None, None),
+        };
+
+        Stmt::If(
+            if_stmt
+                .with_else_if((!else_ifs.is_empty()).then_some(else_ifs))
+                .with_else_token(else_token)
+                .with_else(else_block)
+                .with_end_token(self.symbol(Symbol::End)),
+        )
+    }
+
+    fn numeric_for(&mut self) -> Stmt {
+        let for_token = self.symbol(Symbol::For);
+        let index_variable = self.name();
+
+        #[cfg(feature = "luau")]
+        let type_specifier =
+            (self.lua_version.has_luau() && self.chance()).then(|| self.type_specifier());
+
+        let equal_token = self.symbol(Symbol::Equal);
+        let start = self.expression();
+        let start_end_comma = self.symbol(Symbol::Comma);
+        let end = self.expression();
+
+        let (end_step_comma, step) = match self.chance() {
+            true => (Some(self.symbol(Symbol::Comma)), Some(self.expression())),
+            false => (None, None),
+        };
+
+        let numeric_for = NumericFor::new(index_variable, start, end)
+            .with_for_token(for_token)
+            .with_equal_token(equal_token)
+            .with_start_end_comma(start_end_comma)
+            .with_end_step_comma(end_step_comma)
+            .with_step(step)
+            .with_do_token(self.symbol(Symbol::Do))
+            .with_block(self.block())
+            .with_end_token(self.symbol(Symbol::End));
+
+        #[cfg(feature = "luau")]
+        let numeric_for = numeric_for.with_type_specifier(type_specifier);
+
+        Stmt::NumericFor(numeric_for)
+    }
+
+    fn generic_for(&mut self) -> Stmt {
+        let for_token = self.symbol(Symbol::For);
+        let name_count = 1 + self.choose(MAX_ITEMS);
+        let names = self.punctuated(name_count, Symbol::Comma, Self::name);
+
+        #[cfg(feature = "luau")]
+        let type_specifiers: Vec<_> = (0..name_count)
+            .map(|_| (self.lua_version.has_luau() && self.chance()).then(|| self.type_specifier()))
+            .collect();
+
+        let in_token = self.symbol(Symbol::In);
+        let expression_count = 1 + self.choose(MAX_ITEMS);
+        let expressions = self.expression_list(expression_count);
+
+        let generic_for = GenericFor::new(names, expressions)
+            .with_for_token(for_token)
+            .with_in_token(in_token)
+            .with_do_token(self.symbol(Symbol::Do))
+            .with_block(self.block())
+            .with_end_token(self.symbol(Symbol::End));
+
+        #[cfg(feature = "luau")]
+        let generic_for = generic_for.with_type_specifiers(type_specifiers);
+
+        Stmt::GenericFor(generic_for)
+    }
+
+    fn function_declaration(&mut self) -> Stmt {
+        let function_token = self.symbol(Symbol::Function);
+        let name_count = 1 + self.choose(MAX_ITEMS);
+        let names = self.punctuated(name_count, Symbol::Dot, Self::name);
+
+        let method = self
+            .chance()
+            .then(|| (self.symbol(Symbol::Colon), self.name()));
+
+        Stmt::FunctionDeclaration(
+            FunctionDeclaration::new(FunctionName::new(names).with_method(method))
+                .with_function_token(function_token)
+                .with_body(self.function_body()),
+        )
+    }
+
+    fn local_function(&mut self) -> Stmt {
+        let local_token = self.symbol(Symbol::Local);
+        let function_token = self.symbol(Symbol::Function);
+        let name = self.name();
+
+        Stmt::LocalFunction(
+            LocalFunction::new(name)
+                .with_local_token(local_token)
+                .with_function_token(function_token)
+                .with_body(self.function_body()),
+        )
+    }
+
+    #[cfg(feature = "luau")]
+    fn compound_assignment(&mut self) -> Stmt {
+        let lhs = self.var();
+
+        let operator = match self.choose(8) {
+            0 => CompoundOp::PlusEqual(self.symbol(Symbol::PlusEqual)),
+            1 => CompoundOp::MinusEqual(self.symbol(Symbol::MinusEqual)),
+            2 => CompoundOp::StarEqual(self.symbol(Symbol::StarEqual)),
+            3 => CompoundOp::SlashEqual(self.symbol(Symbol::SlashEqual)),
+            4 => CompoundOp::CaretEqual(self.symbol(Symbol::CaretEqual)),
+            5 => CompoundOp::DoubleSlashEqual(self.symbol(Symbol::DoubleSlashEqual)),
+            6 => CompoundOp::PercentEqual(self.symbol(Symbol::PercentEqual)),
+            _ => CompoundOp::TwoDotsEqual(self.symbol(Symbol::TwoDotsEqual)),
+        };
+
+        Stmt::CompoundAssignment(CompoundAssignment::new(lhs, operator, self.expression()))
+    }
+
+    #[cfg(feature = "luau")]
+    fn type_declaration(&mut self) -> Stmt {
+        Stmt::TypeDeclaration(self.type_declaration_node())
+    }
+
+    #[cfg(feature = "luau")]
+    fn exported_type_declaration(&mut self) -> Stmt {
+        let export_token = self.identifier("export");
+
+        Stmt::ExportedTypeDeclaration(
+            ExportedTypeDeclaration::new(self.type_declaration_node())
+                .with_export_token(export_token),
+        )
+    }
+
+    #[cfg(feature = "luau")]
+    fn type_declaration_node(&mut self) -> TypeDeclaration {
+        let type_token = self.identifier("type");
+        let name = self.name();
+        let generics = self.chance().then(|| self.generic_declaration(true));
+        let equal_token = self.symbol(Symbol::Equal);
+
+        // Leading separators are only allowed where a type pack can't be, such as `type T = | A | B`
+        let type_info = match self.type_info() {
+            TypeInfo::Union(union) if self.chance() => {
+                TypeInfo::Union(union.with_leading(Some(self.symbol(Symbol::Pipe))))
+            }
+
+            TypeInfo::Intersection(intersection) if self.chance() => TypeInfo::Intersection(
+                intersection.with_leading(Some(self.symbol(Symbol::Ampersand))),
+            ),
+
+            type_info => type_info,
+        };
+
+        TypeDeclaration::new(name, type_info)
+            .with_type_token(type_token)
+            .with_generics(generics)
+            .with_equal_token(equal_token)
+    }
+
+    #[cfg(feature = "luau")]
+    fn type_function(&mut self) -> Stmt {
+        let type_token = self.identifier("type");
+        let function_token = self.symbol(Symbol::Function);
+        let name = self.name();
+
+        Stmt::TypeFunction(
+            TypeFunction::new(name, self.function_body())
+                .with_type_token(type_token)
+                .with_function_token(function_token),
+        )
+    }
+
+    #[cfg(any(feature = "lua52", feature = "luajit"))]
+    fn goto(&mut self) -> Stmt {
+        let goto_token = self.symbol(Symbol::Goto);
+        Stmt::Goto(Goto::new(self.name()).with_goto_token(goto_token))
+    }
+
+    #[cfg(any(feature = "lua52", feature = "luajit"))]
+    fn label(&mut self) -> Stmt {
+        let left_colons = self.symbol(Symbol::TwoColons);
+        let name = self.name();
+
+        Stmt::Label(
+            Label::new(name)
+                .with_left_colons(left_colons)
+                .with_right_colons(self.symbol(Symbol::TwoColons)),
+        )
+    }
+
+    fn function_body(&mut self) -> FunctionBody {
+        #[cfg(feature = "luau")]
+        let generics =
+            (self.lua_version.has_luau() && self.chance()).then(|| self.generic_declaration(false));
+
+        let left_parenthesis = self.symbol(Symbol::LeftParen);
+
+        let mut parameter_count = self.count();
+        let has_ellipsis = self.chance();
+        parameter_count += has_ellipsis as usize;
+
+        let mut index = 0;
+        let parameters = self.punctuated(parameter_count, Symbol::Comma, |generator| {
+            index += 1;
+
+            match has_ellipsis && index == parameter_count {
+                true => Parameter::Ellipsis(generator.symbol(Symbol::Ellipsis)),
+                false => Parameter::Name(generator.name()),
+            }
+        });
+
+        #[cfg(feature = "luau")]
+        let type_specifiers: Vec<_> = (0..parameter_count)
+            .map(|_| (self.lua_version.has_luau() && self.chance()).then(|| self.type_specifier()))
+            .collect();
+
+        let parameters_parentheses =
+            ContainedSpan::new(left_parenthesis, self.symbol(Symbol::RightParen));
+
+        #[cfg(feature = "luau")]
+        let return_type = (self.lua_version.has_luau() && self.chance()).then(|| {
+            let punctuation = self.symbol(Symbol::Colon);
+            TypeSpecifier::new(self.return_type()).with_punctuation(punctuation)
+        });
+
+        let function_body = FunctionBody::new()
+            .with_parameters_parentheses(parameters_parentheses)
+            .with_parameters(parameters)
+            .with_block(self.block())
+            .with_end_token(self.symbol(Symbol::End));
+
+        #[cfg(feature = "luau")]
+        let function_body = function_body
+            .with_generics(generics)
+            .with_type_specifiers(type_specifiers)
+            .with_return_type(return_type);
+
+        function_body
+    }
+
+    // Expressions
+
+    // Wraps an expression in parentheses, for when it would otherwise parse differently
+    fn parenthesize(&mut self, expression: Expression) -> Expression {
+        Expression::Parentheses {
+            contained: self.contained_span(Symbol::LeftParen, Symbol::RightParen),
+            expression: Box::new(expression),
+        }
+    }
+
+    fn symbol_expression(&mut self) -> Expression {
+        let symbol = match self.choose(4) {
+            0 => Symbol::Nil,
+            1 => Symbol::True,
+            2 => Symbol::False,
+            _ => Symbol::Ellipsis,
+        };
+
+        Expression::Symbol(self.symbol(symbol))
+    }
+
+    fn number_expression(&mut self) -> Expression {
+        Expression::Number(self.number())
+    }
+
+    fn string_expression(&mut self) -> Expression {
+        Expression::String(self.string())
+    }
+
+    fn name_expression(&mut self) -> Expression {
+        Expression::Var(Var::Name(self.name()))
+    }
+
+    fn binary_operator(&mut self) -> Expression {
+        let lhs = self.expression();
+        let binop = self.binop();
+        let rhs = self.expression();
+
+        let precedence = binop.precedence();
+
+        // `(a + b) * c` and `a ^ (b ^ c)` need parentheses, but `a * b + c` and `a ^ b ^ c` don't
+        let lhs_needs_parentheses = match &lhs {
+            Expression::BinaryOperator {
+                binop: lhs_binop, ..
+            } => {
+                lhs_binop.precedence() < precedence
+                    || (lhs_binop.precedence() == precedence && binop.is_right_associative())
+            }
+
+            Expression::UnaryOperator { .. } => UnOp::precedence() < precedence,
+            _ => is_open_ended(&lhs),
+        };
+
+        let rhs_needs_parentheses = match &rhs {
+            Expression::BinaryOperator {
+                binop: rhs_binop, ..
+            } => {
+                rhs_binop.precedence() < precedence
+                    || (rhs_binop.precedence() == precedence && !binop.is_right_associative())
+            }
+
+            Expression::UnaryOperator { .. } => false,
+            _ => is_open_ended(&rhs),
+        };
+
+        let lhs = match lhs_needs_parentheses {
+            true => self.parenthesize(lhs),
+            false => lhs,
+        };
+
+        let rhs = match rhs_needs_parentheses {
+            true => self.parenthesize(rhs),
+            false => rhs,
+        };
+
+        Expression::BinaryOperator {
+            lhs: Box::new(lhs),
+            binop,
+            rhs: Box::new(rhs),
+        }
+    }
+
+    fn binop(&mut self) -> BinOp {
+        #[allow(unused_mut)]
+        let mut symbols = vec![
+            Symbol::Caret,
+            Symbol::Percent,
+            Symbol::Slash,
+            Symbol::Star,
+            Symbol::Minus,
+            Symbol::Plus,
+            Symbol::TwoDots,
+            Symbol::GreaterThan,
+            Symbol::GreaterThanEqual,
+            Symbol::LessThan,
+            Symbol::LessThanEqual,
+            Symbol::TildeEqual,
+            Symbol::TwoEqual,
+            Symbol::And,
+            Symbol::Or,
+        ];
+
+        #[cfg(any(feature = "luau", feature = "lua53"))]
+        if self.lua_version.has_luau() || self.lua_version.has_lua53() {
+            symbols.push(Symbol::DoubleSlash);
+        }
+
+        #[cfg(feature = "lua53")]
+        if self.lua_version.has_lua53() {
+            symbols.extend([
+                Symbol::DoubleLessThan,
+                Symbol::DoubleGreaterThan,
+                Symbol::Ampersand,
+                Symbol::Tilde,
+                Symbol::Pipe,
+            ]);
+        }
+
+        let symbol = symbols[self.choose(symbols.len())];
+        let token = self.symbol(symbol);
+
+        match symbol {
+            Symbol::Caret => BinOp::Caret(token),
+            Symbol::Percent => BinOp::Percent(token),
+            Symbol::Slash => BinOp::Slash(token),
+            Symbol::Star => BinOp::Star(token),
+            Symbol::Minus => BinOp::Minus(token),
+            Symbol::Plus => BinOp::Plus(token),
+            Symbol::TwoDots => BinOp::TwoDots(token),
+            Symbol::GreaterThan => BinOp::GreaterThan(token),
+            Symbol::GreaterThanEqual => BinOp::GreaterThanEqual(token),
+            Symbol::LessThan => BinOp::LessThan(token),
+            Symbol::LessThanEqual => BinOp::LessThanEqual(token),
+            Symbol::TildeEqual => BinOp::TildeEqual(token),
+            Symbol::TwoEqual => BinOp::TwoEqual(token),
+            Symbol::And => BinOp::And(token),
+            Symbol::Or => BinOp::Or(token),
+            #[cfg(any(feature = "luau", feature = "lua53"))]
+            Symbol::DoubleSlash => BinOp::DoubleSlash(token),
+            #[cfg(feature = "lua53")]
+            Symbol::DoubleLessThan => BinOp::DoubleLessThan(token),
+            #[cfg(feature = "lua53")]
+            Symbol::DoubleGreaterThan => BinOp::DoubleGreaterThan(token),
+            #[cfg(feature = "lua53")]
+            Symbol::Ampersand => BinOp::Ampersand(token),
+            #[cfg(feature = "lua53")]
+            Symbol::Tilde => BinOp::Tilde(token),
+            #[cfg(feature = "lua53")]
+            Symbol::Pipe => BinOp::Pipe(token),
+            _ => unreachable!("{symbol:?} is not a binary operator"),
+        }
+    }
+
+    fn unary_operator(&mut self) -> Expression {
+        #[cfg(feature = "lua53")]
+        let unop_count = if self.lua_version.has_lua53() { 4 } else { 3 };
+        #[cfg(not(feature = "lua53"))]
+        let unop_count = 3;
+
+        let unop = match self.choose(unop_count) {
+            0 => UnOp::Minus(self.symbol(Symbol::Minus)),
+            1 => UnOp::Not(self.symbol(Symbol::Not)),
+            #[cfg(feature = "lua53")]
+            3 => UnOp::Tilde(self.symbol(Symbol::Tilde)),
+            _ => UnOp::Hash(self.symbol(Symbol::Hash)),
+        };
+
+        let expression = self.expression();
+
+        // Only `^` is done before unary operators, so `-a ^ b` is `-(a ^ b)`
+        let needs_parentheses = match &expression {
+            Expression::BinaryOperator { binop, .. } => binop.precedence() < UnOp::precedence(),
+            _ => is_open_ended(&expression),
+        };
+
+        let expression = match needs_parentheses {
+            true => self.parenthesize(expression),
+            false => expression,
+        };
+
+        Expression::UnaryOperator {
+            unop,
+            expression: Box::new(expression),
+        }
+    }
+
+    fn parentheses_expression(&mut self) -> Expression {
+        let expression = self.expression();
+        self.parenthesize(expression)
+    }
+
+    fn function_expression(&mut self) -> Expression {
+        let function_token = self.symbol(Symbol::Function);
+        Expression::Function(Box::new((function_token, self.function_body())))
+    }
+
+    fn function_call_expression(&mut self) -> Expression {
+        Expression::FunctionCall(self.function_call())
+    }
+
+    fn table_constructor_expression(&mut self) -> Expression {
+        Expression::TableConstructor(self.table_constructor())
+    }
+
+    fn var_expression(&mut self) -> Expression {
+        Expression::Var(self.var())
+    }
+
+    #[cfg(feature = "luau")]
+    fn if_expression(&mut self) -> Expression {
+        let if_token = self.symbol(Symbol::If);
+        let condition = self.expression();
+        let then_token = self.symbol(Symbol::Then);
+        let if_expression = self.expression();
+
+        let else_if_count = self.count();
+        let else_ifs: Vec<_> = (0..else_if_count)
+            .map(|_| {
+                let else_if_token = self.symbol(Symbol::ElseIf);
+                let condition = self.expression();
+                let then_token = self.symbol(Symbol::Then);
+
+                ElseIfExpression::new(condition, self.expression())
+                    .with_else_if_token(else_if_token)
+                    .with_then_token(then_token)
+            })
+            .collect();
+
+        let else_token = self.symbol(Symbol::Else);
+
+        Expression::IfExpression(
+            IfExpression::new(condition, if_expression, self.expression())
+                .with_if_token(if_token)
+                .with_then_token(then_token)
+                .with_else_if((!else_ifs.is_empty()).then_some(else_ifs))
+                .with_else_token(else_token),
+        )
+    }
+
+    #[cfg(feature = "luau")]
+    fn type_assertion(&mut self) -> Expression {
+        let expression = self.expression();
+
+        // `-a :: T` is `-(a :: T)`, so anything other than a single value needs parentheses
+        let expression = match expression {
+            Expression::BinaryOperator { .. }
+            | Expression::UnaryOperator { .. }
+            | Expression::IfExpression(_)
+            | Expression::TypeAssertion { .. } => self.parenthesize(expression),
+            _ => expression,
+        };
+
+        let assertion_op = self.symbol(Symbol::TwoColons);
+
+        Expression::TypeAssertion {
+            expression: Box::new(expression),
+            type_assertion: TypeAssertion::new(self.type_info()).with_assertion_op(assertion_op),
+        }
+    }
+
+    #[cfg(feature = "luau")]
+    fn interpolated_string_literal(&mut self, kind: InterpolatedStringKind) -> TokenReference {
+        let literal = ["", "text", "a b"][self.choose(3)];
+
+        self.token(TokenType::InterpolatedString {
+            literal: ShortString::new(literal),
+            kind,
+        })
+    }
+
+    #[cfg(feature = "luau")]
+    fn simple_interpolated_string(&mut self) -> Expression {
+        let last_string = self.interpolated_string_literal(InterpolatedStringKind::Simple);
+        Expression::InterpolatedString(InterpolatedString::new(Vec::new(), last_string))
+    }
+
+    #[cfg(feature = "luau")]
+    fn interpolated_string(&mut self) -> Expression {
+        let segment_count = 1 + self.choose(MAX_ITEMS);
+
+        let segments = (0..segment_count)
+            .map(|index| InterpolatedStringSegment {
+                literal: self.interpolated_string_literal(match index {
+                    0 => InterpolatedStringKind::Begin,
+                    _ => InterpolatedStringKind::Middle,
+                }),
+                expression: self.expression(),
+            })
+            .collect();
+
+        let last_string = self.interpolated_string_literal(InterpolatedStringKind::End);
+        Expression::InterpolatedString(InterpolatedString::new(segments, last_string))
+    }
+
+    fn prefix(&mut self) -> Prefix {
+        match self.chance() {
+            true => Prefix::Expression(Box::new(self.parentheses_expression())),
+            false => Prefix::Name(self.name()),
+        }
+    }
+
+    fn suffixes(&mut self, last_suffix: fn(&mut Self) -> Suffix) -> Vec<Suffix> {
+        let mut suffixes: Vec<_> = (0..self.count())
+            .map(|_| match self.chance() {
+                true => Suffix::Call(self.call()),
+                false => Suffix::Index(self.index()),
+            })
+            .collect();
+
+        suffixes.push(last_suffix(self));
+        suffixes
+    }
+
+    fn function_call(&mut self) -> FunctionCall {
+        let prefix = self.prefix();
+
+        FunctionCall::new(prefix)
+            .with_suffixes(self.suffixes(|generator| Suffix::Call(generator.call())))
+    }
+
+    fn var(&mut self) -> Var {
+        if self.chance() {
+            return Var::Name(self.name());
+        }
+
+        let prefix = self.prefix();
+
+        Var::Expression(Box::new(VarExpression::new(prefix).with_suffixes(
+            self.suffixes(|generator| Suffix::Index(generator.index())),
+        )))
+    }
+
+    fn call(&mut self) -> Call {
+        match self.chance() {
+            true => {
+                let colon_token = self.symbol(Symbol::Colon);
+                let name = self.name();
+
+                Call::MethodCall(
+                    MethodCall::new(name, self.function_args()).with_colon_token(colon_token),
+                )
+            }
+
+            false => Call::AnonymousCall(self.function_args()),
+        }
+    }
+
+    fn index(&mut self) -> Index {
+        match self.chance() {
+            true => {
+                let left_bracket = self.symbol(Symbol::LeftBracket);
+                let expression = self.expression();
+
+                Index::Brackets {
+                    brackets: ContainedSpan::new(left_bracket, self.symbol(Symbol::RightBracket)),
+                    expression,
+                }
+            }
+
+            false => Index::Dot {
+                dot: self.symbol(Symbol::Dot),
+                name: self.name(),
+            },
+        }
+    }
+
+    fn function_args(&mut self) -> FunctionArgs {
+        match self.choose(3) {
+            0 => {
+                let left_parenthesis = self.symbol(Symbol::LeftParen);
+                let argument_count = self.count();
+                let arguments = self.expression_list(argument_count);
+
+                FunctionArgs::Parentheses {
+                    parentheses: ContainedSpan::new(
+                        left_parenthesis,
+                        self.symbol(Symbol::RightParen),
+                    ),
+                    arguments,
+                }
+            }
+
+            1 => FunctionArgs::String(self.string()),
+            _ => FunctionArgs::TableConstructor(self.table_constructor()),
+        }
+    }
+
+    fn table_constructor(&mut self) -> TableConstructor {
+        let left_brace = self.symbol(Symbol::LeftBrace);
+        let field_count = self.count();
+        let mut fields = Punctuated::new();
+
+        for index in 0..field_count {
+            let field = match self.choose(3) {
+                0 => Field::NoKey(self.expression()),
+
+                1 => {
+                    let key = self.name();
+                    let equal = self.symbol(Symbol::Equal);
+
+                    Field::NameKey {
+                        key,
+                        equal,
+                        value: self.expression(),
+                    }
+                }
+
+                _ => {
+                    let left_bracket = self.symbol(Symbol::LeftBracket);
+                    let key = self.expression();
+                    let brackets =
+                        ContainedSpan::new(left_bracket, self.symbol(Symbol::RightBracket));
+                    let equal = self.symbol(Symbol::Equal);
+
+                    Field::ExpressionKey {
+                        brackets,
+                        key,
+                        equal,
+                        value: self.expression(),
+                    }
+                }
+            };
+
+            // The last field can have a trailing separator
+            let separator =
+                (index + 1 < field_count || self.chance()).then(|| match self.chance() {
+                    true => self.symbol(Symbol::Semicolon),
+                    false => self.symbol(Symbol::Comma),
+                });
+
+            fields.push(Pair::new(field, separator));
+        }
+
+        TableConstructor::new()
+            .with_braces(ContainedSpan::new(
+                left_brace,
+                self.symbol(Symbol::RightBrace),
+            ))
+            .with_fields(fields)
+    }
+
+    // Types
+
+    #[cfg(feature = "luau")]
+    fn type_specifier(&mut self) -> TypeSpecifier {
+        let punctuation = self.symbol(Symbol::Colon);
+        TypeSpecifier::new(self.type_info()).with_punctuation(punctuation)
+    }
+
+    // A type that can be followed by `?`, `|`, or `&` without changing what it means
+    #[cfg(feature = "luau")]
+    fn simple_type(&mut self) -> TypeInfo {
+        let choice = match self.can_nest() {
+            true => self.choose(10),
+            false => self.choose(4),
+        };
+
+        match choice {
+            0 => TypeInfo::Basic(self.name()),
+            1 => TypeInfo::Basic(self.symbol(Symbol::Nil)),
+            2 => TypeInfo::String(self.string()),
+
+            3 => TypeInfo::Boolean(match self.chance() {
+                true => self.symbol(Symbol::True),
+                false => self.symbol(Symbol::False),
+            }),
+
+            4 => {
+                let base = self.name();
+                let arrows = self.contained_span(Symbol::LessThan, Symbol::GreaterThan);
+                let generic_count = 1 + self.choose(MAX_ITEMS);
+
+                TypeInfo::Generic {
+                    base,
+                    generics: self.punctuated(generic_count, Symbol::Comma, Self::type_info),
+                    arrows,
+                }
+            }
+
+            5 => {
+                let module = self.name();
+                let punctuation = self.symbol(Symbol::Dot);
+                let base = self.name();
+
+                let type_info = match self.chance() {
+                    true => {
+                        let arrows = self.contained_span(Symbol::LessThan, Symbol::GreaterThan);
+                        let generic_count = 1 + self.choose(MAX_ITEMS);
+
+                        IndexedTypeInfo::Generic {
+                            base,
+                            arrows,
+                            generics: self.punctuated(
+                                generic_count,
+                                Symbol::Comma,
+                                Self::type_info,
+                            ),
+                        }
+                    }
+
+                    false => IndexedTypeInfo::Basic(base),
+                };
+
+                TypeInfo::Module {
+                    module,
+                    punctuation,
+                    type_info: Box::new(type_info),
+                }
+            }
+
+            6 => self.table_type(),
+
+            7 => {
+                let left_brace = self.symbol(Symbol::LeftBrace);
+                let access = self.access();
+                let type_info = self.type_info();
+
+                TypeInfo::Array {
+                    braces: ContainedSpan::new(left_brace, self.symbol(Symbol::RightBrace)),
+                    access,
+                    type_info: Box::new(type_info),
+                }
+            }
+
+            8 => {
+                let typeof_token = self.identifier("typeof");
+                let left_parenthesis = self.symbol(Symbol::LeftParen);
+                let inner = self.expression();
+
+                TypeInfo::Typeof {
+                    typeof_token,
+                    parentheses: ContainedSpan::new(
+                        left_parenthesis,
+                        self.symbol(Symbol::RightParen),
+                    ),
+                    inner: Box::new(inner),
+                }
+            }
+
+            _ => {
+                let left_parenthesis = self.symbol(Symbol::LeftParen);
+                let type_info = self.type_info();
+
+                TypeInfo::Tuple {
+                    parentheses: ContainedSpan::new(
+                        left_parenthesis,
+                        self.symbol(Symbol::RightParen),
+                    ),
+                    types: std::iter::once(Pair::End(type_info)).collect(),
+                }
+            }
+        }
+    }
+
+    #[cfg(feature = "luau")]
+    fn parenthesize_type(&mut self, type_info: TypeInfo) -> TypeInfo {
+        TypeInfo::Tuple {
+            parentheses: self.contained_span(Symbol::LeftParen, Symbol::RightParen),
+            types: std::iter::once(Pair::End(type_info)).collect(),
+        }
+    }
+
+    // A type that can be in a union, which can be optional
+    #[cfg(feature = "luau")]
+    fn union_member(&mut self) -> TypeInfo {
+        let simple_type = self.simple_type();
+
+        match self.chance() {
+            true => TypeInfo::Optional {
+                base: Box::new(simple_type),
+                question_mark: self.symbol(Symbol::QuestionMark),
+            },
+            false => simple_type,
+        }
+    }
+
+    #[cfg(feature = "luau")]
+    fn access(&mut self) -> Option<TokenReference> {
+        match self.choose(4) {
+            1 => Some(self.identifier("read")),
+            2 => Some(self.identifier("write")),
+            _ => None,
+        }
+    }
+
+    #[cfg(feature = "luau")]
+    fn table_type(&mut self) -> TypeInfo {
+        let left_brace = self.symbol(Symbol::LeftBrace);
+        let field_count = self.count();
+        let mut fields = Punctuated::new();
+        let mut has_indexer = false;
+
+        for index in 0..field_count {
+            let access = self.access();
+
+            let key = match self.choose(3) {
+                // Tables can only have one indexer, but any number of string keys
+                0 if !has_indexer => {
+                    has_indexer = true;
+                    let left_bracket = self.symbol(Symbol::LeftBracket);
+                    let inner = self.type_info();
+
+                    // `["key"]` is a string key, which `[("key")?]` isn't
+                    let inner = match inner.tokens().next().map(|token| token.token_kind()) {
+                        Some(TokenKind::StringLiteral) => self.parenthesize_type(inner),
+                        _ => inner,
+                    };
+
+                    TypeFieldKey::IndexSignature {
+                        brackets: ContainedSpan::new(
+                            left_bracket,
+                            self.symbol(Symbol::RightBracket),
+                        ),
+                        inner,
+                    }
+                }
+
+                1 => {
+                    let left_bracket = self.symbol(Symbol::LeftBracket);
+                    let inner = TypeInfo::String(self.string());
+
+                    TypeFieldKey::IndexSignature {
+                        brackets: ContainedSpan::new(
+                            left_bracket,
+                            self.symbol(Symbol::RightBracket),
+                        ),
+                        inner,
+                    }
+                }
+
+                _ => TypeFieldKey::Name(self.name()),
+            };
+
+            let colon = self.symbol(Symbol::Colon);
+
+            let field = TypeField::new(key, self.type_info())
+                .with_access(access)
+                .with_colon_token(colon);
+
+            let separator =
+                (index + 1 < field_count || self.chance()).then(|| match self.chance() {
+                    true => self.symbol(Symbol::Semicolon),
+                    false => self.symbol(Symbol::Comma),
+                });
+
+            fields.push(Pair::new(field, separator));
+        }
+
+        TypeInfo::Table {
+            braces: ContainedSpan::new(left_brace, self.symbol(Symbol::RightBrace)),
+            fields,
+        }
+    }
+
+    // A callback type, which is never put in a union or an optional type, as its return type would
+    // take any `|` or `?` after it
+    #[cfg(feature = "luau")]
+    fn callback_type(&mut self) -> TypeInfo {
+        let generics = self.chance().then(|| self.generic_declaration(false));
+        let left_parenthesis = self.symbol(Symbol::LeftParen);
+
+        let mut argument_count = self.count();
+        let has_variadic = self.chance();
+        argument_count += has_variadic as usize;
+
+        let mut index = 0;
+        let arguments = self.punctuated(argument_count, Symbol::Comma, |generator| {
+            index += 1;
+
+            // Only the last argument can be variadic, such as `(string, ...number) -> ()`
+            if has_variadic && index == argument_count {
+                return TypeArgument::new(TypeInfo::Variadic {
+                    ellipsis: generator.symbol(Symbol::Ellipsis),
+                    type_info: Box::new(generator.simple_type()),
+                })
+                .with_name(None);
+            }
+
+            let name = generator
+                .chance()
+                .then(|| (generator.name(), generator.symbol(Symbol::Colon)));
+
+            TypeArgument::new(generator.type_info()).with_name(name)
+        });
+
+        let parentheses = ContainedSpan::new(left_parenthesis, self.symbol(Symbol::RightParen));
+        let arrow = self.symbol(Symbol::ThinArrow);
+
+        TypeInfo::Callback {
+            generics,
+            parentheses,
+            arguments,
+            arrow,
+            return_type: Box::new(self.return_type()),
+        }
+    }
+
+    // A type that can be returned from a function, which can also be a pack such as `(string, number)`
+    #[cfg(feature = "luau")]
+    fn return_type(&mut self) -> TypeInfo {
+        match self.choose(4) {
+            0 | 1 => self.type_info(),
+
+            2 => {
+                let left_parenthesis = self.symbol(Symbol::LeftParen);
+
+                // A single type in parentheses is not a pack
+                let type_count = match self.count() {
+                    1 => 2,
+                    count => count,
+                };
+
+                let types = self.punctuated(type_count, Symbol::Comma, Self::type_info);
+
+                TypeInfo::Tuple {
+                    parentheses: ContainedSpan::new(
+                        left_parenthesis,
+                        self.symbol(Symbol::RightParen),
+                    ),
+                    types,
+                }
+            }
+
+            _ => TypeInfo::Variadic {
+                ellipsis: self.symbol(Symbol::Ellipsis),
+                type_info: Box::new(self.simple_type()),
+            },
+        }
+    }
+
+    #[cfg(feature = "luau")]
+    fn generic_declaration(&mut self, with_defaults: bool) -> GenericDeclaration {
+        let left_arrow = self.symbol(Symbol::LessThan);
+        let name_count = 1 + self.choose(MAX_ITEMS);
+        let has_pack = self.chance();
+        let has_default = with_defaults && !has_pack && self.chance();
+
+        let parameter_count = name_count + has_pack as usize;
+        let mut index = 0;
+
+        // Packs come after every other generic, and once one generic has a default, so do the rest
+        let generics = self.punctuated(parameter_count, Symbol::Comma, |generator| {
+            index += 1;
+            let is_last = index == parameter_count;
+            let name = generator.name();
+
+            if has_pack && is_last {
+                GenericDeclarationParameter::new(GenericParameterInfo::Variadic {
+                    name,
+                    ellipsis: generator.symbol(Symbol::Ellipsis),
+                })
+            } else if has_default && is_last {
+                let equal = generator.symbol(Symbol::Equal);
+
+                GenericDeclarationParameter::new(GenericParameterInfo::Name(name))
+                    .with_default(Some((equal, generator.type_info())))
+            } else {
+                GenericDeclarationParameter::new(GenericParameterInfo::Name(name))
+            }
+        });
+
+        GenericDeclaration::new()
+            .with_arrows(ContainedSpan::new(
+                left_arrow,
+                self.symbol(Symbol::GreaterThan),
+            ))
+            .with_generics(generics)
+    }
+}
+
+/// Generates code for [`LuaVersion::new`], which has the syntax of every Lua version enabled
+impl<'a> arbitrary::Arbitrary<'a> for Ast {
+    fn arbitrary(unstructured: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        Ok(Generator::from_unstructured(unstructured, LuaVersion::new())?.ast())
+    }
+
+    fn arbitrary_take_rest(unstructured: arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        Ok(Generator::new(unstructured.take_rest(), LuaVersion::new()).ast())
+    }
+}
+
+// Whether the expression ends with something that would take a binary operator after it, such as
+// the else branch of an if expression or the type of a type assertion
+fn is_open_ended(expression: &Expression) -> bool {
+    match expression {
+        #[cfg(feature = "luau")]
+        Expression::IfExpression(_) | Expression::TypeAssertion { .. } => true,
+        _ => false,
+    }
+}
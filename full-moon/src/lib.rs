@@ -9,7 +9,7 @@
 /// Utilities for ASTs (Abstract Syntax Trees). Contains all nodes used by Full Moon (such as blocks).
 pub mod ast;
 
-/// Contains the `Node` trait, implemented on all nodes
+/// Contains the `Node` trait, implemented on all nodes, and `AnyNode`, a reference to any node
 pub mod node;
 
 /// Used for tokenizing, the process of converting the code to individual tokens.
@@ -19,11 +19,33 @@ pub mod tokenizer;
 /// Used to create visitors that recurse through [`Ast`](ast::Ast) nodes.
 pub mod visitors;
 
+/// Rich diagnostics for [`Error`]s, with labels, notes, and suggested fixes.
+/// Can be rendered to a terminal, or reported through [`miette`].
+#[cfg(feature = "diagnostics")]
+pub mod diagnostics;
+
+/// Parsing many files together, such as every file in a repository, in parallel with the `rayon` feature.
+pub mod workspace;
+
+/// Generating random, well-formed [`Ast`](ast::Ast)s, such as for fuzzing that printing and parsing agree.
+#[cfg(feature = "generate")]
+pub mod generate;
+
+/// Formatting [`Ast`](ast::Ast)s with configurable indentation, line width, quotes, and more, keeping comments.
+pub mod format;
+
+/// Minifying [`Ast`](ast::Ast)s by removing comments and whitespace, renaming locals, and shortening literals.
+pub mod minify;
+
+/// Exporting [`Ast`](ast::Ast)s as JSON in the shape of luaparse's trees, for tools written for it.
+#[cfg(feature = "estree")]
+pub mod estree;
+
 mod private;
 mod short_string;
 mod util;
 
-pub use ast::LuaVersion;
+pub use ast::{LuaVersion, ParseOptions};
 pub use short_string::ShortString;
 use tokenizer::Position;
 
@@ -101,11 +123,87 @@ pub fn parse(code: &str) -> Result<ast::Ast, Vec<Error>> {
 ///    will produce a phantom `then` token in order to produce a usable [`If`](ast::If) struct.
 ///    These phantom tokens will have a null position. If you need accurate positions from the
 ///    phantom tokens, you can call [`Ast::update_positions`](ast::Ast::update_positions).
-/// 2. The code, when printed, is not guaranteed to be valid Lua.
+///    Phantom tokens are marked with [`TokenReference::is_phantom`](tokenizer::TokenReference::is_phantom),
+///    and are not printed.
+/// 2. Tokens that could not be placed anywhere are kept in [`Stmt::Error`](ast::Stmt::Error)
+///    and [`Expression::Error`](ast::Expression::Error) nodes, so that printing the Ast gives back
+///    the original code. This does not hold for code that could not be tokenized, such as
+///    unknown characters.
+///
+///    ```rust
+///    # use full_moon::LuaVersion;
+///    let code = "local x = 1 + )\nprint(x)";
+///    let result = full_moon::parse_fallible(code, LuaVersion::new());
+///    assert!(!result.errors().is_empty());
+///    assert_eq!(result.ast().to_string(), code);
+///    ```
+/// 3. The code, when printed, is not guaranteed to be valid Lua.
 ///    This can happen in the case of something like `local x = if`, which will produce a
-///    [`LocalAssignment`](ast::LocalAssignment) that would print to `local x =`.
-/// 3. There are no stability guarantees for partial Ast results, but they are consistent
+///    [`LocalAssignment`](ast::LocalAssignment) that prints back to the same invalid code.
+/// 4. There are no stability guarantees for partial Ast results, but they are consistent
 ///    within the same exact version of full-moon.
 pub fn parse_fallible(code: &str, lua_version: LuaVersion) -> ast::AstResult {
     ast::AstResult::parse_fallible(code, lua_version)
 }
+
+/// Like [`parse_fallible`], but with [`ParseOptions`] such as limits on how deeply the code can
+/// be nested, for when parsing untrusted code, or dropping trivia for faster parsing with
+/// [`ParseOptions::with_trivia_mode`].
+///
+/// If a limit is reached, or parsing is cancelled, an error is reported at the token where parsing
+/// stopped, and the rest of the code is left out of the [`Ast`](ast::Ast).
+/// Unlike `parse` and `parse_fallible`, which can overflow the stack on code such as
+/// `((((...))))`, this is safe as long as [`ParseOptions::max_stack_size`] fits in the stack.
+pub fn parse_with_options(code: &str, options: &ParseOptions) -> ast::AstResult {
+    ast::AstResult::parse_with_options(code, options)
+}
+
+/// Parses a single expression, such as `1 + 2`, using a pinned Lua version.
+/// Any input after the expression is an error.
+///
+/// ```rust
+/// # use full_moon::LuaVersion;
+/// let expression = full_moon::parse_expression("1 + 2", LuaVersion::new());
+/// assert!(expression.errors().is_empty());
+/// assert_eq!(expression.node().unwrap().to_string(), "1 + 2");
+///
+/// assert!(full_moon::parse_expression("1 + 2 3", LuaVersion::new()).into_result().is_err());
+/// ```
+pub fn parse_expression(
+    code: &str,
+    lua_version: LuaVersion,
+) -> ast::FragmentResult<ast::Expression> {
+    ast::fragments::parse_expression(code, lua_version)
+}
+
+/// Parses a single statement, such as `local x = 1`, using a pinned Lua version.
+/// Any input after the statement is an error, including a semicolon.
+/// Last statements, such as `return`, are not statements and should be parsed with [`parse_block`].
+pub fn parse_statement(code: &str, lua_version: LuaVersion) -> ast::FragmentResult<ast::Stmt> {
+    ast::fragments::parse_statement(code, lua_version)
+}
+
+/// Parses a block of statements using a pinned Lua version.
+/// Unlike [`parse_fallible`], this does not try to recover from input after the block, such as a stray `end`.
+pub fn parse_block(code: &str, lua_version: LuaVersion) -> ast::FragmentResult<ast::Block> {
+    ast::fragments::parse_block(code, lua_version)
+}
+
+/// Parses a single type, such as `string | number`, using a pinned Lua version.
+/// Any input after the type is an error.
+#[cfg(feature = "luau")]
+pub fn parse_type_info(
+    code: &str,
+    lua_version: LuaVersion,
+) -> ast::FragmentResult<ast::luau::TypeInfo> {
+    ast::fragments::parse_type_info(code, lua_version)
+}
+
+/// Parses the body of a function, starting from the parameters, such as `(a, b) return a + b end`,
+/// using a pinned Lua version. Any input after the `end` is an error.
+pub fn parse_function_body(
+    code: &str,
+    lua_version: LuaVersion,
+) -> ast::FragmentResult<ast::FunctionBody> {
+    ast::fragments::parse_function_body(code, lua_version)
+}
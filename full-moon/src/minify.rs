@@ -0,0 +1,938 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::{
+    ast::{constructors::needs_semicolon_before, *},
+    tokenizer::{
+        Lexer, LexerResult, StringLiteralQuoteType, Symbol, Token, TokenKind, TokenReference,
+        TokenType,
+    },
+    visitors::{Visit, VisitorMut, VisitorRef},
+    LuaVersion,
+};
+
+/// Options for [`minify`], made with [`MinifyOptions::new`] and changed with its `with_*` methods.
+#[derive(Clone, Debug)]
+pub struct MinifyOptions {
+    rename_locals: bool,
+    shorten_literals: bool,
+    keep_hot_comments: bool,
+    keep_license_comments: bool,
+}
+
+impl MinifyOptions {
+    /// Creates options for renaming locals, shortening literals, and removing every comment.
+    pub fn new() -> Self {
+        Self {
+            rename_locals: true,
+            shorten_literals: true,
+            keep_hot_comments: false,
+            keep_license_comments: false,
+        }
+    }
+
+    /// Whether locals, parameters, and upvalues are given short names. On by default.
+    pub fn rename_locals(&self) -> bool {
+        self.rename_locals
+    }
+
+    /// Whether numbers and strings are rewritten in their shortest spelling. On by default.
+    pub fn shorten_literals(&self) -> bool {
+        self.shorten_literals
+    }
+
+    /// Whether hot comments at the start of the file, such as `--!strict`, are kept. Off by default.
+    pub fn keep_hot_comments(&self) -> bool {
+        self.keep_hot_comments
+    }
+
+    /// Whether comments at the start of the file that mention a license or copyright are kept.
+    /// Off by default.
+    pub fn keep_license_comments(&self) -> bool {
+        self.keep_license_comments
+    }
+
+    /// Returns a new MinifyOptions with locals renamed or not
+    pub fn with_rename_locals(self, rename_locals: bool) -> Self {
+        Self {
+            rename_locals,
+            ..self
+        }
+    }
+
+    /// Returns a new MinifyOptions with literals shortened or not
+    pub fn with_shorten_literals(self, shorten_literals: bool) -> Self {
+        Self {
+            shorten_literals,
+            ..self
+        }
+    }
+
+    /// Returns a new MinifyOptions with hot comments kept or not
+    pub fn with_keep_hot_comments(self, keep_hot_comments: bool) -> Self {
+        Self {
+            keep_hot_comments,
+            ..self
+        }
+    }
+
+    /// Returns a new MinifyOptions with license comments kept or not
+    pub fn with_keep_license_comments(self, keep_license_comments: bool) -> Self {
+        Self {
+            keep_license_comments,
+            ..self
+        }
+    }
+}
+
+impl Default for MinifyOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Returns the Ast made as small as it can be when printed, without changing what it does.
+///
+/// Comments and whitespace are removed, other than what keeps tokens apart, and semicolons are
+/// removed wherever the statements around them parse the same without them. Shebangs are kept.
+///
+/// Locals, parameters, and upvalues are renamed to the shortest names not used by anything else in
+/// scope. Globals, fields, and methods keep their names, as do locals named `_ENV`, which globals are
+/// looked up in from Lua 5.2 on. Code that finds locals by name, such as with `debug.getlocal`, can
+/// see different names after renaming.
+///
+/// Numbers and strings are rewritten in their shortest spelling with the same value, such as `0.50`
+/// as `.5`, `0xFF` as `255`, and `'it\'s'` as `"it's"`.
+///
+/// ```rust
+/// # use full_moon::minify::{self, MinifyOptions};
+/// # fn main() -> Result<(), Vec<full_moon::Error>> {
+/// let ast = full_moon::parse(
+///     "--!strict\n-- Adds a half\nlocal function addHalf(number)\n\treturn number + 0.50\nend\nprint(addHalf(0x10))",
+/// )?;
+///
+/// assert_eq!(
+///     minify::minify(ast.clone(), &MinifyOptions::new()).to_string(),
+///     "local function a(b)return b+.5 end print(a(16))"
+/// );
+///
+/// let options = MinifyOptions::new().with_keep_hot_comments(true);
+/// assert_eq!(
+///     minify::minify(ast, &options).to_string(),
+///     "--!strict\nlocal function a(b)return b+.5 end print(a(16))"
+/// );
+/// # Ok(())
+/// # }
+/// ```
+pub fn minify(ast: Ast, options: &MinifyOptions) -> Ast {
+    let renames = if options.rename_locals {
+        let mut resolver = Resolver::default();
+        ast.nodes().visit(&mut resolver);
+        resolver.renames()
+    } else {
+        HashMap::new()
+    };
+
+    let mut minifier = Minifier {
+        options,
+        renames,
+        index: 0,
+        started: false,
+    };
+
+    minifier.visit_ast(ast).separate_tokens()
+}
+
+// A local, parameter, or upvalue
+struct Binding {
+    name: String,
+    renamable: bool,
+    // The bindings in scope where this one is declared, which it can't share a name with
+    in_scope: Vec<usize>,
+    tokens: Vec<*const TokenReference>,
+}
+
+// Finds what every name refers to, going through the Ast in the same order as the minifier
+#[derive(Default)]
+struct Resolver {
+    token_indices: HashMap<*const TokenReference, usize>,
+    bindings: Vec<Binding>,
+    scopes: Vec<Vec<usize>>,
+    globals: HashSet<String>,
+    // Names declared at the start of a block, such as parameters and loop variables
+    pending: HashMap<*const Block, Vec<(String, Option<*const TokenReference>)>>,
+    // The locals of a `repeat` block are in scope for its condition, after the block ends
+    repeat_blocks: HashSet<*const Block>,
+}
+
+impl Resolver {
+    fn declare(&mut self, name: String, token: Option<*const TokenReference>) {
+        let id = self.bindings.len();
+
+        self.bindings.push(Binding {
+            renamable: token.is_some() && name != "_ENV",
+            name,
+            in_scope: self.scopes.iter().flatten().copied().collect(),
+            tokens: token.into_iter().collect(),
+        });
+
+        self.scopes
+            .last_mut()
+            .expect("declared outside of a block")
+            .push(id);
+    }
+
+    fn declare_at_start(&mut self, block: &Block, token: &TokenReference) {
+        self.pending
+            .entry(block)
+            .or_default()
+            .push((token.token().to_string(), Some(token)));
+    }
+
+    fn reference(&mut self, token: &TokenReference) {
+        let name = token.token().to_string();
+        let binding = self
+            .scopes
+            .iter()
+            .flatten()
+            .rev()
+            .find(|&&id| self.bindings[id].name == name);
+
+        match binding {
+            Some(&id) => self.bindings[id].tokens.push(token),
+            None => {
+                self.globals.insert(name);
+            }
+        }
+    }
+
+    // The new names of the tokens that are renamed, by the order they're visited in
+    fn renames(&self) -> HashMap<usize, String> {
+        let reserved: HashSet<&str> = self
+            .globals
+            .iter()
+            .map(String::as_str)
+            .chain(
+                self.bindings
+                    .iter()
+                    .filter(|binding| !binding.renamable)
+                    .map(|binding| binding.name.as_str()),
+            )
+            .collect();
+
+        let mut names: Vec<String> = Vec::with_capacity(self.bindings.len());
+        let mut renames = HashMap::new();
+
+        for binding in &self.bindings {
+            if !binding.renamable {
+                names.push(binding.name.clone());
+                continue;
+            }
+
+            let taken: HashSet<&str> = binding
+                .in_scope
+                .iter()
+                .map(|&id| names[id].as_str())
+                .collect();
+
+            let name = (0..)
+                .map(short_name)
+                .find(|name| {
+                    !taken.contains(name.as_str())
+                        && !reserved.contains(name.as_str())
+                        && !is_reserved_word(name)
+                })
+                .unwrap();
+
+            for token in &binding.tokens {
+                renames.insert(self.token_indices[token], name.clone());
+            }
+
+            names.push(name);
+        }
+
+        renames
+    }
+}
+
+impl<'a> VisitorRef<'a> for Resolver {
+    fn visit_token_reference(&mut self, token: &'a TokenReference) {
+        let index = self.token_indices.len();
+        self.token_indices.insert(token, index);
+    }
+
+    fn visit_block(&mut self, block: &'a Block) {
+        self.scopes.push(Vec::new());
+
+        for (name, token) in self
+            .pending
+            .remove(&(block as *const _))
+            .unwrap_or_default()
+        {
+            self.declare(name, token);
+        }
+    }
+
+    fn visit_block_end(&mut self, block: &'a Block) {
+        if !self.repeat_blocks.contains(&(block as *const _)) {
+            self.scopes.pop();
+        }
+    }
+
+    fn visit_repeat(&mut self, repeat: &'a Repeat) {
+        self.repeat_blocks.insert(repeat.block());
+    }
+
+    fn visit_repeat_end(&mut self, _repeat: &'a Repeat) {
+        self.scopes.pop();
+    }
+
+    fn visit_local_assignment_end(&mut self, local_assignment: &'a LocalAssignment) {
+        for name in local_assignment.names() {
+            self.declare(name.token().to_string(), Some(name));
+        }
+    }
+
+    fn visit_local_function(&mut self, local_function: &'a LocalFunction) {
+        let name = local_function.name();
+        self.declare(name.token().to_string(), Some(name));
+    }
+
+    fn visit_function_declaration(&mut self, declaration: &'a FunctionDeclaration) {
+        // Methods have an implicit `self` parameter, which can't be renamed
+        if declaration.name().method_colon().is_some() {
+            self.pending
+                .entry(declaration.body().block())
+                .or_default()
+                .push(("self".to_owned(), None));
+        }
+    }
+
+    fn visit_function_body(&mut self, body: &'a FunctionBody) {
+        for parameter in body.parameters() {
+            if let Parameter::Name(name) = parameter {
+                self.declare_at_start(body.block(), name);
+            }
+        }
+    }
+
+    fn visit_numeric_for(&mut self, numeric_for: &'a NumericFor) {
+        self.declare_at_start(numeric_for.block(), numeric_for.index_variable());
+    }
+
+    fn visit_generic_for(&mut self, generic_for: &'a GenericFor) {
+        for name in generic_for.names() {
+            self.declare_at_start(generic_for.block(), name);
+        }
+    }
+
+    fn visit_var(&mut self, var: &'a Var) {
+        if let Var::Name(name) = var {
+            self.reference(name);
+        }
+    }
+
+    fn visit_prefix(&mut self, prefix: &'a Prefix) {
+        if let Prefix::Name(name) = prefix {
+            self.reference(name);
+        }
+    }
+
+    fn visit_function_name(&mut self, function_name: &'a FunctionName) {
+        if let Some(name) = function_name.names().iter().next() {
+            self.reference(name);
+        }
+    }
+}
+
+// The name for the given index, going through every one letter name, then every two letter name,
+// and so on
+fn short_name(mut index: usize) -> String {
+    const FIRST: &[u8] = b"abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ_";
+    const REST: &[u8] = b"abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ_0123456789";
+
+    let mut length = 1;
+    let mut count = FIRST.len();
+
+    while index >= count {
+        index -= count;
+        length += 1;
+        count *= REST.len();
+    }
+
+    let mut name = vec![0; length];
+
+    for (position, character) in name.iter_mut().enumerate().rev() {
+        let characters = if position == 0 { FIRST } else { REST };
+        *character = characters[index % characters.len()];
+        index /= characters.len();
+    }
+
+    String::from_utf8(name).unwrap()
+}
+
+// Keywords of every Lua version, including `goto` without the lua52 feature, and names that mean
+// something in some places, such as `type` in Luau
+fn is_reserved_word(name: &str) -> bool {
+    Symbol::from_str(name, LuaVersion::new()).is_some()
+        || matches!(
+            name,
+            "goto" | "self" | "type" | "typeof" | "export" | "continue" | "_ENV"
+        )
+}
+
+// Removes trivia, renames locals, and shortens literals, in the order the resolver visited tokens
+struct Minifier<'a> {
+    options: &'a MinifyOptions,
+    renames: HashMap<usize, String>,
+    index: usize,
+    started: bool,
+}
+
+impl Minifier<'_> {
+    // Comments before the first token of the file that are kept, and the shebang
+    fn header(&self, trivia: Vec<Token>) -> Vec<Token> {
+        let mut header: Vec<Token> = trivia
+            .into_iter()
+            .filter(|trivia| match trivia.token_type() {
+                TokenType::Shebang { .. } => true,
+
+                TokenType::SingleLineComment { comment } if comment.starts_with('!') => {
+                    self.options.keep_hot_comments
+                }
+
+                TokenType::SingleLineComment { comment }
+                | TokenType::MultiLineComment { comment, .. } => {
+                    let comment = comment.to_lowercase();
+                    self.options.keep_license_comments
+                        && ["license", "licence", "copyright"]
+                            .iter()
+                            .any(|word| comment.contains(word))
+                }
+
+                _ => false,
+            })
+            .collect();
+
+        // Nothing can come after a single line comment but a new line
+        for index in (0..header.len()).rev() {
+            if matches!(
+                header[index].token_kind(),
+                TokenKind::SingleLineComment | TokenKind::Shebang
+            ) {
+                header.insert(
+                    index + 1,
+                    Token::new(TokenType::Whitespace {
+                        characters: "\n".into(),
+                    }),
+                );
+            }
+        }
+
+        header
+    }
+
+    fn minify_token(&self, token: &Token) -> Option<Token> {
+        if let Some(name) = self.renames.get(&self.index) {
+            return Some(Token::new(TokenType::Identifier {
+                identifier: name.as_str().into(),
+            }));
+        }
+
+        if !self.options.shorten_literals {
+            return None;
+        }
+
+        let text = match token.token_type() {
+            TokenType::Number { text } => shorten_number(text)?,
+
+            TokenType::StringLiteral {
+                literal,
+                multi_line_depth,
+                quote_type,
+            } => shorten_string(literal, *multi_line_depth, *quote_type)?,
+
+            _ => return None,
+        };
+
+        if text.len() >= token.to_string().len() {
+            return None;
+        }
+
+        // The new spelling has to be a single token of the same kind
+        match Lexer::new(&text, LuaVersion::new()).collect() {
+            LexerResult::Ok(tokens)
+                if tokens.len() == 2 && tokens[0].token_kind() == token.token_kind() =>
+            {
+                Some(tokens[0].clone())
+            }
+
+            _ => None,
+        }
+    }
+}
+
+impl VisitorMut for Minifier<'_> {
+    fn visit_token_reference(&mut self, token: TokenReference) -> TokenReference {
+        let minified = self.minify_token(token.token());
+        self.index += 1;
+
+        let leading_trivia = if self.started || token.is_phantom() {
+            Vec::new()
+        } else {
+            self.started = true;
+            self.header(token.leading_trivia().cloned().collect())
+        };
+
+        TokenReference {
+            leading_trivia,
+            token: minified.unwrap_or(token.token),
+            trailing_trivia: Vec::new(),
+            ..token
+        }
+    }
+
+    fn visit_eof(&mut self, token: TokenReference) -> TokenReference {
+        let leading_trivia = if self.started {
+            Vec::new()
+        } else {
+            self.header(token.leading_trivia().cloned().collect())
+        };
+
+        TokenReference {
+            leading_trivia,
+            trailing_trivia: Vec::new(),
+            ..token
+        }
+    }
+
+    // Only done once everything in the block has been visited, so tokens are visited in the same
+    // order as the resolver visited them
+    fn visit_block_end(&mut self, mut block: Block) -> Block {
+        let stmts = block.stmts_mut();
+
+        for index in 0..stmts.len() {
+            let needed = stmts
+                .get(index + 1)
+                .is_some_and(|(next, _)| needs_semicolon_before(next));
+
+            if !needed {
+                stmts[index].1 = None;
+            }
+        }
+
+        if let Some((_, semicolon)) = block.last_stmt_mut() {
+            *semicolon = None;
+        }
+
+        block
+    }
+}
+
+// The shortest spelling of a number with the same value, if it can be spelled differently
+fn shorten_number(text: &str) -> Option<String> {
+    // Luau allows underscores between digits, such as in `1_000`
+    let text = text.replace('_', "");
+    let lowercase = text.to_lowercase();
+
+    let integer = |digits: &str, radix: u32, prefix: &str| {
+        let value = u64::from_str_radix(digits, radix).ok()?;
+        let trimmed = format!("{prefix}{}", digits.trim_start_matches('0'));
+
+        // Above 2^53, integers can't be written as a float in Lua 5.1, or as an integer that
+        // doesn't wrap around in Lua 5.3
+        if value < 1 << 53 && value.to_string().len() <= trimmed.len() {
+            Some(value.to_string())
+        } else if trimmed.len() > prefix.len() {
+            Some(trimmed)
+        } else {
+            Some(format!("{prefix}0"))
+        }
+    };
+
+    if let Some(digits) = lowercase.strip_prefix("0x") {
+        if digits.contains(['.', 'p']) {
+            return Some(text);
+        }
+
+        let prefix = &text[..2];
+        return integer(&text[2..], 16, prefix);
+    }
+
+    if let Some(digits) = lowercase.strip_prefix("0b") {
+        return integer(digits, 2, "0b");
+    }
+
+    let (mantissa, exponent) = match lowercase.split_once('e') {
+        Some((mantissa, exponent)) => (mantissa, Some(exponent)),
+        None => (lowercase.as_str(), None),
+    };
+
+    let shortened = match (mantissa.split_once('.'), exponent) {
+        (None, None) => {
+            let digits = mantissa.trim_start_matches('0');
+            return Some(if digits.is_empty() { "0" } else { digits }.to_owned());
+        }
+
+        (fraction, exponent) => {
+            let (whole, fraction) = fraction.unwrap_or((mantissa, ""));
+            let whole = whole.trim_start_matches('0');
+            let fraction = fraction.trim_end_matches('0');
+
+            let mantissa = match (whole, fraction) {
+                ("", "") if exponent.is_some() => "0".to_owned(),
+                ("", "") => "0.".to_owned(),
+                (whole, "") if exponent.is_some() => whole.to_owned(),
+                (whole, "") => format!("{whole}."),
+                (whole, fraction) => format!("{whole}.{fraction}"),
+            };
+
+            match exponent {
+                Some(exponent) => {
+                    let (sign, digits) = match exponent.strip_prefix('-') {
+                        Some(digits) => ("-", digits),
+                        None => ("", exponent.trim_start_matches('+')),
+                    };
+
+                    let digits = digits.trim_start_matches('0');
+                    format!(
+                        "{mantissa}e{sign}{}",
+                        if digits.is_empty() { "0" } else { digits }
+                    )
+                }
+
+                None => mantissa,
+            }
+        }
+    };
+
+    (shortened.parse::<f64>().ok()? == lowercase.parse::<f64>().ok()?).then_some(shortened)
+}
+
+// The shortest spelling of a string with the same contents, if it can be spelled differently
+fn shorten_string(
+    literal: &str,
+    multi_line_depth: usize,
+    quote_type: StringLiteralQuoteType,
+) -> Option<String> {
+    let bytes = match quote_type {
+        StringLiteralQuoteType::Single | StringLiteralQuoteType::Double => unescape(literal)?,
+
+        // The first new line of a string in brackets isn't part of it, and the new lines after it
+        // can be spelled differently, so only strings on one line are rewritten
+        StringLiteralQuoteType::Brackets => {
+            if literal.contains(['\r', '\n']) {
+                return Some(brackets(literal, multi_line_depth));
+            }
+
+            literal.as_bytes().to_vec()
+        }
+
+        #[allow(unreachable_patterns)]
+        _ => return None,
+    };
+
+    let mut spellings = vec![quote(&bytes, b'"'), quote(&bytes, b'\'')];
+
+    // Strings in brackets can't have escapes, so control characters would be written as they are
+    if let Ok(text) = std::str::from_utf8(&bytes) {
+        if !text.contains(|character: char| character.is_ascii_control() && character != '\t') {
+            spellings.push(brackets(text, multi_line_depth));
+        }
+    }
+
+    spellings.into_iter().min_by_key(String::len)
+}
+
+// The bytes of a quoted string, unless it has an escape that means different things in different
+// versions of Lua, such as `\x` or `\z`
+pub(crate) fn unescape(literal: &str) -> Option<Vec<u8>> {
+    let mut bytes = Vec::with_capacity(literal.len());
+    let mut characters = literal.bytes().peekable();
+
+    while let Some(byte) = characters.next() {
+        if byte != b'\\' {
+            bytes.push(byte);
+            continue;
+        }
+
+        let escaped = characters.next()?;
+
+        bytes.push(match escaped {
+            b'a' => 7,
+            b'b' => 8,
+            b'f' => 12,
+            b'n' => b'\n',
+            b'r' => b'\r',
+            b't' => b'\t',
+            b'v' => 11,
+            b'\\' | b'"' | b'\'' => escaped,
+
+            // A new line after a backslash is a new line in the string, even if it's `\r\n`
+            b'\n' | b'\r' => {
+                if characters
+                    .peek()
+                    .is_some_and(|&next| matches!((escaped, next), (b'\r', b'\n') | (b'\n', b'\r')))
+                {
+                    characters.next();
+                }
+
+                b'\n'
+            }
+
+            b'0'..=b'9' => {
+                let mut value = u32::from(escaped - b'0');
+
+                for _ in 0..2 {
+                    match characters.peek() {
+                        Some(digit @ b'0'..=b'9') => {
+                            value = value * 10 + u32::from(digit - b'0');
+                            characters.next();
+                        }
+                        _ => break,
+                    }
+                }
+
+                u8::try_from(value).ok()?
+            }
+
+            _ => return None,
+        });
+    }
+
+    Some(bytes)
+}
+
+// The bytes in quotes, with only what has to be escaped escaped
+fn quote(bytes: &[u8], quote: u8) -> String {
+    let mut text = String::with_capacity(bytes.len() + 2);
+    text.push(char::from(quote));
+
+    let mut index = 0;
+
+    while index < bytes.len() {
+        let byte = bytes[index];
+
+        // Characters outside of ASCII are kept as they are if they're valid UTF-8
+        if !byte.is_ascii() {
+            let length = match byte {
+                0xC0..=0xDF => 2,
+                0xE0..=0xEF => 3,
+                0xF0..=0xF7 => 4,
+                _ => 1,
+            };
+
+            if let Some(Ok(character)) = bytes.get(index..index + length).map(std::str::from_utf8) {
+                text.push_str(character);
+                index += length;
+                continue;
+            }
+        }
+
+        match byte {
+            b'\\' => text.push_str("\\\\"),
+            b'\n' => text.push_str("\\n"),
+            b'\r' => text.push_str("\\r"),
+            byte if byte == quote => {
+                text.push('\\');
+                text.push(char::from(byte));
+            }
+
+            byte if byte == b'\t' || (byte.is_ascii() && !byte.is_ascii_control()) => {
+                text.push(char::from(byte))
+            }
+
+            // A digit after the escape would be read as part of it
+            byte if bytes.get(index + 1).is_some_and(u8::is_ascii_digit) => {
+                text.push_str(&format!("\\{byte:03}"))
+            }
+
+            byte => text.push_str(&format!("\\{byte}")),
+        }
+
+        index += 1;
+    }
+
+    text.push(char::from(quote));
+    text
+}
+
+// The text in brackets, with as few equals signs as can be used
+fn brackets(text: &str, multi_line_depth: usize) -> String {
+    let depth = (0..=multi_line_depth)
+        .find(|&depth| {
+            let equals = "=".repeat(depth);
+            !text.contains(&format!("]{equals}]")) && !text.ends_with(&format!("]{equals}"))
+        })
+        .unwrap_or(multi_line_depth);
+
+    let equals = "=".repeat(depth);
+    format!("[{equals}[{text}]{equals}]")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse;
+    use pretty_assertions::assert_eq;
+
+    fn minify_with(code: &str, options: &MinifyOptions) -> String {
+        let minified = minify(parse(code).unwrap(), options).to_string();
+        assert!(
+            parse(&minified).is_ok(),
+            "minifying {code:?} gave {minified:?}, which doesn't parse"
+        );
+
+        minified
+    }
+
+    #[test]
+    fn test_rename_locals() {
+        for (code, expected) in [
+            (
+                "local first, second = 1, 2\nprint(first + second)",
+                "local a,b=1,2 print(a+b)",
+            ),
+            // Globals and fields keep their names, and aren't used as new names
+            ("local value = a.value\na = value", "local b=a.value a=b"),
+            // Shadowed locals get new names of their own
+            (
+                "local x = 1\ndo\n\tlocal x = x + 1\n\tprint(x)\nend\nprint(x)",
+                "local a=1 do local b=a+1 print(b)end print(a)",
+            ),
+            // Upvalues keep the name they're given outside of the function
+            (
+                "local count = 0\nlocal function increment(by)\n\tcount = count + by\nend",
+                "local a=0 local function b(c)a=a+c end",
+            ),
+            (
+                "for index, value in pairs(t) do print(index, value) end\nfor i = 1, 10 do end",
+                "for a,b in pairs(t)do print(a,b)end for a=1,10 do end",
+            ),
+            (
+                "repeat local done = step() until done",
+                "repeat local a=step()until a",
+            ),
+            // `self` can't be renamed, so nothing else is named it
+            (
+                "function Class:method(argument) return self, argument end",
+                "function Class:method(a)return self,a end",
+            ),
+            (
+                "local Class = {}\nfunction Class.new() return Class end",
+                "local a={}function a.new()return a end",
+            ),
+        ] {
+            assert_eq!(minify_with(code, &MinifyOptions::new()), expected);
+        }
+
+        assert_eq!(
+            minify_with(
+                "local first = 1 print(first)",
+                &MinifyOptions::new().with_rename_locals(false)
+            ),
+            "local first=1 print(first)"
+        );
+    }
+
+    #[test]
+    fn test_short_names() {
+        assert_eq!(short_name(0), "a");
+        assert_eq!(short_name(52), "_");
+        assert_eq!(short_name(53), "aa");
+        assert_eq!(short_name(53 + 63), "ba");
+        assert!(is_reserved_word("do"));
+        assert!(is_reserved_word("goto"));
+        assert!(!is_reserved_word("a"));
+    }
+
+    #[test]
+    fn test_shorten_literals() {
+        for (number, expected) in [
+            ("0.50", ".5"),
+            ("1.0", "1."),
+            ("007", "7"),
+            ("0x10", "16"),
+            ("0xFFFFFFFFFFFFFFFF", "0xFFFFFFFFFFFFFFFF"),
+            ("0x000FFFFFFFFFFFFFFF", "0xFFFFFFFFFFFFFFF"),
+            ("1e+05", "1e5"),
+            ("1.50E-02", "1.5e-2"),
+            ("0.0", "0."),
+        ] {
+            assert_eq!(
+                minify_with(&format!("x = {number}"), &MinifyOptions::new()),
+                format!("x={expected}"),
+                "shortening {number}"
+            );
+        }
+
+        for (string, expected) in [
+            (r#"'it\'s'"#, r#""it's""#),
+            (r#""\"quoted\"""#, r#"'"quoted"'"#),
+            (r"'\65\066'", r#""AB""#),
+            (r"'\0001'", r"'\0001'"),
+            (r"'\0010'", r"'\0010'"),
+            (r"'\10\r'", r#""\n\r""#),
+            ("[==[brackets]==]", r#""brackets""#),
+            ("[==[\nlines\n]==]", "[[\nlines\n]]"),
+            ("[=[a]]b]=]", r#""a]]b""#),
+            // Escapes that mean different things in different versions are left alone
+            (r#""\x41""#, r#""\x41""#),
+        ] {
+            assert_eq!(
+                minify_with(&format!("x = {string}"), &MinifyOptions::new()),
+                format!("x={expected}"),
+                "shortening {string}"
+            );
+        }
+
+        assert_eq!(
+            minify_with(
+                "x = 0x10 .. 'a'",
+                &MinifyOptions::new().with_shorten_literals(false)
+            ),
+            "x=0x10 ..'a'"
+        );
+    }
+
+    #[test]
+    fn test_comments() {
+        let code =
+            "#!/usr/bin/env lua\n--!strict\n-- Copyright 2024 Someone\n-- Prints\nprint(1) -- one";
+
+        assert_eq!(
+            minify_with(code, &MinifyOptions::new()),
+            "#!/usr/bin/env lua\nprint(1)"
+        );
+
+        assert_eq!(
+            minify_with(code, &MinifyOptions::new().with_keep_hot_comments(true)),
+            "#!/usr/bin/env lua\n--!strict\nprint(1)"
+        );
+
+        assert_eq!(
+            minify_with(
+                code,
+                &MinifyOptions::new()
+                    .with_keep_hot_comments(true)
+                    .with_keep_license_comments(true)
+            ),
+            "#!/usr/bin/env lua\n--!strict\n-- Copyright 2024 Someone\nprint(1)"
+        );
+
+        assert_eq!(
+            minify_with(
+                "--[[ MIT License ]]\n-- nothing else",
+                &MinifyOptions::new().with_keep_license_comments(true)
+            ),
+            "--[[ MIT License ]]"
+        );
+    }
+
+    #[test]
+    fn test_semicolons() {
+        assert_eq!(
+            minify_with("a = b; (f)(); c(); return;", &MinifyOptions::new()),
+            "a=b;(f)()c()return"
+        );
+    }
+}
@@ -1,9 +1,17 @@
 use crate::{
-    ast::Ast,
+    ast::{
+        self,
+        punctuated::{Pair, Punctuated},
+        span::ContainedSpan,
+        Ast,
+    },
     private,
-    tokenizer::{Position, Token, TokenReference},
+    tokenizer::{self, Position, Token, TokenReference},
+};
+use std::{
+    fmt,
+    hash::{Hash, Hasher},
 };
-use std::fmt;
 
 /// Used to represent nodes such as tokens or function definitions
 ///
@@ -21,7 +29,38 @@ pub trait Node: private::Sealed {
         Self: Sized;
 
     /// The token references that comprise a node
-    fn tokens(&self) -> Tokens;
+    fn tokens(&self) -> Tokens<'_>;
+
+    // Feeds the hasher with everything that `similar` compares, and nothing that it doesn't, such
+    // as the separators of a `Punctuated`, so that similar nodes have the same structural hash
+    #[doc(hidden)]
+    fn hash_similar(&self, state: &mut dyn Hasher);
+
+    /// A hash of the node that ignores trivia and positions, so that nodes that are
+    /// [`similar`](Node::similar) have the same hash. Nodes that aren't similar almost always
+    /// have different hashes, but can have the same one, such as nodes of different types with the
+    /// same tokens. Like `similar`, it leaves out the separators of a
+    /// [`Punctuated`](ast::punctuated::Punctuated), so `{1, 2}` and `{1; 2;}` have the same hash.
+    /// The hash is 64 bit FNV-1a, so it is the same across builds, platforms, and features for the
+    /// same version of full-moon.
+    ///
+    /// ```rust
+    /// # use full_moon::node::Node;
+    /// # fn main() -> Result<(), Vec<full_moon::Error>> {
+    /// let ast = full_moon::parse("local x = 1")?;
+    /// let spaced = full_moon::parse("local  x=1 -- one")?;
+    /// let changed = full_moon::parse("local x = 2")?;
+    ///
+    /// assert_eq!(ast.structural_hash(), spaced.structural_hash());
+    /// assert_ne!(ast.structural_hash(), changed.structural_hash());
+    /// # Ok(())
+    /// # }
+    /// ```
+    fn structural_hash(&self) -> u64 {
+        let mut hasher = StructuralHasher::default();
+        self.hash_similar(&mut hasher);
+        hasher.finish()
+    }
 
     /// The full range of a node, if it has both start and end positions
     fn range(&self) -> Option<(Position, Position)> {
@@ -49,6 +88,92 @@ pub trait Node: private::Sealed {
     }
 }
 
+// The hasher of `Node::structural_hash`, which is 64 bit FNV-1a. Unlike `DefaultHasher`, which
+// can change between versions of Rust, it always gives the same hashes, and integers are hashed
+// as 64 bit little endian, so that they're the same on every platform.
+pub(crate) struct StructuralHasher(u64);
+
+impl Default for StructuralHasher {
+    fn default() -> Self {
+        Self(0xcbf2_9ce4_8422_2325)
+    }
+}
+
+impl Hasher for StructuralHasher {
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 = (self.0 ^ u64::from(*byte)).wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+
+    fn write_u16(&mut self, value: u16) {
+        self.write_u64(u64::from(value));
+    }
+
+    fn write_u32(&mut self, value: u32) {
+        self.write_u64(u64::from(value));
+    }
+
+    fn write_u64(&mut self, value: u64) {
+        self.write(&value.to_le_bytes());
+    }
+
+    fn write_u128(&mut self, value: u128) {
+        self.write(&value.to_le_bytes());
+    }
+
+    fn write_usize(&mut self, value: usize) {
+        self.write_u64(value as u64);
+    }
+
+    fn finish(&self) -> u64 {
+        self.0
+    }
+}
+
+/// A node that is compared with [`Node::similar`] and hashed with [`Node::structural_hash`],
+/// ignoring trivia and positions, so that nodes can be put in a `HashMap` or `HashSet` by what
+/// they are rather than by where they are.
+///
+/// ```rust
+/// # use std::collections::HashMap;
+/// # use full_moon::{ast::Stmt, node::Similar};
+/// # fn main() -> Result<(), Vec<full_moon::Error>> {
+/// let ast = full_moon::parse("print(1)\nprint(2)\nprint( 1 ) -- again")?;
+///
+/// let mut counts: HashMap<Similar<&Stmt>, usize> = HashMap::new();
+/// for stmt in ast.nodes().stmts() {
+///     *counts.entry(Similar(stmt)).or_default() += 1;
+/// }
+///
+/// assert_eq!(counts.len(), 2);
+/// assert_eq!(counts[&Similar(ast.nodes().stmts().next().unwrap())], 2);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct Similar<T>(pub T);
+
+impl<T: Node> PartialEq for Similar<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.similar(&other.0)
+    }
+}
+
+impl<T: Node> Eq for Similar<T> {}
+
+impl<T: Node> Hash for Similar<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        state.write_u64(self.0.structural_hash());
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for Similar<T> {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        self.0.fmt(formatter)
+    }
+}
+
 pub(crate) enum TokenItem<'a> {
     MoreTokens(&'a dyn Node),
     TokenReference(&'a TokenReference),
@@ -122,7 +247,11 @@ impl Node for Ast {
         self.nodes().similar(other.nodes())
     }
 
-    fn tokens(&self) -> Tokens {
+    fn hash_similar(&self, state: &mut dyn Hasher) {
+        self.nodes().hash_similar(state);
+    }
+
+    fn tokens(&self) -> Tokens<'_> {
         self.nodes().tokens()
     }
 }
@@ -140,7 +269,11 @@ impl<T: Node> Node for Box<T> {
         (**self).similar(other)
     }
 
-    fn tokens(&self) -> Tokens {
+    fn hash_similar(&self, state: &mut dyn Hasher) {
+        (**self).hash_similar(state);
+    }
+
+    fn tokens(&self) -> Tokens<'_> {
         (**self).tokens()
     }
 }
@@ -158,7 +291,11 @@ impl<T: Node> Node for &T {
         (**self).similar(other)
     }
 
-    fn tokens(&self) -> Tokens {
+    fn hash_similar(&self, state: &mut dyn Hasher) {
+        (**self).hash_similar(state);
+    }
+
+    fn tokens(&self) -> Tokens<'_> {
         (**self).tokens()
     }
 }
@@ -176,7 +313,11 @@ impl<T: Node> Node for &mut T {
         (**self).similar(other)
     }
 
-    fn tokens(&self) -> Tokens {
+    fn hash_similar(&self, state: &mut dyn Hasher) {
+        (**self).hash_similar(state);
+    }
+
+    fn tokens(&self) -> Tokens<'_> {
         (**self).tokens()
     }
 }
@@ -194,7 +335,11 @@ impl Node for TokenReference {
         *self.token_type() == *other.token_type()
     }
 
-    fn tokens(&self) -> Tokens {
+    fn hash_similar(&self, mut state: &mut dyn Hasher) {
+        self.token_type().hash(&mut state);
+    }
+
+    fn tokens(&self) -> Tokens<'_> {
         Tokens {
             items: vec![TokenItem::TokenReference(self)],
         }
@@ -218,7 +363,15 @@ impl<T: Node> Node for Option<T> {
         }
     }
 
-    fn tokens(&self) -> Tokens {
+    // Neither `None` nor the length of a `Vec` are hashed, so that fields only some features have,
+    // such as type specifiers, hash the same as if they weren't there when they're empty
+    fn hash_similar(&self, state: &mut dyn Hasher) {
+        if let Some(node) = self {
+            node.hash_similar(state);
+        }
+    }
+
+    fn tokens(&self) -> Tokens<'_> {
         match self {
             Some(node) => node.tokens(),
             None => Tokens::default(),
@@ -243,7 +396,13 @@ impl<T: Node> Node for Vec<T> {
         }
     }
 
-    fn tokens(&self) -> Tokens {
+    fn hash_similar(&self, state: &mut dyn Hasher) {
+        for node in self {
+            node.hash_similar(state);
+        }
+    }
+
+    fn tokens(&self) -> Tokens<'_> {
         Tokens {
             items: self.iter().flat_map(|node| node.tokens().items).collect(),
         }
@@ -273,10 +432,155 @@ impl<A: Node, B: Node> Node for (A, B) {
         self.0.similar(&other.0) && self.1.similar(&other.1)
     }
 
-    fn tokens(&self) -> Tokens {
+    fn hash_similar(&self, state: &mut dyn Hasher) {
+        self.0.hash_similar(state);
+        self.1.hash_similar(state);
+    }
+
+    fn tokens(&self) -> Tokens<'_> {
         let mut items = self.0.tokens().items;
         items.append(&mut self.1.tokens().items);
 
         Tokens { items }
     }
 }
+
+full_moon_derive::any_node! {
+    ast::Assignment,
+    ast::BinOp,
+    ast::Block,
+    ast::Call,
+    ast::Do,
+    ast::ElseIf,
+    ast::Expression,
+    ast::Field,
+    ast::FunctionArgs,
+    ast::FunctionBody,
+    ast::FunctionCall,
+    ast::FunctionDeclaration,
+    ast::FunctionName,
+    ast::GenericFor,
+    ast::If,
+    ast::Index,
+    ast::LastStmt,
+    ast::LocalAssignment,
+    ast::LocalFunction,
+    ast::MethodCall,
+    ast::NumericFor,
+    ast::Parameter,
+    ast::Prefix,
+    ast::Repeat,
+    ast::Return,
+    ast::Stmt,
+    ast::Suffix,
+    ast::TableConstructor,
+    ast::UnOp,
+    ast::Var,
+    ast::VarExpression,
+    ast::While,
+    ast::span::ContainedSpan,
+    tokenizer::TokenReference,
+
+    #[cfg(feature = "luau")] ast::luau::ElseIfExpression,
+    #[cfg(feature = "luau")] ast::luau::ExportedTypeDeclaration,
+    #[cfg(feature = "luau")] ast::luau::ExportedTypeFunction,
+    #[cfg(feature = "luau")] ast::luau::GenericDeclaration,
+    #[cfg(feature = "luau")] ast::luau::GenericDeclarationParameter,
+    #[cfg(feature = "luau")] ast::luau::GenericParameterInfo,
+    #[cfg(feature = "luau")] ast::luau::IfExpression,
+    #[cfg(feature = "luau")] ast::luau::IndexedTypeInfo,
+    #[cfg(feature = "luau")] ast::luau::InterpolatedString,
+    #[cfg(feature = "luau")] ast::luau::InterpolatedStringSegment,
+    #[cfg(feature = "luau")] ast::luau::TypeArgument,
+    #[cfg(feature = "luau")] ast::luau::TypeAssertion,
+    #[cfg(feature = "luau")] ast::luau::TypeDeclaration,
+    #[cfg(feature = "luau")] ast::luau::TypeField,
+    #[cfg(feature = "luau")] ast::luau::TypeFieldKey,
+    #[cfg(feature = "luau")] ast::luau::TypeFunction,
+    #[cfg(feature = "luau")] ast::luau::TypeInfo,
+    #[cfg(feature = "luau")] ast::luau::TypeIntersection,
+    #[cfg(feature = "luau")] ast::luau::TypeSpecifier,
+    #[cfg(feature = "luau")] ast::luau::TypeUnion,
+
+    #[cfg(any(feature = "lua52", feature = "luajit"))] ast::lua52::Goto,
+    #[cfg(any(feature = "lua52", feature = "luajit"))] ast::lua52::Label,
+
+    #[cfg(feature = "lua54")] ast::lua54::Attribute,
+
+    #[cfg(any(feature = "luau", feature = "cfxlua"))] ast::CompoundAssignment,
+    #[cfg(any(feature = "luau", feature = "cfxlua"))] ast::CompoundOp,
+}
+
+// Implemented by `#[derive(Node)]` from the fields of every node, which is what keeps
+// `AnyNode::children` from missing any
+pub(crate) trait Children<'a> {
+    // Adds this as a child, or the nodes inside of it if it isn't a node itself, such as a `Vec`
+    fn add_as_child(&'a self, children: &mut Vec<AnyNode<'a>>);
+
+    fn add_children(&'a self, _children: &mut Vec<AnyNode<'a>>) {}
+}
+
+impl<'a> Children<'a> for TokenReference {
+    fn add_as_child(&'a self, children: &mut Vec<AnyNode<'a>>) {
+        children.push(AnyNode::TokenReference(self));
+    }
+}
+
+impl<'a> Children<'a> for ContainedSpan {
+    fn add_as_child(&'a self, children: &mut Vec<AnyNode<'a>>) {
+        children.push(AnyNode::ContainedSpan(self));
+    }
+
+    fn add_children(&'a self, children: &mut Vec<AnyNode<'a>>) {
+        let (start, end) = self.tokens();
+        children.push(AnyNode::TokenReference(start));
+        children.push(AnyNode::TokenReference(end));
+    }
+}
+
+impl<'a, T: Children<'a>> Children<'a> for Box<T> {
+    fn add_as_child(&'a self, children: &mut Vec<AnyNode<'a>>) {
+        (**self).add_as_child(children);
+    }
+}
+
+impl<'a, T: Children<'a>> Children<'a> for Option<T> {
+    fn add_as_child(&'a self, children: &mut Vec<AnyNode<'a>>) {
+        if let Some(node) = self {
+            node.add_as_child(children);
+        }
+    }
+}
+
+impl<'a, T: Children<'a>> Children<'a> for Vec<T> {
+    fn add_as_child(&'a self, children: &mut Vec<AnyNode<'a>>) {
+        for node in self {
+            node.add_as_child(children);
+        }
+    }
+}
+
+impl<'a, A: Children<'a>, B: Children<'a>> Children<'a> for (A, B) {
+    fn add_as_child(&'a self, children: &mut Vec<AnyNode<'a>>) {
+        self.0.add_as_child(children);
+        self.1.add_as_child(children);
+    }
+}
+
+impl<'a, T: Children<'a>> Children<'a> for Punctuated<T> {
+    fn add_as_child(&'a self, children: &mut Vec<AnyNode<'a>>) {
+        for pair in self.pairs() {
+            pair.add_as_child(children);
+        }
+    }
+}
+
+impl<'a, T: Children<'a>> Children<'a> for Pair<T> {
+    fn add_as_child(&'a self, children: &mut Vec<AnyNode<'a>>) {
+        self.value().add_as_child(children);
+
+        if let Some(punctuation) = self.punctuation() {
+            punctuation.add_as_child(children);
+        }
+    }
+}
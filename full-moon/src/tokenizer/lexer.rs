@@ -7,6 +7,24 @@ use super::{
 #[cfg(feature = "luau")]
 use super::{interpolated_strings, InterpolatedStringKind};
 
+/// Which trivia, such as whitespace and comments, a [`Lexer`] keeps in the
+/// [`TokenReference`]s it produces.
+///
+/// Dropping trivia makes lexing and parsing faster and use less memory, for when only the meaning
+/// of the code matters, such as for analysis. Code printed from tokens without their trivia is not
+/// the original code, and may not even be valid, such as `localx=1`. Positions are still those in
+/// the original code.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum TriviaMode {
+    /// Keeps all trivia, so that printing the tokens gives back the original code
+    #[default]
+    KeepAll,
+    /// Keeps only comments, dropping whitespace and shebangs
+    KeepComments,
+    /// Drops all trivia
+    DropAll,
+}
+
 /// A lexer, which will produce a stream of tokens from a source string.
 /// If you just want to create an [`Ast`](crate::ast::Ast) from a string, you want to use
 /// [`parse`](crate::parse) instead.
@@ -22,12 +40,25 @@ pub struct Lexer {
 
     /// The Lua version(s) to parse for.
     pub lua_version: LuaVersion,
+
+    pub(crate) trivia_mode: TriviaMode,
 }
 
 impl Lexer {
     /// Creates a new Lexer from the given source string and Lua version(s).
     pub fn new(source: &str, lua_version: LuaVersion) -> Self {
+        Self::new_with_trivia_mode(source, lua_version, TriviaMode::KeepAll)
+    }
+
+    /// Creates a new Lexer from the given source string and Lua version(s), keeping only the trivia
+    /// allowed by `trivia_mode`.
+    pub fn new_with_trivia_mode(
+        source: &str,
+        lua_version: LuaVersion,
+        trivia_mode: TriviaMode,
+    ) -> Self {
         let mut lexer = Self::new_lazy(source, lua_version);
+        lexer.trivia_mode = trivia_mode;
 
         lexer.next_token = lexer.process_first_with_trivia();
         lexer.peek_token = lexer.process_next_with_trivia();
@@ -49,9 +80,29 @@ impl Lexer {
             brace_stack: Vec::new(),
 
             lua_version,
+            trivia_mode: TriviaMode::KeepAll,
         }
     }
 
+    /// Creates a new Lexer that starts partway through a source string, where `source` is the
+    /// rest of the source string from `position` onwards.
+    /// `position` must be a token boundary, such as the start of a token's leading trivia.
+    pub(crate) fn new_at(
+        source: &str,
+        position: Position,
+        lua_version: LuaVersion,
+        trivia_mode: TriviaMode,
+    ) -> Self {
+        let mut lexer = Self::new_lazy(source, lua_version);
+        lexer.trivia_mode = trivia_mode;
+        lexer.source.lexer_position.position = position;
+
+        lexer.next_token = lexer.process_next_with_trivia();
+        lexer.peek_token = lexer.process_next_with_trivia();
+
+        lexer
+    }
+
     /// Returns the current token.
     pub fn current(&self) -> Option<&LexerResult<TokenReference>> {
         self.next_token.as_ref()
@@ -127,14 +178,30 @@ impl Lexer {
         ))
     }
 
+    // Whether the trivia mode keeps the given trivia token
+    fn keeps_trivia(&self, token: &Token) -> bool {
+        match self.trivia_mode {
+            TriviaMode::KeepAll => true,
+            TriviaMode::KeepComments => token.token_type().is_comment(),
+            TriviaMode::DropAll => false,
+        }
+    }
+
     fn process_next_with_trivia(&mut self) -> Option<LexerResult<TokenReference>> {
         let mut leading_trivia = Vec::new();
         let mut errors: Option<Vec<TokenizerError>> = None;
 
         let nontrivial_token = loop {
+            // Whitespace is dropped anyway, so skip it without creating tokens for it
+            if self.trivia_mode != TriviaMode::KeepAll {
+                self.source.skip_whitespace();
+            }
+
             match self.process_next()? {
                 LexerResult::Ok(token) if token.token_type().is_trivia() => {
-                    leading_trivia.push(token);
+                    if self.keeps_trivia(&token) {
+                        leading_trivia.push(token);
+                    }
                 }
 
                 LexerResult::Ok(token) => {
@@ -152,20 +219,27 @@ impl Lexer {
                         errors = Some(new_errors);
                     }
 
-                    if token.token_type().is_trivia() {
-                        leading_trivia.push(token);
-                    } else {
+                    if !token.token_type().is_trivia() {
                         break token;
+                    } else if self.keeps_trivia(&token) {
+                        leading_trivia.push(token);
                     }
                 }
             }
         };
 
-        let trailing_trivia = self.collect_trailing_trivia();
+        let trailing_trivia = match self.trivia_mode {
+            // Trailing trivia is otherwise picked up as the leading trivia of the next token
+            TriviaMode::DropAll => Vec::new(),
+            TriviaMode::KeepAll | TriviaMode::KeepComments => self.collect_trailing_trivia(),
+        };
+
         let token = TokenReference {
             token: nontrivial_token,
             leading_trivia,
             trailing_trivia,
+            phantom: false,
+            id: None,
         };
 
         if let Some(errors) = errors {
@@ -202,7 +276,10 @@ impl Lexer {
 
             // rewrite todo: handle LexerResult better here
             if let Some(LexerResult::Ok(mut token_reference)) = self.process_next_with_trivia() {
-                token_reference.leading_trivia.insert(0, shebang);
+                if self.trivia_mode == TriviaMode::KeepAll {
+                    token_reference.leading_trivia.insert(0, shebang);
+                }
+
                 return Some(LexerResult::Ok(token_reference));
             }
         }
@@ -234,7 +311,9 @@ impl Lexer {
                                 false
                             };
 
-                        trailing_trivia.push(token);
+                        if self.keeps_trivia(&token) {
+                            trailing_trivia.push(token);
+                        }
 
                         if should_break {
                             break;
@@ -1434,6 +1513,32 @@ impl LexerSource {
         }
     }
 
+    // Returns the source between the given bytes
+    pub(crate) fn slice(&self, start_bytes: usize, end_bytes: usize) -> String {
+        self.source[self.index_at(start_bytes)..self.index_at(end_bytes)]
+            .iter()
+            .collect()
+    }
+
+    // Returns the index of the character at the given byte. This walks from the current
+    // position, since what gets sliced is usually just behind it.
+    fn index_at(&self, bytes: usize) -> usize {
+        let mut index = self.lexer_position.index;
+        let mut index_bytes = self.lexer_position.position.bytes;
+
+        while index_bytes > bytes && index > 0 {
+            index -= 1;
+            index_bytes -= self.source[index].len_utf8();
+        }
+
+        while index_bytes < bytes && index < self.source.len() {
+            index_bytes += self.source[index].len_utf8();
+            index += 1;
+        }
+
+        index
+    }
+
     pub(crate) fn current(&self) -> Option<char> {
         self.source.get(self.lexer_position.index).copied()
     }
@@ -1458,6 +1563,12 @@ impl LexerSource {
         self.source.get(self.lexer_position.index + 1).copied()
     }
 
+    pub(crate) fn skip_whitespace(&mut self) {
+        while matches!(self.current(), Some(' ' | '\t' | '\r' | '\n')) {
+            self.next();
+        }
+    }
+
     pub(crate) fn consume(&mut self, character: char) -> bool {
         if self.current() == Some(character) {
             self.next();
@@ -1,6 +1,6 @@
 use crate::{
     ast::LuaVersion,
-    visitors::{Visit, VisitMut, Visitor, VisitorMut},
+    visitors::{Visit, VisitMut, VisitorMut, VisitorRef},
     ShortString,
 };
 
@@ -10,6 +10,7 @@ use serde::{Deserialize, Serialize};
 use std::{
     cmp::Ordering,
     fmt::{self, Display},
+    hash::{Hash, Hasher},
 };
 
 macro_rules! symbol {
@@ -87,6 +88,23 @@ macro_rules! symbol {
                     }
                 }
             }
+
+            // Symbols are hashed by their text rather than by their variant, which changes with
+            // the features enabled, so that structural hashes are the same with any features
+            impl Hash for Symbol {
+                fn hash<H: Hasher>(&self, state: &mut H) {
+                    match self {
+                        $(
+                            $(
+                                #[cfg(any(
+                                    $(feature = "" $version),+
+                                ))]
+                            )*
+                            Self::$name => $string.hash(state),
+                        )+
+                    }
+                }
+            }
         }
     };
 }
@@ -178,7 +196,7 @@ symbol! {
 }
 
 #[cfg(feature = "luau")]
-#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 /// Whether or not this section is the beginning, middle, end, or if this is a standalone string.
 pub enum InterpolatedStringKind {
@@ -233,8 +251,13 @@ fn is_usize_zero(input: &usize) -> bool {
     *input == 0
 }
 
+// Used by serde
+fn is_false(input: &bool) -> bool {
+    !*input
+}
+
 /// The type of tokens in parsed code
-#[derive(Clone, Debug, Eq, PartialEq)]
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
 #[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 #[cfg_attr(feature = "serde", serde(tag = "type"))]
 #[non_exhaustive]
@@ -344,6 +367,23 @@ impl TokenType {
         );
     }
 
+    /// Returns whether the token is a comment, such as `-- comment` or `--[[ comment ]]`
+    pub fn is_comment(&self) -> bool {
+        #[cfg(not(feature = "cfxlua"))]
+        return matches!(
+            self,
+            TokenType::SingleLineComment { .. } | TokenType::MultiLineComment { .. }
+        );
+
+        #[cfg(feature = "cfxlua")]
+        return matches!(
+            self,
+            TokenType::SingleLineComment { .. }
+                | TokenType::MultiLineComment { .. }
+                | TokenType::CStyleComment { .. }
+        );
+    }
+
     /// Returns the kind of the token type.
     ///
     /// ```rust
@@ -393,6 +433,7 @@ impl TokenType {
 
 /// The kind of token. Contains no additional data.
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 #[non_exhaustive]
 pub enum TokenKind {
     /// End of file, should always be the very last token
@@ -535,7 +576,7 @@ impl PartialOrd for Token {
 }
 
 impl Visit for Token {
-    fn visit<V: Visitor>(&self, visitor: &mut V) {
+    fn visit<'ast, V: VisitorRef<'ast>>(&'ast self, visitor: &mut V) {
         visitor.visit_token(self);
 
         match self.token_kind() {
@@ -590,6 +631,14 @@ pub struct TokenReference {
     pub(crate) leading_trivia: Vec<Token>,
     pub(crate) token: Token,
     pub(crate) trailing_trivia: Vec<Token>,
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "is_false"))]
+    pub(crate) phantom: bool,
+    // Given by `Ast::with_node_ids`, and used to make `NodeId`s
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub(crate) id: Option<u32>,
 }
 
 impl TokenReference {
@@ -599,6 +648,8 @@ impl TokenReference {
             leading_trivia,
             token,
             trailing_trivia,
+            phantom: false,
+            id: None,
         }
     }
 
@@ -626,6 +677,14 @@ impl TokenReference {
         TokenReference::symbol_specific_lua_version(text, LuaVersion::lua51()).unwrap()
     }
 
+    // A symbol that isn't in the code, created while recovering from an error
+    pub(crate) fn phantom_symbol(text: &str) -> Self {
+        Self {
+            phantom: true,
+            ..TokenReference::symbol(text).unwrap()
+        }
+    }
+
     /// Returns a symbol with the leading and trailing whitespace,
     /// much like [`TokenReference::symbol`], but only if it's valid
     /// for the given Lua version.
@@ -733,6 +792,8 @@ impl TokenReference {
             leading_trivia,
             token: symbol,
             trailing_trivia,
+            phantom: false,
+            id: None,
         })
     }
 
@@ -757,9 +818,19 @@ impl TokenReference {
             token,
             leading_trivia: self.leading_trivia.clone(),
             trailing_trivia: self.trailing_trivia.clone(),
+            phantom: false,
+            id: self.id,
         }
     }
 
+    /// Whether the token is a phantom token, which is not in the code and was created while
+    /// recovering from an error, such as the missing `then` in `if x == 2 code()`.
+    /// Phantom tokens are not printed, and have no position unless
+    /// [`Ast::update_positions`](crate::ast::Ast::update_positions) is called.
+    pub fn is_phantom(&self) -> bool {
+        self.phantom
+    }
+
     /// Checks if the token is the given symbol
     pub fn is_symbol(&self, symbol: Symbol) -> bool {
         self.token.token_type() == &TokenType::Symbol { symbol }
@@ -782,6 +853,10 @@ impl std::ops::Deref for TokenReference {
 
 impl fmt::Display for TokenReference {
     fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        if self.phantom {
+            return Ok(());
+        }
+
         for trivia in &self.leading_trivia {
             trivia.fmt(formatter)?;
         }
@@ -801,6 +876,7 @@ impl PartialEq<Self> for TokenReference {
         (**self).eq(other)
             && self.leading_trivia == other.leading_trivia
             && self.trailing_trivia == other.trailing_trivia
+            && self.phantom == other.phantom
     }
 }
 
@@ -819,7 +895,8 @@ impl PartialOrd for TokenReference {
 }
 
 impl Visit for TokenReference {
-    fn visit<V: Visitor>(&self, visitor: &mut V) {
+    fn visit<'ast, V: VisitorRef<'ast>>(&'ast self, visitor: &mut V) {
+        visitor.visit_token_reference(self);
         visitor.visit_token(self);
 
         if matches!(self.token().token_kind(), TokenKind::Eof) {
@@ -834,6 +911,7 @@ impl Visit for TokenReference {
 
 impl VisitMut for TokenReference {
     fn visit_mut<V: VisitorMut>(self, visitor: &mut V) -> Self {
+        let id = self.id;
         let mut token_reference = visitor.visit_token_reference(self);
 
         if matches!(token_reference.token().token_kind(), TokenKind::Eof) {
@@ -843,6 +921,12 @@ impl VisitMut for TokenReference {
         token_reference.leading_trivia = token_reference.leading_trivia.visit_mut(visitor);
         token_reference.token = token_reference.token.visit_mut(visitor);
         token_reference.trailing_trivia = token_reference.trailing_trivia.visit_mut(visitor);
+
+        // A visitor that changes a token, such as renaming it, doesn't change what node it's in
+        if token_reference.id.is_none() {
+            token_reference.id = id;
+        }
+
         token_reference
     }
 }
@@ -886,7 +970,7 @@ impl PartialOrd for Position {
 }
 
 /// The types of quotes used in a Lua string
-#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
 #[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 #[non_exhaustive]
 pub enum StringLiteralQuoteType {
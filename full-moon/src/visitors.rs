@@ -53,8 +53,9 @@ macro_rules! create_visitor {
         pub trait Visitor {
             /// Visit the nodes of an [`Ast`](crate::ast::Ast)
             fn visit_ast(&mut self, ast: &Ast) where Self: Sized {
-                ast.nodes().visit(self);
-                ast.eof().visit(self);
+                let mut visitor = VisitorRefAdapter(self);
+                ast.nodes().visit(&mut visitor);
+                ast.eof().visit(&mut visitor);
             }
 
             paste::item! {
@@ -91,14 +92,149 @@ macro_rules! create_visitor {
             )+
         }
 
+        mod borrowed_visitor {
+            use super::*;
+
+            /// Like [`Visitor`], but nodes/tokens passed are borrowed for as long as the
+            /// [`Ast`](crate::ast::Ast) they're in, so that they can be kept after visiting.
+            /// Nodes are visited with `Visit::visit`, and a [`Visitor`] can be used as a
+            /// `VisitorRef` through [`VisitorRefAdapter`].
+            /// ```rust
+            /// # use full_moon::ast;
+            /// # use full_moon::visitors::{Visit, VisitorRef};
+            /// # fn main() -> Result<(), Vec<full_moon::Error>> {
+            /// #[derive(Default)]
+            /// struct CallCollector<'ast> {
+            ///     calls: Vec<&'ast ast::FunctionCall>,
+            /// }
+            ///
+            /// impl<'ast> VisitorRef<'ast> for CallCollector<'ast> {
+            ///     fn visit_function_call(&mut self, call: &'ast ast::FunctionCall) {
+            ///         self.calls.push(call);
+            ///     }
+            /// }
+            ///
+            /// let ast = full_moon::parse("print(f(1))")?;
+            /// let mut collector = CallCollector::default();
+            /// ast.nodes().visit(&mut collector);
+            /// assert_eq!(collector.calls.len(), 2);
+            /// # Ok(())
+            /// # }
+            /// ```
+            pub trait VisitorRef<'ast> {
+                paste::item! {
+                    $(
+                        #[allow(missing_docs)]
+                        fn $visit_name(&mut self, _node: &'ast $ast_type) { }
+                        #[allow(missing_docs)]
+                        fn [<$visit_name _end>](&mut self, _node: &'ast $ast_type) { }
+                    )+
+
+                    $(
+                        $(
+                            #[$meta]
+                            #[allow(missing_docs)]
+                            fn $meta_visit_name(&mut self, _node: &'ast $meta_ast_type) { }
+                            #[$meta]
+                            #[allow(missing_docs)]
+                            fn [<$meta_visit_name _end>](&mut self, _node: &'ast $meta_ast_type) { }
+                        )+
+                    )+
+                }
+
+                $(
+                    #[allow(missing_docs)]
+                    fn $visit_token(&mut self, _token: &'ast Token) { }
+                )+
+
+                $(
+                    $(
+                        #[$token_meta]
+                        #[allow(missing_docs)]
+                        fn $meta_visit_token(&mut self, _token: &'ast Token) { }
+                    )+
+                )+
+            }
+
+            /// Uses a [`Visitor`] as a [`VisitorRef`], so that it can visit a single node with
+            /// `Visit::visit`.
+            /// ```rust
+            /// # use full_moon::ast;
+            /// # use full_moon::visitors::{Visit, Visitor, VisitorRefAdapter};
+            /// # fn main() -> Result<(), Vec<full_moon::Error>> {
+            /// #[derive(Default)]
+            /// struct CallCounter(usize);
+            ///
+            /// impl Visitor for CallCounter {
+            ///     fn visit_function_call(&mut self, _: &ast::FunctionCall) {
+            ///         self.0 += 1;
+            ///     }
+            /// }
+            ///
+            /// let ast = full_moon::parse("print(f(1))\nprint(2)")?;
+            /// let mut counter = CallCounter::default();
+            /// ast.nodes().stmts().next().unwrap().visit(&mut VisitorRefAdapter(&mut counter));
+            /// assert_eq!(counter.0, 2);
+            /// # Ok(())
+            /// # }
+            /// ```
+            #[derive(Debug)]
+            pub struct VisitorRefAdapter<'a, V>(pub &'a mut V);
+
+            impl<'ast, V: Visitor> VisitorRef<'ast> for VisitorRefAdapter<'_, V> {
+                paste::item! {
+                    $(
+                        fn $visit_name(&mut self, node: &'ast $ast_type) {
+                            Visitor::$visit_name(self.0, node);
+                        }
+
+                        fn [<$visit_name _end>](&mut self, node: &'ast $ast_type) {
+                            Visitor::[<$visit_name _end>](self.0, node);
+                        }
+                    )+
+
+                    $(
+                        $(
+                            #[$meta]
+                            fn $meta_visit_name(&mut self, node: &'ast $meta_ast_type) {
+                                Visitor::$meta_visit_name(self.0, node);
+                            }
+
+                            #[$meta]
+                            fn [<$meta_visit_name _end>](&mut self, node: &'ast $meta_ast_type) {
+                                Visitor::[<$meta_visit_name _end>](self.0, node);
+                            }
+                        )+
+                    )+
+                }
+
+                $(
+                    fn $visit_token(&mut self, token: &'ast Token) {
+                        Visitor::$visit_token(self.0, token);
+                    }
+                )+
+
+                $(
+                    $(
+                        #[$token_meta]
+                        fn $meta_visit_token(&mut self, token: &'ast Token) {
+                            Visitor::$meta_visit_token(self.0, token);
+                        }
+                    )+
+                )+
+            }
+        }
+
+        pub use borrowed_visitor::{VisitorRef, VisitorRefAdapter};
+
         /// A trait that implements functions to listen for specific nodes/tokens.
         /// Unlike [`Visitor`], nodes/tokens passed are mutable.
         pub trait VisitorMut {
             /// Visit the nodes of an [`Ast`](crate::ast::Ast)
             fn visit_ast(&mut self, ast: Ast) -> Ast where Self: Sized {
                 // TODO: Visit tokens?
-                let eof = ast.eof().to_owned();
-                let nodes = ast.nodes.visit_mut(self);
+                let (nodes, eof) = ast.into_parts();
+                let nodes = nodes.visit_mut(self);
 
                 Ast {
                     nodes,
@@ -161,7 +297,7 @@ macro_rules! create_visitor {
 
 #[doc(hidden)]
 pub trait Visit: Sealed {
-    fn visit<V: Visitor>(&self, visitor: &mut V);
+    fn visit<'ast, V: VisitorRef<'ast>>(&'ast self, visitor: &mut V);
 }
 
 #[doc(hidden)]
@@ -173,19 +309,19 @@ where
 }
 
 impl<T: Visit> Visit for &T {
-    fn visit<V: Visitor>(&self, visitor: &mut V) {
+    fn visit<'ast, V: VisitorRef<'ast>>(&'ast self, visitor: &mut V) {
         (**self).visit(visitor);
     }
 }
 
 impl<T: Visit> Visit for &mut T {
-    fn visit<V: Visitor>(&self, visitor: &mut V) {
+    fn visit<'ast, V: VisitorRef<'ast>>(&'ast self, visitor: &mut V) {
         (**self).visit(visitor);
     }
 }
 
 impl<T: Visit> Visit for Vec<T> {
-    fn visit<V: Visitor>(&self, visitor: &mut V) {
+    fn visit<'ast, V: VisitorRef<'ast>>(&'ast self, visitor: &mut V) {
         for item in self {
             item.visit(visitor);
         }
@@ -201,7 +337,7 @@ impl<T: VisitMut> VisitMut for Vec<T> {
 }
 
 impl<T: Visit> Visit for Option<T> {
-    fn visit<V: Visitor>(&self, visitor: &mut V) {
+    fn visit<'ast, V: VisitorRef<'ast>>(&'ast self, visitor: &mut V) {
         if let Some(item) = self {
             item.visit(visitor);
         }
@@ -215,7 +351,7 @@ impl<T: VisitMut> VisitMut for Option<T> {
 }
 
 impl<A: Visit, B: Visit> Visit for (A, B) {
-    fn visit<V: Visitor>(&self, visitor: &mut V) {
+    fn visit<'ast, V: VisitorRef<'ast>>(&'ast self, visitor: &mut V) {
         self.0.visit(visitor);
         self.1.visit(visitor);
     }
@@ -228,7 +364,7 @@ impl<A: VisitMut, B: VisitMut> VisitMut for (A, B) {
 }
 
 impl<T: Visit> Visit for Box<T> {
-    fn visit<V: Visitor>(&self, visitor: &mut V) {
+    fn visit<'ast, V: VisitorRef<'ast>>(&'ast self, visitor: &mut V) {
         (**self).visit(visitor);
     }
 }
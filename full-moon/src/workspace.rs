@@ -0,0 +1,520 @@
+#[cfg(feature = "rayon")]
+use std::sync::OnceLock;
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+
+use crate::{ast::AstResult, Error, LuaVersion, ParseOptions};
+
+// The stack each thread parsing a workspace has on top of the most that parsing can use,
+// for everything else on the stack and for going over the limit by a level of nesting
+#[cfg(feature = "rayon")]
+const STACK_HEADROOM: usize = 1024 * 1024;
+
+/// The extensions of the files picked up by [`Workspace::add_directory`]
+pub const SOURCE_EXTENSIONS: &[&str] = &["lua", "luau"];
+
+/// Identifies a file added to a [`Workspace`], and its result in a [`WorkspaceResult`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct FileId(usize);
+
+impl FileId {
+    /// The index of the file, in the order files were added to the workspace
+    pub fn index(self) -> usize {
+        self.0
+    }
+}
+
+enum FileSource {
+    Disk,
+    Memory(String),
+}
+
+struct WorkspaceFile {
+    path: PathBuf,
+    source: FileSource,
+}
+
+/// A set of files to parse together, such as every file in a repository.
+/// Files are parsed in parallel when the `rayon` feature is enabled.
+///
+/// The Lua version each file is parsed with can be configured by directory and by extension,
+/// such as parsing `.luau` files as Luau and `.lua` files as Lua 5.1:
+///
+/// ```rust
+/// # use full_moon::{workspace::Workspace, LuaVersion};
+/// let mut workspace = Workspace::new()
+///     .with_extension_lua_version("lua", LuaVersion::lua51())
+///     .with_directory_lua_version("vendor", LuaVersion::new());
+///
+/// let main = workspace.add_source("src/main.lua", "local x = 1");
+/// let broken = workspace.add_source("src/broken.lua", "local x = ");
+///
+/// let result = workspace.parse();
+/// assert!(result.file(main).errors().is_empty());
+/// assert_eq!(result.file(main).lua_version(), LuaVersion::lua51());
+///
+/// let errors: Vec<_> = result.errors().collect();
+/// assert_eq!(errors.len(), 1);
+/// assert_eq!(errors[0].0, broken);
+/// ```
+#[derive(Default)]
+pub struct Workspace {
+    files: Vec<WorkspaceFile>,
+    options: ParseOptions,
+    directory_lua_versions: Vec<(PathBuf, LuaVersion)>,
+    extension_lua_versions: Vec<(String, LuaVersion)>,
+    // Made the first time the workspace is parsed, and kept for every parse after
+    #[cfg(feature = "rayon")]
+    thread_pool: OnceLock<Result<rayon::ThreadPool, rayon::ThreadPoolBuildError>>,
+}
+
+impl Workspace {
+    /// Creates an empty workspace, parsing with the default [`ParseOptions`]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns a new Workspace parsing with the given options. The Lua version of the options is
+    /// used for files that don't match any directory or extension.
+    pub fn with_options(self, options: ParseOptions) -> Self {
+        Self {
+            options,
+            // The stack the threads need can change with the options
+            #[cfg(feature = "rayon")]
+            thread_pool: OnceLock::new(),
+            ..self
+        }
+    }
+
+    /// Returns a new Workspace that parses files in the given directory, or any directory inside
+    /// of it, with the given Lua version. When directories are nested, the innermost one is used.
+    /// Directories take precedence over extensions.
+    pub fn with_directory_lua_version(
+        mut self,
+        directory: impl Into<PathBuf>,
+        lua_version: LuaVersion,
+    ) -> Self {
+        self.directory_lua_versions
+            .push((directory.into(), lua_version));
+        self
+    }
+
+    /// Returns a new Workspace that parses files with the given extension, such as `"luau"`,
+    /// with the given Lua version.
+    pub fn with_extension_lua_version(
+        mut self,
+        extension: impl Into<String>,
+        lua_version: LuaVersion,
+    ) -> Self {
+        self.extension_lua_versions
+            .push((extension.into(), lua_version));
+        self
+    }
+
+    /// The options files are parsed with
+    pub fn options(&self) -> &ParseOptions {
+        &self.options
+    }
+
+    /// Returns the Lua version a file at the given path is parsed with
+    pub fn lua_version_for(&self, path: &Path) -> LuaVersion {
+        let directory_lua_version = self
+            .directory_lua_versions
+            .iter()
+            .filter(|(directory, _)| path.starts_with(directory))
+            .max_by_key(|(directory, _)| directory.components().count())
+            .map(|(_, lua_version)| *lua_version);
+
+        let extension_lua_version = || {
+            let extension = path.extension()?.to_str()?;
+
+            self.extension_lua_versions
+                .iter()
+                .rev()
+                .find(|(other_extension, _)| other_extension == extension)
+                .map(|(_, lua_version)| *lua_version)
+        };
+
+        directory_lua_version
+            .or_else(extension_lua_version)
+            .unwrap_or_else(|| self.options.lua_version())
+    }
+
+    /// Adds a file on disk, which is read when the workspace is parsed.
+    pub fn add_path(&mut self, path: impl Into<PathBuf>) -> FileId {
+        self.add(path.into(), FileSource::Disk)
+    }
+
+    /// Adds files on disk, which are read when the workspace is parsed.
+    pub fn add_paths<P: Into<PathBuf>>(
+        &mut self,
+        paths: impl IntoIterator<Item = P>,
+    ) -> Vec<FileId> {
+        paths.into_iter().map(|path| self.add_path(path)).collect()
+    }
+
+    /// Adds every file in a directory, and the directories inside of it, with one of the
+    /// [`SOURCE_EXTENSIONS`]. Files are added in order of their paths.
+    pub fn add_directory(&mut self, directory: impl AsRef<Path>) -> io::Result<Vec<FileId>> {
+        let mut paths = Vec::new();
+        collect_source_paths(directory.as_ref(), &mut paths)?;
+        paths.sort();
+
+        Ok(self.add_paths(paths))
+    }
+
+    /// Adds a file that is already in memory, using `path` to pick its Lua version and to
+    /// identify it in errors.
+    pub fn add_source(&mut self, path: impl Into<PathBuf>, source: impl Into<String>) -> FileId {
+        self.add(path.into(), FileSource::Memory(source.into()))
+    }
+
+    fn add(&mut self, path: PathBuf, source: FileSource) -> FileId {
+        self.files.push(WorkspaceFile { path, source });
+        FileId(self.files.len() - 1)
+    }
+
+    /// The number of files in the workspace
+    pub fn len(&self) -> usize {
+        self.files.len()
+    }
+
+    /// Whether the workspace has no files
+    pub fn is_empty(&self) -> bool {
+        self.files.is_empty()
+    }
+
+    /// Parses every file in the workspace, in parallel when the `rayon` feature is enabled.
+    ///
+    /// Parsing nested code uses a lot of stack space, so with the `rayon` feature, files are
+    /// parsed on a thread pool made for this, with enough stack for the
+    /// [`max_stack_size`](ParseOptions::max_stack_size) of the options. The thread pool is made
+    /// the first time the workspace is parsed and used for every parse after. If its threads can't
+    /// be created, such as when the stack is too large, files are parsed one at a time on the
+    /// current thread instead, and the error is given by
+    /// [`thread_pool_error`](Workspace::thread_pool_error).
+    pub fn parse(&self) -> WorkspaceResult {
+        let parse_file = |(index, file)| self.parse_file(FileId(index), file);
+
+        #[cfg(feature = "rayon")]
+        if let Ok(thread_pool) = self.thread_pool() {
+            return WorkspaceResult {
+                files: thread_pool
+                    .install(|| self.files.par_iter().enumerate().map(parse_file).collect()),
+            };
+        }
+
+        WorkspaceResult {
+            files: self.files.iter().enumerate().map(parse_file).collect(),
+        }
+    }
+
+    /// The error from creating the threads that files are parsed on, if they couldn't be created
+    /// and files were parsed on the current thread instead. This is `None` until the workspace is
+    /// parsed.
+    #[cfg(feature = "rayon")]
+    pub fn thread_pool_error(&self) -> Option<&rayon::ThreadPoolBuildError> {
+        self.thread_pool.get()?.as_ref().err()
+    }
+
+    #[cfg(feature = "rayon")]
+    fn thread_pool(&self) -> &Result<rayon::ThreadPool, rayon::ThreadPoolBuildError> {
+        self.thread_pool.get_or_init(|| {
+            rayon::ThreadPoolBuilder::new()
+                .stack_size(self.options.max_stack_size().saturating_add(STACK_HEADROOM))
+                .build()
+        })
+    }
+
+    fn parse_file(&self, id: FileId, file: &WorkspaceFile) -> ParsedFile {
+        let lua_version = self.lua_version_for(&file.path);
+
+        let source = match &file.source {
+            FileSource::Disk => fs::read_to_string(&file.path),
+            FileSource::Memory(source) => Ok(source.clone()),
+        };
+
+        let (source, result) = match source {
+            Ok(source) => {
+                let options = self.options.clone().with_lua_version(lua_version);
+                let ast_result = crate::parse_with_options(&source, &options);
+                (source, Ok(ast_result))
+            }
+
+            Err(error) => (String::new(), Err(error)),
+        };
+
+        ParsedFile {
+            id,
+            path: file.path.clone(),
+            lua_version,
+            source,
+            result,
+        }
+    }
+}
+
+fn collect_source_paths(directory: &Path, paths: &mut Vec<PathBuf>) -> io::Result<()> {
+    for entry in fs::read_dir(directory)? {
+        let entry = entry?;
+        let path = entry.path();
+
+        if entry.file_type()?.is_dir() {
+            collect_source_paths(&path, paths)?;
+        } else if path
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .is_some_and(|extension| SOURCE_EXTENSIONS.contains(&extension))
+        {
+            paths.push(path);
+        }
+    }
+
+    Ok(())
+}
+
+/// A file parsed as part of a [`Workspace`]
+pub struct ParsedFile {
+    id: FileId,
+    path: PathBuf,
+    lua_version: LuaVersion,
+    source: String,
+    result: Result<AstResult, io::Error>,
+}
+
+impl ParsedFile {
+    /// The ID of the file, returned when it was added to the workspace
+    pub fn id(&self) -> FileId {
+        self.id
+    }
+
+    /// The path of the file
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// The Lua version the file was parsed with
+    pub fn lua_version(&self) -> LuaVersion {
+        self.lua_version
+    }
+
+    /// The source code of the file, which is empty if it couldn't be read
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    /// The result of parsing the file, or the error from reading it
+    pub fn result(&self) -> Result<&AstResult, &io::Error> {
+        self.result.as_ref()
+    }
+
+    /// Consumes the file, returning the result of parsing it, or the error from reading it
+    pub fn into_result(self) -> Result<AstResult, io::Error> {
+        self.result
+    }
+
+    /// The errors found while parsing the file, which is empty if it couldn't be read
+    pub fn errors(&self) -> &[Error] {
+        match &self.result {
+            Ok(ast_result) => ast_result.errors(),
+            Err(_) => &[],
+        }
+    }
+}
+
+/// The results of parsing every file in a [`Workspace`], in the order they were added
+pub struct WorkspaceResult {
+    files: Vec<ParsedFile>,
+}
+
+impl WorkspaceResult {
+    /// Returns the parsed file with the given ID.
+    ///
+    /// # Panics
+    /// Panics if the ID is from a different workspace with more files.
+    pub fn file(&self, id: FileId) -> &ParsedFile {
+        &self.files[id.0]
+    }
+
+    /// Every parsed file, in the order they were added
+    pub fn files(&self) -> &[ParsedFile] {
+        &self.files
+    }
+
+    /// Consumes the result, returning every parsed file, in the order they were added
+    pub fn into_files(self) -> Vec<ParsedFile> {
+        self.files
+    }
+
+    /// Every error found while parsing, along with the file it was found in
+    pub fn errors(&self) -> impl Iterator<Item = (FileId, &Error)> {
+        self.files
+            .iter()
+            .flat_map(|file| file.errors().iter().map(move |error| (file.id, error)))
+    }
+
+    /// Every file that couldn't be read, along with the error from reading it
+    pub fn io_errors(&self) -> impl Iterator<Item = (FileId, &io::Error)> {
+        self.files
+            .iter()
+            .filter_map(|file| Some((file.id, file.result.as_ref().err()?)))
+    }
+
+    /// Whether every file was read and parsed without any errors
+    pub fn is_ok(&self) -> bool {
+        self.files
+            .iter()
+            .all(|file| matches!(&file.result, Ok(ast_result) if ast_result.errors().is_empty()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(feature = "luau")]
+    #[test]
+    fn test_lua_versions() {
+        let workspace = Workspace::new()
+            .with_options(ParseOptions::new(LuaVersion::lua51()))
+            .with_extension_lua_version("luau", LuaVersion::luau())
+            .with_directory_lua_version("vendor", LuaVersion::new())
+            .with_directory_lua_version("vendor/luau", LuaVersion::luau());
+
+        assert_eq!(
+            workspace.lua_version_for(Path::new("a.lua")),
+            LuaVersion::lua51()
+        );
+        assert_eq!(
+            workspace.lua_version_for(Path::new("a.luau")),
+            LuaVersion::luau()
+        );
+        assert_eq!(
+            workspace.lua_version_for(Path::new("vendor/a.luau")),
+            LuaVersion::new()
+        );
+        assert_eq!(
+            workspace.lua_version_for(Path::new("vendor/luau/a.lua")),
+            LuaVersion::luau()
+        );
+        assert_eq!(
+            workspace.lua_version_for(Path::new("vendored/a.luau")),
+            LuaVersion::luau()
+        );
+    }
+
+    #[cfg(feature = "luau")]
+    #[test]
+    fn test_parse() {
+        let mut workspace = Workspace::new()
+            .with_options(ParseOptions::new(LuaVersion::lua51()))
+            .with_extension_lua_version("luau", LuaVersion::luau());
+
+        let luau = workspace.add_source("a.luau", "local x: number = 1");
+        let lua = workspace.add_source("b.lua", "local x: number = 1");
+        let missing = workspace.add_path("does/not/exist.lua");
+
+        let result = workspace.parse();
+
+        assert!(result.file(luau).errors().is_empty());
+        assert!(!result.file(lua).errors().is_empty());
+        assert!(result.file(missing).result().is_err());
+
+        assert!(result.errors().all(|(id, _)| id == lua));
+        assert_eq!(
+            result.io_errors().map(|(id, _)| id).collect::<Vec<_>>(),
+            vec![missing]
+        );
+        assert!(!result.is_ok());
+    }
+
+    #[test]
+    fn test_add_directory() {
+        let directory = tempfile::tempdir().unwrap();
+        let path = directory.path();
+
+        fs::create_dir(path.join("nested")).unwrap();
+        for file in [
+            "z.lua",
+            "a.lua",
+            "nested/b.luau",
+            "nested/c.txt",
+            "d.lua.bak",
+        ] {
+            fs::write(path.join(file), "local x = 1").unwrap();
+        }
+
+        let mut workspace = Workspace::new();
+        let ids = workspace.add_directory(path).unwrap();
+
+        assert_eq!(ids, vec![FileId(0), FileId(1), FileId(2)]);
+        assert_eq!(
+            workspace
+                .files
+                .iter()
+                .map(|file| file.path.clone())
+                .collect::<Vec<_>>(),
+            vec![
+                path.join("a.lua"),
+                path.join("nested/b.luau"),
+                path.join("z.lua")
+            ]
+        );
+        assert!(workspace.parse().is_ok());
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn test_parse_deeply_nested() {
+        // Far more stack than the 2 MiB that threads have by default
+        let options = ParseOptions::default()
+            .with_max_depth(usize::MAX)
+            .with_max_stack_size(64 * 1024 * 1024);
+
+        let mut workspace = Workspace::new().with_options(options);
+        for _ in 0..4 {
+            workspace.add_source(
+                "nested.lua",
+                format!("local x = {}1{}", "(".repeat(500), ")".repeat(500)),
+            );
+        }
+
+        assert!(workspace.parse().is_ok());
+        assert!(workspace.thread_pool_error().is_none());
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn test_thread_pool_reused() {
+        let mut workspace = Workspace::new();
+        workspace.add_source("a.lua", "local x = 1");
+
+        assert!(workspace.parse().is_ok());
+        let thread_pool = workspace.thread_pool() as *const _;
+
+        assert!(workspace.parse().is_ok());
+        assert_eq!(workspace.thread_pool() as *const _, thread_pool);
+
+        // New options make a new thread pool, for the stack they need
+        let workspace = workspace.with_options(ParseOptions::default());
+        assert!(workspace.thread_pool.get().is_none());
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn test_thread_pool_error() {
+        // Threads with this much stack can't be created, so files are parsed on this thread
+        let mut workspace =
+            Workspace::new().with_options(ParseOptions::default().with_max_stack_size(usize::MAX));
+        workspace.add_source("a.lua", "local x = 1");
+
+        assert!(workspace.thread_pool_error().is_none());
+        assert!(workspace.parse().is_ok());
+        assert!(workspace.thread_pool_error().is_some());
+    }
+}
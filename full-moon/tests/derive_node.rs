@@ -30,3 +30,25 @@ fn test_position_min_max() {
     visitor.visit_ast(&ast);
     assert!(visitor.0, "TableConstructor was never found");
 }
+
+#[test]
+fn test_mut_accessors() {
+    let mut ast = parse("local function f() if a then elseif b then end end").unwrap();
+
+    let ast::Stmt::LocalFunction(local_function) = &mut ast.nodes_mut().stmts_mut()[0].0 else {
+        unreachable!("expected local function");
+    };
+
+    let ast::Stmt::If(if_stmt) = &mut local_function.body_mut().block_mut().stmts_mut()[0].0 else {
+        unreachable!("expected if");
+    };
+
+    let condition = if_stmt.condition().clone();
+    let else_ifs = if_stmt.else_if_mut().as_mut().unwrap();
+    *else_ifs[0].condition_mut() = condition;
+
+    assert_eq!(
+        ast.to_string(),
+        "local function f() if a then elseif a then end end"
+    );
+}
@@ -67,6 +67,15 @@ fn run_parser_fail_cases(folder: &str, lua_version: LuaVersion) {
             .unwrap();
         assert_yaml_snapshot!("tokens", tokens);
 
+        // Recovering from parser errors never loses any of the code
+        assert_eq!(
+            full_moon::parse_fallible(&source, lua_version)
+                .ast()
+                .to_string(),
+            source,
+            "parse_fallible lost code for {path:?}"
+        );
+
         process_fail_case(path, &source, lua_version);
     });
 }
@@ -157,3 +166,41 @@ fn test_cfxlua_tokenizer_fail_cases() {
         process_fail_case(path, &source, LuaVersion::cfxlua());
     })
 }
+
+// Operators from other versions of Lua are errors, even when their feature flag is enabled
+#[test]
+#[cfg(all(feature = "luau", feature = "lua53"))]
+fn test_binary_operator_from_other_version() {
+    let result = full_moon::parse_fallible("local x = 1 & 2", LuaVersion::luau());
+    assert!(!result.errors().is_empty());
+}
+
+// Tokens skipped while recovering from errors are kept, even with multi-byte characters
+// before them, and with many errors in one file
+#[test]
+fn test_recovered_tokens_many_errors() {
+    let source = "for i = 1 do end -- é\n".repeat(20_000);
+    let result = full_moon::parse_fallible(&source, LuaVersion::new());
+
+    assert!(!result.errors().is_empty());
+    assert_eq!(result.ast().to_string(), source);
+}
+
+// Errors serialized before errors had kinds can still be deserialized
+#[test]
+#[cfg(feature = "serde")]
+fn test_deserialize_error_without_kind() {
+    use full_moon::ast::{AstError, AstErrorKind};
+
+    let errors = full_moon::parse("local = 1").unwrap_err();
+    let full_moon::Error::AstError(error) = &errors[0] else {
+        unreachable!()
+    };
+
+    let mut json = serde_json::to_value(error).unwrap();
+    json.as_object_mut().unwrap().remove("kind");
+
+    let old_error: AstError = serde_json::from_value(json).unwrap();
+    assert_eq!(old_error.kind(), AstErrorKind::Unknown);
+    assert_eq!(old_error.error_message(), error.error_message());
+}
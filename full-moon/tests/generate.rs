@@ -0,0 +1,121 @@
+#![cfg(feature = "generate")]
+use arbitrary::{Arbitrary, Unstructured};
+use full_moon::{ast::Ast, generate::Generator, node::Node, LuaVersion};
+
+const SEEDS: u64 = 500;
+const ENTROPY_LENGTH: usize = 4096;
+
+// xorshift, so that failures can be reproduced from the seed alone
+fn entropy(seed: u64) -> Vec<u8> {
+    let mut state = seed.wrapping_mul(0x9E37_79B9_7F4A_7C15) | 1;
+
+    (0..ENTROPY_LENGTH)
+        .map(|_| {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            (state >> 24) as u8
+        })
+        .collect()
+}
+
+fn assert_round_trips(lua_version: LuaVersion) {
+    for seed in 0..SEEDS {
+        let entropy = entropy(seed);
+        let ast = Generator::new(&entropy, lua_version).ast();
+        let code = ast.to_string();
+
+        let parsed = match full_moon::parse_fallible(&code, lua_version).into_result() {
+            Ok(parsed) => parsed,
+            Err(errors) => panic!(
+                "seed {seed} for {lua_version:?} generated code that doesn't parse: {}\n{code}",
+                errors
+                    .iter()
+                    .map(ToString::to_string)
+                    .collect::<Vec<_>>()
+                    .join("\n"),
+            ),
+        };
+
+        assert_eq!(parsed.to_string(), code, "seed {seed} for {lua_version:?}");
+        assert!(
+            ast.similar(&parsed),
+            "seed {seed} for {lua_version:?} parsed differently than generated:\n{code}"
+        );
+    }
+}
+
+#[test]
+fn test_lua51() {
+    assert_round_trips(LuaVersion::lua51());
+}
+
+#[test]
+#[cfg(feature = "luau")]
+fn test_luau() {
+    assert_round_trips(LuaVersion::luau());
+}
+
+#[test]
+#[cfg(feature = "lua52")]
+fn test_lua52() {
+    assert_round_trips(LuaVersion::lua52());
+}
+
+#[test]
+#[cfg(feature = "lua53")]
+fn test_lua53() {
+    assert_round_trips(LuaVersion::lua53());
+}
+
+#[test]
+#[cfg(feature = "lua54")]
+fn test_lua54() {
+    assert_round_trips(LuaVersion::lua54());
+}
+
+#[test]
+#[cfg(feature = "luajit")]
+fn test_luajit() {
+    assert_round_trips(LuaVersion::luajit());
+}
+
+#[test]
+fn test_every_version() {
+    assert_round_trips(LuaVersion::new());
+}
+
+#[test]
+fn test_empty_entropy() {
+    let ast = Generator::new(&[], LuaVersion::lua51()).ast();
+    assert_eq!(ast.to_string(), "");
+}
+
+#[test]
+fn test_max_depth() {
+    let entropy = entropy(0);
+    let shallow = Generator::new(&entropy, LuaVersion::new())
+        .with_max_depth(1)
+        .ast();
+
+    assert!(shallow.nodes().stmts().next().is_none());
+}
+
+#[test]
+fn test_arbitrary() {
+    for seed in 0..SEEDS {
+        let entropy = entropy(seed);
+        let mut unstructured = Unstructured::new(&entropy);
+
+        let ast = Ast::arbitrary(&mut unstructured).unwrap();
+        let code = ast.to_string();
+        let parsed = full_moon::parse(&code).unwrap();
+        assert!(ast.similar(&parsed), "seed {seed}:\n{code}");
+
+        let generator = Generator::from_unstructured(&mut unstructured, LuaVersion::lua51());
+        let ast = generator.unwrap().ast();
+        let code = ast.to_string();
+        let parsed = full_moon::parse_fallible(&code, LuaVersion::lua51()).into_result();
+        assert!(ast.similar(&parsed.unwrap()), "seed {seed}:\n{code}");
+    }
+}
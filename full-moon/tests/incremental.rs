@@ -0,0 +1,269 @@
+use full_moon::{
+    ast::{Ast, AstResult, LastStmt, LuaVersion, Stmt, TextEdit},
+    node::Node,
+    tokenizer::{Lexer, Token, TokenReference, TokenType},
+    visitors::{Visitor, VisitorMut},
+};
+use pretty_assertions::assert_eq;
+use std::{collections::BTreeSet, fs, path::Path};
+
+mod common;
+use common::run_test_folder;
+
+// Besides the first and last token of every statement, every edit is tried on at most this many
+// other tokens per file, to keep the test fast
+const TOKENS_PER_FILE: usize = 40;
+
+fn apply_edits(source: &str, edits: &[TextEdit]) -> String {
+    let mut edits = edits.to_vec();
+    edits.sort_by_key(|edit| edit.range().start);
+
+    let mut new_source = source.to_owned();
+    for edit in edits.iter().rev() {
+        new_source.replace_range(edit.range(), edit.replacement());
+    }
+
+    new_source
+}
+
+fn edits_around(source: &str, token: &Token, other_token: &Token) -> Vec<Vec<TextEdit>> {
+    let start = token.start_position().bytes();
+    let end = token.end_position().bytes();
+    let other_start = other_token.start_position().bytes();
+
+    vec![
+        vec![TextEdit::new(start..start, " ")],
+        vec![TextEdit::new(end..end, "x")],
+        vec![TextEdit::new(start..end, "")],
+        vec![TextEdit::new(start..end, &source[start..end])],
+        vec![TextEdit::new(start..start, "\nlocal inserted = call()\n")],
+        vec![TextEdit::new(start..start, "-")],
+        vec![TextEdit::new(start..start, "end ")],
+        vec![TextEdit::new(end..end, " -- comment")],
+        vec![
+            TextEdit::new(start..end, "renamed"),
+            TextEdit::new(other_start..other_start, "  "),
+        ],
+    ]
+}
+
+// Renames every name, making new tokens with no position, and removes every comment
+struct Renamer;
+
+impl VisitorMut for Renamer {
+    fn visit_token_reference(&mut self, token: TokenReference) -> TokenReference {
+        let without_comments = |trivia: &mut dyn Iterator<Item = &Token>| {
+            trivia
+                .filter(|trivia| !trivia.token_type().is_comment())
+                .cloned()
+                .collect()
+        };
+
+        let new_token = match token.token_type() {
+            TokenType::Identifier { identifier } => Token::new(TokenType::Identifier {
+                identifier: format!("{identifier}_").into(),
+            }),
+            _ => token.token().clone(),
+        };
+
+        TokenReference::new(
+            without_comments(&mut token.leading_trivia()),
+            new_token,
+            without_comments(&mut token.trailing_trivia()),
+        )
+    }
+}
+
+// Finds where every statement starts and ends, at any depth, since these are where reparsing has
+// to pick which block to reparse
+#[derive(Default)]
+struct StatementBoundaries {
+    starts: BTreeSet<usize>,
+    ends: BTreeSet<usize>,
+}
+
+impl StatementBoundaries {
+    fn add(&mut self, node: &impl Node) {
+        if let Some((start, end)) = node.range() {
+            self.starts.insert(start.bytes());
+            self.ends.insert(end.bytes());
+        }
+    }
+}
+
+impl Visitor for StatementBoundaries {
+    fn visit_stmt(&mut self, stmt: &Stmt) {
+        self.add(stmt);
+    }
+
+    fn visit_last_stmt(&mut self, last_stmt: &LastStmt) {
+        self.add(last_stmt);
+    }
+}
+
+fn assert_same_result(incremental: &AstResult, full: &AstResult, new_source: &str) {
+    assert_eq!(
+        format!("{:?}", incremental.errors()),
+        format!("{:?}", full.errors()),
+        "errors differ for:\n{new_source}"
+    );
+
+    assert_eq!(
+        incremental.ast().nodes(),
+        full.ast().nodes(),
+        "nodes differ for:\n{new_source}"
+    );
+
+    assert_eq!(
+        incremental.ast().eof(),
+        full.ast().eof(),
+        "eof differs for:\n{new_source}"
+    );
+
+    assert_eq!(
+        incremental.ast().eof().token(),
+        full.ast().eof().token(),
+        "eof position differs for:\n{new_source}"
+    );
+}
+
+fn test_incremental_case(path: &Path, lua_version: LuaVersion) {
+    let source = fs::read_to_string(path.join("source.lua")).expect("couldn't read source.lua");
+    let ast = full_moon::parse_fallible(&source, lua_version)
+        .into_result()
+        .unwrap_or_else(|error| panic!("couldn't make ast for {path:?} - {error:#?}"));
+
+    // Text edits from an Ast changed by a visitor turn the source into its code
+    assert_eq!(Ast::diff_text(&source, &ast), []);
+
+    let renamed = Renamer.visit_ast(ast.clone());
+    let edits = Ast::diff_text(&source, &renamed);
+    assert_eq!(apply_edits(&source, &edits), renamed.to_string());
+
+    let tokens = Lexer::new(&source, lua_version)
+        .collect()
+        .unwrap()
+        .into_iter()
+        .filter(|token| !token.token_type().is_trivia())
+        .collect::<Vec<_>>();
+
+    let mut boundaries = StatementBoundaries::default();
+    boundaries.visit_ast(&ast);
+
+    let stride = tokens.len().div_ceil(TOKENS_PER_FILE).max(1);
+
+    for (index, token) in tokens.iter().enumerate().filter(|(index, token)| {
+        index % stride == 0
+            || boundaries.starts.contains(&token.start_position().bytes())
+            || boundaries.ends.contains(&token.end_position().bytes())
+    }) {
+        let other_token = &tokens[(index + tokens.len() / 2) % tokens.len()];
+
+        for edits in edits_around(&source, token, other_token) {
+            if edits.windows(2).any(|pair| {
+                let (first, second) = if pair[0].range().start <= pair[1].range().start {
+                    (&pair[0], &pair[1])
+                } else {
+                    (&pair[1], &pair[0])
+                };
+
+                first.range().end > second.range().start
+            }) {
+                continue;
+            }
+
+            let new_source = apply_edits(&source, &edits);
+            let full = full_moon::parse_fallible(&new_source, lua_version);
+            let incremental = ast.clone().reparse(&source, &edits, lua_version);
+
+            assert_same_result(&incremental, &full, &new_source);
+        }
+    }
+}
+
+#[test]
+#[cfg_attr(feature = "no-source-tests", ignore)]
+fn test_incremental_pass_cases() {
+    run_test_folder("./tests/cases/pass", |path| {
+        test_incremental_case(path, LuaVersion::lua51())
+    });
+}
+
+#[test]
+#[cfg(feature = "luau")]
+#[cfg_attr(feature = "no-source-tests", ignore)]
+fn test_incremental_roblox_pass_cases() {
+    run_test_folder("./tests/roblox_cases/pass", |path| {
+        test_incremental_case(path, LuaVersion::luau())
+    });
+}
+
+#[test]
+#[cfg(feature = "lua54")]
+#[cfg_attr(feature = "no-source-tests", ignore)]
+fn test_incremental_lua54_pass_cases() {
+    run_test_folder("./tests/lua54_cases/pass", |path| {
+        test_incremental_case(path, LuaVersion::lua54())
+    });
+}
+
+#[test]
+fn test_incremental_nested_edit() {
+    let source =
+        "local a = 1\n\nfunction f()\n\tif a then\n\t\tprint(a)\n\tend\nend\n\nlocal b = 2\n";
+    let ast = full_moon::parse(source).unwrap();
+
+    let start = source.find("print").unwrap();
+    let edits = [TextEdit::new(start..start + "print".len(), "warn")];
+
+    let new_source = apply_edits(source, &edits);
+    let incremental = ast.reparse(source, &edits, LuaVersion::new());
+
+    assert_same_result(
+        &incremental,
+        &full_moon::parse_fallible(&new_source, LuaVersion::new()),
+        &new_source,
+    );
+
+    assert_eq!(incremental.ast().to_string(), new_source);
+}
+
+#[test]
+fn test_diff_text_moved() {
+    let source = "local a = 1\nlocal b = 2\nlocal c = 3\n";
+    let ast = full_moon::parse(source).unwrap();
+
+    let mut stmts = ast
+        .nodes()
+        .stmts_with_semicolon()
+        .cloned()
+        .collect::<Vec<_>>();
+    stmts.swap(0, 2);
+
+    let new_ast = ast
+        .clone()
+        .with_nodes(ast.nodes().clone().with_stmts(stmts));
+    let edits = Ast::diff_text(source, &new_ast);
+
+    assert_eq!(
+        apply_edits(source, &edits),
+        "local c = 3\nlocal b = 2\nlocal a = 1\n"
+    );
+    assert_eq!(edits.len(), 2);
+}
+
+#[test]
+fn test_diff_text_phantom() {
+    let source = "if x print(x) end";
+    let ast = full_moon::parse_fallible(source, LuaVersion::new()).into_ast();
+    let renamed = Renamer.visit_ast(ast);
+
+    assert_eq!(
+        Ast::diff_text(source, &renamed),
+        [
+            TextEdit::new(4..4, "_"),
+            TextEdit::new(10..10, "_"),
+            TextEdit::new(12..12, "_"),
+        ]
+    );
+}
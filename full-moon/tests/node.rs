@@ -1,4 +1,8 @@
-use full_moon::{node::Node, parse};
+use full_moon::{
+    node::{Node, Similar},
+    parse,
+};
+use std::collections::HashSet;
 
 #[test]
 fn surrounding_trivia() {
@@ -23,6 +27,29 @@ fn test_similar() {
     assert!(!stmts[0].similar(stmts[2]));
 }
 
+#[test]
+fn test_structural_hash() {
+    let ast = parse("local x = 1; --[[ uh oh, filler ]] local x = 1; local x = 2;").unwrap();
+    let stmts = ast.nodes().stmts().collect::<Vec<_>>();
+
+    assert_eq!(stmts[0].structural_hash(), stmts[1].structural_hash());
+    assert_ne!(stmts[0].structural_hash(), stmts[2].structural_hash());
+
+    // Separators are left out of both `similar` and the hash
+    let ast = parse("local t = {1, 2}\nlocal t = {1, 2,}\nlocal t = {1; 2}").unwrap();
+    let tables = ast.nodes().stmts().collect::<Vec<_>>();
+
+    assert!(tables[0].similar(tables[1]) && tables[0].similar(tables[2]));
+    assert_eq!(tables[0].structural_hash(), tables[1].structural_hash());
+    assert_eq!(tables[0].structural_hash(), tables[2].structural_hash());
+
+    let similar = tables.into_iter().map(Similar).collect::<HashSet<_>>();
+    assert_eq!(similar.len(), 1);
+
+    // The hash is the same in every build, whatever features are enabled
+    assert_eq!(stmts[0].structural_hash(), 7266276451013144175);
+}
+
 #[test]
 fn test_tokens_collect() {
     let source = parse("local abcd = 1").unwrap();
@@ -36,3 +63,19 @@ fn test_tokens_back() {
     let mut tokens = source.nodes().tokens();
     assert_eq!(tokens.next_back().unwrap().to_string(), "1");
 }
+
+// Every parameter has an entry in the type specifiers, even when the Lua version has no types
+#[test]
+#[cfg(feature = "luau")]
+fn test_variadic_type_specifiers() {
+    let ast = full_moon::parse_fallible("function f(a, ...) end", full_moon::LuaVersion::lua51())
+        .into_result()
+        .unwrap();
+
+    let Some(full_moon::ast::Stmt::FunctionDeclaration(declaration)) = ast.nodes().stmts().next()
+    else {
+        panic!("expected a function declaration");
+    };
+
+    assert_eq!(declaration.body().type_specifiers().count(), 2);
+}
@@ -0,0 +1,192 @@
+use full_moon::{
+    ast::{
+        punctuated::{Pair, Punctuated},
+        span::ContainedSpan,
+        AstErrorKind, BinOp, Block, Expression, LastStmt, Return,
+    },
+    tokenizer::TokenReference,
+    LuaVersion, ParseOptions,
+};
+use std::sync::{atomic::AtomicBool, Arc};
+
+fn error_kinds(code: &str, options: &ParseOptions) -> Vec<AstErrorKind> {
+    full_moon::parse_with_options(code, options)
+        .errors()
+        .iter()
+        .map(|error| match error {
+            full_moon::Error::AstError(error) => error.kind(),
+            full_moon::Error::TokenizerError(error) => panic!("unexpected error: {error}"),
+        })
+        .collect()
+}
+
+#[test]
+fn test_within_limits() {
+    let code = "local x = ((1 + 2) .. 3)\nif x then\n\tprint(x)\nend\n";
+    let result = full_moon::parse_with_options(code, &ParseOptions::default());
+
+    assert!(result.errors().is_empty());
+    assert_eq!(result.ast().to_string(), code);
+}
+
+// Code nested `depth` levels deep in each way that nesting takes up stack space while parsing
+fn deeply_nested_code(depth: usize) -> Vec<String> {
+    vec![
+        format!("local x = {}1{}", "(".repeat(depth), ")".repeat(depth)),
+        format!("local x = {}1{}", "{".repeat(depth), "}".repeat(depth)),
+        format!("local x = {}1{}", "f(".repeat(depth), ")".repeat(depth)),
+        format!("local x = t{}1{}", "[t".repeat(depth), "]".repeat(depth)),
+        format!("local x = {}1", "not ".repeat(depth)),
+        format!("local x = {}1", "- ".repeat(depth)),
+        format!("{}{}", "do ".repeat(depth), "end ".repeat(depth)),
+        format!("{}{}", "if x then ".repeat(depth), "end ".repeat(depth)),
+        format!("{}{}", "while x do ".repeat(depth), "end ".repeat(depth)),
+        format!(
+            "{}{}",
+            "for i = 1, 2 do ".repeat(depth),
+            "end ".repeat(depth)
+        ),
+        "call(function() ".repeat(depth),
+    ]
+}
+
+#[test]
+fn test_too_deeply_nested() {
+    let options = ParseOptions::new(LuaVersion::new()).with_max_depth(32);
+
+    for code in deeply_nested_code(100_000) {
+        assert_eq!(
+            error_kinds(&code, &options),
+            vec![AstErrorKind::TooDeeplyNested],
+        );
+    }
+}
+
+// Runs on the test's own thread, which has the default stack size of 2 MiB
+#[test]
+fn test_default_options_fit_in_stack() {
+    for code in deeply_nested_code(10_000) {
+        assert_eq!(
+            error_kinds(&code, &ParseOptions::default()),
+            vec![AstErrorKind::TooDeeplyNested],
+        );
+    }
+}
+
+#[test]
+fn test_max_stack_size() {
+    let code = format!("local x = {}1{}", "(".repeat(10), ")".repeat(10));
+    let options = ParseOptions::new(LuaVersion::new()).with_max_depth(usize::MAX);
+
+    assert_eq!(error_kinds(&code, &options), vec![]);
+    assert_eq!(
+        error_kinds(&code, &options.with_max_stack_size(0)),
+        vec![AstErrorKind::TooDeeplyNested],
+    );
+}
+
+#[test]
+fn test_long_operator_chains() {
+    for operator in ["+", "..", "^", "and", "or"] {
+        let code = format!("local x = 1{}", format!(" {operator} 1").repeat(100_000));
+        let result = full_moon::parse_with_options(&code, &ParseOptions::default());
+
+        assert!(result.errors().is_empty(), "{operator}");
+    }
+}
+
+#[test]
+fn test_keeps_code_before_stopping() {
+    let options = ParseOptions::new(LuaVersion::new()).with_max_depth(4);
+    let result = full_moon::parse_with_options("local x = 1\nlocal y = ((((2))))", &options);
+
+    assert_eq!(result.errors().len(), 1);
+    assert!(result.ast().to_string().starts_with("local x = 1\n"));
+}
+
+#[test]
+fn test_too_many_tokens() {
+    let code = "local x = 1\nlocal y = 2\n";
+
+    let options = ParseOptions::new(LuaVersion::new()).with_max_tokens(8);
+    assert_eq!(error_kinds(code, &options), vec![]);
+
+    let options = ParseOptions::new(LuaVersion::new()).with_max_tokens(7);
+    assert_eq!(
+        error_kinds(code, &options),
+        vec![AstErrorKind::TooManyTokens],
+    );
+}
+
+#[test]
+fn test_cancellation() {
+    let cancellation = Arc::new(AtomicBool::new(false));
+    let options = ParseOptions::new(LuaVersion::new()).with_cancellation(cancellation.clone());
+
+    assert_eq!(error_kinds("local x = 1", &options), vec![]);
+
+    cancellation.store(true, std::sync::atomic::Ordering::Relaxed);
+    assert_eq!(
+        error_kinds("local x = 1", &options),
+        vec![AstErrorKind::Cancelled],
+    );
+}
+
+#[test]
+fn test_drop_deeply_nested_ast() {
+    let code = format!("local x = 1{}", " + 1".repeat(100_000));
+    let ast = full_moon::parse(&code).unwrap();
+    drop(ast);
+
+    let one = full_moon::parse_expression("1", LuaVersion::new())
+        .into_node()
+        .unwrap();
+
+    let mut expression = one.clone();
+
+    for _ in 0..1_000_000 {
+        expression = Expression::Parentheses {
+            contained: ContainedSpan::new(
+                TokenReference::symbol("(").unwrap(),
+                TokenReference::symbol(")").unwrap(),
+            ),
+            expression: Box::new(Expression::BinaryOperator {
+                lhs: Box::new(expression),
+                binop: BinOp::Plus(TokenReference::symbol("+").unwrap()),
+                rhs: Box::new(one.clone()),
+            }),
+        };
+    }
+
+    let block = Block::new().with_last_stmt(Some((
+        LastStmt::Return(
+            Return::new().with_returns(Punctuated::from_iter([Pair::End(expression)])),
+        ),
+        None,
+    )));
+
+    let ast = full_moon::parse("").unwrap().with_nodes(block);
+    drop(ast);
+}
+
+#[cfg(feature = "luau")]
+#[test]
+fn test_too_deeply_nested_types() {
+    let depth = 100_000;
+
+    let tables = format!("type T = {}T{}", "{".repeat(depth), "}".repeat(depth));
+    let parentheses = format!("type T = {}T{}", "(".repeat(depth), ")".repeat(depth));
+    let generics = format!("type T = {}T{}", "A<".repeat(depth), ">".repeat(depth));
+
+    for code in [tables, parentheses, generics] {
+        for options in [
+            ParseOptions::new(LuaVersion::luau()).with_max_depth(32),
+            ParseOptions::new(LuaVersion::luau()),
+        ] {
+            assert_eq!(
+                error_kinds(&code, &options),
+                vec![AstErrorKind::TooDeeplyNested],
+            );
+        }
+    }
+}
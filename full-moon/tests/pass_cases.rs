@@ -1,7 +1,18 @@
 use full_moon::{
-    ast::LuaVersion,
-    node::Node,
-    tokenizer::{self, Token, TokenReference},
+    ast::{
+        borrowed,
+        diff::{self, DiffOptions},
+        punctuated::Pair,
+        span::ContainedSpan,
+        syntax::SyntaxTree,
+        Ast, Expression, FunctionArgs, LuaVersion, Stmt, TableConstructor,
+    },
+    format::{self, CallParentheses, FormatOptions, QuoteStyle, TrailingSeparator},
+    minify::{self, MinifyOptions},
+    node::{AnyNode, Node},
+    tokenizer::{self, StringLiteralQuoteType, Token, TokenReference, TokenType, TriviaMode},
+    visitors::VisitorMut,
+    ParseOptions,
 };
 use insta::assert_yaml_snapshot;
 use pretty_assertions::assert_eq;
@@ -29,6 +40,22 @@ fn unpack_token_reference(token: &TokenReference) -> Vec<Token> {
         .collect()
 }
 
+fn non_trivia_tokens(ast: &Ast) -> Vec<Token> {
+    ast.tokens().map(|token| token.token().clone()).collect()
+}
+
+fn token_types(ast: &Ast) -> Vec<TokenType> {
+    ast.tokens()
+        .map(|token| token.token_type().clone())
+        .collect()
+}
+
+fn parse(code: &str, lua_version: LuaVersion, path: &Path, what: &str) -> Ast {
+    full_moon::parse_fallible(code, lua_version)
+        .into_result()
+        .unwrap_or_else(|error| panic!("couldn't parse {what} {path:?} - {code}\n{error:#?}"))
+}
+
 fn test_pass_case(path: &Path, lua_version: LuaVersion) {
     let source = fs::read_to_string(path.join("source.lua")).expect("couldn't read source.lua");
 
@@ -118,3 +145,395 @@ fn test_cfxlua_pass_cases() {
         test_pass_case(path, LuaVersion::cfxlua())
     });
 }
+
+// The tests below check one feature each against every pass case of every enabled Lua version
+fn pass_case_folders() -> Vec<(&'static str, LuaVersion)> {
+    [
+        ("./tests/cases/pass", LuaVersion::lua51()),
+        #[cfg(feature = "luau")]
+        ("./tests/roblox_cases/pass", LuaVersion::luau()),
+        #[cfg(feature = "lua52")]
+        ("./tests/lua52_cases/pass", LuaVersion::lua52()),
+        #[cfg(feature = "lua53")]
+        ("./tests/lua53_cases/pass", LuaVersion::lua53()),
+        #[cfg(feature = "lua54")]
+        ("./tests/lua54_cases/pass", LuaVersion::lua54()),
+        #[cfg(feature = "luajit")]
+        ("./tests/luajit_cases/pass", LuaVersion::luajit()),
+        #[cfg(feature = "cfxlua")]
+        ("./tests/cfxlua_cases/pass", LuaVersion::cfxlua()),
+    ]
+    .into()
+}
+
+fn for_each_pass_case(test_fn: impl Fn(&Path, &str, &Ast, LuaVersion)) {
+    for (folder, lua_version) in pass_case_folders() {
+        for entry in fs::read_dir(folder).expect("couldn't read directory") {
+            let path = entry.unwrap().path();
+            dbg!(path.to_string_lossy());
+
+            let source =
+                fs::read_to_string(path.join("source.lua")).expect("couldn't read source.lua");
+            let ast = parse(&source, lua_version, &path, "source of");
+
+            test_fn(&path, &source, &ast, lua_version);
+        }
+    }
+}
+
+// Dropping trivia keeps everything else the same
+#[test]
+#[cfg_attr(feature = "no-source-tests", ignore)]
+fn test_trivia_modes() {
+    for_each_pass_case(|path, source, ast, lua_version| {
+        for trivia_mode in [TriviaMode::KeepComments, TriviaMode::DropAll] {
+            let options = ParseOptions::new(lua_version)
+                .with_max_depth(usize::MAX)
+                .with_trivia_mode(trivia_mode);
+
+            let lossy_ast = full_moon::parse_with_options(source, &options)
+                .into_result()
+                .unwrap_or_else(|error| {
+                    panic!("couldn't make lossy ast for {path:?} - {error:#?}")
+                });
+
+            assert_eq!(non_trivia_tokens(ast), non_trivia_tokens(&lossy_ast));
+
+            for token in lossy_ast.tokens() {
+                for trivia in token.leading_trivia().chain(token.trailing_trivia()) {
+                    assert!(
+                        trivia_mode == TriviaMode::KeepComments && trivia.token_type().is_comment(),
+                        "{trivia_mode:?} kept {trivia:?}",
+                    );
+                }
+            }
+        }
+    });
+}
+
+// Without whitespace, tokens are kept apart by separating them
+#[test]
+#[cfg_attr(feature = "no-source-tests", ignore)]
+fn test_separate_tokens() {
+    for_each_pass_case(|path, source, ast, lua_version| {
+        let options = ParseOptions::new(lua_version).with_trivia_mode(TriviaMode::DropAll);
+        let lossy_ast = full_moon::parse_with_options(source, &options)
+            .into_result()
+            .unwrap();
+
+        let separated_code = lossy_ast.separate_tokens().to_string();
+        let separated_ast = parse(&separated_code, lua_version, path, "separated");
+        assert_eq!(token_types(ast), token_types(&separated_ast));
+    });
+}
+
+fn kept_format_options() -> FormatOptions {
+    FormatOptions::new()
+        .with_quote_style(QuoteStyle::Keep)
+        .with_call_parentheses(CallParentheses::Keep)
+        .with_trailing_separator(TrailingSeparator::Keep)
+}
+
+// Formatting again changes nothing
+#[test]
+#[cfg_attr(feature = "no-source-tests", ignore)]
+fn test_format_idempotent() {
+    for_each_pass_case(|path, _, ast, lua_version| {
+        for options in [FormatOptions::new(), kept_format_options()] {
+            let formatted = format::format(ast, &options);
+            let formatted_ast = parse(&formatted, lua_version, path, "formatted");
+            let formatted_again = format::format(&formatted_ast, &options);
+            assert_eq!(PrettyString(&formatted_again), PrettyString(&formatted));
+        }
+    });
+}
+
+// Rewrites what formatting with the default options changes without changing what the code means,
+// which is the quotes around strings, the parentheses around the arguments of calls, and
+// separators after the last field of tables
+struct Canonicalize;
+
+impl VisitorMut for Canonicalize {
+    fn visit_function_args(&mut self, arguments: FunctionArgs) -> FunctionArgs {
+        let argument = match arguments {
+            FunctionArgs::String(string) => Expression::String(string),
+            FunctionArgs::TableConstructor(table) => Expression::TableConstructor(table),
+            other => return other,
+        };
+
+        FunctionArgs::Parentheses {
+            parentheses: ContainedSpan::new(
+                TokenReference::symbol("(").unwrap(),
+                TokenReference::symbol(")").unwrap(),
+            ),
+            arguments: std::iter::once(Pair::End(argument)).collect(),
+        }
+    }
+
+    fn visit_table_constructor(&mut self, table: TableConstructor) -> TableConstructor {
+        let mut fields = table.fields().clone();
+
+        if let Some(last) = fields.pop() {
+            fields.push(Pair::End(last.into_value()));
+        }
+
+        table.with_fields(fields)
+    }
+
+    // Quoted strings with the same value are written the same, other than for escaped quotes
+    fn visit_string_literal(&mut self, token: Token) -> Token {
+        let TokenType::StringLiteral {
+            literal,
+            multi_line_depth,
+            quote_type: StringLiteralQuoteType::Double | StringLiteralQuoteType::Single,
+        } = token.token_type()
+        else {
+            return token;
+        };
+
+        let mut value = String::with_capacity(literal.len());
+        let mut characters = literal.chars();
+
+        while let Some(character) = characters.next() {
+            match character {
+                '\\' => match characters.next() {
+                    Some(quote @ ('"' | '\'')) => value.push(quote),
+                    Some(escaped) => {
+                        value.push('\\');
+                        value.push(escaped);
+                    }
+                    None => value.push('\\'),
+                },
+
+                character => value.push(character),
+            }
+        }
+
+        Token::new(TokenType::StringLiteral {
+            literal: value.into(),
+            multi_line_depth: *multi_line_depth,
+            quote_type: StringLiteralQuoteType::Double,
+        })
+    }
+}
+
+// Formatting with the default options keeps what the code means
+#[test]
+#[cfg_attr(feature = "no-source-tests", ignore)]
+fn test_format_keeps_meaning() {
+    for_each_pass_case(|path, _, ast, lua_version| {
+        let formatted = format::format(ast, &FormatOptions::new());
+        let formatted_ast = parse(&formatted, lua_version, path, "formatted");
+
+        let canonical_ast = Canonicalize.visit_ast(ast.clone());
+        let canonical_formatted_ast = Canonicalize.visit_ast(formatted_ast);
+        assert!(
+            canonical_ast.similar(&canonical_formatted_ast),
+            "formatting changed the meaning of {path:?} - {formatted}"
+        );
+    });
+}
+
+// Formatting while keeping quotes, parentheses, and separators keeps the same tokens
+#[test]
+#[cfg_attr(feature = "no-source-tests", ignore)]
+fn test_format_keeps_tokens() {
+    for_each_pass_case(|path, _, ast, lua_version| {
+        let formatted = format::format(ast, &kept_format_options());
+        let formatted_ast = parse(&formatted, lua_version, path, "formatted");
+        assert_eq!(token_types(ast), token_types(&formatted_ast));
+    });
+}
+
+// Formatting only changes trivia, so the diff is only updates to trivia
+#[test]
+#[cfg_attr(feature = "no-source-tests", ignore)]
+fn test_diff_of_formatted() {
+    for_each_pass_case(|path, _, ast, lua_version| {
+        let formatted = format::format(ast, &kept_format_options());
+        let formatted_ast = parse(&formatted, lua_version, path, "formatted");
+
+        let options = DiffOptions::new();
+        assert!(diff::diff(ast, &formatted_ast, &options)
+            .iter()
+            .all(|edit| edit.is_trivia_only()));
+
+        assert!(diff::diff(ast, &formatted_ast, &options.with_ignore_trivia(true)).is_empty());
+    });
+}
+
+// Formatting only changes trivia, which isn't part of the structural hash
+#[test]
+#[cfg_attr(feature = "no-source-tests", ignore)]
+fn test_structural_hash_of_formatted() {
+    for_each_pass_case(|path, _, ast, lua_version| {
+        let formatted = format::format(ast, &kept_format_options());
+        let formatted_ast = parse(&formatted, lua_version, path, "formatted");
+        assert_eq!(ast.structural_hash(), formatted_ast.structural_hash());
+    });
+}
+
+// Minifying makes code that parses, and without renaming or shortening literals, keeps the same
+// tokens other than semicolons
+#[test]
+#[cfg_attr(feature = "no-source-tests", ignore)]
+fn test_minify() {
+    let unchanged_options = MinifyOptions::new()
+        .with_rename_locals(false)
+        .with_shorten_literals(false);
+
+    for_each_pass_case(|path, _, ast, lua_version| {
+        for options in [MinifyOptions::new(), unchanged_options.clone()] {
+            let minified = minify::minify(ast.clone(), &options).to_string();
+            let minified_ast = parse(&minified, lua_version, path, "minified");
+
+            if !options.rename_locals() {
+                let token_types = |ast: &Ast| {
+                    ast.tokens()
+                        .filter(|token| !token.is_symbol(tokenizer::Symbol::Semicolon))
+                        .map(|token| token.token_type().clone())
+                        .collect::<Vec<_>>()
+                };
+
+                assert_eq!(token_types(ast), token_types(&minified_ast));
+            }
+        }
+    });
+}
+
+// Exporting as JSON gives every statement and comment, with ranges inside the code
+#[test]
+#[cfg(feature = "estree")]
+#[cfg_attr(feature = "no-source-tests", ignore)]
+fn test_estree() {
+    let options = full_moon::estree::EstreeOptions::new()
+        .with_locations(true)
+        .with_ranges(true);
+
+    for_each_pass_case(|_, source, ast, _| {
+        let json = full_moon::estree::to_json(ast, &options);
+        assert_eq!(json["type"], "Chunk");
+        assert_eq!(
+            json["body"].as_array().unwrap().len(),
+            ast.nodes().stmts().count() + usize::from(ast.nodes().last_stmt().is_some())
+        );
+
+        let comments = ast
+            .tokens()
+            .chain(std::iter::once(ast.eof()))
+            .flat_map(|token| token.leading_trivia().chain(token.trailing_trivia()))
+            .filter(|trivia| trivia.token_type().is_comment())
+            .count();
+
+        assert_eq!(json["comments"].as_array().unwrap().len(), comments);
+
+        let length = source.encode_utf16().count();
+        assert_eq!(json["range"][1], length);
+    });
+}
+
+// The borrowed Ast has the same tokens and trivia as the owned one
+#[test]
+#[cfg_attr(feature = "no-source-tests", ignore)]
+fn test_borrowed_ast() {
+    for_each_pass_case(|path, source, ast, lua_version| {
+        let borrowed_ast = borrowed::Ast::parse(source, lua_version)
+            .unwrap_or_else(|error| panic!("couldn't make borrowed ast for {path:?} - {error:#?}"));
+
+        assert_eq!(
+            PrettyString(&borrowed_ast.to_string()),
+            PrettyString(source)
+        );
+
+        // Unlike the borrowed Ast, Node::tokens gives the tokens of a ContainedSpan before those inside it
+        let mut tokens: Vec<_> = ast.tokens().collect();
+        tokens.sort_by_key(|token| token.token().start_position().bytes());
+
+        assert_eq!(borrowed_ast.tokens().count(), tokens.len());
+
+        for (borrowed_token, token) in borrowed_ast.tokens().zip(tokens) {
+            let trivia_text = |trivia: &mut dyn Iterator<Item = &Token>| {
+                trivia.map(ToString::to_string).collect::<String>()
+            };
+
+            assert_eq!(borrowed_token.text(), token.token().to_string());
+            assert_eq!(borrowed_token.kind(), token.token_kind());
+            assert_eq!(
+                borrowed_token.leading_trivia(),
+                trivia_text(&mut token.leading_trivia())
+            );
+            assert_eq!(
+                borrowed_token.trailing_trivia(),
+                trivia_text(&mut token.trailing_trivia())
+            );
+            assert_eq!(
+                borrowed_token.start_position(),
+                token.token().start_position()
+            );
+            assert_eq!(borrowed_token.end_position(), token.token().end_position());
+        }
+    });
+}
+
+// The syntax tree has the same nodes as the borrowed Ast, and can find them from their typed nodes
+#[test]
+#[cfg_attr(feature = "no-source-tests", ignore)]
+fn test_syntax_tree() {
+    for_each_pass_case(|_, source, ast, lua_version| {
+        let borrowed_ast = borrowed::Ast::parse(source, lua_version).unwrap();
+        let syntax_tree = SyntaxTree::new(ast);
+
+        assert_eq!(
+            PrettyString(&format!("{}{}", syntax_tree.root(), ast.eof())),
+            PrettyString(source)
+        );
+
+        assert_eq!(
+            syntax_tree
+                .nodes()
+                .map(|node| node.kind())
+                .collect::<Vec<_>>(),
+            borrowed_ast
+                .nodes()
+                .map(|node| node.kind())
+                .collect::<Vec<_>>(),
+        );
+
+        for node in syntax_tree.nodes() {
+            if let Some(parent) = node.parent() {
+                assert_eq!(node.depth(), parent.depth() + 1);
+            }
+
+            if let Some(stmt) = node.cast::<Stmt>() {
+                assert_eq!(syntax_tree.find(stmt), Some(node));
+            } else if let Some(expression) = node.cast::<Expression>() {
+                assert_eq!(syntax_tree.find(expression), Some(node));
+            }
+        }
+    });
+}
+
+fn any_node_tokens<'a>(node: AnyNode<'a>, tokens: &mut Vec<*const TokenReference>) {
+    match node {
+        AnyNode::TokenReference(token) => tokens.push(token),
+        _ => node
+            .children()
+            .for_each(|child| any_node_tokens(child, tokens)),
+    }
+}
+
+// Every token is found by walking the children of every node, and only once
+#[test]
+#[cfg_attr(feature = "no-source-tests", ignore)]
+fn test_any_node_children() {
+    for_each_pass_case(|_, _, ast, _| {
+        let mut walked_tokens = Vec::new();
+        any_node_tokens(AnyNode::from(ast.nodes()), &mut walked_tokens);
+        walked_tokens.sort();
+
+        let mut tokens: Vec<*const TokenReference> =
+            ast.nodes().tokens().map(|token| token as _).collect();
+        tokens.sort();
+        assert_eq!(walked_tokens, tokens);
+    });
+}
@@ -170,3 +170,92 @@ fn test_unary_visitor_regression() {
     visitor.visit_ast(&parse("local x = #{}").unwrap());
     assert!(visitor.0, "Unary operation was not visited");
 }
+
+#[test]
+fn test_visit_name_commas() {
+    #[derive(Default)]
+    struct CommaVisitor(usize);
+
+    impl Visitor for CommaVisitor {
+        fn visit_symbol(&mut self, token: &Token) {
+            if token.to_string() == "," {
+                self.0 += 1;
+            }
+        }
+    }
+
+    let mut visitor = CommaVisitor::default();
+    visitor.visit_ast(
+        &parse("local a, b = 1, 2\nfor i, v in pairs(t) do end\nfunction f(x, y) end").unwrap(),
+    );
+    assert_eq!(visitor.0, 4);
+}
+
+#[test]
+fn test_visitor_ref() {
+    use full_moon::visitors::{Visit, VisitorRef};
+
+    // Unlike with `Visitor`, nodes can be kept after visiting
+    #[derive(Default)]
+    struct CallVisitor<'ast> {
+        calls: Vec<&'ast ast::FunctionCall>,
+    }
+
+    impl<'ast> VisitorRef<'ast> for CallVisitor<'ast> {
+        fn visit_function_call(&mut self, call: &'ast ast::FunctionCall) {
+            self.calls.push(call);
+        }
+    }
+
+    let ast = parse("print(f(1))\nlocal x = g()").unwrap();
+    let mut visitor = CallVisitor::default();
+    ast.nodes().visit(&mut visitor);
+
+    let calls = visitor
+        .calls
+        .iter()
+        .map(|call| call.to_string())
+        .collect::<Vec<_>>();
+
+    assert_eq!(calls, ["print(f(1))\n", "f(1)", "g()"]);
+}
+
+// Calling the methods of a `Visitor` isn't ambiguous with everything in `visitors` imported
+#[test]
+fn test_visitor_glob_import() {
+    use full_moon::visitors::*;
+
+    #[derive(Default)]
+    struct BlockVisitor(usize);
+
+    impl Visitor for BlockVisitor {
+        fn visit_block(&mut self, _: &ast::Block) {
+            self.0 += 1;
+        }
+    }
+
+    let ast = parse("do end").unwrap();
+    let mut visitor = BlockVisitor::default();
+    visitor.visit_block(ast.nodes());
+    visitor.visit_block_end(ast.nodes());
+    assert_eq!(visitor.0, 1);
+
+    ast.nodes().visit(&mut VisitorRefAdapter(&mut visitor));
+    assert_eq!(visitor.0, 3);
+}
+
+#[test]
+fn test_visit_token_reference() {
+    #[derive(Default)]
+    struct TokenVisitor(Vec<String>);
+
+    impl Visitor for TokenVisitor {
+        fn visit_token_reference(&mut self, token: &TokenReference) {
+            self.0.push(token.token().to_string());
+        }
+    }
+
+    let mut visitor = TokenVisitor::default();
+    visitor.visit_ast(&parse("local x = (1)").unwrap());
+    assert_eq!(visitor.0, ["local", "x", "=", "(", "1", ")", ""]);
+}